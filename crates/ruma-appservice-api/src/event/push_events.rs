@@ -146,6 +146,7 @@ pub mod v1 {
 
     /// Information on E2E device updates.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[cfg(feature = "unstable-msc3202")]
     pub struct DeviceLists {
@@ -176,6 +177,7 @@ pub mod v1 {
     /// Type for passing ephemeral data to homeservers.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[non_exhaustive]
     pub enum Edu {
         /// An EDU representing presence updates for users of the sending homeserver.
@@ -221,6 +223,7 @@ pub mod v1 {
     /// The content for "m.presence" Edu.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct PresenceContent {
         /// A list of presence updates that the receiving server is likely to be interested in.
@@ -238,6 +241,7 @@ pub mod v1 {
     /// An update to the presence of a user.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct PresenceUpdate {
         /// The user ID this presence EDU is for.
@@ -277,6 +281,7 @@ pub mod v1 {
     /// The content for "m.receipt" Edu.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(transparent)]
     pub struct ReceiptContent(pub BTreeMap<OwnedRoomId, ReceiptMap>);
@@ -328,6 +333,7 @@ pub mod v1 {
     /// Mapping between user and `ReceiptData`.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ReceiptMap {
         /// Read receipts for users in the room.
@@ -346,6 +352,7 @@ pub mod v1 {
     /// Metadata about the event that was last read and when.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ReceiptData {
         /// Metadata for the read receipt.
@@ -366,6 +373,7 @@ pub mod v1 {
     /// The content for "m.typing" Edu.
     #[cfg(feature = "unstable-msc2409")]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct TypingContent {
         /// The room where the user's typing status has been updated.
@@ -10,6 +10,8 @@
 use serde::{Deserialize, Serialize};
 
 pub mod event;
+#[cfg(all(feature = "client", feature = "json-schema"))]
+pub mod openapi;
 pub mod ping;
 pub mod query;
 pub mod thirdparty;
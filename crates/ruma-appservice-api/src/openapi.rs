@@ -0,0 +1,21 @@
+//! Generation of an [OpenAPI] document for the endpoints of the application service API.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+
+use ruma_common::api::openapi::OpenApiDocument;
+
+/// Add all the endpoints of the application service API to the given OpenAPI document.
+///
+/// Endpoints that are behind features that are not enabled are not added.
+#[allow(deprecated)]
+pub fn add_endpoints(document: &mut OpenApiDocument) {
+    document.add_endpoint::<crate::event::push_events::v1::Request>();
+    document.add_endpoint::<crate::ping::send_ping::v1::Request>();
+    document.add_endpoint::<crate::query::query_room_alias::v1::Request>();
+    document.add_endpoint::<crate::query::query_user_id::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::get_location_for_protocol::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::get_location_for_room_alias::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::get_protocol::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::get_user_for_protocol::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::get_user_for_user_id::v1::Request>();
+}
@@ -26,6 +26,7 @@ use crate::PrivOwnedStr;
 
 /// Additional authentication information for requestToken endpoints.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct IdentityServerInfo {
     /// The ID server to send the onward request to as a hostname with an
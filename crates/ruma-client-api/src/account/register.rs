@@ -170,6 +170,7 @@ pub mod v3 {
 
 /// The kind of account being registered.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum RegistrationKind {
@@ -185,6 +186,7 @@ pub enum RegistrationKind {
 
 /// The login type.
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum LoginType {
     /// An appservice-specific login type
@@ -15,17 +15,27 @@ pub mod get_backup_keys_for_session;
 pub mod get_latest_backup_info;
 pub mod update_backup_version;
 
+#[cfg(feature = "backup-encryption")]
+mod encryption;
+
 use std::collections::BTreeMap;
 
 use js_int::UInt;
 use ruma_common::{
     serde::{Base64, Raw},
-    OwnedDeviceKeyId, OwnedUserId,
+    DeviceKeyAlgorithm, EventEncryptionAlgorithm, OwnedDeviceKeyId, OwnedUserId,
 };
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "backup-encryption")]
+pub use self::encryption::{
+    verify_backup_auth_data, BackupDecryptionError, BackupDecryptionKey, BackupKeyError,
+    BackupPublicKey, BackupSignatureVerification, SignatureState,
+};
+
 /// A wrapper around a mapping of session IDs to key data.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomKeyBackup {
     /// A map of session IDs to key data.
@@ -41,6 +51,7 @@ impl RoomKeyBackup {
 
 /// The algorithm used for storing backups.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(tag = "algorithm", content = "auth_data")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum BackupAlgorithm {
@@ -60,6 +71,7 @@ pub enum BackupAlgorithm {
 /// To create an instance of this type, first create a [`KeyBackupDataInit`] and convert it via
 /// `KeyBackupData::from` / `.into()`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct KeyBackupData {
     /// The index of the first message in the session that the key can decrypt.
@@ -108,6 +120,7 @@ impl From<KeyBackupDataInit> for KeyBackupData {
 /// To create an instance of this type, first create an [`EncryptedSessionDataInit`] and convert it
 /// via `EncryptedSessionData::from` / `.into()`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct EncryptedSessionData {
     /// Unpadded base64-encoded public half of the ephemeral key.
@@ -143,3 +156,74 @@ impl From<EncryptedSessionDataInit> for EncryptedSessionData {
         Self { ephemeral, ciphertext, mac }
     }
 }
+
+/// The decrypted algorithm-dependent data for backups using the
+/// `m.megolm_backup.v1.curve25519-aes-sha2` algorithm.
+///
+/// To create an instance of this type, first create a [`BackedUpSessionDataInit`] and convert it
+/// via `BackedUpSessionData::from` / `.into()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct BackedUpSessionData {
+    /// The end-to-end message encryption algorithm that the key is for.
+    pub algorithm: EventEncryptionAlgorithm,
+
+    /// Chain of Curve25519 keys through which this session was forwarded, via
+    /// `m.forwarded_room_key` events.
+    pub forwarded_curve25519_key_chain: Vec<String>,
+
+    /// A map from algorithm name to the public part of the key of the device that initiated the
+    /// session.
+    pub sender_claimed_keys: BTreeMap<DeviceKeyAlgorithm, String>,
+
+    /// Unpadded base64-encoded device Curve25519 key.
+    pub sender_key: String,
+
+    /// Unpadded base64-encoded session key in session-export format.
+    pub session_key: String,
+}
+
+/// The decrypted algorithm-dependent data for backups using the
+/// `m.megolm_backup.v1.curve25519-aes-sha2` algorithm.
+///
+/// This struct will not be updated even if additional fields are added to [`BackedUpSessionData`]
+/// in a new (non-breaking) release of the Matrix specification.
+#[derive(Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct BackedUpSessionDataInit {
+    /// The end-to-end message encryption algorithm that the key is for.
+    pub algorithm: EventEncryptionAlgorithm,
+
+    /// Chain of Curve25519 keys through which this session was forwarded, via
+    /// `m.forwarded_room_key` events.
+    pub forwarded_curve25519_key_chain: Vec<String>,
+
+    /// A map from algorithm name to the public part of the key of the device that initiated the
+    /// session.
+    pub sender_claimed_keys: BTreeMap<DeviceKeyAlgorithm, String>,
+
+    /// Unpadded base64-encoded device Curve25519 key.
+    pub sender_key: String,
+
+    /// Unpadded base64-encoded session key in session-export format.
+    pub session_key: String,
+}
+
+impl From<BackedUpSessionDataInit> for BackedUpSessionData {
+    fn from(init: BackedUpSessionDataInit) -> Self {
+        let BackedUpSessionDataInit {
+            algorithm,
+            forwarded_curve25519_key_chain,
+            sender_claimed_keys,
+            sender_key,
+            session_key,
+        } = init;
+        Self {
+            algorithm,
+            forwarded_curve25519_key_chain,
+            sender_claimed_keys,
+            sender_key,
+            session_key,
+        }
+    }
+}
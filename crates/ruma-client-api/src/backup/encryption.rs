@@ -0,0 +1,381 @@
+//! Encryption of key backups with the `m.megolm_backup.v1.curve25519-aes-sha2` algorithm.
+//!
+//! See the [spec] for the details of the algorithm.
+//!
+//! [spec]: https://spec.matrix.org/latest/client-server-api/#backup-algorithm-mmegolm_backupv1curve25519-aes-sha2
+
+use std::{collections::BTreeMap, fmt};
+
+use aes::{
+    cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit},
+    Aes256,
+};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::thread_rng;
+use ruma_common::{
+    encryption::{CrossSigningKey, DeviceKeys, KeyUsage},
+    serde::{base64::Standard, Base64, Raw},
+    CanonicalJsonObject, CanonicalJsonValue, DeviceKeyAlgorithm, DeviceKeyId, OwnedDeviceId,
+    OwnedDeviceKeyId, UserId,
+};
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use super::{BackedUpSessionData, BackupAlgorithm, EncryptedSessionData, EncryptedSessionDataInit};
+
+type Aes256CbcEnc = cbc::Encryptor<Aes256>;
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+const KEY_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+const MAC_SIZE: usize = 8;
+
+/// The public part of a key backup key.
+///
+/// It is used to encrypt the session data uploaded to the backup.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BackupPublicKey {
+    key: PublicKey,
+}
+
+impl BackupPublicKey {
+    /// Creates a new `BackupPublicKey` from the given bytes.
+    pub fn new(key: [u8; KEY_SIZE]) -> Self {
+        Self { key: key.into() }
+    }
+
+    /// Get the public key of the given backup algorithm.
+    pub fn from_algorithm(algorithm: &BackupAlgorithm) -> Result<Self, BackupKeyError> {
+        let BackupAlgorithm::MegolmBackupV1Curve25519AesSha2 { public_key, .. } = algorithm;
+        let key: [u8; KEY_SIZE] =
+            public_key.as_bytes().try_into().map_err(|_| BackupKeyError::InvalidKeyLength)?;
+        Ok(Self::new(key))
+    }
+
+    /// The bytes of this key.
+    pub fn as_bytes(&self) -> &[u8; KEY_SIZE] {
+        self.key.as_bytes()
+    }
+
+    /// Encode this key as unpadded base64, as used in the `auth_data` of the backup.
+    pub fn to_base64(&self) -> Base64 {
+        Base64::new(self.as_bytes().to_vec())
+    }
+
+    /// Encrypt the given session data for the backup.
+    ///
+    /// For compatibility with libolm, the MAC is computed over an empty string rather than over
+    /// the ciphertext.
+    pub fn encrypt(&self, session_data: &BackedUpSessionData) -> EncryptedSessionData {
+        let plaintext =
+            serde_json::to_vec(session_data).expect("session data serialization should succeed");
+
+        let ephemeral_secret = EphemeralSecret::random_from_rng(thread_rng());
+        let ephemeral_key = PublicKey::from(&ephemeral_secret);
+        let shared_secret = ephemeral_secret.diffie_hellman(&self.key);
+
+        let keys = DerivedKeys::new(shared_secret.as_bytes());
+        let ciphertext = Aes256CbcEnc::new(&keys.aes_key.into(), &keys.aes_iv.into())
+            .encrypt_padded_vec_mut::<Pkcs7>(&plaintext);
+        let mac = keys.mac(&[]);
+
+        EncryptedSessionDataInit {
+            ephemeral: Base64::new(ephemeral_key.as_bytes().to_vec()),
+            ciphertext: Base64::new(ciphertext),
+            mac: Base64::new(mac.to_vec()),
+        }
+        .into()
+    }
+}
+
+impl fmt::Debug for BackupPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BackupPublicKey").field(&self.to_base64()).finish()
+    }
+}
+
+/// The private part of a key backup key.
+///
+/// It is used to decrypt the session data downloaded from the backup. It is usually shared
+/// between devices as the `m.megolm_backup.v1` secret.
+#[derive(Clone)]
+pub struct BackupDecryptionKey {
+    key: StaticSecret,
+}
+
+impl BackupDecryptionKey {
+    /// Creates a new `BackupDecryptionKey` from the given bytes.
+    pub fn new(key: [u8; KEY_SIZE]) -> Self {
+        Self { key: key.into() }
+    }
+
+    /// Generates a new random `BackupDecryptionKey`.
+    pub fn generate() -> Self {
+        Self { key: StaticSecret::random_from_rng(thread_rng()) }
+    }
+
+    /// Parses a `BackupDecryptionKey` from its base64 encoding, as used in the
+    /// `m.megolm_backup.v1` secret.
+    pub fn from_base64(key: &str) -> Result<Self, BackupKeyError> {
+        let key = Base64::<Standard>::parse(key).map_err(|_| BackupKeyError::InvalidBase64)?;
+        let key: [u8; KEY_SIZE] =
+            key.as_bytes().try_into().map_err(|_| BackupKeyError::InvalidKeyLength)?;
+        Ok(Self::new(key))
+    }
+
+    /// Encode this key as unpadded base64, as used in the `m.megolm_backup.v1` secret.
+    pub fn to_base64(&self) -> Base64 {
+        Base64::new(self.key.to_bytes().to_vec())
+    }
+
+    /// The public part of this key.
+    pub fn public_key(&self) -> BackupPublicKey {
+        BackupPublicKey { key: PublicKey::from(&self.key) }
+    }
+
+    /// Whether this key is the private part of the public key of the given backup algorithm.
+    pub fn matches(&self, algorithm: &BackupAlgorithm) -> bool {
+        BackupPublicKey::from_algorithm(algorithm).is_ok_and(|key| key == self.public_key())
+    }
+
+    /// Decrypt the given session data from the backup.
+    ///
+    /// The MAC is accepted if it was computed over the ciphertext, as specified, or over an empty
+    /// string, like libolm does.
+    pub fn decrypt(
+        &self,
+        session_data: &EncryptedSessionData,
+    ) -> Result<BackedUpSessionData, BackupDecryptionError> {
+        let ephemeral_key: [u8; KEY_SIZE] = session_data
+            .ephemeral
+            .as_bytes()
+            .try_into()
+            .map_err(|_| BackupDecryptionError::InvalidEphemeralKeyLength)?;
+        let shared_secret = self.key.diffie_hellman(&ephemeral_key.into());
+        let keys = DerivedKeys::new(shared_secret.as_bytes());
+
+        let ciphertext = session_data.ciphertext.as_bytes();
+        let mac = session_data.mac.as_bytes();
+        if !keys.verify_mac(ciphertext, mac) && !keys.verify_mac(&[], mac) {
+            return Err(BackupDecryptionError::MacMismatch);
+        }
+
+        let plaintext = Aes256CbcDec::new(&keys.aes_key.into(), &keys.aes_iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+            .map_err(|_| BackupDecryptionError::InvalidPadding)?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+impl fmt::Debug for BackupDecryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackupDecryptionKey").finish_non_exhaustive()
+    }
+}
+
+/// The keys derived from the shared secret between the ephemeral key and the backup key.
+struct DerivedKeys {
+    aes_key: [u8; KEY_SIZE],
+    mac_key: [u8; KEY_SIZE],
+    aes_iv: [u8; IV_SIZE],
+}
+
+impl DerivedKeys {
+    fn new(shared_secret: &[u8; KEY_SIZE]) -> Self {
+        let mut okm = [0; 2 * KEY_SIZE + IV_SIZE];
+        Hkdf::<Sha256>::new(Some(&[0; KEY_SIZE]), shared_secret)
+            .expand(&[], &mut okm)
+            .expect("80 bytes is a valid length for HKDF-SHA-256");
+
+        let mut keys =
+            Self { aes_key: [0; KEY_SIZE], mac_key: [0; KEY_SIZE], aes_iv: [0; IV_SIZE] };
+        keys.aes_key.copy_from_slice(&okm[..KEY_SIZE]);
+        keys.mac_key.copy_from_slice(&okm[KEY_SIZE..2 * KEY_SIZE]);
+        keys.aes_iv.copy_from_slice(&okm[2 * KEY_SIZE..]);
+        keys
+    }
+
+    /// Compute the truncated MAC of the given data.
+    fn mac(&self, data: &[u8]) -> [u8; MAC_SIZE] {
+        let mut hmac =
+            HmacSha256::new_from_slice(&self.mac_key).expect("HMAC can take a key of any size");
+        hmac.update(data);
+
+        let mut mac = [0; MAC_SIZE];
+        mac.copy_from_slice(&hmac.finalize().into_bytes()[..MAC_SIZE]);
+        mac
+    }
+
+    /// Check in constant time that the given truncated MAC matches the given data.
+    fn verify_mac(&self, data: &[u8], mac: &[u8]) -> bool {
+        if mac.len() != MAC_SIZE {
+            return false;
+        }
+
+        let mut hmac =
+            HmacSha256::new_from_slice(&self.mac_key).expect("HMAC can take a key of any size");
+        hmac.update(data);
+        hmac.verify_truncated_left(mac).is_ok()
+    }
+}
+
+/// The state of a signature of the `auth_data` of a backup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::exhaustive_enums)]
+pub enum SignatureState {
+    /// There is no signature by the key.
+    Missing,
+
+    /// The signature by the key is invalid.
+    Invalid,
+
+    /// The signature by the key is valid.
+    Valid,
+}
+
+impl SignatureState {
+    /// Whether the signature is valid.
+    pub fn is_valid(self) -> bool {
+        self == Self::Valid
+    }
+}
+
+/// The result of the verification of the signatures of the `auth_data` of a backup.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct BackupSignatureVerification {
+    /// The state of the signatures by the Ed25519 keys of the devices of the user.
+    pub device_signatures: BTreeMap<OwnedDeviceId, SignatureState>,
+
+    /// The state of the signature by the master cross-signing key of the user.
+    ///
+    /// This is [`SignatureState::Missing`] if no master key was provided.
+    pub master_key_signature: SignatureState,
+}
+
+impl BackupSignatureVerification {
+    /// Whether the `auth_data` has at least one valid signature.
+    ///
+    /// It is up to the caller to decide whether the signing device or master key is trusted.
+    pub fn has_valid_signature(&self) -> bool {
+        self.master_key_signature.is_valid()
+            || self.device_signatures.values().any(|state| state.is_valid())
+    }
+}
+
+/// Verify the signatures of the `auth_data` of a backup by the given user.
+///
+/// The signatures are checked against the Ed25519 keys of the given devices of the user and
+/// against the given master cross-signing key of the user. Devices and keys of other users are
+/// ignored.
+///
+/// The raw JSON of the algorithm is used, so that fields unknown to ruma are also covered by the
+/// signatures.
+pub fn verify_backup_auth_data<'a>(
+    algorithm: &Raw<BackupAlgorithm>,
+    user_id: &UserId,
+    devices: impl IntoIterator<Item = &'a DeviceKeys>,
+    master_key: Option<&CrossSigningKey>,
+) -> Result<BackupSignatureVerification, serde_json::Error> {
+    let mut algorithm = algorithm.deserialize_as::<CanonicalJsonObject>()?;
+    let Some(CanonicalJsonValue::Object(mut auth_data)) = algorithm.remove("auth_data") else {
+        return Err(serde::de::Error::missing_field("auth_data"));
+    };
+    let signatures = match auth_data.remove("signatures") {
+        Some(CanonicalJsonValue::Object(mut signatures)) => signatures.remove(user_id.as_str()),
+        _ => None,
+    };
+    let signatures = match signatures {
+        Some(CanonicalJsonValue::Object(signatures)) => signatures,
+        _ => CanonicalJsonObject::new(),
+    };
+
+    let check = |key_id: &OwnedDeviceKeyId, public_key: &str| {
+        let Some(signature) = signatures.get(key_id.as_str()) else {
+            return SignatureState::Missing;
+        };
+        let Ok(public_key) = Base64::parse(public_key) else {
+            return SignatureState::Invalid;
+        };
+
+        let public_key_map = BTreeMap::from([(
+            user_id.as_str().to_owned(),
+            BTreeMap::from([(key_id.as_str().to_owned(), public_key)]),
+        )]);
+        let mut object = auth_data.clone();
+        object.insert(
+            "signatures".to_owned(),
+            CanonicalJsonValue::Object(BTreeMap::from([(
+                user_id.as_str().to_owned(),
+                CanonicalJsonValue::Object(BTreeMap::from([(
+                    key_id.as_str().to_owned(),
+                    signature.clone(),
+                )])),
+            )])),
+        );
+
+        match ruma_signatures::verify_json(&public_key_map, &object) {
+            Ok(()) => SignatureState::Valid,
+            Err(_) => SignatureState::Invalid,
+        }
+    };
+
+    let device_signatures = devices
+        .into_iter()
+        .filter(|device| device.user_id == user_id)
+        .map(|device| {
+            let key_id = DeviceKeyId::from_parts(DeviceKeyAlgorithm::Ed25519, &device.device_id);
+            let state = match device.keys.get(&key_id) {
+                Some(public_key) => check(&key_id, public_key),
+                None => SignatureState::Missing,
+            };
+            (device.device_id.clone(), state)
+        })
+        .collect();
+
+    let master_key_signature = master_key
+        .filter(|key| key.user_id == user_id && key.usage.contains(&KeyUsage::Master))
+        .and_then(|key| {
+            key.keys.iter().find(|(key_id, _)| key_id.algorithm() == DeviceKeyAlgorithm::Ed25519)
+        })
+        .map_or(SignatureState::Missing, |(key_id, public_key)| check(key_id, public_key));
+
+    Ok(BackupSignatureVerification { device_signatures, master_key_signature })
+}
+
+/// An error encountered when parsing a key backup key.
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum BackupKeyError {
+    /// The key is not valid base64.
+    #[error("the backup key is not valid base64")]
+    InvalidBase64,
+
+    /// The key doesn't have a length of 256 bits.
+    #[error("the backup key has an invalid length")]
+    InvalidKeyLength,
+}
+
+/// An error encountered when decrypting the session data of a backup.
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum BackupDecryptionError {
+    /// The ephemeral key doesn't have a length of 256 bits.
+    #[error("the ephemeral key of the session data has an invalid length")]
+    InvalidEphemeralKeyLength,
+
+    /// The MAC of the session data doesn't match, which usually means that the key is wrong.
+    #[error("the MAC of the session data doesn't match")]
+    MacMismatch,
+
+    /// The padding of the decrypted session data is invalid.
+    #[error("the padding of the decrypted session data is invalid")]
+    InvalidPadding,
+
+    /// The decrypted session data is not valid JSON.
+    #[error("the decrypted session data is invalid: {0}")]
+    Json(#[from] serde_json::Error),
+}
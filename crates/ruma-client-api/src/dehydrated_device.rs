@@ -12,6 +12,7 @@ pub mod put_dehydrated_device;
 
 /// Data for a dehydrated device.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(try_from = "Helper", into = "Helper")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum DehydratedDeviceData {
@@ -59,6 +60,7 @@ pub enum DeviceDehydrationAlgorithm {
 }
 
 #[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 struct Helper {
     algorithm: DeviceDehydrationAlgorithm,
     device_pickle: String,
@@ -11,6 +11,7 @@ pub mod update_device;
 
 /// Information about a registered device.
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Device {
     /// Device ID
@@ -84,6 +84,7 @@ impl Response {
 
 /// Information about a discovered homeserver.
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct HomeserverInfo {
     /// The base URL for the homeserver for client-server connections.
@@ -99,6 +100,7 @@ impl HomeserverInfo {
 
 /// Information about a discovered identity server.
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct IdentityServerInfo {
     /// The base URL for the identity server for client-server connections.
@@ -115,6 +117,7 @@ impl IdentityServerInfo {
 /// Information about a discovered map tile server.
 #[cfg(feature = "unstable-msc3488")]
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct TileServerInfo {
     /// The URL of a map tile server's `style.json` file.
@@ -134,6 +137,7 @@ impl TileServerInfo {
 /// Information about a discovered authentication server.
 #[cfg(feature = "unstable-msc2965")]
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AuthenticationServerInfo {
     /// The OIDC Provider that is trusted by the homeserver.
@@ -156,6 +160,7 @@ impl AuthenticationServerInfo {
 /// Information about a discovered sliding sync proxy.
 #[cfg(feature = "unstable-msc3575")]
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SlidingSyncProxyInfo {
     /// The URL of a sliding sync proxy that is trusted by the homeserver.
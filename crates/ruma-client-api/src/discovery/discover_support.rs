@@ -67,6 +67,7 @@ impl Response {
 
 /// A way to contact the server administrator.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Contact {
     /// An informal description of what the contact methods are used for.
@@ -20,6 +20,7 @@ pub mod v3;
 
 /// Contains information about all the capabilities that the server supports.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Capabilities {
     /// Capability to indicate if the user can change their password.
@@ -131,6 +132,7 @@ impl<'a> IntoIterator for &'a Capabilities {
 
 /// Information about the m.change_password capability
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ChangePasswordCapability {
     /// `true` if the user can change their password, `false` otherwise.
@@ -157,6 +159,7 @@ impl Default for ChangePasswordCapability {
 
 /// Information about the m.room_versions capability
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomVersionsCapability {
     /// The default room version the server is using for new rooms.
@@ -215,6 +218,7 @@ pub enum RoomVersionStability {
 
 /// Information about the `m.set_displayname` capability
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SetDisplayNameCapability {
     /// `true` if the user can change their display name, `false` otherwise.
@@ -241,6 +245,7 @@ impl Default for SetDisplayNameCapability {
 
 /// Information about the `m.set_avatar_url` capability
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SetAvatarUrlCapability {
     /// `true` if the user can change their avatar, `false` otherwise.
@@ -267,6 +272,7 @@ impl Default for SetAvatarUrlCapability {
 
 /// Information about the `m.3pid_changes` capability
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ThirdPartyIdChangesCapability {
     /// `true` if the user can change the third-party identifiers associated with their account,
@@ -53,7 +53,19 @@ impl Serialize for LazyLoadOptions {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for LazyLoadOptions {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("LazyLoadOptions")
+    }
+
+    fn json_schema(generator: &mut ruma_common::json_schema::SchemaGenerator) -> serde_json::Value {
+        generator.subschema_for::<LazyLoadJsonRepr>()
+    }
+}
+
 #[derive(Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 struct LazyLoadJsonRepr {
     lazy_load_members: Option<bool>,
     include_redundant_members: Option<bool>,
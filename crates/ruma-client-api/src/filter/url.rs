@@ -38,6 +38,27 @@ impl<'de> Deserialize<'de> for UrlFilter {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for UrlFilter {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("UrlFilter")
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(
+        _generator: &mut ruma_common::json_schema::SchemaGenerator,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "type": "boolean",
+            "description": "Whether to include only events with a `url` key in their content, \
+                            or to exclude them.",
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
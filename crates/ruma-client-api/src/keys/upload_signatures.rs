@@ -65,6 +65,7 @@ pub mod v3 {
 
     /// A map of key IDs to signed key objects.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[serde(transparent)]
     pub struct SignedKeys(BTreeMap<Box<str>, Box<RawJsonValue>>);
 
@@ -96,6 +97,7 @@ pub mod v3 {
 
     /// A failure to process a signed key.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct Failure {
         /// Machine-readable error code.
         errcode: FailureErrorCode,
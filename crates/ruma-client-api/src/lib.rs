@@ -28,6 +28,8 @@ pub mod knock;
 pub mod media;
 pub mod membership;
 pub mod message;
+#[cfg(all(feature = "client", feature = "json-schema"))]
+pub mod openapi;
 pub mod presence;
 pub mod profile;
 pub mod push;
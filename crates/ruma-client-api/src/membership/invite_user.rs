@@ -67,6 +67,7 @@ pub mod v3 {
 
     /// Distinguishes between invititations by Matrix or third party identifiers.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(untagged)]
     pub enum InvitationRecipient {
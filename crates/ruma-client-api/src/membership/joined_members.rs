@@ -58,6 +58,7 @@ pub mod v3 {
 
     /// Information about a room member.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct RoomMember {
         /// The display name of the user.
@@ -0,0 +1,182 @@
+//! Generation of an [OpenAPI] document for the endpoints of the client-server API.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+
+use ruma_common::api::openapi::OpenApiDocument;
+
+/// Add all the endpoints of the client-server API to the given OpenAPI document.
+///
+/// Endpoints that are behind features that are not enabled are not added.
+#[allow(deprecated)]
+pub fn add_endpoints(document: &mut OpenApiDocument) {
+    document.add_endpoint::<crate::account::add_3pid::v3::Request>();
+    document.add_endpoint::<crate::account::bind_3pid::v3::Request>();
+    document.add_endpoint::<crate::account::change_password::v3::Request>();
+    document.add_endpoint::<crate::account::check_registration_token_validity::v1::Request>();
+    document.add_endpoint::<crate::account::deactivate::v3::Request>();
+    document.add_endpoint::<crate::account::delete_3pid::v3::Request>();
+    document.add_endpoint::<crate::account::get_3pids::v3::Request>();
+    document.add_endpoint::<crate::account::get_username_availability::v3::Request>();
+    document.add_endpoint::<crate::account::register::v3::Request>();
+    document.add_endpoint::<crate::account::request_3pid_management_token_via_email::v3::Request>();
+    document
+        .add_endpoint::<crate::account::request_3pid_management_token_via_msisdn::v3::Request>();
+    document.add_endpoint::<crate::account::request_openid_token::v3::Request>();
+    document.add_endpoint::<crate::account::request_password_change_token_via_email::v3::Request>();
+    document
+        .add_endpoint::<crate::account::request_password_change_token_via_msisdn::v3::Request>();
+    document.add_endpoint::<crate::account::request_registration_token_via_email::v3::Request>();
+    document.add_endpoint::<crate::account::request_registration_token_via_msisdn::v3::Request>();
+    document.add_endpoint::<crate::account::unbind_3pid::v3::Request>();
+    document.add_endpoint::<crate::account::whoami::v3::Request>();
+    document.add_endpoint::<crate::alias::create_alias::v3::Request>();
+    document.add_endpoint::<crate::alias::delete_alias::v3::Request>();
+    document.add_endpoint::<crate::alias::get_alias::v3::Request>();
+    document.add_endpoint::<crate::appservice::request_ping::v1::Request>();
+    document.add_endpoint::<crate::appservice::set_room_visibility::v3::Request>();
+    document.add_endpoint::<crate::backup::add_backup_keys::v3::Request>();
+    document.add_endpoint::<crate::backup::add_backup_keys_for_room::v3::Request>();
+    document.add_endpoint::<crate::backup::add_backup_keys_for_session::v3::Request>();
+    document.add_endpoint::<crate::backup::create_backup_version::v3::Request>();
+    document.add_endpoint::<crate::backup::delete_backup_keys::v3::Request>();
+    document.add_endpoint::<crate::backup::delete_backup_keys_for_room::v3::Request>();
+    document.add_endpoint::<crate::backup::delete_backup_keys_for_session::v3::Request>();
+    document.add_endpoint::<crate::backup::delete_backup_version::v3::Request>();
+    document.add_endpoint::<crate::backup::get_backup_info::v3::Request>();
+    document.add_endpoint::<crate::backup::get_backup_keys::v3::Request>();
+    document.add_endpoint::<crate::backup::get_backup_keys_for_room::v3::Request>();
+    document.add_endpoint::<crate::backup::get_backup_keys_for_session::v3::Request>();
+    document.add_endpoint::<crate::backup::get_latest_backup_info::v3::Request>();
+    document.add_endpoint::<crate::backup::update_backup_version::v3::Request>();
+    document.add_endpoint::<crate::config::get_global_account_data::v3::Request>();
+    document.add_endpoint::<crate::config::get_room_account_data::v3::Request>();
+    document.add_endpoint::<crate::config::set_global_account_data::v3::Request>();
+    document.add_endpoint::<crate::config::set_room_account_data::v3::Request>();
+    document.add_endpoint::<crate::context::get_context::v3::Request>();
+    #[cfg(feature = "unstable-msc3814")]
+    document
+        .add_endpoint::<crate::dehydrated_device::delete_dehydrated_device::unstable::Request>();
+    #[cfg(feature = "unstable-msc3814")]
+    document.add_endpoint::<crate::dehydrated_device::get_dehydrated_device::unstable::Request>();
+    #[cfg(feature = "unstable-msc3814")]
+    document.add_endpoint::<crate::dehydrated_device::get_events::unstable::Request>();
+    #[cfg(feature = "unstable-msc3814")]
+    document.add_endpoint::<crate::dehydrated_device::put_dehydrated_device::unstable::Request>();
+    document.add_endpoint::<crate::device::delete_device::v3::Request>();
+    document.add_endpoint::<crate::device::delete_devices::v3::Request>();
+    document.add_endpoint::<crate::device::get_device::v3::Request>();
+    document.add_endpoint::<crate::device::get_devices::v3::Request>();
+    document.add_endpoint::<crate::device::update_device::v3::Request>();
+    document.add_endpoint::<crate::directory::get_public_rooms::v3::Request>();
+    document.add_endpoint::<crate::directory::get_public_rooms_filtered::v3::Request>();
+    document.add_endpoint::<crate::directory::get_room_visibility::v3::Request>();
+    document.add_endpoint::<crate::directory::set_room_visibility::v3::Request>();
+    document.add_endpoint::<crate::discovery::discover_homeserver::Request>();
+    document.add_endpoint::<crate::discovery::discover_support::Request>();
+    #[cfg(feature = "unstable-msc2965")]
+    document.add_endpoint::<crate::discovery::get_authentication_issuer::msc2965::Request>();
+    document.add_endpoint::<crate::discovery::get_capabilities::v3::Request>();
+    document.add_endpoint::<crate::discovery::get_supported_versions::Request>();
+    document.add_endpoint::<crate::filter::create_filter::v3::Request>();
+    document.add_endpoint::<crate::filter::get_filter::v3::Request>();
+    document.add_endpoint::<crate::keys::claim_keys::v3::Request>();
+    #[cfg(feature = "unstable-msc3983")]
+    document.add_endpoint::<crate::keys::claim_keys::v4::Request>();
+    document.add_endpoint::<crate::keys::get_key_changes::v3::Request>();
+    document.add_endpoint::<crate::keys::get_keys::v3::Request>();
+    document.add_endpoint::<crate::keys::upload_keys::v3::Request>();
+    document.add_endpoint::<crate::keys::upload_signatures::v3::Request>();
+    document.add_endpoint::<crate::keys::upload_signing_keys::v3::Request>();
+    document.add_endpoint::<crate::knock::knock_room::v3::Request>();
+    document.add_endpoint::<crate::media::create_content::v3::Request>();
+    document.add_endpoint::<crate::media::create_content_async::v3::Request>();
+    document.add_endpoint::<crate::media::create_mxc_uri::v1::Request>();
+    document.add_endpoint::<crate::media::get_content::v3::Request>();
+    document.add_endpoint::<crate::media::get_content_as_filename::v3::Request>();
+    document.add_endpoint::<crate::media::get_content_thumbnail::v3::Request>();
+    document.add_endpoint::<crate::media::get_media_config::v3::Request>();
+    document.add_endpoint::<crate::media::get_media_preview::v3::Request>();
+    document.add_endpoint::<crate::membership::ban_user::v3::Request>();
+    document.add_endpoint::<crate::membership::forget_room::v3::Request>();
+    document.add_endpoint::<crate::membership::get_member_events::v3::Request>();
+    document.add_endpoint::<crate::membership::invite_user::v3::Request>();
+    document.add_endpoint::<crate::membership::join_room_by_id::v3::Request>();
+    document.add_endpoint::<crate::membership::join_room_by_id_or_alias::v3::Request>();
+    document.add_endpoint::<crate::membership::joined_members::v3::Request>();
+    document.add_endpoint::<crate::membership::joined_rooms::v3::Request>();
+    document.add_endpoint::<crate::membership::kick_user::v3::Request>();
+    document.add_endpoint::<crate::membership::leave_room::v3::Request>();
+    #[cfg(feature = "unstable-msc2666")]
+    document.add_endpoint::<crate::membership::mutual_rooms::unstable::Request>();
+    document.add_endpoint::<crate::membership::unban_user::v3::Request>();
+    document.add_endpoint::<crate::message::get_message_events::v3::Request>();
+    document.add_endpoint::<crate::message::send_message_event::v3::Request>();
+    document.add_endpoint::<crate::presence::get_presence::v3::Request>();
+    document.add_endpoint::<crate::presence::set_presence::v3::Request>();
+    document.add_endpoint::<crate::profile::get_avatar_url::v3::Request>();
+    document.add_endpoint::<crate::profile::get_display_name::v3::Request>();
+    document.add_endpoint::<crate::profile::get_profile::v3::Request>();
+    document.add_endpoint::<crate::profile::set_avatar_url::v3::Request>();
+    document.add_endpoint::<crate::profile::set_display_name::v3::Request>();
+    document.add_endpoint::<crate::push::delete_pushrule::v3::Request>();
+    document.add_endpoint::<crate::push::get_notifications::v3::Request>();
+    document.add_endpoint::<crate::push::get_pushers::v3::Request>();
+    document.add_endpoint::<crate::push::get_pushrule::v3::Request>();
+    document.add_endpoint::<crate::push::get_pushrule_actions::v3::Request>();
+    document.add_endpoint::<crate::push::get_pushrule_enabled::v3::Request>();
+    document.add_endpoint::<crate::push::get_pushrules_all::v3::Request>();
+    document.add_endpoint::<crate::push::get_pushrules_global_scope::v3::Request>();
+    document.add_endpoint::<crate::push::set_pusher::v3::Request>();
+    document.add_endpoint::<crate::push::set_pushrule::v3::Request>();
+    document.add_endpoint::<crate::push::set_pushrule_actions::v3::Request>();
+    document.add_endpoint::<crate::push::set_pushrule_enabled::v3::Request>();
+    document.add_endpoint::<crate::read_marker::set_read_marker::v3::Request>();
+    document.add_endpoint::<crate::receipt::create_receipt::v3::Request>();
+    document.add_endpoint::<crate::redact::redact_event::v3::Request>();
+    document.add_endpoint::<crate::relations::get_relating_events::v1::Request>();
+    document.add_endpoint::<crate::relations::get_relating_events_with_rel_type::v1::Request>();
+    document.add_endpoint::<crate::relations::get_relating_events_with_rel_type_and_event_type::v1::Request>();
+    #[cfg(feature = "unstable-msc4108")]
+    document.add_endpoint::<crate::rendezvous::create_rendezvous_session::unstable::Request>();
+    document.add_endpoint::<crate::room::aliases::v3::Request>();
+    document.add_endpoint::<crate::room::create_room::v3::Request>();
+    document.add_endpoint::<crate::room::get_event_by_timestamp::v1::Request>();
+    document.add_endpoint::<crate::room::get_room_event::v3::Request>();
+    #[cfg(feature = "unstable-msc3266")]
+    document.add_endpoint::<crate::room::get_summary::msc3266::Request>();
+    document.add_endpoint::<crate::room::report_content::v3::Request>();
+    document.add_endpoint::<crate::room::upgrade_room::v3::Request>();
+    document.add_endpoint::<crate::search::search_events::v3::Request>();
+    document.add_endpoint::<crate::server::get_user_info::v3::Request>();
+    document.add_endpoint::<crate::session::get_login_token::v1::Request>();
+    document.add_endpoint::<crate::session::get_login_types::v3::Request>();
+    document.add_endpoint::<crate::session::login::v3::Request>();
+    document.add_endpoint::<crate::session::login_fallback::Request>();
+    document.add_endpoint::<crate::session::logout::v3::Request>();
+    document.add_endpoint::<crate::session::logout_all::v3::Request>();
+    document.add_endpoint::<crate::session::refresh_token::v3::Request>();
+    document.add_endpoint::<crate::session::sso_login::v3::Request>();
+    document.add_endpoint::<crate::session::sso_login_with_provider::v3::Request>();
+    document.add_endpoint::<crate::space::get_hierarchy::v1::Request>();
+    document.add_endpoint::<crate::state::get_state_events::v3::Request>();
+    document.add_endpoint::<crate::state::get_state_events_for_key::v3::Request>();
+    document.add_endpoint::<crate::state::send_state_event::v3::Request>();
+    document.add_endpoint::<crate::sync::sync_events::v3::Request>();
+    #[cfg(feature = "unstable-msc3575")]
+    document.add_endpoint::<crate::sync::sync_events::v4::Request>();
+    document.add_endpoint::<crate::tag::create_tag::v3::Request>();
+    document.add_endpoint::<crate::tag::delete_tag::v3::Request>();
+    document.add_endpoint::<crate::tag::get_tags::v3::Request>();
+    document.add_endpoint::<crate::thirdparty::get_location_for_protocol::v3::Request>();
+    document.add_endpoint::<crate::thirdparty::get_location_for_room_alias::v3::Request>();
+    document.add_endpoint::<crate::thirdparty::get_protocol::v3::Request>();
+    document.add_endpoint::<crate::thirdparty::get_protocols::v3::Request>();
+    document.add_endpoint::<crate::thirdparty::get_user_for_protocol::v3::Request>();
+    document.add_endpoint::<crate::thirdparty::get_user_for_user_id::v3::Request>();
+    document.add_endpoint::<crate::threads::get_threads::v1::Request>();
+    document.add_endpoint::<crate::to_device::send_event_to_device::v3::Request>();
+    document.add_endpoint::<crate::typing::create_typing_event::v3::Request>();
+    document.add_endpoint::<crate::uiaa::get_uiaa_fallback_page::v3::Request>();
+    document.add_endpoint::<crate::user_directory::search_users::v3::Request>();
+    document.add_endpoint::<crate::voip::get_turn_server_info::v3::Request>();
+}
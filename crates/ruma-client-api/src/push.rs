@@ -5,8 +5,8 @@ pub use ruma_common::push::RuleKind;
 use ruma_common::{
     push::{
         Action, AnyPushRule, AnyPushRuleRef, ConditionalPushRule, ConditionalPushRuleInit,
-        HttpPusherData, PatternedPushRule, PatternedPushRuleInit, PushCondition, SimplePushRule,
-        SimplePushRuleInit,
+        HttpPusherData, PatternedPushRule, PatternedPushRuleInit, PushCondition, PushRuleChange,
+        SimplePushRule, SimplePushRuleInit,
     },
     serde::{JsonObject, StringEnum},
 };
@@ -33,6 +33,7 @@ pub mod set_pushrule_enabled;
 ///
 /// To create an instance of this type, use one of its `From` implementations.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PushRule {
     /// The actions to perform when this rule is matched.
@@ -188,6 +189,54 @@ impl From<PushRule> for ConditionalPushRule {
     }
 }
 
+/// A request to apply a [`PushRuleChange`] on the homeserver.
+///
+/// This allows to send the changes of a [`RulesetDiff`](ruma_common::push::RulesetDiff) to the
+/// homeserver.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum PushRuleChangeRequest {
+    /// A request to add, modify or move a user-defined rule.
+    Set(set_pushrule::v3::Request),
+
+    /// A request to remove a user-defined rule.
+    Delete(delete_pushrule::v3::Request),
+
+    /// A request to enable or disable a rule.
+    SetEnabled(set_pushrule_enabled::v3::Request),
+
+    /// A request to change the actions of a rule.
+    SetActions(set_pushrule_actions::v3::Request),
+}
+
+impl PushRuleChangeRequest {
+    /// Creates the request to apply the given change to the rules in the given scope.
+    pub fn new(scope: RuleScope, change: PushRuleChange) -> Self {
+        // The catch-all is unreachable if the "unstable-exhaustive-types" feature is enabled.
+        #[allow(unreachable_patterns)]
+        match change {
+            PushRuleChange::Added { rule, after, before }
+            | PushRuleChange::Modified { rule, after, before }
+            | PushRuleChange::Moved { rule, after, before } => {
+                let mut request = set_pushrule::v3::Request::new(scope, rule);
+                request.after = after;
+                request.before = before;
+                Self::Set(request)
+            }
+            PushRuleChange::Removed { kind, rule_id } => {
+                Self::Delete(delete_pushrule::v3::Request::new(scope, kind, rule_id))
+            }
+            PushRuleChange::EnabledChanged { kind, rule_id, enabled } => Self::SetEnabled(
+                set_pushrule_enabled::v3::Request::new(scope, kind, rule_id, enabled),
+            ),
+            PushRuleChange::ActionsChanged { kind, rule_id, actions } => Self::SetActions(
+                set_pushrule_actions::v3::Request::new(scope, kind, rule_id, actions),
+            ),
+            _ => unreachable!(),
+        }
+    }
+}
+
 /// Which kind a pusher is, and the information for that kind.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -207,6 +256,7 @@ pub enum PusherKind {
 /// To create an instance of this type, first create a `PusherInit` and convert it via
 /// `Pusher::from` / `.into()`.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Pusher {
     /// Identifiers for this pusher.
@@ -267,6 +317,7 @@ impl From<PusherInit> for Pusher {
 
 /// Strings to uniquely identify a `Pusher`.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PusherIds {
     /// A unique identifier for the pusher.
@@ -81,6 +81,7 @@ pub mod v3 {
 
     /// Represents a notification.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Notification {
         /// The actions to perform when the conditions for this rule are met.
@@ -77,6 +77,44 @@ impl<'de> Deserialize<'de> for PusherKind {
     }
 }
 
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::{
+        json_schema::{
+            private::{any_of, constant, ObjectSchema},
+            JsonSchema, SchemaGenerator,
+        },
+        push::HttpPusherData,
+    };
+    use serde_json::{json, Value as JsonValue};
+
+    use super::PusherKind;
+
+    impl JsonSchema for PusherKind {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("PusherKind")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            let kind_schema = |kind: JsonValue, data: JsonValue| {
+                let mut object = ObjectSchema::new();
+                object.property("kind", kind, true);
+                object.property("data", data, true);
+                object.finish()
+            };
+
+            any_of(vec![
+                kind_schema(constant("http"), generator.subschema_for::<HttpPusherData>()),
+                kind_schema(constant("email"), json!({ "type": "object" })),
+                // Custom kinds.
+                kind_schema(json!({ "type": "string" }), json!({ "type": "object" })),
+            ])
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches2::assert_matches;
@@ -77,6 +77,7 @@ pub mod v3 {
 
     /// Data necessary to create or update a pusher.
     #[derive(Clone, Debug, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct PusherPostData {
         /// The pusher to configure.
@@ -65,6 +65,35 @@ impl<'de> Deserialize<'de> for PusherAction {
     }
 }
 
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::json_schema::{
+        private::{any_of, null, ObjectSchema},
+        JsonSchema, SchemaGenerator,
+    };
+    use serde_json::Value as JsonValue;
+
+    use super::{PusherAction, PusherPostData};
+
+    impl JsonSchema for PusherAction {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("PusherAction")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            // A `null` kind deletes the pusher.
+            let mut delete = ObjectSchema::new();
+            delete.property("pushkey", generator.subschema_for::<String>(), true);
+            delete.property("app_id", generator.subschema_for::<String>(), true);
+            delete.property("kind", null(), true);
+
+            any_of(vec![generator.subschema_for::<PusherPostData>(), delete.finish()])
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches2::assert_matches;
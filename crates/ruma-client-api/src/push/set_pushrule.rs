@@ -194,16 +194,71 @@ pub mod v3 {
         }
     }
 
+    #[cfg(feature = "json-schema")]
+    impl ruma_common::json_schema::RequestSchema for Request {
+        fn path_parameters_schemas(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Vec<(&'static str, serde_json::Value)> {
+            use ruma_common::json_schema::private::{enumeration, with_description};
+
+            vec![
+                (
+                    "scope",
+                    with_description(
+                        generator.subschema_for::<RuleScope>(),
+                        "The scope to set the rule in.",
+                    ),
+                ),
+                (
+                    "kind",
+                    with_description(
+                        enumeration(&["override", "underride", "sender", "room", "content"]),
+                        "The kind of the rule.",
+                    ),
+                ),
+                (
+                    "rule_id",
+                    with_description(
+                        generator.subschema_for::<String>(),
+                        "The identifier of the rule.",
+                    ),
+                ),
+            ]
+        }
+
+        fn query_schema(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<serde_json::Value> {
+            use ruma_common::json_schema::JsonSchema;
+
+            Some(RequestQuery::json_schema(generator))
+        }
+
+        fn body_schema(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<ruma_common::json_schema::BodySchema> {
+            use ruma_common::json_schema::JsonSchema;
+
+            Some(ruma_common::json_schema::BodySchema::Json(RequestBody::json_schema(generator)))
+        }
+    }
+
     #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     struct RequestQuery {
+        /// Use 'before' with a rule_id as its value to make the new rule the next-most important
+        /// rule with respect to the given user defined rule.
         #[serde(skip_serializing_if = "Option::is_none")]
         before: Option<String>,
 
+        /// This makes the new rule the next-less important rule relative to the given user defined
+        /// rule.
         #[serde(skip_serializing_if = "Option::is_none")]
         after: Option<String>,
     }
 
     #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[serde(untagged)]
     enum RequestBody {
         Simple(SimpleRequestBody),
@@ -214,11 +269,13 @@ pub mod v3 {
     }
 
     #[derive(Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     struct SimpleRequestBody {
         actions: Vec<Action>,
     }
 
     #[derive(Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     struct PatternedRequestBody {
         actions: Vec<Action>,
 
@@ -226,6 +283,7 @@ pub mod v3 {
     }
 
     #[derive(Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     struct ConditionalRequestBody {
         actions: Vec<Action>,
 
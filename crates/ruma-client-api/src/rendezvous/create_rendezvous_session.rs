@@ -105,6 +105,28 @@ pub mod unstable {
         }
     }
 
+    #[cfg(feature = "json-schema")]
+    impl ruma_common::json_schema::RequestSchema for Request {
+        fn path_parameters_schemas(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Vec<(&'static str, serde_json::Value)> {
+            Vec::new()
+        }
+
+        fn query_schema(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<serde_json::Value> {
+            None
+        }
+
+        fn body_schema(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<ruma_common::json_schema::BodySchema> {
+            // The body is arbitrary text.
+            Some(ruma_common::json_schema::BodySchema::Binary)
+        }
+    }
+
     impl Request {
         /// Creates a new `Request` with the given content.
         pub fn new(content: String) -> Self {
@@ -137,6 +159,27 @@ pub mod unstable {
         url: Url,
     }
 
+    #[cfg(feature = "json-schema")]
+    impl ruma_common::json_schema::ResponseSchema for Response {
+        const STATUS: http::StatusCode = http::StatusCode::OK;
+
+        fn body_schema(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<ruma_common::json_schema::BodySchema> {
+            Some(ruma_common::json_schema::BodySchema::Json(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "description": "The absolute URL of the rendezvous session.",
+                        "type": "string",
+                        "format": "uri",
+                    },
+                },
+                "required": ["url"],
+            })))
+        }
+    }
+
     #[cfg(feature = "client")]
     impl ruma_common::api::IncomingResponse for Response {
         type EndpointError = crate::Error;
@@ -67,6 +67,7 @@ pub mod v3 {
 
     /// Categories of events that can be searched for.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Categories {
         /// Criteria for searching room events.
@@ -83,6 +84,7 @@ pub mod v3 {
 
     /// Criteria for searching a category of events.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Criteria {
         /// The string to search events for.
@@ -132,6 +134,7 @@ pub mod v3 {
 
     /// Configures whether any context for the events returned are included in the response.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct EventContext {
         /// How many events before the result are returned.
@@ -189,6 +192,7 @@ pub mod v3 {
 
     /// Context for search results, if requested.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct EventContextResult {
         /// Pagination token for the end of the chunk.
@@ -230,6 +234,7 @@ pub mod v3 {
 
     /// A grouping for partitioning the result set.
     #[derive(Clone, Default, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Grouping {
         /// The key within events to use for this grouping.
@@ -266,6 +271,7 @@ pub mod v3 {
 
     /// Requests that the server partitions the result set based on the provided list of keys.
     #[derive(Clone, Default, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Groupings {
         /// List of groups to request.
@@ -325,6 +331,7 @@ pub mod v3 {
 
     /// Categories of events that can be searched for.
     #[derive(Clone, Default, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ResultCategories {
         /// Room event results.
@@ -341,6 +348,7 @@ pub mod v3 {
 
     /// Categories of events that can be searched for.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ResultRoomEvents {
         /// An approximate count of the total number of results found.
@@ -393,6 +401,7 @@ pub mod v3 {
 
     /// A grouping of results, if requested.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ResultGroup {
         /// Token that can be used to get the next batch of results in the group, by passing as the
@@ -425,6 +434,7 @@ pub mod v3 {
 
     /// A search result.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct SearchResult {
         /// Context for result, if requested.
@@ -456,6 +466,7 @@ pub mod v3 {
 
     /// A user profile.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct UserProfile {
         /// The user's avatar URL, if set.
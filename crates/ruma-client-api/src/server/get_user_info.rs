@@ -62,6 +62,7 @@ pub mod v3 {
 
     /// Information about a user's device.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct DeviceInfo {
         /// A list of user sessions on this device.
@@ -78,6 +79,7 @@ pub mod v3 {
 
     /// Information about a user session.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct SessionInfo {
         /// A list of connections in this session.
@@ -94,6 +96,7 @@ pub mod v3 {
 
     /// Information about a connection in a user session.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ConnectionInfo {
         /// Most recently seen IP address of the session.
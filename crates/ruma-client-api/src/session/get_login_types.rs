@@ -59,6 +59,7 @@ pub mod v3 {
 
     /// An authentication mechanism.
     #[derive(Clone, Debug, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(untagged)]
     pub enum LoginType {
@@ -136,6 +137,7 @@ pub mod v3 {
 
     /// The payload for password login.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.password")]
     pub struct PasswordLoginType {}
@@ -149,6 +151,7 @@ pub mod v3 {
 
     /// The payload for token-based login.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.token")]
     pub struct TokenLoginType {
@@ -166,6 +169,7 @@ pub mod v3 {
 
     /// The payload for SSO login.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.sso")]
     pub struct SsoLoginType {
@@ -184,6 +188,7 @@ pub mod v3 {
     /// An SSO login identity provider.
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct IdentityProvider {
         /// The ID of the provider.
         pub id: String,
@@ -250,6 +255,7 @@ pub mod v3 {
 
     /// The payload for Application Service login.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.application_service")]
     pub struct ApplicationServiceLoginType {}
@@ -264,6 +270,7 @@ pub mod v3 {
     /// A custom login payload.
     #[doc(hidden)]
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[allow(clippy::exhaustive_structs)]
     pub struct CustomLoginType {
         /// A custom type
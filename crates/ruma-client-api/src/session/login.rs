@@ -141,6 +141,7 @@ pub mod v3 {
 
     /// The authentication mechanism.
     #[derive(Clone, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(untagged)]
     pub enum LoginInfo {
@@ -222,6 +223,7 @@ pub mod v3 {
 
     /// An identifier and password to supply as authentication.
     #[derive(Clone, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.password")]
     pub struct Password {
@@ -280,6 +282,7 @@ pub mod v3 {
 
     /// A token to supply as authentication.
     #[derive(Clone, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.token")]
     pub struct Token {
@@ -303,6 +306,7 @@ pub mod v3 {
 
     /// An identifier to supply for Application Service authentication.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     #[serde(tag = "type", rename = "m.login.application_service")]
     pub struct ApplicationService {
@@ -328,6 +332,7 @@ pub mod v3 {
 
     #[doc(hidden)]
     #[derive(Clone, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[non_exhaustive]
     pub struct CustomLoginInfo {
         #[serde(rename = "type")]
@@ -347,6 +352,7 @@ pub mod v3 {
 
     /// Client configuration provided by the server.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct DiscoveryInfo {
         /// Information about the homeserver to connect to.
@@ -367,6 +373,7 @@ pub mod v3 {
 
     /// Information about the homeserver to connect to.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct HomeserverInfo {
         /// The base URL for the homeserver for client-server connections.
@@ -382,6 +389,7 @@ pub mod v3 {
 
     /// Information about the identity server to connect to.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct IdentityServerInfo {
         /// The base URL for the identity server for client-server connections.
@@ -91,3 +91,14 @@ impl ruma_common::api::IncomingResponse for Response {
         Ok(Self { body })
     }
 }
+
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::ResponseSchema for Response {
+    const STATUS: http::StatusCode = http::StatusCode::OK;
+
+    fn body_schema(
+        _generator: &mut ruma_common::json_schema::SchemaGenerator,
+    ) -> Option<ruma_common::json_schema::BodySchema> {
+        Some(ruma_common::json_schema::BodySchema::Binary)
+    }
+}
@@ -19,6 +19,7 @@ pub mod get_hierarchy;
 /// To create an instance of this type, first create a `SpaceHierarchyRoomsChunkInit` and convert it
 /// via `SpaceHierarchyRoomsChunk::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SpaceHierarchyRoomsChunk {
     /// The canonical alias of the room, if any.
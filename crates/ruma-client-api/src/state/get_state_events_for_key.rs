@@ -142,4 +142,49 @@ pub mod v3 {
             Ok(Self { room_id, event_type, state_key })
         }
     }
+
+    #[cfg(feature = "json-schema")]
+    impl ruma_common::json_schema::RequestSchema for Request {
+        fn path_parameters_schemas(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Vec<(&'static str, serde_json::Value)> {
+            use ruma_common::json_schema::private::with_description;
+
+            vec![
+                (
+                    "room_id",
+                    with_description(
+                        generator.subschema_for::<OwnedRoomId>(),
+                        "The room to look up the state for.",
+                    ),
+                ),
+                (
+                    "event_type",
+                    with_description(
+                        generator.subschema_for::<StateEventType>(),
+                        "The type of state to look up.",
+                    ),
+                ),
+                (
+                    "state_key",
+                    with_description(
+                        generator.subschema_for::<String>(),
+                        "The key of the state to look up.",
+                    ),
+                ),
+            ]
+        }
+
+        fn query_schema(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<serde_json::Value> {
+            None
+        }
+
+        fn body_schema(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<ruma_common::json_schema::BodySchema> {
+            None
+        }
+    }
 }
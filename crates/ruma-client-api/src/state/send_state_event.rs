@@ -194,10 +194,60 @@ pub mod v3 {
         }
     }
 
+    #[cfg(feature = "json-schema")]
+    impl ruma_common::json_schema::RequestSchema for Request {
+        fn path_parameters_schemas(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Vec<(&'static str, serde_json::Value)> {
+            use ruma_common::json_schema::private::with_description;
+
+            vec![
+                (
+                    "room_id",
+                    with_description(
+                        generator.subschema_for::<OwnedRoomId>(),
+                        "The room to set the state in.",
+                    ),
+                ),
+                (
+                    "event_type",
+                    with_description(
+                        generator.subschema_for::<StateEventType>(),
+                        "The type of event to send.",
+                    ),
+                ),
+                (
+                    "state_key",
+                    with_description(
+                        generator.subschema_for::<String>(),
+                        "The state_key for the state to send.",
+                    ),
+                ),
+            ]
+        }
+
+        fn query_schema(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<serde_json::Value> {
+            use ruma_common::json_schema::JsonSchema;
+
+            Some(RequestQuery::json_schema(generator))
+        }
+
+        fn body_schema(
+            generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<ruma_common::json_schema::BodySchema> {
+            Some(ruma_common::json_schema::BodySchema::Json(
+                generator.subschema_for::<AnyStateEventContent>(),
+            ))
+        }
+    }
+
     /// Data in the request's query string.
     #[derive(Debug)]
     #[cfg_attr(feature = "client", derive(serde::Serialize))]
     #[cfg_attr(feature = "server", derive(serde::Deserialize))]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     struct RequestQuery {
         /// Timestamp to use for the `origin_server_ts` of the event.
         #[serde(rename = "ts", skip_serializing_if = "Option::is_none")]
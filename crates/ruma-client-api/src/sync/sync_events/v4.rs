@@ -179,6 +179,7 @@ impl Response {
 /// Filters are considered _sticky_, meaning that the filter only has to be provided once and their
 /// parameters 'sticks' for future requests until a new filter overwrites them.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SyncRequestListFilters {
     /// Whether to return DMs, non-DM rooms or both.
@@ -269,6 +270,7 @@ pub struct SyncRequestListFilters {
 
 /// Sliding Sync Request for each list.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SyncRequestList {
     /// Put this list into the all-rooms-mode.
@@ -325,6 +327,7 @@ pub struct SyncRequestList {
 
 /// Configuration for requesting room details.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomDetailsConfig {
     /// Required state for each room returned. An array of event type and state key tuples.
@@ -341,6 +344,7 @@ pub struct RoomDetailsConfig {
 
 /// Configuration for old rooms to include
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct IncludeOldRooms {
     /// Required state for each room returned. An array of event type and state key tuples.
@@ -357,6 +361,7 @@ pub struct IncludeOldRooms {
 
 /// Configuration for room subscription
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomSubscription {
     /// Required state for each room returned. An array of event type and state key tuples.
@@ -377,6 +382,7 @@ pub struct RoomSubscription {
 
 /// Operation applied to the specific SlidingSyncList
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(rename_all = "UPPERCASE")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum SlidingOp {
@@ -393,6 +399,7 @@ pub enum SlidingOp {
 
 /// Updates to joined rooms.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SyncList {
     /// The sync operation to apply, if any.
@@ -405,6 +412,7 @@ pub struct SyncList {
 
 /// Updates to joined rooms.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SyncOp {
     /// The sync operation to apply.
@@ -426,6 +434,7 @@ pub struct SyncOp {
 
 /// Updates to joined rooms.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SlidingSyncRoom {
     /// The name of the room as calculated by the server.
@@ -505,6 +514,7 @@ impl SlidingSyncRoom {
 
 /// A sliding sync room hero.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SlidingSyncRoomHero {
     /// The user ID of the hero.
@@ -522,6 +532,7 @@ pub struct SlidingSyncRoomHero {
 
 /// Sliding-Sync extension configuration.
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ExtensionsConfig {
     /// Request to devices messages with the given config.
@@ -563,6 +574,7 @@ impl ExtensionsConfig {
 
 /// Extensions specific response data.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Extensions {
     /// To-device extension in response.
@@ -603,6 +615,7 @@ impl Extensions {
 ///
 /// According to [MSC3885](https://github.com/matrix-org/matrix-spec-proposals/pull/3885).
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ToDeviceConfig {
     /// Activate or deactivate this extension. Sticky.
@@ -647,6 +660,7 @@ impl ToDeviceConfig {
 ///
 /// According to [MSC3885](https://github.com/matrix-org/matrix-spec-proposals/pull/3885).
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ToDevice {
     /// Fetch the next batch from this entry.
@@ -661,6 +675,7 @@ pub struct ToDevice {
 ///
 /// According to [MSC3884](https://github.com/matrix-org/matrix-spec-proposals/pull/3884).
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct E2EEConfig {
     /// Activate or deactivate this extension. Sticky.
@@ -679,6 +694,7 @@ impl E2EEConfig {
 ///
 /// According to [MSC3884](https://github.com/matrix-org/matrix-spec-proposals/pull/3884).
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct E2EE {
     /// Information on E2EE device updates.
@@ -715,6 +731,7 @@ impl E2EE {
 /// Not yet part of the spec proposal. Taken from the reference implementation
 /// <https://github.com/matrix-org/sliding-sync/blob/main/sync3/extensions/account_data.go>
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AccountDataConfig {
     /// Activate or deactivate this extension. Sticky.
@@ -756,6 +773,7 @@ impl AccountDataConfig {
 /// Not yet part of the spec proposal. Taken from the reference implementation
 /// <https://github.com/matrix-org/sliding-sync/blob/main/sync3/extensions/account_data.go>
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AccountData {
     /// The global private data created by this user.
@@ -810,10 +828,25 @@ impl<'de> Deserialize<'de> for RoomReceiptConfig {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for RoomReceiptConfig {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("RoomReceiptConfig")
+    }
+
+    fn json_schema(generator: &mut ruma_common::json_schema::SchemaGenerator) -> serde_json::Value {
+        use ruma_common::json_schema::private::{any_of, constant};
+
+        // `*` means all the subscribed rooms.
+        any_of(vec![constant("*"), generator.subschema_for::<OwnedRoomId>()])
+    }
+}
+
 /// Receipt extension configuration.
 ///
 /// According to [MSC3960](https://github.com/matrix-org/matrix-spec-proposals/pull/3960)
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ReceiptsConfig {
     /// Activate or deactivate this extension. Sticky.
@@ -850,6 +883,7 @@ impl ReceiptsConfig {
 ///
 /// According to [MSC3960](https://github.com/matrix-org/matrix-spec-proposals/pull/3960)
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Receipts {
     /// The ephemeral receipt room event for each room
@@ -869,6 +903,7 @@ impl Receipts {
 /// Not yet part of the spec proposal. Taken from the reference implementation
 /// <https://github.com/matrix-org/sliding-sync/blob/main/sync3/extensions/typing.go>
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct TypingConfig {
     /// Activate or deactivate this extension. Sticky.
@@ -906,6 +941,7 @@ impl TypingConfig {
 /// Not yet part of the spec proposal. Taken from the reference implementation
 /// <https://github.com/matrix-org/sliding-sync/blob/main/sync3/extensions/typing.go>
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Typing {
     /// The ephemeral typing event for each room
@@ -7,16 +7,16 @@ pub mod v3 {
     //!
     //! [spec]: https://spec.matrix.org/latest/client-server-api/#put_matrixclientv3sendtodeviceeventtypetxnid
 
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, BTreeSet};
 
     use ruma_common::{
         api::{request, response, Metadata},
         metadata,
         serde::Raw,
         to_device::DeviceIdOrAllDevices,
-        OwnedTransactionId, OwnedUserId,
+        OwnedTransactionId, OwnedUserId, TransactionId, UserId,
     };
-    use ruma_events::{AnyToDeviceEventContent, ToDeviceEventType};
+    use ruma_events::{AnyToDeviceEventContent, ToDeviceEventContent, ToDeviceEventType};
 
     const METADATA: Metadata = metadata! {
         method: PUT,
@@ -82,4 +82,366 @@ pub mod v3 {
     /// Represented as a map of `{ user-ids => { device-ids => message-content } }`.
     pub type Messages =
         BTreeMap<OwnedUserId, BTreeMap<DeviceIdOrAllDevices, Raw<AnyToDeviceEventContent>>>;
+
+    /// A builder that splits the messages of a to-device fan-out into several requests.
+    ///
+    /// Servers usually limit the size of request bodies, so sending a message to a lot of devices
+    /// in a single request can fail. This accumulates messages per recipient and splits them into
+    /// requests that stay under a maximum size and a maximum number of recipients.
+    ///
+    /// Every request gets its own transaction ID, derived from the one given to
+    /// [`RequestBatcher::new()`]. When a request was sent successfully, it should be marked with
+    /// [`RequestBatcher::mark_sent()`]. Calling [`RequestBatcher::requests()`] again then only
+    /// returns the requests that still need to be sent, with the same transaction IDs, so they can
+    /// be retried safely.
+    #[derive(Clone, Debug)]
+    pub struct RequestBatcher {
+        event_type: ToDeviceEventType,
+        txn_id: OwnedTransactionId,
+        max_size: usize,
+        max_recipients: usize,
+        pending: Messages,
+        batches: Vec<Batch>,
+        sent: BTreeSet<(OwnedUserId, DeviceIdOrAllDevices)>,
+    }
+
+    /// A batch of messages, sent in a single request.
+    #[derive(Clone, Debug)]
+    struct Batch {
+        txn_id: OwnedTransactionId,
+        messages: Messages,
+        sent: bool,
+    }
+
+    impl RequestBatcher {
+        /// The default maximum size of the body of a request, in bytes.
+        pub const DEFAULT_MAX_SIZE: usize = 256 * 1024;
+
+        /// The default maximum number of recipients of a request.
+        pub const DEFAULT_MAX_RECIPIENTS: usize = 250;
+
+        /// Creates a new `RequestBatcher` for messages of the given event type.
+        ///
+        /// The transaction IDs of the requests are derived from the given transaction ID, which
+        /// must be unique like for a single request.
+        pub fn new(event_type: ToDeviceEventType, txn_id: OwnedTransactionId) -> Self {
+            Self {
+                event_type,
+                txn_id,
+                max_size: Self::DEFAULT_MAX_SIZE,
+                max_recipients: Self::DEFAULT_MAX_RECIPIENTS,
+                pending: Messages::new(),
+                batches: Vec::new(),
+                sent: BTreeSet::new(),
+            }
+        }
+
+        /// Set the maximum size of the body of a request, in bytes.
+        ///
+        /// A message that is bigger than this size on its own is sent in its own request.
+        pub fn max_size(mut self, max_size: usize) -> Self {
+            self.max_size = max_size;
+            self
+        }
+
+        /// Set the maximum number of recipients of a request.
+        ///
+        /// A recipient is a pair of user and device, or all the devices of a user.
+        pub fn max_recipients(mut self, max_recipients: usize) -> Self {
+            self.max_recipients = max_recipients.max(1);
+            self
+        }
+
+        /// Add a message for the given recipient.
+        ///
+        /// If there was already a message for the same recipient that is not in a request yet,
+        /// it is replaced.
+        ///
+        /// Returns an error if the event type of the content is not the one of this batcher, or
+        /// if the content fails to serialize.
+        pub fn add_message<C: ToDeviceEventContent>(
+            &mut self,
+            user_id: OwnedUserId,
+            device: DeviceIdOrAllDevices,
+            content: &C,
+        ) -> Result<&mut Self, RequestBatcherError> {
+            let event_type = content.event_type();
+            if event_type != self.event_type {
+                return Err(RequestBatcherError::EventTypeMismatch {
+                    expected: self.event_type.clone(),
+                    found: event_type,
+                });
+            }
+
+            Ok(self.add_raw_message(user_id, device, Raw::new(content)?.cast()))
+        }
+
+        /// Add a raw message for the given recipient.
+        ///
+        /// If there was already a message for the same recipient that is not in a request yet,
+        /// it is replaced.
+        ///
+        /// The event type of the content is not checked.
+        pub fn add_raw_message(
+            &mut self,
+            user_id: OwnedUserId,
+            device: DeviceIdOrAllDevices,
+            content: Raw<AnyToDeviceEventContent>,
+        ) -> &mut Self {
+            self.pending.entry(user_id).or_default().insert(device, content);
+            self
+        }
+
+        /// Get the requests that still need to be sent.
+        ///
+        /// The messages added since the last call are split into new requests. The requests
+        /// returned by a previous call that were not marked as sent are returned again, with the
+        /// same transaction IDs.
+        pub fn requests(&mut self) -> Vec<Request> {
+            self.split_pending();
+
+            self.batches
+                .iter()
+                .filter(|batch| !batch.sent)
+                .map(|batch| {
+                    Request::new_raw(
+                        self.event_type.clone(),
+                        batch.txn_id.clone(),
+                        batch.messages.clone(),
+                    )
+                })
+                .collect()
+        }
+
+        /// Mark the request with the given transaction ID as sent.
+        ///
+        /// Returns `false` if no request has this transaction ID.
+        pub fn mark_sent(&mut self, txn_id: &TransactionId) -> bool {
+            let Some(batch) = self.batches.iter_mut().find(|batch| batch.txn_id == txn_id) else {
+                return false;
+            };
+
+            batch.sent = true;
+            for (user_id, devices) in &batch.messages {
+                for device in devices.keys() {
+                    self.sent.insert((user_id.clone(), device.clone()));
+                }
+            }
+
+            true
+        }
+
+        /// Whether a message was already sent to the given recipient.
+        pub fn is_sent(&self, user_id: &UserId, device: &DeviceIdOrAllDevices) -> bool {
+            self.sent.contains(&(user_id.to_owned(), device.clone()))
+        }
+
+        /// The recipients that a message was already sent to.
+        pub fn sent_recipients(&self) -> impl Iterator<Item = (&UserId, &DeviceIdOrAllDevices)> {
+            self.sent.iter().map(|(user_id, device)| (&**user_id, device))
+        }
+
+        /// Whether all the messages were sent.
+        pub fn is_done(&self) -> bool {
+            self.pending.is_empty() && self.batches.iter().all(|batch| batch.sent)
+        }
+
+        /// Split the pending messages into new batches.
+        fn split_pending(&mut self) {
+            // The size of `{"messages":{}}`.
+            const BASE_SIZE: usize = 15;
+
+            let mut messages = Messages::new();
+            let mut size = BASE_SIZE;
+            let mut recipients = 0;
+
+            for (user_id, devices) in std::mem::take(&mut self.pending) {
+                for (device, content) in devices {
+                    let message_size = message_size(&user_id, &device, &content);
+
+                    if recipients > 0
+                        && (size + message_size > self.max_size
+                            || recipients >= self.max_recipients)
+                    {
+                        self.push_batch(std::mem::take(&mut messages));
+                        size = BASE_SIZE;
+                        recipients = 0;
+                    }
+
+                    messages.entry(user_id.clone()).or_default().insert(device, content);
+                    size += message_size;
+                    recipients += 1;
+                }
+            }
+
+            if recipients > 0 {
+                self.push_batch(messages);
+            }
+        }
+
+        fn push_batch(&mut self, messages: Messages) {
+            let txn_id = format!("{}-{}", self.txn_id, self.batches.len()).into();
+            self.batches.push(Batch { txn_id, messages, sent: false });
+        }
+    }
+
+    /// The size taken by a message in the body of a request, in bytes.
+    ///
+    /// This is an upper bound, that assumes that every message has its own user entry.
+    fn message_size(
+        user_id: &UserId,
+        device: &DeviceIdOrAllDevices,
+        content: &Raw<AnyToDeviceEventContent>,
+    ) -> usize {
+        let json_string_len =
+            |s: &str| serde_json::to_string(s).expect("string serialization should succeed").len();
+
+        // `"user_id":{"device":content},`
+        json_string_len(user_id.as_str())
+            + json_string_len(&device.to_string())
+            + content.json().get().len()
+            + 5
+    }
+
+    /// An error encountered when adding a message to a [`RequestBatcher`].
+    #[derive(Debug, thiserror::Error)]
+    #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+    pub enum RequestBatcherError {
+        /// The event type of the content doesn't match the event type of the batcher.
+        #[error("expected content of type `{expected}`, found `{found}`")]
+        EventTypeMismatch {
+            /// The event type of the batcher.
+            expected: ToDeviceEventType,
+
+            /// The event type of the content.
+            found: ToDeviceEventType,
+        },
+
+        /// The content failed to serialize.
+        #[error("failed to serialize content: {0}")]
+        Serialization(#[from] serde_json::Error),
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use assert_matches2::assert_matches;
+        use ruma_common::{
+            owned_device_id, owned_user_id, to_device::DeviceIdOrAllDevices, user_id,
+        };
+        use ruma_events::{dummy::ToDeviceDummyEventContent, ToDeviceEventType};
+
+        use super::{RequestBatcher, RequestBatcherError};
+
+        fn recipients(batcher: &mut RequestBatcher) -> Vec<usize> {
+            batcher
+                .requests()
+                .iter()
+                .map(|request| request.messages.values().map(|devices| devices.len()).sum())
+                .collect()
+        }
+
+        #[test]
+        fn split_by_recipients() {
+            let mut batcher =
+                RequestBatcher::new(ToDeviceEventType::Dummy, "txn".into()).max_recipients(2);
+
+            for i in 0..5 {
+                batcher
+                    .add_message(
+                        owned_user_id!("@alice:example.org"),
+                        DeviceIdOrAllDevices::DeviceId(format!("DEVICE{i}").into()),
+                        &ToDeviceDummyEventContent::new(),
+                    )
+                    .unwrap();
+            }
+
+            let requests = batcher.requests();
+            assert_eq!(requests.len(), 3);
+            assert_eq!(requests[0].txn_id, "txn-0");
+            assert_eq!(requests[1].txn_id, "txn-1");
+            assert_eq!(requests[2].txn_id, "txn-2");
+            assert_eq!(recipients(&mut batcher), [2, 2, 1]);
+        }
+
+        #[test]
+        fn split_by_size() {
+            let mut batcher =
+                RequestBatcher::new(ToDeviceEventType::Dummy, "txn".into()).max_size(100);
+
+            for user_id in ["@alice:example.org", "@bob:example.org", "@carl:example.org"] {
+                batcher
+                    .add_message(
+                        user_id.try_into().unwrap(),
+                        DeviceIdOrAllDevices::AllDevices,
+                        &ToDeviceDummyEventContent::new(),
+                    )
+                    .unwrap();
+            }
+
+            // Each message takes `"@alice:example.org":{"*":{}},` = 30 bytes or less, plus 15
+            // bytes for the body.
+            let requests = batcher.requests();
+            assert_eq!(requests.len(), 2);
+            for request in &requests {
+                let body = serde_json::to_string(&serde_json::json!({
+                    "messages": request.messages,
+                }))
+                .unwrap();
+                assert!(body.len() <= 100, "{body}");
+            }
+        }
+
+        #[test]
+        fn retry_unsent_requests() {
+            let mut batcher =
+                RequestBatcher::new(ToDeviceEventType::Dummy, "txn".into()).max_recipients(1);
+
+            let alice = owned_user_id!("@alice:example.org");
+            let device = DeviceIdOrAllDevices::DeviceId(owned_device_id!("ALICEDEVICE"));
+            let content = ToDeviceDummyEventContent::new();
+            batcher.add_message(alice.clone(), device.clone(), &content).unwrap();
+            batcher
+                .add_message(owned_user_id!("@bob:example.org"), device.clone(), &content)
+                .unwrap();
+
+            let requests = batcher.requests();
+            assert_eq!(requests.len(), 2);
+            assert!(batcher.mark_sent(&requests[0].txn_id));
+            assert!(!batcher.mark_sent("unknown".into()));
+            assert!(batcher.is_sent(&alice, &device));
+            assert!(!batcher.is_sent(user_id!("@bob:example.org"), &device));
+            assert!(!batcher.is_done());
+
+            // Only the unsent request is returned again, with the same transaction ID.
+            let retry = batcher.requests();
+            assert_eq!(retry.len(), 1);
+            assert_eq!(retry[0].txn_id, requests[1].txn_id);
+
+            // New messages get new transaction IDs.
+            batcher.add_message(owned_user_id!("@carl:example.org"), device, &content).unwrap();
+            let requests = batcher.requests();
+            assert_eq!(requests.len(), 2);
+            assert_eq!(requests[1].txn_id, "txn-2");
+
+            for request in requests {
+                batcher.mark_sent(&request.txn_id);
+            }
+            assert!(batcher.is_done());
+            assert_eq!(batcher.sent_recipients().count(), 3);
+        }
+
+        #[test]
+        fn event_type_mismatch() {
+            let mut batcher = RequestBatcher::new(ToDeviceEventType::RoomKeyRequest, "txn".into());
+
+            let result = batcher.add_message(
+                owned_user_id!("@alice:example.org"),
+                DeviceIdOrAllDevices::AllDevices,
+                &ToDeviceDummyEventContent::new(),
+            );
+            assert_matches!(result, Err(RequestBatcherError::EventTypeMismatch { .. }));
+            assert!(batcher.requests().is_empty());
+            assert!(batcher.is_done());
+        }
+    }
 }
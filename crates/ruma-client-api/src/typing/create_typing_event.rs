@@ -62,6 +62,7 @@ pub mod v3 {
 
     /// A mark for whether the user is typing within a length of time or not.
     #[derive(Clone, Copy, Debug, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[serde(into = "TypingInner")]
     #[allow(clippy::exhaustive_enums)]
     pub enum Typing {
@@ -73,6 +74,7 @@ pub mod v3 {
     }
 
     #[derive(Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     struct TypingInner {
         typing: bool,
 
@@ -29,6 +29,7 @@ mod user_serde;
 
 /// Information for one authentication stage.
 #[derive(Clone, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[non_exhaustive]
 #[serde(untagged)]
 pub enum AuthData {
@@ -262,6 +263,7 @@ pub enum AuthType {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#password-based
 #[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.login.password")]
 pub struct Password {
@@ -298,6 +300,7 @@ impl fmt::Debug for Password {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#google-recaptcha
 #[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.login.recaptcha")]
 pub struct ReCaptcha {
@@ -328,6 +331,7 @@ impl fmt::Debug for ReCaptcha {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#email-based-identity--homeserver
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.login.email.identity")]
 pub struct EmailIdentity {
@@ -345,6 +349,7 @@ pub struct EmailIdentity {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#phone-numbermsisdn-based-identity--homeserver
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.login.msisdn")]
 pub struct Msisdn {
@@ -362,6 +367,7 @@ pub struct Msisdn {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#dummy-auth
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.login.dummy")]
 pub struct Dummy {
@@ -382,6 +388,7 @@ impl Dummy {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#token-authenticated-registration
 #[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.login.registration_token")]
 pub struct RegistrationToken {
@@ -412,6 +419,7 @@ impl fmt::Debug for RegistrationToken {
 ///
 /// [the spec]: https://spec.matrix.org/latest/client-server-api/#fallback
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct FallbackAcknowledgement {
     /// The value of the session key given by the homeserver.
@@ -427,6 +435,7 @@ impl FallbackAcknowledgement {
 
 #[doc(hidden)]
 #[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[non_exhaustive]
 pub struct CustomAuthData {
     #[serde(rename = "type")]
@@ -540,6 +549,7 @@ pub struct IncomingCustomThirdPartyId {
 
 /// Credentials for third-party authentication (e.g. email / phone number).
 #[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ThirdpartyIdCredentials {
     /// Identity server session ID.
@@ -98,6 +98,24 @@ pub mod v3 {
         }
     }
 
+    #[cfg(feature = "json-schema")]
+    impl ruma_common::json_schema::ResponseSchema for Response {
+        const STATUS: http::StatusCode = http::StatusCode::OK;
+
+        fn body_schema(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Option<ruma_common::json_schema::BodySchema> {
+            Some(ruma_common::json_schema::BodySchema::Binary)
+        }
+
+        fn other_responses(
+            _generator: &mut ruma_common::json_schema::SchemaGenerator,
+        ) -> Vec<(http::StatusCode, Option<ruma_common::json_schema::BodySchema>)> {
+            // The redirect doesn't have a body.
+            vec![(http::StatusCode::FOUND, None)]
+        }
+    }
+
     #[cfg(feature = "client")]
     impl ruma_common::api::IncomingResponse for Response {
         type EndpointError = crate::Error;
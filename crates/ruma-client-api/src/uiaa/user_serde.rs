@@ -102,6 +102,47 @@ impl<'de> Deserialize<'de> for UserIdentifier {
     }
 }
 
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::{
+        json_schema::{
+            private::{one_of, with_tag, ObjectSchema},
+            JsonSchema, SchemaGenerator,
+        },
+        thirdparty::Medium,
+    };
+    use serde_json::Value as JsonValue;
+
+    use super::UserIdentifier;
+
+    impl JsonSchema for UserIdentifier {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("UserIdentifier")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            let mut user = ObjectSchema::new();
+            user.property("user", generator.subschema_for::<String>(), true);
+
+            let mut third_party = ObjectSchema::new();
+            third_party.property("medium", generator.subschema_for::<Medium>(), true);
+            third_party.property("address", generator.subschema_for::<String>(), true);
+
+            let mut phone = ObjectSchema::new();
+            phone.property("country", generator.subschema_for::<String>(), true);
+            phone.property("phone", generator.subschema_for::<String>(), true);
+
+            one_of(vec![
+                with_tag(user.finish(), "type", "m.id.user"),
+                with_tag(third_party.finish(), "type", "m.id.thirdparty"),
+                with_tag(phone.finish(), "type", "m.id.phone"),
+            ])
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches2::assert_matches;
@@ -80,6 +80,7 @@ pub mod v3 {
 
     /// User data as result of a search.
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct User {
         /// The user's matrix user ID.
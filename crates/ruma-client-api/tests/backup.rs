@@ -0,0 +1,172 @@
+#![cfg(feature = "backup-encryption")]
+
+use assert_matches2::assert_matches;
+use ruma_client_api::backup::{
+    verify_backup_auth_data, BackedUpSessionData, BackupAlgorithm, BackupDecryptionError,
+    BackupDecryptionKey, BackupPublicKey, EncryptedSessionData, SignatureState,
+};
+use ruma_common::{
+    device_id,
+    encryption::{CrossSigningKey, DeviceKeys},
+    serde::Raw,
+    user_id, EventEncryptionAlgorithm,
+};
+use serde_json::{from_value as from_json_value, json};
+
+const PRIVATE_KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8";
+const PUBLIC_KEY: &str = "j0DFrbaPJWJK5bIU6nZ6bslNgp09e14a0bpvPiE4KF8";
+const EPHEMERAL_KEY: &str = "NYBy1jZYgNGu6jKa35EhODhR7SGijjt16WXQ0s0WYlQ";
+const CIPHERTEXT: &str = "NyTZtbywcyOWvYzbO/xK9XxSq9r43M/5t6zFfrxZL2HFZCFIMlRJ9MGBPA0hupvNJiVJ5Hj9PYp5mB14KFI9UVP3VxRGT9xqCBQ6LlM+K9+khm9EzJ9tYAKKwHmnor5Ljjf/fig61LcIdyJ1HUxqV/FuAVwPhTYjUkhgz+jnS8qvwb/H7lDn6JSs2Tlu5R5LDd+bJ8XJyn7yVrZz/M39XILHp9+VVTOPok91V2bTxP5Ty0y73cN+QOmlFqh7jhgRg5ChhD+FPO1UpZaAG8ALaqBne3Ylu+5WueDRRIRASCKZd+Fh/nEVWIVxWnly5Xqo1+8azEyi+FJC7b72oUmwtDjbuFR/UzehFEw7sRXIqhU";
+
+const DEVICE_KEY: &str = "JUO5L/EJVRFHatyDadtt3JM2ZaEZeN2hQE7hBmypVZ0";
+const DEVICE_SIGNATURE: &str =
+    "uE5K+ggpNKkXjXIFTOqkrelPG2oyFeOEnlJMhhueZf8v5WVSxQrVdG3mE4pWgTKDiTShDMN/3Cjc1ZuR8YdICA";
+const MASTER_KEY: &str = "F0VTtFbd38aQjsqxwQH+arIeK6oGF3lbfUOmNIKZP9U";
+const MASTER_SIGNATURE: &str =
+    "gta1oRwrF2NrUt9f0I9Dq28OM8Unz77G+R3YKiRIpaPWYWkhnFrneidfSobIIsqaVF62KHNc5sDFCyzSmVdgDA";
+
+fn session_data(mac: &str) -> EncryptedSessionData {
+    from_json_value(json!({
+        "ephemeral": EPHEMERAL_KEY,
+        "ciphertext": CIPHERTEXT,
+        "mac": mac,
+    }))
+    .unwrap()
+}
+
+fn device_keys(device_id: &str, key: &str) -> DeviceKeys {
+    from_json_value(json!({
+        "user_id": "@alice:example.org",
+        "device_id": device_id,
+        "algorithms": ["m.olm.v1.curve25519-aes-sha2", "m.megolm.v1.aes-sha2"],
+        "keys": {
+            format!("ed25519:{device_id}"): key,
+        },
+        "signatures": {},
+    }))
+    .unwrap()
+}
+
+fn master_key() -> CrossSigningKey {
+    from_json_value(json!({
+        "user_id": "@alice:example.org",
+        "usage": ["master"],
+        "keys": {
+            format!("ed25519:{MASTER_KEY}"): MASTER_KEY,
+        },
+        "signatures": {},
+    }))
+    .unwrap()
+}
+
+#[test]
+fn key_encoding() {
+    let key = BackupDecryptionKey::from_base64(PRIVATE_KEY).unwrap();
+    assert_eq!(key.to_base64().encode(), PRIVATE_KEY);
+    assert_eq!(key.public_key().to_base64().encode(), PUBLIC_KEY);
+
+    let algorithm: BackupAlgorithm = from_json_value(json!({
+        "algorithm": "m.megolm_backup.v1.curve25519-aes-sha2",
+        "auth_data": {
+            "public_key": PUBLIC_KEY,
+            "signatures": {},
+        },
+    }))
+    .unwrap();
+    assert_eq!(BackupPublicKey::from_algorithm(&algorithm).unwrap(), key.public_key());
+    assert!(key.matches(&algorithm));
+    assert!(!BackupDecryptionKey::generate().matches(&algorithm));
+
+    BackupDecryptionKey::from_base64("AAECAwQF").unwrap_err();
+    BackupDecryptionKey::from_base64("not base64!").unwrap_err();
+}
+
+#[test]
+fn decrypt_session_data() {
+    let key = BackupDecryptionKey::from_base64(PRIVATE_KEY).unwrap();
+
+    // MAC of the ciphertext, as specified.
+    let data = key.decrypt(&session_data("fNC/Qro0ppg")).unwrap();
+    assert_eq!(data.algorithm, EventEncryptionAlgorithm::MegolmV1AesSha2);
+    assert_eq!(data.sender_key, "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU");
+    assert_eq!(data.session_key, "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf");
+    assert!(data.forwarded_curve25519_key_chain.is_empty());
+
+    // MAC of an empty string, like libolm.
+    key.decrypt(&session_data("G7+Y+C958ko")).unwrap();
+
+    assert_matches!(
+        key.decrypt(&session_data("AAAAAAAAAAA")),
+        Err(BackupDecryptionError::MacMismatch)
+    );
+    // A truncated MAC is rejected.
+    assert_matches!(
+        key.decrypt(&session_data("fNC/Qro0")),
+        Err(BackupDecryptionError::MacMismatch)
+    );
+    assert_matches!(
+        BackupDecryptionKey::generate().decrypt(&session_data("fNC/Qro0ppg")),
+        Err(BackupDecryptionError::MacMismatch)
+    );
+}
+
+#[test]
+fn encrypt_roundtrip() {
+    let key = BackupDecryptionKey::generate();
+    let data: BackedUpSessionData = from_json_value(json!({
+        "algorithm": "m.megolm.v1.aes-sha2",
+        "forwarded_curve25519_key_chain": ["hPQNcabIABgGnx3/ACv/jmMmiQHoeFfuLB17tzWp6Hw"],
+        "sender_claimed_keys": {
+            "ed25519": "aj40p+aw64yPIdsxoog8Jhlu9i0xddiBd1Wy0R3Kz4U",
+        },
+        "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+        "session_key": "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf",
+    }))
+    .unwrap();
+
+    let encrypted = key.public_key().encrypt(&data);
+    let decrypted = key.decrypt(&encrypted).unwrap();
+    assert_eq!(decrypted.forwarded_curve25519_key_chain, data.forwarded_curve25519_key_chain);
+    assert_eq!(decrypted.sender_claimed_keys, data.sender_claimed_keys);
+    assert_eq!(decrypted.session_key, data.session_key);
+}
+
+#[test]
+fn verify_auth_data() {
+    let user_id = user_id!("@alice:example.org");
+    let algorithm: Raw<BackupAlgorithm> = from_json_value(json!({
+        "algorithm": "m.megolm_backup.v1.curve25519-aes-sha2",
+        "auth_data": {
+            "public_key": PUBLIC_KEY,
+            "extra": "unknown field",
+            "signatures": {
+                "@alice:example.org": {
+                    "ed25519:DEVICE": DEVICE_SIGNATURE,
+                    "ed25519:FORGED": DEVICE_SIGNATURE,
+                    format!("ed25519:{MASTER_KEY}"): MASTER_SIGNATURE,
+                },
+            },
+        },
+    }))
+    .unwrap();
+    let devices = [
+        device_keys("DEVICE", DEVICE_KEY),
+        device_keys("FORGED", MASTER_KEY),
+        device_keys("UNSIGNED", DEVICE_KEY),
+    ];
+
+    let verification =
+        verify_backup_auth_data(&algorithm, user_id, &devices, Some(&master_key())).unwrap();
+    assert!(verification.has_valid_signature());
+    assert_eq!(verification.master_key_signature, SignatureState::Valid);
+    assert_eq!(verification.device_signatures[device_id!("DEVICE")], SignatureState::Valid);
+    assert_eq!(verification.device_signatures[device_id!("FORGED")], SignatureState::Invalid);
+    assert_eq!(verification.device_signatures[device_id!("UNSIGNED")], SignatureState::Missing);
+
+    // Signatures of other users are ignored.
+    let verification =
+        verify_backup_auth_data(&algorithm, user_id!("@bob:example.org"), &devices, None).unwrap();
+    assert!(!verification.has_valid_signature());
+    assert!(verification.device_signatures.is_empty());
+    assert_eq!(verification.master_key_signature, SignatureState::Missing);
+}
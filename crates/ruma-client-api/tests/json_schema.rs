@@ -0,0 +1,105 @@
+#![cfg(all(feature = "json-schema", any(feature = "client", feature = "server")))]
+
+use assert_matches2::assert_matches;
+use ruma_client_api::{
+    media::create_content, membership::joined_members, message::get_message_events,
+    sync::sync_events,
+};
+use ruma_common::json_schema::{BodySchema, RequestSchema, ResponseSchema, SchemaGenerator};
+use serde_json::json;
+
+#[test]
+fn path_parameters() {
+    let mut generator = SchemaGenerator::new();
+
+    let path_parameters = joined_members::v3::Request::path_parameters_schemas(&mut generator);
+    assert_eq!(
+        path_parameters,
+        [(
+            "room_id",
+            json!({
+                "allOf": [{ "$ref": "#/$defs/RoomId" }],
+                "description": "The room to get the members of.",
+            })
+        )]
+    );
+    assert_eq!(generator.definitions()["RoomId"], json!({ "type": "string" }));
+
+    assert_eq!(joined_members::v3::Request::query_schema(&mut generator), None);
+    assert_matches!(joined_members::v3::Request::body_schema(&mut generator), None);
+}
+
+#[test]
+fn query_schema() {
+    let mut generator = SchemaGenerator::new();
+
+    let query = get_message_events::v3::Request::query_schema(&mut generator).unwrap();
+    let properties = query["properties"].as_object().unwrap();
+    assert_eq!(properties.keys().collect::<Vec<_>>(), ["dir", "filter", "from", "limit", "to"]);
+    assert_eq!(query["required"], json!(["dir"]));
+}
+
+#[test]
+fn body_schemas() {
+    let mut generator = SchemaGenerator::new();
+
+    assert_matches!(
+        create_content::v3::Request::body_schema(&mut generator),
+        Some(BodySchema::Binary)
+    );
+
+    assert_matches!(
+        joined_members::v3::Response::body_schema(&mut generator),
+        Some(BodySchema::Json(body))
+    );
+    assert_eq!(
+        body,
+        json!({
+            "type": "object",
+            "properties": {
+                "joined": {
+                    "description": "A list of the rooms the user is in, i.e.\nthe ID of each room in which the user has joined membership.",
+                    "type": "object",
+                    "additionalProperties": { "$ref": "#/$defs/RoomMember" },
+                },
+            },
+            "required": ["joined"],
+        })
+    );
+    assert_eq!(
+        generator.definitions()["RoomMember"]["properties"]["display_name"],
+        json!({
+            "anyOf": [{ "type": "string" }, { "type": "null" }],
+            "description": "The display name of the user.",
+        })
+    );
+}
+
+#[test]
+fn sync_response_schema() {
+    let mut generator = SchemaGenerator::new();
+
+    assert_matches!(
+        sync_events::v3::Response::body_schema(&mut generator),
+        Some(BodySchema::Json(body))
+    );
+    for (field, definition) in [
+        ("rooms", "Rooms"),
+        ("presence", "Presence"),
+        ("account_data", "GlobalAccountData"),
+        ("to_device", "ToDevice"),
+        ("device_lists", "DeviceLists"),
+    ] {
+        assert_eq!(body["properties"][field]["allOf"][0]["$ref"], format!("#/$defs/{definition}"));
+    }
+    assert_eq!(body["required"], json!(["next_batch"]));
+
+    let definitions = generator.definitions();
+    let rooms = definitions["Rooms"]["properties"].as_object().unwrap();
+    assert_eq!(rooms.keys().collect::<Vec<_>>(), ["invite", "join", "knock", "leave"]);
+    assert_eq!(
+        definitions["Timeline"]["properties"]["events"]["items"]["$ref"],
+        "#/$defs/AnySyncTimelineEvent"
+    );
+    assert!(!definitions.values().any(|def| def.as_object().is_some_and(|def| def.is_empty())));
+}
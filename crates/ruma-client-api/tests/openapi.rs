@@ -0,0 +1,116 @@
+#![cfg(all(feature = "client", feature = "json-schema"))]
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+use ruma_client_api::openapi::add_endpoints;
+use ruma_common::api::openapi::OpenApiDocument;
+use serde_json::json;
+
+#[test]
+fn client_server_document() {
+    let mut document = OpenApiDocument::new("Matrix Client-Server API", "1.0.0");
+    add_endpoints(&mut document);
+    let document = document.into_json();
+
+    assert_eq!(document["openapi"], "3.1.0");
+    assert_eq!(document["info"]["title"], "Matrix Client-Server API");
+
+    let joined_members = &document["paths"]["/_matrix/client/v3/rooms/{room_id}/joined_members"];
+    let operation = joined_members["get"].as_object().unwrap();
+    assert_eq!(operation["operationId"], "membership::joined_members::v3");
+    assert_eq!(
+        operation["security"],
+        json!([{ "accessTokenBearer": [] }, { "accessTokenQuery": [] }])
+    );
+    assert_eq!(
+        operation["parameters"],
+        json!([{
+            "in": "path",
+            "name": "room_id",
+            "required": true,
+            "description": "The room to get the members of.",
+            "schema": { "$ref": "#/components/schemas/RoomId" },
+        }])
+    );
+    assert!(!operation.contains_key("requestBody"));
+    assert!(!operation.contains_key("deprecated"));
+    assert_eq!(
+        operation["responses"]["200"]["content"]["application/json"]["schema"]["required"],
+        json!(["joined"])
+    );
+
+    let versions = &document["paths"]["/_matrix/client/versions"]["get"];
+    assert_eq!(versions["operationId"], "discovery::get_supported_versions");
+    assert_eq!(versions["security"][0], json!({}));
+
+    let availability = &document["paths"]["/_matrix/client/v3/register/available"]["get"];
+    assert!(availability.get("security").is_none());
+    assert_eq!(availability["parameters"][0]["in"], "query");
+
+    let upload = &document["paths"]["/_matrix/media/v3/upload"]["post"];
+    assert!(upload["requestBody"]["content"]["application/octet-stream"].is_object());
+    assert_eq!(upload["responses"]["429"]["description"], "This request was rate-limited.");
+
+    let fallback = &document["paths"]["/_matrix/client/v3/auth/{auth_type}/fallback/web"]["get"];
+    assert!(fallback["responses"]["200"]["content"]["application/octet-stream"].is_object());
+    assert_eq!(fallback["responses"]["302"], json!({ "description": "Found" }));
+
+    let state =
+        &document["paths"]["/_matrix/client/v3/rooms/{room_id}/state/{event_type}/{state_key}"];
+    assert_eq!(state["get"]["operationId"], "state::get_state_events_for_key::v3");
+    let send_state = &state["put"];
+    assert_eq!(send_state["operationId"], "state::send_state_event::v3");
+    assert_eq!(send_state["parameters"][3]["in"], "query");
+    assert_eq!(send_state["parameters"][3]["name"], "ts");
+    assert!(send_state["requestBody"]["content"]["application/json"].is_object());
+
+    let schemas = document["components"]["schemas"].as_object().unwrap();
+    assert_eq!(schemas["RoomId"], json!({ "type": "string" }));
+    assert!(schemas.contains_key("RoomMember"));
+}
+
+/// Check that `add_endpoints` lists all the endpoints of the crate, by comparing the paths of the
+/// modules where `METADATA` is declared with the ones of the requests in `src/openapi.rs`.
+#[test]
+fn all_endpoints_are_added() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+    let mut endpoints = BTreeSet::new();
+    collect_endpoints(&src, &src, &mut endpoints);
+
+    let openapi = fs::read_to_string(src.join("openapi.rs")).unwrap();
+    let added = openapi
+        .split("add_endpoint::<crate::")
+        .skip(1)
+        .map(|s| s.split_once("::Request>").unwrap().0.to_owned())
+        .collect::<BTreeSet<_>>();
+
+    assert_eq!(added, endpoints);
+}
+
+/// Collect the paths of the modules declaring a `METADATA` constant in the given directory.
+fn collect_endpoints(src: &Path, dir: &Path, endpoints: &mut BTreeSet<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_endpoints(src, &path, endpoints);
+            continue;
+        }
+
+        let module = path.strip_prefix(src).unwrap().with_extension("");
+        let module = module.iter().map(|s| s.to_str().unwrap()).collect::<Vec<_>>().join("::");
+
+        // The versions of an endpoint are in `pub mod` blocks at the root of the file.
+        let mut version = None;
+        for line in fs::read_to_string(&path).unwrap().lines() {
+            if let Some(name) = line.strip_prefix("pub mod ").and_then(|l| l.strip_suffix(" {")) {
+                version = Some(name);
+            } else if line.trim_start().starts_with("const METADATA: Metadata") {
+                endpoints.insert(match version {
+                    Some(version) => format!("{module}::{version}"),
+                    None => module.clone(),
+                });
+            }
+        }
+    }
+}
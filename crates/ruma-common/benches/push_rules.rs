@@ -0,0 +1,125 @@
+// `cargo bench` works, but if you use `cargo bench -- --save-baseline <name>`
+// or pass any other args to it, it fails with the error
+// `cargo bench unknown option --save-baseline`.
+// To pass args to criterion, use this form
+// `cargo bench --features criterion --bench <name of the bench> -- --save-baseline <name>`.
+
+use std::collections::BTreeMap;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use js_int::{int, uint};
+use ruma_common::{
+    owned_room_id,
+    power_levels::NotificationPowerLevels,
+    push::{
+        CompiledRuleset, FlattenedJson, PushConditionPowerLevelsCtx, PushConditionRoomCtx, Ruleset,
+    },
+    serde::Raw,
+    OwnedUserId, UserId,
+};
+use serde_json::{json, Value as JsonValue};
+
+const MEMBERS: usize = 1000;
+
+fn members() -> Vec<(Ruleset, PushConditionRoomCtx)> {
+    (0..MEMBERS)
+        .map(|i| {
+            let user_id: OwnedUserId = UserId::parse(format!("@user{i}:localhost")).unwrap();
+            let ruleset = Ruleset::server_default(&user_id);
+            let context = PushConditionRoomCtx {
+                room_id: owned_room_id!("!room:localhost"),
+                member_count: uint!(1000),
+                user_id,
+                user_display_name: format!("User {i}"),
+                power_levels: Some(PushConditionPowerLevelsCtx {
+                    users: BTreeMap::new(),
+                    users_default: int!(0),
+                    notifications: NotificationPowerLevels::new(),
+                }),
+                #[cfg(feature = "unstable-msc3931")]
+                supported_features: Default::default(),
+            };
+
+            (ruleset, context)
+        })
+        .collect()
+}
+
+fn message() -> Raw<JsonValue> {
+    Raw::new(&json!({
+        "content": {
+            "body": "Is anyone around? User 42 said they would answer the questions of user500",
+            "msgtype": "m.text"
+        },
+        "event_id": "$15139375514XsgmR:localhost",
+        "origin_server_ts": 1_513_937_555,
+        "room_id": "!room:localhost",
+        "sender": "@example:localhost",
+        "type": "m.room.message",
+    }))
+    .unwrap()
+}
+
+fn get_actions_ruleset(c: &mut Criterion) {
+    let members = members();
+    let event = message();
+
+    c.bench_function("get actions for all room members with `Ruleset`", |b| {
+        b.iter(|| {
+            for (ruleset, context) in &members {
+                let _ = ruleset.get_actions(&event, context);
+            }
+        });
+    });
+}
+
+fn get_match_batch_ruleset(c: &mut Criterion) {
+    let members = members();
+    let event = message();
+
+    c.bench_function("get match for all room members with `Ruleset::get_match_batch`", |b| {
+        b.iter(|| {
+            let _ = Ruleset::get_match_batch(
+                &event,
+                members.iter().map(|(ruleset, context)| (ruleset, context)),
+            );
+        });
+    });
+}
+
+fn get_match_batch_compiled(c: &mut Criterion) {
+    let members = members();
+    let compiled =
+        members.iter().map(|(ruleset, context)| (ruleset.compile(), context)).collect::<Vec<_>>();
+    let event = message();
+
+    c.bench_function("get match for all room members with `CompiledRuleset`", |b| {
+        b.iter(|| {
+            let event = FlattenedJson::from_raw(&event);
+            let _ = CompiledRuleset::get_match_batch(
+                &event,
+                compiled.iter().map(|(ruleset, context)| (ruleset, *context)),
+            );
+        });
+    });
+}
+
+fn compile_ruleset(c: &mut Criterion) {
+    let ruleset = Ruleset::server_default(&UserId::parse("@example:localhost").unwrap());
+
+    c.bench_function("compile server default `Ruleset`", |b| {
+        b.iter(|| {
+            let _ = ruleset.compile();
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    get_actions_ruleset,
+    get_match_batch_ruleset,
+    get_match_batch_compiled,
+    compile_ruleset
+);
+
+criterion_main!(benches);
@@ -308,6 +308,8 @@ pub use ruma_macros::response;
 
 pub mod error;
 mod metadata;
+#[cfg(feature = "json-schema")]
+pub mod openapi;
 
 pub use self::metadata::{MatrixVersion, Metadata, VersionHistory, VersioningDecision};
 
@@ -519,6 +521,7 @@ pub enum AuthScheme {
 
 /// The direction to return events from.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[allow(clippy::exhaustive_enums)]
 pub enum Direction {
     /// Return events backwards in time from the requested `from` token.
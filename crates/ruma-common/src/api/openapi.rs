@@ -0,0 +1,401 @@
+//! Generation of [OpenAPI] documents from the metadata of endpoints.
+//!
+//! An [`OpenApiDocument`] collects endpoints with their [`Metadata`], and the JSON Schemas of
+//! their requests and responses provided by the [`RequestSchema`] and [`ResponseSchema`] traits.
+//! The generated document follows the conventions of the definitions of the [Matrix
+//! specification], so they can be compared:
+//!
+//! * Endpoints use their latest stable path, or their latest unstable path if they were never
+//!   stabilized.
+//! * Path parameters use the names of the fields of the request.
+//! * The Matrix version that added an endpoint is in the `x-addedInMatrixVersion` extension, unless
+//!   it is Matrix 1.0.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+//! [Matrix specification]: https://github.com/matrix-org/matrix-spec/tree/main/data/api
+
+use std::any::type_name;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::{AuthScheme, MatrixVersion, Metadata, OutgoingRequest};
+use crate::{
+    json_schema::{BodySchema, RequestSchema, ResponseSchema, SchemaGenerator},
+    serde::JsonObject,
+};
+
+/// The version of the OpenAPI specification used by the generated documents.
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// The path of the definitions of the schemas in the document.
+const SCHEMAS_PATH: &str = "#/components/schemas/";
+
+/// A builder for an OpenAPI document.
+#[derive(Debug)]
+pub struct OpenApiDocument {
+    title: String,
+    version: String,
+    paths: JsonObject,
+    generator: SchemaGenerator,
+}
+
+impl OpenApiDocument {
+    /// Creates a new empty `OpenApiDocument` with the given title and version.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            paths: JsonObject::new(),
+            generator: SchemaGenerator::with_definitions_path(SCHEMAS_PATH),
+        }
+    }
+
+    /// Add the endpoint of the request type `R` to this document.
+    ///
+    /// The ID of the operation is the path of the module of `R` inside its crate, like
+    /// `account::whoami::v3`.
+    pub fn add_endpoint<R>(&mut self)
+    where
+        R: OutgoingRequest + RequestSchema,
+        R::IncomingResponse: ResponseSchema,
+    {
+        let metadata = &R::METADATA;
+        let Some(path) = endpoint_path(metadata) else {
+            return;
+        };
+
+        let mut operation = JsonObject::new();
+        operation.insert("operationId".to_owned(), operation_id::<R>().into());
+
+        // Like in the Matrix specification, endpoints that were in the first version don't have it.
+        if let Some(version) = metadata.history.added_in().filter(|v| *v != MatrixVersion::V1_0) {
+            operation.insert("x-addedInMatrixVersion".to_owned(), version_number(version).into());
+        }
+        if metadata.history.deprecated_in().is_some() {
+            operation.insert("deprecated".to_owned(), true.into());
+        }
+        if let Some(security) = security_requirements(metadata.authentication) {
+            operation.insert("security".to_owned(), security);
+        }
+
+        let mut parameters = Vec::new();
+        for (name, schema) in R::path_parameters_schemas(&mut self.generator) {
+            parameters.push(parameter(name, "path", true, schema));
+        }
+        if let Some(schema) = R::query_schema(&mut self.generator) {
+            parameters.extend(
+                object_properties(&schema, &self.generator)
+                    .into_iter()
+                    .map(|(name, schema, required)| parameter(&name, "query", required, schema)),
+            );
+        }
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_owned(), parameters.into());
+        }
+
+        if let Some(body) = R::body_schema(&mut self.generator) {
+            operation.insert(
+                "requestBody".to_owned(),
+                json!({ "required": true, "content": body_content(body) }),
+            );
+        }
+
+        let mut responses = JsonObject::new();
+        let status = <R::IncomingResponse as ResponseSchema>::STATUS;
+        let body = <R::IncomingResponse as ResponseSchema>::body_schema(&mut self.generator);
+        responses.insert(status.as_str().to_owned(), response(status, body));
+        for (status, body) in
+            <R::IncomingResponse as ResponseSchema>::other_responses(&mut self.generator)
+        {
+            responses.insert(status.as_str().to_owned(), response(status, body));
+        }
+        if metadata.rate_limited {
+            responses.insert(
+                "429".to_owned(),
+                json!({ "description": "This request was rate-limited." }),
+            );
+        }
+        operation.insert("responses".to_owned(), responses.into());
+
+        let method = metadata.method.as_str().to_ascii_lowercase();
+        let JsonValue::Object(path_item) =
+            self.paths.entry(path).or_insert_with(|| JsonObject::new().into())
+        else {
+            unreachable!("path items are objects");
+        };
+        path_item.insert(method, operation.into());
+    }
+
+    /// Consume this builder and get the OpenAPI document as JSON.
+    pub fn into_json(self) -> JsonValue {
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": self.title,
+                "version": self.version,
+            },
+            "paths": self.paths,
+            "components": {
+                "schemas": self.generator.into_definitions(),
+                "securitySchemes": security_schemes(),
+            },
+        })
+    }
+}
+
+/// The path of the endpoint in the document, with `{param}` path parameters.
+fn endpoint_path(metadata: &Metadata) -> Option<String> {
+    let history = &metadata.history;
+    let path =
+        history.stable_paths().last().map(|(_, path)| path).or_else(|| history.unstable())?;
+
+    let segments: Vec<_> = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{name}}}"),
+            None => segment.to_owned(),
+        })
+        .collect();
+
+    Some(segments.join("/"))
+}
+
+/// The ID of the operation of the request type `R`.
+fn operation_id<R>() -> String {
+    let path = type_name::<R>();
+    let path = path.strip_suffix("::Request").unwrap_or(path);
+
+    match path.split_once("::") {
+        Some((_crate_name, module)) => module.to_owned(),
+        None => path.to_owned(),
+    }
+}
+
+/// The number of the given Matrix version, like `1.1`.
+fn version_number(version: MatrixVersion) -> String {
+    let (major, minor) = version.into_parts();
+    format!("{major}.{minor}")
+}
+
+/// The security schemes that can be used by the endpoints.
+fn security_schemes() -> JsonValue {
+    json!({
+        "accessTokenBearer": {
+            "type": "http",
+            "scheme": "bearer",
+            "description": "The access token of the user, or of the application service.",
+        },
+        "accessTokenQuery": {
+            "type": "apiKey",
+            "in": "query",
+            "name": "access_token",
+            "description": "The access token of the user, or of the application service.\n\n\
+                            Deprecated in favor of the `Authorization` header.",
+        },
+        "signedRequest": {
+            "type": "apiKey",
+            "in": "header",
+            "name": "Authorization",
+            "description": "The `X-Matrix` signatures of the request by the origin server.",
+        },
+    })
+}
+
+/// The security requirements of an endpoint with the given authentication scheme.
+fn security_requirements(authentication: AuthScheme) -> Option<JsonValue> {
+    let access_token =
+        || vec![json!({ "accessTokenBearer": [] }), json!({ "accessTokenQuery": [] })];
+
+    let requirements = match authentication {
+        AuthScheme::None => return None,
+        AuthScheme::AccessToken | AuthScheme::AppserviceToken => access_token(),
+        AuthScheme::AccessTokenOptional => {
+            let mut requirements = vec![json!({})];
+            requirements.extend(access_token());
+            requirements
+        }
+        AuthScheme::ServerSignatures => vec![json!({ "signedRequest": [] })],
+    };
+
+    Some(requirements.into())
+}
+
+/// A parameter of an operation.
+fn parameter(name: &str, location: &str, required: bool, mut schema: JsonValue) -> JsonValue {
+    let mut parameter = JsonObject::new();
+    parameter.insert("in".to_owned(), location.into());
+    parameter.insert("name".to_owned(), name.into());
+    parameter.insert("required".to_owned(), required.into());
+
+    // The description belongs to the parameter.
+    if let Some(object) = schema.as_object_mut() {
+        if let Some(description) = object.remove("description") {
+            parameter.insert("description".to_owned(), description);
+        }
+
+        // Unwrap the `allOf` that was only used to add the description to a reference.
+        if object.len() == 1 {
+            if let Some(JsonValue::Array(all_of)) = object.get_mut("allOf") {
+                if all_of.len() == 1 {
+                    schema = all_of.pop().expect("allOf has one item");
+                }
+            }
+        }
+    }
+    parameter.insert("schema".to_owned(), schema);
+
+    parameter.into()
+}
+
+/// A response with the given status code and body.
+fn response(status: http::StatusCode, body: Option<BodySchema>) -> JsonValue {
+    let mut response = JsonObject::new();
+    response.insert(
+        "description".to_owned(),
+        status.canonical_reason().unwrap_or("Successful response.").into(),
+    );
+    if let Some(body) = body {
+        response.insert("content".to_owned(), body_content(body));
+    }
+
+    response.into()
+}
+
+/// The content of a request or response body.
+fn body_content(body: BodySchema) -> JsonValue {
+    match body {
+        BodySchema::Json(schema) => json!({ "application/json": { "schema": schema } }),
+        BodySchema::Binary => json!({
+            "application/octet-stream": {
+                "schema": { "type": "string", "contentMediaType": "application/octet-stream" },
+            },
+        }),
+    }
+}
+
+/// The properties of the given object schema, with their schema and whether they are required.
+///
+/// This follows the `allOf`s of flattened fields, including references to definitions.
+fn object_properties(
+    schema: &JsonValue,
+    generator: &SchemaGenerator,
+) -> Vec<(String, JsonValue, bool)> {
+    if let Some(name) = schema.get("$ref").and_then(|r| r.as_str()?.strip_prefix(SCHEMAS_PATH)) {
+        return generator
+            .definitions()
+            .get(name)
+            .map(|schema| object_properties(schema, generator))
+            .unwrap_or_default();
+    }
+
+    let required = schema.get("required").and_then(JsonValue::as_array);
+    let is_required = |name: &str| required.is_some_and(|r| r.iter().any(|v| v == name));
+
+    let mut properties: Vec<_> = schema
+        .get("properties")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, schema)| (name.clone(), schema.clone(), is_required(name)))
+        .collect();
+
+    if let Some(all_of) = schema.get("allOf").and_then(JsonValue::as_array) {
+        properties.extend(all_of.iter().flat_map(|schema| object_properties(schema, generator)));
+    }
+
+    properties
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{endpoint_path, object_properties, security_requirements, SCHEMAS_PATH};
+    use crate::{
+        api::{AuthScheme, Metadata},
+        json_schema::SchemaGenerator,
+    };
+
+    const METADATA: Metadata = metadata! {
+        method: GET,
+        rate_limited: false,
+        authentication: AccessToken,
+        history: {
+            unstable => "/_matrix/client/unstable/org.example/rooms/:room_id",
+            1.0 => "/_matrix/client/r0/rooms/:room_id",
+            1.1 => "/_matrix/client/v3/rooms/:room_id",
+        }
+    };
+
+    const UNSTABLE_METADATA: Metadata = metadata! {
+        method: GET,
+        rate_limited: false,
+        authentication: None,
+        history: {
+            unstable => "/_matrix/client/unstable/org.example/rooms/:room_id/:event_id",
+        }
+    };
+
+    #[test]
+    fn path_with_parameters() {
+        assert_eq!(endpoint_path(&METADATA).unwrap(), "/_matrix/client/v3/rooms/{room_id}");
+        assert_eq!(
+            endpoint_path(&UNSTABLE_METADATA).unwrap(),
+            "/_matrix/client/unstable/org.example/rooms/{room_id}/{event_id}"
+        );
+    }
+
+    #[test]
+    fn security() {
+        assert_eq!(security_requirements(AuthScheme::None), None);
+        assert_eq!(
+            security_requirements(AuthScheme::AccessTokenOptional).unwrap(),
+            json!([{}, { "accessTokenBearer": [] }, { "accessTokenQuery": [] }])
+        );
+        assert_eq!(
+            security_requirements(AuthScheme::ServerSignatures).unwrap(),
+            json!([{ "signedRequest": [] }])
+        );
+    }
+
+    #[test]
+    fn flattened_properties() {
+        let mut generator = SchemaGenerator::with_definitions_path(SCHEMAS_PATH);
+        generator.subschema_for::<Flattened>();
+
+        let schema = json!({
+            "allOf": [
+                {
+                    "type": "object",
+                    "properties": { "from": { "type": "string" } },
+                    "required": ["from"],
+                },
+                { "$ref": "#/components/schemas/Flattened" },
+            ],
+        });
+
+        let properties = object_properties(&schema, &generator);
+        assert_eq!(
+            properties,
+            [
+                ("from".to_owned(), json!({ "type": "string" }), true),
+                (
+                    "limit".to_owned(),
+                    json!({
+                        "anyOf": [
+                            { "type": "integer", "minimum": 0, "maximum": 9_007_199_254_740_991_u64 },
+                            { "type": "null" },
+                        ],
+                    }),
+                    false,
+                ),
+            ]
+        );
+    }
+
+    #[derive(crate::json_schema::JsonSchema)]
+    #[allow(dead_code)]
+    struct Flattened {
+        limit: Option<js_int::UInt>,
+    }
+}
@@ -15,6 +15,7 @@ use crate::{
 /// To create an instance of this type, first create a `PublicRoomsChunkInit` and convert it via
 /// `PublicRoomsChunk::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PublicRoomsChunk {
     /// The canonical alias of the room, if any.
@@ -111,6 +112,7 @@ impl From<PublicRoomsChunkInit> for PublicRoomsChunk {
 
 /// A filter for public rooms lists.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Filter {
     /// A string to search for in the room metadata, e.g. name, topic, canonical alias etc.
@@ -0,0 +1,725 @@
+//! Generation of [JSON Schema] documents for Ruma types.
+//!
+//! Types that implement [`JsonSchema`] can describe their JSON representation, following the
+//! (de)serialization implementations and `serde` attributes of the type. This includes renamed
+//! fields, flattened fields, and the attributes that depend on compatibility features, since they
+//! are only applied when the feature is enabled.
+//!
+//! The trait is implemented by the `StringEnum` derive and for identifiers, and the `EventContent`,
+//! `Event`, `request` and `response` macros implement it for events, event contents and for the
+//! bodies of requests and responses. Other types can derive it with
+//! [`derive(JsonSchema)`](derive@JsonSchema).
+//!
+//! The types of all the fields must implement [`JsonSchema`], a missing implementation is a
+//! compilation error.
+//!
+//! # Example
+//!
+//! ```
+//! use ruma_common::json_schema::{schema_for, JsonSchema};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize, JsonSchema)]
+//! struct Topic {
+//!     #[serde(rename = "m.text")]
+//!     text: String,
+//!
+//!     #[serde(skip_serializing_if = "Option::is_none")]
+//!     html: Option<String>,
+//! }
+//!
+//! let schema = schema_for::<Topic>();
+//! assert_eq!(schema["properties"]["m.text"]["type"], "string");
+//! assert_eq!(schema["required"], serde_json::json!(["m.text"]));
+//! ```
+//!
+//! [JSON Schema]: https://json-schema.org/
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+    rc::Rc,
+    sync::Arc,
+};
+
+use js_int::{Int, UInt};
+pub use ruma_macros::JsonSchema;
+use serde_json::{json, value::RawValue as RawJsonValue, Value as JsonValue};
+
+use crate::{
+    serde::{Base64, JsonObject, Raw},
+    MilliSecondsSinceUnixEpoch, RoomVersionId, SecondsSinceUnixEpoch, Signatures, VoipVersionId,
+};
+
+/// The JSON Schema dialect of the generated schemas.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The default path of the definitions, used in `$ref`s.
+pub const DEFAULT_DEFINITIONS_PATH: &str = "#/$defs/";
+
+/// A type that can describe its JSON representation with a JSON Schema.
+///
+/// # Fields without a schema
+///
+/// The generated implementations require the types of the fields to implement this trait, except
+/// for fields that use `#[serde(with = "...")]` or similar attributes. Their representation can't
+/// be known, so they are described with the empty schema `{}`, which accepts any value.
+pub trait JsonSchema {
+    /// The name of the schema.
+    ///
+    /// This is used as the key of the definition of the schema, if it is
+    /// [referenceable](Self::is_referenceable).
+    fn schema_name() -> Cow<'static, str>;
+
+    /// Whether the schema of this type should be stored in the definitions and referenced with a
+    /// `$ref`, rather than inlined.
+    ///
+    /// Defaults to `true`. Simple types like primitives or containers should return `false`.
+    fn is_referenceable() -> bool {
+        true
+    }
+
+    /// Generate the JSON Schema of this type.
+    ///
+    /// The schemas of other types should be obtained with [`SchemaGenerator::subschema_for()`].
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue;
+}
+
+/// A generator of JSON Schemas that keeps track of the definitions of referenceable types.
+#[derive(Clone, Debug)]
+pub struct SchemaGenerator {
+    definitions_path: Cow<'static, str>,
+    definitions: JsonObject,
+    type_names: BTreeMap<&'static str, String>,
+}
+
+impl SchemaGenerator {
+    /// Creates a new `SchemaGenerator` that references the definitions under `#/$defs/`.
+    pub fn new() -> Self {
+        Self::with_definitions_path(DEFAULT_DEFINITIONS_PATH)
+    }
+
+    /// Creates a new `SchemaGenerator` that references the definitions under the given path.
+    ///
+    /// The path should end with a `/`, like `#/components/schemas/` for an OpenAPI document.
+    pub fn with_definitions_path(path: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            definitions_path: path.into(),
+            definitions: JsonObject::new(),
+            type_names: BTreeMap::new(),
+        }
+    }
+
+    /// Get the schema to use for `T` in another schema.
+    ///
+    /// If `T` is referenceable, its schema is added to the definitions and a `$ref` to it is
+    /// returned.
+    pub fn subschema_for<T: JsonSchema + ?Sized>(&mut self) -> JsonValue {
+        if !T::is_referenceable() {
+            return T::json_schema(self);
+        }
+
+        let type_name = std::any::type_name::<T>();
+        let name = match self.type_names.get(type_name) {
+            Some(name) => name.clone(),
+            None => {
+                let name = self.unique_name(T::schema_name());
+                self.type_names.insert(type_name, name.clone());
+
+                // Insert a placeholder first, to support recursive types.
+                self.definitions.insert(name.clone(), JsonValue::Bool(true));
+                let schema = T::json_schema(self);
+                self.definitions.insert(name.clone(), schema);
+
+                name
+            }
+        };
+
+        json!({ "$ref": format!("{}{name}", self.definitions_path) })
+    }
+
+    /// Generate the root schema of `T`, with the definitions of the referenced types.
+    pub fn root_schema_for<T: JsonSchema + ?Sized>(mut self) -> JsonValue {
+        let mut schema = match T::json_schema(&mut self) {
+            JsonValue::Object(schema) => schema,
+            JsonValue::Bool(true) => JsonObject::new(),
+            schema => return schema,
+        };
+
+        let mut root = JsonObject::new();
+        root.insert("$schema".to_owned(), DIALECT.into());
+        root.insert("title".to_owned(), T::schema_name().into());
+        root.append(&mut schema);
+
+        if !self.definitions.is_empty() {
+            root.insert("$defs".to_owned(), self.definitions.into());
+        }
+
+        root.into()
+    }
+
+    /// The definitions of the referenceable types that were encountered.
+    pub fn definitions(&self) -> &JsonObject {
+        &self.definitions
+    }
+
+    /// Consume this generator and get the definitions of the referenceable types that were
+    /// encountered.
+    pub fn into_definitions(self) -> JsonObject {
+        self.definitions
+    }
+
+    /// Get a definition name based on the given name that is not used yet.
+    fn unique_name(&self, name: Cow<'static, str>) -> String {
+        if !self.definitions.contains_key(&*name) {
+            return name.into_owned();
+        }
+
+        (2..)
+            .map(|i| format!("{name}{i}"))
+            .find(|name| !self.definitions.contains_key(name))
+            .expect("there should be an unused name")
+    }
+}
+
+impl Default for SchemaGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generate the root schema of `T`.
+pub fn schema_for<T: JsonSchema + ?Sized>() -> JsonValue {
+    SchemaGenerator::new().root_schema_for::<T>()
+}
+
+/// The JSON Schemas of the parts of an endpoint's request.
+///
+/// This is implemented by the [`request`](crate::api::request) macro.
+#[cfg(feature = "api")]
+pub trait RequestSchema {
+    /// The names and schemas of the path parameters, in the order in which they appear in the
+    /// path.
+    fn path_parameters_schemas(generator: &mut SchemaGenerator) -> Vec<(&'static str, JsonValue)>;
+
+    /// The schema of the query string, as an object schema, if the request has query parameters.
+    fn query_schema(generator: &mut SchemaGenerator) -> Option<JsonValue>;
+
+    /// The schema of the body, if the request has a body.
+    fn body_schema(generator: &mut SchemaGenerator) -> Option<BodySchema>;
+}
+
+/// The JSON Schemas of the parts of an endpoint's response.
+///
+/// This is implemented by the [`response`](crate::api::response) macro.
+#[cfg(feature = "api")]
+pub trait ResponseSchema {
+    /// The status code of a successful response.
+    const STATUS: http::StatusCode;
+
+    /// The schema of the body, if the response has a body.
+    fn body_schema(generator: &mut SchemaGenerator) -> Option<BodySchema>;
+
+    /// The other successful responses of the endpoint, like redirects, with their status code and
+    /// the schema of their body, if they have a body.
+    ///
+    /// Defaults to none.
+    fn other_responses(
+        generator: &mut SchemaGenerator,
+    ) -> Vec<(http::StatusCode, Option<BodySchema>)> {
+        let _ = generator;
+        Vec::new()
+    }
+}
+
+/// The schema of the body of a request or a response.
+#[cfg(feature = "api")]
+#[derive(Clone, Debug)]
+#[allow(clippy::exhaustive_enums)]
+pub enum BodySchema {
+    /// A JSON body with the given schema.
+    Json(JsonValue),
+
+    /// A body with arbitrary bytes.
+    Binary,
+}
+
+/// Helpers used by the generated implementations of [`JsonSchema`].
+///
+/// It is not considered part of this module's public API.
+#[doc(hidden)]
+pub mod private {
+    use serde_json::Value as JsonValue;
+
+    use super::SchemaGenerator;
+    use crate::serde::JsonObject;
+
+    /// A builder for the schema of an object.
+    #[derive(Default)]
+    pub struct ObjectSchema {
+        properties: JsonObject,
+        required: Vec<JsonValue>,
+        flattened: Vec<JsonValue>,
+    }
+
+    impl ObjectSchema {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn property(&mut self, name: &str, schema: JsonValue, required: bool) {
+            self.properties.insert(name.to_owned(), schema);
+            if required {
+                self.required.push(name.into());
+            }
+        }
+
+        pub fn flatten(&mut self, schema: JsonValue) {
+            self.flattened.push(schema);
+        }
+
+        pub fn finish(self) -> JsonValue {
+            let mut schema = JsonObject::new();
+            schema.insert("type".to_owned(), "object".into());
+
+            if !self.properties.is_empty() {
+                schema.insert("properties".to_owned(), self.properties.into());
+            }
+            if !self.required.is_empty() {
+                schema.insert("required".to_owned(), self.required.into());
+            }
+
+            if self.flattened.is_empty() {
+                schema.into()
+            } else {
+                let mut all_of = vec![JsonValue::Object(schema)];
+                all_of.extend(self.flattened);
+                serde_json::json!({ "allOf": all_of })
+            }
+        }
+    }
+
+    /// Make the properties of the given object schema optional.
+    ///
+    /// This is used for flattened `Option`s, whose properties are only present if the value is
+    /// `Some`. References to definitions are resolved to be able to change the schema.
+    pub fn optional_properties(generator: &SchemaGenerator, mut schema: JsonValue) -> JsonValue {
+        if let Some(definition) = schema
+            .get("$ref")
+            .and_then(JsonValue::as_str)
+            .and_then(|reference| reference.strip_prefix(&*generator.definitions_path))
+            .and_then(|name| generator.definitions.get(name))
+        {
+            schema = definition.clone();
+        }
+
+        remove_required(&mut schema);
+        schema
+    }
+
+    fn remove_required(schema: &mut JsonValue) {
+        let JsonValue::Object(object) = schema else {
+            return;
+        };
+
+        object.remove("required");
+        for key in ["allOf", "anyOf", "oneOf"] {
+            if let Some(JsonValue::Array(schemas)) = object.get_mut(key) {
+                schemas.iter_mut().for_each(remove_required);
+            }
+        }
+    }
+
+    /// The schema that accepts any value.
+    pub fn any() -> JsonValue {
+        JsonValue::Object(JsonObject::new())
+    }
+
+    /// The schema of `null`.
+    pub fn null() -> JsonValue {
+        serde_json::json!({ "type": "null" })
+    }
+
+    /// The schema of the given string constant.
+    pub fn constant(value: &str) -> JsonValue {
+        serde_json::json!({ "type": "string", "const": value })
+    }
+
+    /// The schema of the given event type.
+    ///
+    /// Event types ending with `.*` are prefixes and match any string that starts with the prefix.
+    pub fn event_type(event_type: &str) -> JsonValue {
+        match event_type.strip_suffix('*') {
+            Some(prefix) => serde_json::json!({
+                "type": "string",
+                "pattern": format!("^{}", prefix.replace('.', "\\.")),
+            }),
+            None => constant(event_type),
+        }
+    }
+
+    /// The schema that matches any of the given schemas.
+    pub fn any_of(schemas: Vec<JsonValue>) -> JsonValue {
+        serde_json::json!({ "anyOf": schemas })
+    }
+
+    /// The schema that matches exactly one of the given schemas.
+    pub fn one_of(schemas: Vec<JsonValue>) -> JsonValue {
+        serde_json::json!({ "oneOf": schemas })
+    }
+
+    /// The schema of one of the given strings.
+    pub fn enumeration(values: &[&str]) -> JsonValue {
+        serde_json::json!({ "type": "string", "enum": values })
+    }
+
+    /// The schema of a tuple with the given items.
+    pub fn tuple(items: Vec<JsonValue>, len: usize) -> JsonValue {
+        serde_json::json!({
+            "type": "array",
+            "prefixItems": items,
+            "minItems": len,
+            "maxItems": len,
+        })
+    }
+
+    /// Add a required tag property with the given value to the given schema.
+    pub fn with_tag(schema: JsonValue, tag: &str, value: &str) -> JsonValue {
+        let mut object = ObjectSchema::new();
+        object.property(tag, constant(value), true);
+        object.flatten(schema);
+        object.finish()
+    }
+
+    /// Add a description to the given schema, if it is an object.
+    pub fn with_description(mut schema: JsonValue, description: &str) -> JsonValue {
+        if let JsonValue::Object(object) = &mut schema {
+            // Descriptions are ignored next to a `$ref` in some tools, so wrap it.
+            if object.contains_key("$ref") {
+                return serde_json::json!({ "allOf": [schema], "description": description });
+            }
+
+            object.insert("description".to_owned(), description.into());
+        }
+
+        schema
+    }
+
+    /// The schema of a string enum with the given known values, that also accepts any other
+    /// string.
+    pub fn string_enum(values: &[&str]) -> JsonValue {
+        serde_json::json!({
+            "anyOf": [
+                { "type": "string", "enum": values },
+                { "type": "string" },
+            ],
+        })
+    }
+}
+
+macro_rules! impl_inline_schema {
+    ($( $ty:ty => $name:literal: $schema:tt, )*) => {
+        $(
+            impl JsonSchema for $ty {
+                fn schema_name() -> Cow<'static, str> {
+                    Cow::Borrowed($name)
+                }
+
+                fn is_referenceable() -> bool {
+                    false
+                }
+
+                fn json_schema(_generator: &mut SchemaGenerator) -> JsonValue {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+impl_inline_schema! {
+    bool => "boolean": { "type": "boolean" },
+    str => "string": { "type": "string" },
+    String => "string": { "type": "string" },
+    char => "character": { "type": "string", "minLength": 1, "maxLength": 1 },
+    f32 => "float": { "type": "number", "format": "float" },
+    f64 => "double": { "type": "number", "format": "double" },
+    i8 => "int8": { "type": "integer", "format": "int8" },
+    i16 => "int16": { "type": "integer", "format": "int16" },
+    i32 => "int32": { "type": "integer", "format": "int32" },
+    i64 => "int64": { "type": "integer", "format": "int64" },
+    u8 => "uint8": { "type": "integer", "format": "uint8", "minimum": 0 },
+    u16 => "uint16": { "type": "integer", "format": "uint16", "minimum": 0 },
+    u32 => "uint32": { "type": "integer", "format": "uint32", "minimum": 0 },
+    u64 => "uint64": { "type": "integer", "format": "uint64", "minimum": 0 },
+    usize => "uint": { "type": "integer", "format": "uint", "minimum": 0 },
+    UInt => "UInt": { "type": "integer", "minimum": 0, "maximum": 9_007_199_254_740_991_u64 },
+    Int => "Int": {
+        "type": "integer",
+        "minimum": -9_007_199_254_740_991_i64,
+        "maximum": 9_007_199_254_740_991_i64,
+    },
+    JsonValue => "AnyValue": {},
+    RawJsonValue => "AnyValue": {},
+    JsonObject => "Object": { "type": "object" },
+    MilliSecondsSinceUnixEpoch => "MilliSecondsSinceUnixEpoch": {
+        "type": "integer",
+        "minimum": 0,
+        "maximum": 9_007_199_254_740_991_u64,
+        "description": "A timestamp in milliseconds since the unix epoch.",
+    },
+    SecondsSinceUnixEpoch => "SecondsSinceUnixEpoch": {
+        "type": "integer",
+        "minimum": 0,
+        "maximum": 9_007_199_254_740_991_u64,
+        "description": "A timestamp in seconds since the unix epoch.",
+    },
+    RoomVersionId => "RoomVersionId": { "type": "string", "minLength": 1, "maxLength": 32 },
+    VoipVersionId => "VoipVersionId": {
+        "anyOf": [{ "type": "string" }, { "type": "integer", "const": 0 }],
+    },
+}
+
+impl_inline_schema! {
+    crate::directory::RoomNetwork => "RoomNetwork": {
+        "type": "object",
+        "properties": {
+            "include_all_networks": { "type": "boolean" },
+            "third_party_instance_id": { "type": "string" },
+        },
+    },
+    crate::directory::RoomTypeFilter => "RoomTypeFilter": {
+        "anyOf": [{ "type": "string" }, { "type": "null" }],
+    },
+    crate::push::RoomMemberCountIs => "RoomMemberCountIs": {
+        "type": "string",
+        "pattern": "^(==|<|>|>=|<=)?[0-9]+$",
+    },
+    crate::push::ScalarJsonValue => "ScalarJsonValue": {
+        "anyOf": [
+            { "type": "null" },
+            { "type": "boolean" },
+            {
+                "type": "integer",
+                "minimum": -9_007_199_254_740_991_i64,
+                "maximum": 9_007_199_254_740_991_i64,
+            },
+            { "type": "string" },
+        ],
+    },
+}
+
+#[cfg(feature = "canonical-json")]
+impl_inline_schema! {
+    crate::CanonicalJsonValue => "AnyValue": {},
+}
+
+macro_rules! impl_deref_schema {
+    ($( $ty:ty ),* $(,)?) => {
+        $(
+            impl<T: JsonSchema + ?Sized> JsonSchema for $ty {
+                fn schema_name() -> Cow<'static, str> {
+                    T::schema_name()
+                }
+
+                fn is_referenceable() -> bool {
+                    false
+                }
+
+                fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+                    generator.subschema_for::<T>()
+                }
+            }
+        )*
+    };
+}
+
+impl_deref_schema!(&T, Box<T>, Rc<T>, Arc<T>);
+
+impl<T: JsonSchema + ToOwned + ?Sized> JsonSchema for Cow<'_, T> {
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        generator.subschema_for::<T>()
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    fn schema_name() -> Cow<'static, str> {
+        format!("Nullable_{}", T::schema_name()).into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        json!({ "anyOf": [generator.subschema_for::<T>(), { "type": "null" }] })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for js_option::JsOption<T> {
+    fn schema_name() -> Cow<'static, str> {
+        format!("Nullable_{}", T::schema_name()).into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        generator.subschema_for::<Option<T>>()
+    }
+}
+
+macro_rules! impl_array_schema {
+    ($( $ty:ty => $unique:literal ),* $(,)?) => {
+        $(
+            impl<T: JsonSchema> JsonSchema for $ty {
+                fn schema_name() -> Cow<'static, str> {
+                    format!("Array_of_{}", T::schema_name()).into()
+                }
+
+                fn is_referenceable() -> bool {
+                    false
+                }
+
+                fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+                    let mut schema = json!({
+                        "type": "array",
+                        "items": generator.subschema_for::<T>(),
+                    });
+                    if $unique {
+                        schema["uniqueItems"] = true.into();
+                    }
+                    schema
+                }
+            }
+        )*
+    };
+}
+
+impl_array_schema!(
+    [T] => false,
+    Vec<T> => false,
+    BTreeSet<T> => true,
+    indexmap::IndexSet<T> => true,
+);
+
+impl<A: JsonSchema, B: JsonSchema> JsonSchema for (A, B) {
+    fn schema_name() -> Cow<'static, str> {
+        format!("Tuple_of_{}_and_{}", A::schema_name(), B::schema_name()).into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        private::tuple(vec![generator.subschema_for::<A>(), generator.subschema_for::<B>()], 2)
+    }
+}
+
+impl<K, V: JsonSchema> JsonSchema for BTreeMap<K, V> {
+    fn schema_name() -> Cow<'static, str> {
+        format!("Map_of_{}", V::schema_name()).into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        json!({
+            "type": "object",
+            "additionalProperties": generator.subschema_for::<V>(),
+        })
+    }
+}
+
+impl<K, V: JsonSchema, S> JsonSchema for indexmap::IndexMap<K, V, S> {
+    fn schema_name() -> Cow<'static, str> {
+        format!("Map_of_{}", V::schema_name()).into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        json!({
+            "type": "object",
+            "additionalProperties": generator.subschema_for::<V>(),
+        })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Raw<T> {
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+        generator.subschema_for::<T>()
+    }
+}
+
+impl<C, B> JsonSchema for Base64<C, B> {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("Base64")
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> JsonValue {
+        json!({ "type": "string", "contentEncoding": "base64" })
+    }
+}
+
+impl<E: Ord, K: ?Sized> JsonSchema for Signatures<E, K> {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("Signatures")
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> JsonValue {
+        json!({
+            "type": "object",
+            "description": "Signatures by entity and by key identifier.",
+            "additionalProperties": {
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+            },
+        })
+    }
+}
+
+impl<T: ?Sized> JsonSchema for PhantomData<T> {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("Null")
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> JsonValue {
+        json!({ "type": "null" })
+    }
+}
@@ -25,6 +25,8 @@ pub mod canonical_json;
 pub mod directory;
 pub mod encryption;
 mod identifiers;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
 mod percent_encode;
 pub mod power_levels;
 pub mod presence;
@@ -29,20 +29,30 @@ use crate::{
 };
 
 mod action;
+mod compiled;
 mod condition;
+mod diff;
+mod evaluation;
 mod iter;
+mod notification_settings;
 mod predefined;
 
 #[cfg(feature = "unstable-msc3932")]
 pub use self::condition::RoomVersionFeature;
 pub use self::{
     action::{Action, Tweak},
+    compiled::{CompiledPushRule, CompiledRuleset},
     condition::{
         ComparisonOperator, FlattenedJson, FlattenedJsonValue, PushCondition,
         PushConditionPowerLevelsCtx, PushConditionRoomCtx, RoomMemberCountIs, ScalarJsonValue,
         _CustomPushCondition,
     },
+    diff::{ApplyPushRuleChangeError, PushRuleChange, RulesetDiff, ShadowingPushRule},
+    evaluation::{ConditionEvaluation, RuleEvaluation, RuleOutcome, RulesetEvaluation},
     iter::{AnyPushRule, AnyPushRuleRef, RulesetIntoIter, RulesetIter},
+    notification_settings::{
+        NotificationSettings, NotificationSettingsError, RoomNotificationMode,
+    },
     predefined::{
         PredefinedContentRuleId, PredefinedOverrideRuleId, PredefinedRuleId,
         PredefinedUnderrideRuleId,
@@ -54,6 +64,7 @@ pub use self::{
 /// For example, some rules may only be applied for messages from a particular sender, a particular
 /// room, or by default. The push ruleset contains the entire set of scopes and rules.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Ruleset {
     /// These rules configure behavior for (unencrypted) messages that match certain patterns.
@@ -368,6 +379,7 @@ impl Ruleset {
 /// To create an instance of this type, first create a `SimplePushRuleInit` and convert it via
 /// `SimplePushRule::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SimplePushRule<T> {
     /// Actions to determine if and how a notification is delivered for events matching this rule.
@@ -453,6 +465,7 @@ where
 /// To create an instance of this type, first create a `ConditionalPushRuleInit` and convert it via
 /// `ConditionalPushRule::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ConditionalPushRule {
     /// Actions to determine if and how a notification is delivered for events matching this rule.
@@ -483,8 +496,21 @@ impl ConditionalPushRule {
     /// * `event` - The flattened JSON representation of a room message event.
     /// * `context` - The context of the room at the time of the event.
     pub fn applies(&self, event: &FlattenedJson, context: &PushConditionRoomCtx) -> bool {
+        self.skip_reason(event, context).is_none()
+            && self.conditions.iter().all(|cond| cond.applies(event, context))
+    }
+
+    /// Check if the push rule should be skipped for the event regardless of its conditions.
+    ///
+    /// Returns the reason why the rule is skipped, if any.
+    #[cfg_attr(not(feature = "unstable-msc3932"), allow(unused_variables))]
+    fn skip_reason(
+        &self,
+        event: &FlattenedJson,
+        context: &PushConditionRoomCtx,
+    ) -> Option<RuleOutcome> {
         if !self.enabled {
-            return false;
+            return Some(RuleOutcome::Disabled);
         }
 
         #[cfg(feature = "unstable-msc3932")]
@@ -505,7 +531,7 @@ impl ConditionalPushRule {
                 });
 
                 if room_supports_ext_ev && !rule_has_room_version_supports {
-                    return false;
+                    return Some(RuleOutcome::UnsupportedRoomVersion);
                 }
             }
         }
@@ -516,10 +542,10 @@ impl ConditionalPushRule {
             || self.rule_id == PredefinedOverrideRuleId::ContainsDisplayName.as_ref())
             && event.contains_mentions()
         {
-            return false;
+            return Some(RuleOutcome::SupersededByMentions);
         }
 
-        self.conditions.iter().all(|cond| cond.applies(event, context))
+        None
     }
 }
 
@@ -586,6 +612,7 @@ impl Equivalent<ConditionalPushRule> for str {
 /// To create an instance of this type, first create a `PatternedPushRuleInit` and convert it via
 /// `PatternedPushRule::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PatternedPushRule {
     /// Actions to determine if and how a notification is delivered for events matching this rule.
@@ -617,19 +644,31 @@ impl PatternedPushRule {
         event: &FlattenedJson,
         context: &PushConditionRoomCtx,
     ) -> bool {
+        if event.get_str("sender").is_some_and(|sender| sender == context.user_id) {
+            return false;
+        }
+
+        self.skip_reason(event).is_none()
+            && condition::check_event_match(event, key, &self.pattern, context)
+    }
+
+    /// Check if the push rule should be skipped for the event regardless of its pattern.
+    ///
+    /// Returns the reason why the rule is skipped, if any.
+    fn skip_reason(&self, event: &FlattenedJson) -> Option<RuleOutcome> {
+        if !self.enabled {
+            return Some(RuleOutcome::Disabled);
+        }
+
         // The old mention rules are disabled when an m.mentions field is present.
         #[allow(deprecated)]
         if self.rule_id == PredefinedContentRuleId::ContainsUserName.as_ref()
             && event.contains_mentions()
         {
-            return false;
+            return Some(RuleOutcome::SupersededByMentions);
         }
 
-        if event.get_str("sender").is_some_and(|sender| sender == context.user_id) {
-            return false;
-        }
-
-        self.enabled && condition::check_event_match(event, key, &self.pattern, context)
+        None
     }
 }
 
@@ -688,6 +727,7 @@ impl Equivalent<PatternedPushRule> for str {
 
 /// Information for a pusher using the Push Gateway API.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct HttpPusherData {
     /// The URL to use to send notifications to.
@@ -961,6 +1001,12 @@ where
 
     // Only move the item if it's new or if it was positioned.
     if replaced.is_none() || after.is_some() || before.is_some() {
+        // The indexes above include the item itself, so moving it to a lower priority shifts the
+        // other items up.
+        if from < to {
+            to -= 1;
+        }
+
         set.move_index(from, to);
     }
 
@@ -1001,7 +1047,7 @@ mod tests {
     use crate::{
         owned_room_id, owned_user_id,
         power_levels::NotificationPowerLevels,
-        push::{PredefinedContentRuleId, PredefinedOverrideRuleId},
+        push::{NewPushRule, NewSimplePushRule, PredefinedContentRuleId, PredefinedOverrideRuleId},
         serde::Raw,
         user_id,
     };
@@ -1869,4 +1915,33 @@ mod tests {
             PredefinedOverrideRuleId::InviteForMe.as_ref()
         );
     }
+
+    #[test]
+    fn insert_user_rule_relative_to_other_rule() {
+        let mut set = Ruleset::new();
+        for id in ["c", "b", "a"] {
+            let rule =
+                NewSimplePushRule::new(format!("@{id}:server.name").try_into().unwrap(), vec![]);
+            set.insert(NewPushRule::Sender(rule), None, None).unwrap();
+        }
+
+        let sender_ids =
+            |set: &Ruleset| set.sender.iter().map(|r| r.rule_id.to_string()).collect::<Vec<_>>();
+        assert_eq!(sender_ids(&set), ["@a:server.name", "@b:server.name", "@c:server.name"]);
+
+        // Move to a lower priority.
+        let rule = NewSimplePushRule::new(owned_user_id!("@a:server.name"), vec![]);
+        set.insert(NewPushRule::Sender(rule.clone()), Some("@b:server.name"), None).unwrap();
+        assert_eq!(sender_ids(&set), ["@b:server.name", "@a:server.name", "@c:server.name"]);
+
+        set.insert(NewPushRule::Sender(rule.clone()), Some("@c:server.name"), None).unwrap();
+        assert_eq!(sender_ids(&set), ["@b:server.name", "@c:server.name", "@a:server.name"]);
+
+        // Move to a higher priority.
+        set.insert(NewPushRule::Sender(rule.clone()), None, Some("@c:server.name")).unwrap();
+        assert_eq!(sender_ids(&set), ["@b:server.name", "@a:server.name", "@c:server.name"]);
+
+        set.insert(NewPushRule::Sender(rule), None, Some("@b:server.name")).unwrap();
+        assert_eq!(sender_ids(&set), ["@a:server.name", "@b:server.name", "@c:server.name"]);
+    }
 }
@@ -108,6 +108,46 @@ impl Serialize for Action {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl crate::json_schema::JsonSchema for Action {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("Action")
+    }
+
+    fn json_schema(_generator: &mut crate::json_schema::SchemaGenerator) -> JsonValue {
+        serde_json::json!({
+            "anyOf": [
+                { "type": "string", "const": "notify" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "set_tweak": { "type": "string", "const": "sound" },
+                        "value": { "type": "string" },
+                    },
+                    "required": ["set_tweak", "value"],
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "set_tweak": { "type": "string", "const": "highlight" },
+                        "value": { "type": "boolean" },
+                    },
+                    "required": ["set_tweak"],
+                },
+                // Custom actions and tweaks.
+                { "type": "string" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "set_tweak": { "type": "string" },
+                        "value": {},
+                    },
+                },
+            ],
+        })
+    }
+}
+
 /// An unknown action.
 #[doc(hidden)]
 #[allow(unknown_lints, unnameable_types)]
@@ -0,0 +1,593 @@
+//! A precompiled form of a `Ruleset`, optimized for evaluating many events.
+
+use std::ops::RangeBounds;
+
+use regex::bytes::Regex;
+use wildmatch::WildMatch;
+
+#[cfg(feature = "unstable-msc3931")]
+use super::RoomVersionFeature;
+use super::{
+    condition::{self, StrExt},
+    Action, AnyPushRuleRef, FlattenedJson, PredefinedContentRuleId, PredefinedOverrideRuleId,
+    PushCondition, PushConditionRoomCtx, RoomMemberCountIs, RuleKind, Ruleset, ScalarJsonValue,
+};
+use crate::UserId;
+
+impl Ruleset {
+    /// Compile this `Ruleset` into a form that is faster to evaluate.
+    ///
+    /// The patterns of the push rules are only parsed once, when the `CompiledRuleset` is built,
+    /// instead of every time an event is evaluated. Disabled push rules are left out.
+    ///
+    /// The `CompiledRuleset` doesn't reflect later changes to this `Ruleset`, so it must be
+    /// compiled again after the rules are modified.
+    pub fn compile(&self) -> CompiledRuleset {
+        CompiledRuleset { rules: self.iter().filter_map(CompiledPushRule::new).collect() }
+    }
+}
+
+/// A precompiled form of a [`Ruleset`].
+///
+/// It is meant for servers that evaluate the push rules of many users for each event: the patterns
+/// of the push rules are parsed once, and the same [`FlattenedJson`] can be evaluated against the
+/// rulesets of all the members of a room.
+///
+/// To get an instance of this type, use [`Ruleset::compile()`].
+#[derive(Clone, Debug)]
+pub struct CompiledRuleset {
+    rules: Vec<CompiledPushRule>,
+}
+
+impl CompiledRuleset {
+    /// Get the first push rule that applies to this event, if any.
+    ///
+    /// This returns the same rule as [`Ruleset::get_match()`] on the original `Ruleset`.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The flattened JSON representation of a room message event.
+    /// * `context` - The context of the message and room at the time of the event.
+    pub fn get_match(
+        &self,
+        event: &FlattenedJson,
+        context: &PushConditionRoomCtx,
+    ) -> Option<&CompiledPushRule> {
+        self.get_match_inner(event, event.contains_mentions(), context)
+    }
+
+    /// Get the push actions that apply to this event.
+    ///
+    /// Returns an empty slice if no push rule applies.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The flattened JSON representation of a room message event.
+    /// * `context` - The context of the message and room at the time of the event.
+    pub fn get_actions(&self, event: &FlattenedJson, context: &PushConditionRoomCtx) -> &[Action] {
+        self.get_match(event, context).map(|rule| rule.actions()).unwrap_or(&[])
+    }
+
+    /// Get the first push rule that applies to this event, for each of the given rulesets.
+    ///
+    /// This is equivalent to calling [`CompiledRuleset::get_match()`] for each ruleset, but the
+    /// properties of the event that don't depend on the ruleset are only computed once.
+    ///
+    /// The results are in the same order as `rulesets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The flattened JSON representation of a room message event.
+    /// * `rulesets` - The rulesets to evaluate, with the context of the message and room at the
+    ///   time of the event for their owner.
+    pub fn get_match_batch<'a, 'b>(
+        event: &FlattenedJson,
+        rulesets: impl IntoIterator<Item = (&'a CompiledRuleset, &'b PushConditionRoomCtx)>,
+    ) -> Vec<Option<&'a CompiledPushRule>> {
+        let contains_mentions = event.contains_mentions();
+
+        rulesets
+            .into_iter()
+            .map(|(ruleset, context)| ruleset.get_match_inner(event, contains_mentions, context))
+            .collect()
+    }
+
+    /// Creates a borrowing iterator over all the enabled push rules of this `CompiledRuleset`, by
+    /// order of priority.
+    pub fn iter(&self) -> impl Iterator<Item = &CompiledPushRule> {
+        self.rules.iter()
+    }
+
+    fn get_match_inner(
+        &self,
+        event: &FlattenedJson,
+        contains_mentions: bool,
+        context: &PushConditionRoomCtx,
+    ) -> Option<&CompiledPushRule> {
+        if event.get_str("sender").is_some_and(|sender| sender == context.user_id) {
+            // no need to look at the rules if the event was by the user themselves
+            return None;
+        }
+
+        self.rules.iter().find(|rule| rule.applies(event, contains_mentions, context))
+    }
+}
+
+/// A precompiled push rule.
+///
+/// To get an instance of this type, use [`Ruleset::compile()`].
+#[derive(Clone, Debug)]
+pub struct CompiledPushRule {
+    kind: RuleKind,
+    rule_id: String,
+    actions: Vec<Action>,
+    conditions: Vec<CompiledCondition>,
+
+    /// Whether this is one of the deprecated mention rules, that are disabled when the event has
+    /// an `m.mentions` property.
+    is_legacy_mention_rule: bool,
+
+    /// Whether this rule is disabled in rooms that support extensible events.
+    #[cfg(feature = "unstable-msc3932")]
+    disabled_for_extensible_events: bool,
+}
+
+impl CompiledPushRule {
+    fn new(rule: AnyPushRuleRef<'_>) -> Option<Self> {
+        if !rule.enabled() {
+            return None;
+        }
+
+        let kind = match rule {
+            AnyPushRuleRef::Override(_) => RuleKind::Override,
+            AnyPushRuleRef::Content(_) => RuleKind::Content,
+            AnyPushRuleRef::Room(_) => RuleKind::Room,
+            AnyPushRuleRef::Sender(_) => RuleKind::Sender,
+            AnyPushRuleRef::Underride(_) => RuleKind::Underride,
+        };
+
+        #[allow(deprecated)]
+        let (conditions, is_legacy_mention_rule) = match rule {
+            AnyPushRuleRef::Override(rule) | AnyPushRuleRef::Underride(rule) => (
+                rule.conditions.iter().map(CompiledCondition::new).collect(),
+                rule.rule_id == PredefinedOverrideRuleId::RoomNotif.as_ref()
+                    || rule.rule_id == PredefinedOverrideRuleId::ContainsDisplayName.as_ref(),
+            ),
+            AnyPushRuleRef::Content(rule) => (
+                vec![CompiledCondition::event_match("content.body", &rule.pattern)],
+                rule.rule_id == PredefinedContentRuleId::ContainsUserName.as_ref(),
+            ),
+            AnyPushRuleRef::Room(rule) => {
+                (vec![CompiledCondition::event_match("room_id", rule.rule_id.as_str())], false)
+            }
+            AnyPushRuleRef::Sender(rule) => {
+                (vec![CompiledCondition::event_match("sender", rule.rule_id.as_str())], false)
+            }
+        };
+
+        #[cfg(feature = "unstable-msc3932")]
+        let disabled_for_extensible_events = match rule {
+            AnyPushRuleRef::Override(rule) | AnyPushRuleRef::Underride(rule) => {
+                // These 3 rules always apply.
+                #[allow(deprecated)]
+                let always_applies = rule.rule_id == PredefinedOverrideRuleId::Master.as_ref()
+                    || rule.rule_id == PredefinedOverrideRuleId::RoomNotif.as_ref()
+                    || rule.rule_id == PredefinedOverrideRuleId::ContainsDisplayName.as_ref();
+
+                !always_applies
+                    && !rule.conditions.iter().any(|condition| {
+                        matches!(condition, PushCondition::RoomVersionSupports { .. })
+                    })
+            }
+            _ => false,
+        };
+
+        Some(Self {
+            kind,
+            rule_id: rule.rule_id().to_owned(),
+            actions: rule.actions().to_owned(),
+            conditions,
+            is_legacy_mention_rule,
+            #[cfg(feature = "unstable-msc3932")]
+            disabled_for_extensible_events,
+        })
+    }
+
+    /// The kind of the push rule.
+    pub fn kind(&self) -> RuleKind {
+        self.kind.clone()
+    }
+
+    /// The ID of the push rule.
+    pub fn rule_id(&self) -> &str {
+        &self.rule_id
+    }
+
+    /// The actions of the push rule.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    fn applies(
+        &self,
+        event: &FlattenedJson,
+        contains_mentions: bool,
+        context: &PushConditionRoomCtx,
+    ) -> bool {
+        #[cfg(feature = "unstable-msc3932")]
+        if self.disabled_for_extensible_events
+            && context.supported_features.contains(&RoomVersionFeature::ExtensibleEvents)
+        {
+            return false;
+        }
+
+        // The old mention rules are disabled when an m.mentions field is present.
+        if self.is_legacy_mention_rule && contains_mentions {
+            return false;
+        }
+
+        self.conditions.iter().all(|condition| condition.applies(event, context))
+    }
+}
+
+/// A precompiled push condition.
+#[derive(Clone, Debug)]
+enum CompiledCondition {
+    EventMatch {
+        key: String,
+        matcher: PatternMatcher,
+    },
+    ContainsDisplayName,
+    RoomMemberCount {
+        is: RoomMemberCountIs,
+    },
+    SenderNotificationPermission {
+        key: String,
+    },
+    #[cfg(feature = "unstable-msc3931")]
+    RoomVersionSupports {
+        feature: RoomVersionFeature,
+    },
+    EventPropertyIs {
+        key: String,
+        value: ScalarJsonValue,
+    },
+    EventPropertyContains {
+        key: String,
+        value: ScalarJsonValue,
+    },
+    Never,
+}
+
+impl CompiledCondition {
+    fn new(condition: &PushCondition) -> Self {
+        match condition {
+            PushCondition::EventMatch { key, pattern } => Self::event_match(key, pattern),
+            PushCondition::ContainsDisplayName => Self::ContainsDisplayName,
+            PushCondition::RoomMemberCount { is } => Self::RoomMemberCount { is: *is },
+            PushCondition::SenderNotificationPermission { key } => {
+                Self::SenderNotificationPermission { key: key.clone() }
+            }
+            #[cfg(feature = "unstable-msc3931")]
+            PushCondition::RoomVersionSupports { feature } => {
+                Self::RoomVersionSupports { feature: feature.clone() }
+            }
+            PushCondition::EventPropertyIs { key, value } => {
+                Self::EventPropertyIs { key: key.clone(), value: value.clone() }
+            }
+            PushCondition::EventPropertyContains { key, value } => {
+                Self::EventPropertyContains { key: key.clone(), value: value.clone() }
+            }
+            PushCondition::_Custom(_) => Self::Never,
+        }
+    }
+
+    fn event_match(key: &str, pattern: &str) -> Self {
+        Self::EventMatch {
+            key: key.to_owned(),
+            matcher: PatternMatcher::new(pattern, key == "content.body"),
+        }
+    }
+
+    fn applies(&self, event: &FlattenedJson, context: &PushConditionRoomCtx) -> bool {
+        match self {
+            Self::EventMatch { key, matcher } => {
+                let value = match key.as_str() {
+                    "room_id" => context.room_id.as_str(),
+                    _ => match event.get_str(key) {
+                        Some(v) => v,
+                        None => return false,
+                    },
+                };
+
+                matcher.matches(value)
+            }
+            Self::ContainsDisplayName => {
+                let value = match event.get_str("content.body") {
+                    Some(v) => v,
+                    None => return false,
+                };
+
+                value.matches_pattern(&context.user_display_name, true)
+            }
+            Self::RoomMemberCount { is } => is.contains(&context.member_count),
+            Self::SenderNotificationPermission { key } => {
+                let Some(power_levels) = &context.power_levels else {
+                    return false;
+                };
+
+                let Some(sender_id) =
+                    event.get_str("sender").and_then(|sender| <&UserId>::try_from(sender).ok())
+                else {
+                    return false;
+                };
+
+                let sender_level =
+                    power_levels.users.get(sender_id).unwrap_or(&power_levels.users_default);
+
+                match power_levels.notifications.get(key) {
+                    Some(l) => sender_level >= l,
+                    None => false,
+                }
+            }
+            #[cfg(feature = "unstable-msc3931")]
+            Self::RoomVersionSupports { feature } => match feature {
+                RoomVersionFeature::ExtensibleEvents => {
+                    context.supported_features.contains(&RoomVersionFeature::ExtensibleEvents)
+                }
+                RoomVersionFeature::_Custom(_) => false,
+            },
+            Self::EventPropertyIs { key, value } => event.get(key).is_some_and(|v| v == value),
+            Self::EventPropertyContains { key, value } => {
+                event.get(key).and_then(|v| v.as_array()).is_some_and(|a| a.contains(value))
+            }
+            Self::Never => false,
+        }
+    }
+}
+
+/// A precompiled glob pattern.
+#[derive(Clone, Debug)]
+enum PatternMatcher {
+    /// Matches the whole value against a glob.
+    Glob(WildMatch),
+
+    /// Matches words of the value against a pattern without wildcards.
+    Word(String),
+
+    /// Matches words of the value against a glob.
+    WordGlob {
+        /// The lowercase pattern, to match values that are equal to it.
+        pattern: String,
+
+        /// The regex equivalent to the pattern.
+        regex: Regex,
+    },
+}
+
+impl PatternMatcher {
+    fn new(pattern: &str, match_words: bool) -> Self {
+        let pattern = pattern.to_lowercase();
+
+        if !match_words {
+            Self::Glob(WildMatch::new(&pattern))
+        } else if !pattern.is_empty() && condition::has_wildcards(&pattern) {
+            let regex = condition::word_regex(&pattern);
+            Self::WordGlob { pattern, regex }
+        } else {
+            Self::Word(pattern)
+        }
+    }
+
+    /// Matches the value against the pattern, case-insensitively.
+    ///
+    /// This has the same behavior as `StrExt::matches_pattern()`.
+    fn matches(&self, value: &str) -> bool {
+        let value = value.to_lowercase();
+
+        match self {
+            Self::Glob(glob) => glob.matches(&value),
+            Self::Word(pattern) => value.matches_word(pattern),
+            Self::WordGlob { pattern, regex } => {
+                *pattern == value || regex.is_match(value.as_bytes())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_matches2::assert_matches;
+    use js_int::{int, uint};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::CompiledRuleset;
+    use crate::{
+        owned_room_id, owned_user_id,
+        power_levels::NotificationPowerLevels,
+        push::{
+            Action, FlattenedJson, NewConditionalPushRule, NewPatternedPushRule, NewPushRule,
+            NewSimplePushRule, PredefinedOverrideRuleId, PushCondition,
+            PushConditionPowerLevelsCtx, PushConditionRoomCtx, RuleKind, Ruleset,
+        },
+        serde::Raw,
+        user_id, OwnedUserId,
+    };
+
+    fn context(user_id: OwnedUserId, display_name: &str) -> PushConditionRoomCtx {
+        PushConditionRoomCtx {
+            room_id: owned_room_id!("!room:server.name"),
+            member_count: uint!(3),
+            user_id,
+            user_display_name: display_name.to_owned(),
+            power_levels: Some(PushConditionPowerLevelsCtx {
+                users: BTreeMap::new(),
+                users_default: int!(50),
+                notifications: NotificationPowerLevels { room: int!(50) },
+            }),
+            #[cfg(feature = "unstable-msc3931")]
+            supported_features: Default::default(),
+        }
+    }
+
+    fn ruleset() -> Ruleset {
+        let mut set = Ruleset::server_default(user_id!("@jolly_jumper:server.name"));
+
+        set.insert(
+            NewPushRule::Content(NewPatternedPushRule::new(
+                "saloon".to_owned(),
+                "sal*n".to_owned(),
+                vec![Action::Notify],
+            )),
+            None,
+            None,
+        )
+        .unwrap();
+        set.insert(
+            NewPushRule::Sender(NewSimplePushRule::new(
+                owned_user_id!("@dalton:server.name"),
+                vec![],
+            )),
+            None,
+            None,
+        )
+        .unwrap();
+        set.insert(
+            NewPushRule::Override(NewConditionalPushRule::new(
+                "lucky_luke".to_owned(),
+                vec![PushCondition::EventPropertyIs {
+                    key: r"content.com\.example\.lucky".to_owned(),
+                    value: true.into(),
+                }],
+                vec![Action::Notify],
+            )),
+            None,
+            None,
+        )
+        .unwrap();
+
+        set
+    }
+
+    fn events() -> Vec<FlattenedJson> {
+        [
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": { "msgtype": "m.text", "body": "Hello" },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": { "msgtype": "m.text", "body": "Hello jolly_jumper" },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": { "msgtype": "m.text", "body": "Hello Jolly Jumper" },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": {
+                    "msgtype": "m.text",
+                    "body": "Hello Jolly Jumper",
+                    "m.mentions": {},
+                },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": { "msgtype": "m.text", "body": "Meet me at the SALOON." },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": { "msgtype": "m.notice", "body": "Hello Jolly Jumper" },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@dalton:server.name",
+                "content": { "msgtype": "m.text", "body": "Hello @room" },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@rantanplan:server.name",
+                "content": { "msgtype": "m.text", "body": "Hello", "com.example.lucky": true },
+            }),
+            json!({
+                "type": "m.room.message",
+                "sender": "@jolly_jumper:server.name",
+                "content": { "msgtype": "m.text", "body": "Hello Jolly Jumper" },
+            }),
+            json!({
+                "type": "m.call.invite",
+                "sender": "@rantanplan:server.name",
+                "content": {},
+            }),
+        ]
+        .iter()
+        .map(|json| FlattenedJson::from_raw(&Raw::<JsonValue>::new(json).unwrap()))
+        .collect()
+    }
+
+    #[test]
+    fn same_match_as_ruleset() {
+        let set = ruleset();
+        let compiled = set.compile();
+        let context = context(owned_user_id!("@jolly_jumper:server.name"), "Jolly Jumper");
+
+        for event in events() {
+            let rule = set.iter().find(|rule| rule.applies(&event, &context));
+            let compiled_rule = compiled.get_match(&event, &context);
+
+            assert_eq!(
+                compiled_rule.map(|rule| rule.rule_id()),
+                rule.map(|rule| rule.rule_id()),
+                "{event:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let mut set = ruleset();
+        set.set_enabled(RuleKind::Override, PredefinedOverrideRuleId::Master, true).unwrap();
+        set.set_enabled(RuleKind::Override, PredefinedOverrideRuleId::SuppressNotices, false)
+            .unwrap();
+
+        let compiled = set.compile();
+        assert_eq!(compiled.iter().count(), set.iter().filter(|rule| rule.enabled()).count());
+
+        let context = context(owned_user_id!("@jolly_jumper:server.name"), "Jolly Jumper");
+        for event in events() {
+            if let Some(rule) = compiled.get_match(&event, &context) {
+                assert_eq!(rule.rule_id(), PredefinedOverrideRuleId::Master.as_ref());
+            }
+        }
+    }
+
+    #[test]
+    fn batch() {
+        let jolly_jumper_set = ruleset().compile();
+        let jolly_jumper_context =
+            context(owned_user_id!("@jolly_jumper:server.name"), "Jolly Jumper");
+        let rantanplan_set = Ruleset::server_default(user_id!("@rantanplan:server.name")).compile();
+        let rantanplan_context = context(owned_user_id!("@rantanplan:server.name"), "Rantanplan");
+
+        let events = events();
+        let matches = CompiledRuleset::get_match_batch(
+            &events[2],
+            [(&jolly_jumper_set, &jolly_jumper_context), (&rantanplan_set, &rantanplan_context)],
+        );
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            matches[0].unwrap().rule_id(),
+            jolly_jumper_set.get_match(&events[2], &jolly_jumper_context).unwrap().rule_id()
+        );
+        assert_matches!(matches[1], None);
+    }
+}
@@ -209,6 +209,39 @@ impl PushCondition {
             Self::_Custom(_) => false,
         }
     }
+
+    /// Get the value of the event or of the context that this condition looks at.
+    ///
+    /// Returns `None` if the value is missing or if the condition doesn't look at a single value.
+    pub(super) fn checked_value(
+        &self,
+        event: &FlattenedJson,
+        context: &PushConditionRoomCtx,
+    ) -> Option<FlattenedJsonValue> {
+        match self {
+            Self::EventMatch { key, .. } => match key.as_str() {
+                "room_id" => Some(context.room_id.as_str().into()),
+                _ => event.get(key).cloned(),
+            },
+            Self::ContainsDisplayName => event.get("content.body").cloned(),
+            Self::RoomMemberCount { .. } => Some(Int::from(context.member_count).into()),
+            Self::SenderNotificationPermission { .. } => {
+                let power_levels = context.power_levels.as_ref()?;
+                let sender_id = <&UserId>::try_from(event.get_str("sender")?).ok()?;
+
+                Some(
+                    (*power_levels.users.get(sender_id).unwrap_or(&power_levels.users_default))
+                        .into(),
+                )
+            }
+            #[cfg(feature = "unstable-msc3931")]
+            Self::RoomVersionSupports { .. } => None,
+            Self::EventPropertyIs { key, .. } | Self::EventPropertyContains { key, .. } => {
+                event.get(key).cloned()
+            }
+            Self::_Custom(_) => None,
+        }
+    }
 }
 
 /// An unknown push condition.
@@ -277,7 +310,7 @@ impl CharExt for char {
 }
 
 /// Additional functions for string matching.
-trait StrExt {
+pub(super) trait StrExt {
     /// Get the length of the char at `index`. The byte index must correspond to
     /// the start of a char boundary.
     fn char_len(&self, index: usize) -> usize;
@@ -364,43 +397,8 @@ impl StrExt for str {
             return false;
         }
 
-        let has_wildcards = pattern.contains(|c| matches!(c, '?' | '*'));
-
-        if has_wildcards {
-            let mut chunks: Vec<String> = vec![];
-            let mut prev_wildcard = false;
-            let mut chunk_start = 0;
-
-            for (i, c) in pattern.char_indices() {
-                if matches!(c, '?' | '*') && !prev_wildcard {
-                    if i != 0 {
-                        chunks.push(regex::escape(&pattern[chunk_start..i]));
-                        chunk_start = i;
-                    }
-
-                    prev_wildcard = true;
-                } else if prev_wildcard {
-                    let chunk = &pattern[chunk_start..i];
-                    chunks.push(chunk.wildcards_to_regex());
-
-                    chunk_start = i;
-                    prev_wildcard = false;
-                }
-            }
-
-            let len = pattern.len();
-            if !prev_wildcard {
-                chunks.push(regex::escape(&pattern[chunk_start..len]));
-            } else if prev_wildcard {
-                let chunk = &pattern[chunk_start..len];
-                chunks.push(chunk.wildcards_to_regex());
-            }
-
-            // The word characters in ASCII compatible mode (with the `-u` flag) match the
-            // definition in the spec: any character not in the set `[A-Za-z0-9_]`.
-            let regex = format!(r"(?-u:^|\W|\b){}(?-u:\b|\W|$)", chunks.concat());
-            let re = Regex::new(&regex).expect("regex construction should succeed");
-            re.is_match(self.as_bytes())
+        if has_wildcards(pattern) {
+            word_regex(pattern).is_match(self.as_bytes())
         } else {
             match self.find(pattern) {
                 Some(start) => {
@@ -454,6 +452,50 @@ impl StrExt for str {
     }
 }
 
+/// Whether the given pattern contains glob wildcards.
+pub(super) fn has_wildcards(pattern: &str) -> bool {
+    pattern.contains(|c| matches!(c, '?' | '*'))
+}
+
+/// Build the regex to match the given glob pattern with word boundaries.
+///
+/// See [`StrExt::matches_word()`] for more details.
+pub(super) fn word_regex(pattern: &str) -> Regex {
+    let mut chunks: Vec<String> = vec![];
+    let mut prev_wildcard = false;
+    let mut chunk_start = 0;
+
+    for (i, c) in pattern.char_indices() {
+        if matches!(c, '?' | '*') && !prev_wildcard {
+            if i != 0 {
+                chunks.push(regex::escape(&pattern[chunk_start..i]));
+                chunk_start = i;
+            }
+
+            prev_wildcard = true;
+        } else if prev_wildcard {
+            let chunk = &pattern[chunk_start..i];
+            chunks.push(chunk.wildcards_to_regex());
+
+            chunk_start = i;
+            prev_wildcard = false;
+        }
+    }
+
+    let len = pattern.len();
+    if !prev_wildcard {
+        chunks.push(regex::escape(&pattern[chunk_start..len]));
+    } else if prev_wildcard {
+        let chunk = &pattern[chunk_start..len];
+        chunks.push(chunk.wildcards_to_regex());
+    }
+
+    // The word characters in ASCII compatible mode (with the `-u` flag) match the
+    // definition in the spec: any character not in the set `[A-Za-z0-9_]`.
+    let regex = format!(r"(?-u:^|\W|\b){}(?-u:\b|\W|$)", chunks.concat());
+    Regex::new(&regex).expect("regex construction should succeed")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
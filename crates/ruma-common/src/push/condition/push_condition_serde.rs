@@ -46,12 +46,36 @@ impl<'de> Deserialize<'de> for PushCondition {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl crate::json_schema::JsonSchema for PushCondition {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("PushCondition")
+    }
+
+    fn json_schema(generator: &mut crate::json_schema::SchemaGenerator) -> serde_json::Value {
+        serde_json::json!({
+            "anyOf": [
+                PushConditionSerDeHelper::json_schema(generator),
+                // Custom conditions.
+                {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "type": "string" },
+                    },
+                    "required": ["kind"],
+                },
+            ],
+        })
+    }
+}
+
 #[derive(Deserialize)]
 struct ExtractKind {
     kind: String,
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 enum PushConditionSerDeHelper {
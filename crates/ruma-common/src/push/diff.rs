@@ -0,0 +1,791 @@
+//! Types to compute the differences between two push rulesets and to apply them.
+
+use serde::Serialize;
+use thiserror::Error;
+
+use super::{
+    Action, AnyPushRuleRef, InsertPushRuleError, NewConditionalPushRule, NewPatternedPushRule,
+    NewPushRule, NewSimplePushRule, RemovePushRuleError, RuleKind, RuleNotFoundError, Ruleset,
+};
+
+impl Ruleset {
+    /// Compute the changes needed to go from this ruleset to the `other` ruleset.
+    ///
+    /// The changes are computed so they can be sent to the homeserver as `set_pushrule`,
+    /// `delete_pushrule`, `set_pushrule_enabled` and `set_pushrule_actions` requests, in order.
+    /// This means that:
+    ///
+    /// * Only the `enabled` state and the `actions` of server-default rules are compared, since
+    ///   they can't be added, removed, moved or otherwise modified by clients.
+    /// * User-defined rules are only positioned relative to other user-defined rules.
+    ///
+    /// Applying the diff to this ruleset with [`RulesetDiff::apply()`] results in a ruleset with
+    /// the same rules as `other`, in the same order.
+    ///
+    /// Server-default rules that were renamed or removed in `other` can be shadowed by
+    /// user-defined rules, which can't be migrated automatically. They are reported in
+    /// [`RulesetDiff::shadowing_rules`].
+    pub fn diff(&self, other: &Ruleset) -> RulesetDiff {
+        let mut changes = Vec::new();
+        let mut shadowing_rules = Vec::new();
+
+        for kind in [
+            RuleKind::Override,
+            RuleKind::Content,
+            RuleKind::Room,
+            RuleKind::Sender,
+            RuleKind::Underride,
+        ] {
+            diff_rules_of_kind(self, other, kind.clone(), &mut changes);
+            shadowing_rules_of_kind(self, other, kind, &mut shadowing_rules);
+        }
+
+        RulesetDiff { changes, shadowing_rules }
+    }
+}
+
+/// Compute the changes between the rules of the given kind in the two rulesets.
+fn diff_rules_of_kind(
+    from: &Ruleset,
+    to: &Ruleset,
+    kind: RuleKind,
+    changes: &mut Vec<PushRuleChange>,
+) {
+    let old_rules = rules_of_kind(from, &kind);
+    let new_rules = rules_of_kind(to, &kind);
+
+    // Remove the user-defined rules that don't exist anymore.
+    for old_rule in old_rules.iter().filter(|rule| !rule.is_server_default()) {
+        if to.get(kind.clone(), old_rule.rule_id()).is_none() {
+            changes.push(PushRuleChange::Removed {
+                kind: kind.clone(),
+                rule_id: old_rule.rule_id().to_owned(),
+            });
+        }
+    }
+
+    // Only the state of server-default rules can change.
+    for new_rule in new_rules.iter().filter(|rule| rule.is_server_default()) {
+        let Some(old_rule) = from.get(kind.clone(), new_rule.rule_id()) else {
+            continue;
+        };
+
+        push_state_changes(&kind, old_rule, *new_rule, changes);
+    }
+
+    let mut current = old_rules
+        .iter()
+        .filter(|rule| !rule.is_server_default() && to.get(kind.clone(), rule.rule_id()).is_some())
+        .map(|rule| rule.rule_id())
+        .collect::<Vec<_>>();
+    let new_user_rules =
+        new_rules.into_iter().filter(|rule| !rule.is_server_default()).collect::<Vec<_>>();
+    let unmoved = unmoved_rules(&current, from, &new_user_rules);
+
+    let mut previous_rule_id: Option<&str> = None;
+    for new_rule in new_user_rules {
+        let rule_id = new_rule.rule_id();
+        let old_rule = from.get(kind.clone(), rule_id).filter(|rule| !rule.is_server_default());
+
+        if let Some(old_rule) = old_rule.filter(|_| unmoved.contains(&rule_id)) {
+            push_state_changes(&kind, old_rule, new_rule, changes);
+        } else {
+            // The rule must be (re-)inserted, so place it right after the previous rule.
+            current.retain(|id| *id != rule_id);
+
+            let (after, before) = match previous_rule_id {
+                Some(previous_rule_id) => {
+                    let idx = current
+                        .iter()
+                        .position(|id| *id == previous_rule_id)
+                        .map_or(0, |idx| idx + 1);
+                    current.insert(idx, rule_id);
+                    (Some(previous_rule_id.to_owned()), None)
+                }
+                None => {
+                    let before = current.first().map(|id| (*id).to_owned());
+                    current.insert(0, rule_id);
+                    (None, before)
+                }
+            };
+
+            let rule = to_new_push_rule(new_rule);
+
+            match old_rule {
+                None => {
+                    changes.push(PushRuleChange::Added { rule, after, before });
+
+                    // New rules are always enabled.
+                    if !new_rule.enabled() {
+                        changes.push(PushRuleChange::EnabledChanged {
+                            kind: kind.clone(),
+                            rule_id: rule_id.to_owned(),
+                            enabled: false,
+                        });
+                    }
+                }
+                Some(old_rule) => {
+                    if has_same_definition(old_rule, new_rule) {
+                        changes.push(PushRuleChange::Moved { rule, after, before });
+                    } else {
+                        changes.push(PushRuleChange::Modified { rule, after, before });
+                    }
+
+                    // Replacing a rule keeps its previous `enabled` state.
+                    if old_rule.enabled() != new_rule.enabled() {
+                        changes.push(PushRuleChange::EnabledChanged {
+                            kind: kind.clone(),
+                            rule_id: rule_id.to_owned(),
+                            enabled: new_rule.enabled(),
+                        });
+                    }
+                }
+            }
+        }
+
+        previous_rule_id = Some(rule_id);
+    }
+}
+
+/// Find the user-defined rules of `to` that shadow the server-default rules of the given kind that
+/// are in `from` but not in `to`.
+///
+/// A user-defined rule shadows a server-default rule if it has the same ID or if it matches the
+/// same events.
+fn shadowing_rules_of_kind(
+    from: &Ruleset,
+    to: &Ruleset,
+    kind: RuleKind,
+    shadowing_rules: &mut Vec<ShadowingPushRule>,
+) {
+    let new_rules = rules_of_kind(to, &kind);
+    let removed_server_default_rules = rules_of_kind(from, &kind).into_iter().filter(|rule| {
+        rule.is_server_default()
+            && !new_rules.iter().any(|new_rule| {
+                new_rule.is_server_default() && new_rule.rule_id() == rule.rule_id()
+            })
+    });
+
+    for server_default_rule in removed_server_default_rules {
+        let shadowing = new_rules.iter().filter(|rule| {
+            !rule.is_server_default()
+                && (rule.rule_id() == server_default_rule.rule_id()
+                    || matches!(kind, RuleKind::Override | RuleKind::Content | RuleKind::Underride)
+                        && has_same_definition(server_default_rule, **rule))
+        });
+
+        for rule in shadowing {
+            shadowing_rules.push(ShadowingPushRule {
+                kind: kind.clone(),
+                rule_id: rule.rule_id().to_owned(),
+                server_default_rule_id: server_default_rule.rule_id().to_owned(),
+            });
+        }
+    }
+}
+
+/// Get the rules of the given kind in the ruleset, in order of priority.
+fn rules_of_kind<'a>(ruleset: &'a Ruleset, kind: &RuleKind) -> Vec<AnyPushRuleRef<'a>> {
+    match kind {
+        RuleKind::Override => ruleset.override_.iter().map(AnyPushRuleRef::Override).collect(),
+        RuleKind::Content => ruleset.content.iter().map(AnyPushRuleRef::Content).collect(),
+        RuleKind::Room => ruleset.room.iter().map(AnyPushRuleRef::Room).collect(),
+        RuleKind::Sender => ruleset.sender.iter().map(AnyPushRuleRef::Sender).collect(),
+        RuleKind::Underride => ruleset.underride.iter().map(AnyPushRuleRef::Underride).collect(),
+        RuleKind::_Custom(_) => Vec::new(),
+    }
+}
+
+/// Get the IDs of the user-defined rules that don't need to be moved or modified.
+///
+/// They are the longest sequence of unmodified rules that are in the same order in both rulesets.
+fn unmoved_rules<'a>(
+    current: &[&str],
+    from: &Ruleset,
+    new_user_rules: &[AnyPushRuleRef<'a>],
+) -> Vec<&'a str> {
+    let candidates = new_user_rules
+        .iter()
+        .filter_map(|new_rule| {
+            let old_idx = current.iter().position(|id| *id == new_rule.rule_id())?;
+            let old_rule = from.get(rule_kind(*new_rule), new_rule.rule_id())?;
+            has_same_definition(old_rule, *new_rule).then_some((old_idx, new_rule.rule_id()))
+        })
+        .collect::<Vec<_>>();
+
+    // Longest increasing subsequence of the old indexes, the lists are small so the quadratic
+    // algorithm is fine.
+    let mut lengths = vec![1; candidates.len()];
+    let mut previous = vec![None; candidates.len()];
+
+    for i in 0..candidates.len() {
+        for j in 0..i {
+            if candidates[j].0 < candidates[i].0 && lengths[j] + 1 > lengths[i] {
+                lengths[i] = lengths[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+
+    let mut unmoved = Vec::new();
+    let mut next = lengths.iter().enumerate().max_by_key(|(_, len)| **len).map(|(idx, _)| idx);
+
+    while let Some(idx) = next {
+        unmoved.push(candidates[idx].1);
+        next = previous[idx];
+    }
+
+    unmoved
+}
+
+/// Push the changes of `enabled` state and `actions` between the two versions of a rule.
+fn push_state_changes(
+    kind: &RuleKind,
+    old_rule: AnyPushRuleRef<'_>,
+    new_rule: AnyPushRuleRef<'_>,
+    changes: &mut Vec<PushRuleChange>,
+) {
+    if !has_same_json(old_rule.actions(), new_rule.actions()) {
+        changes.push(PushRuleChange::ActionsChanged {
+            kind: kind.clone(),
+            rule_id: new_rule.rule_id().to_owned(),
+            actions: new_rule.actions().to_owned(),
+        });
+    }
+
+    if old_rule.enabled() != new_rule.enabled() {
+        changes.push(PushRuleChange::EnabledChanged {
+            kind: kind.clone(),
+            rule_id: new_rule.rule_id().to_owned(),
+            enabled: new_rule.enabled(),
+        });
+    }
+}
+
+/// Whether the two versions of a rule match the same events.
+fn has_same_definition(old_rule: AnyPushRuleRef<'_>, new_rule: AnyPushRuleRef<'_>) -> bool {
+    match (old_rule, new_rule) {
+        (AnyPushRuleRef::Override(old), AnyPushRuleRef::Override(new))
+        | (AnyPushRuleRef::Underride(old), AnyPushRuleRef::Underride(new)) => {
+            has_same_json(&old.conditions, &new.conditions)
+        }
+        (AnyPushRuleRef::Content(old), AnyPushRuleRef::Content(new)) => old.pattern == new.pattern,
+        (AnyPushRuleRef::Room(_), AnyPushRuleRef::Room(_))
+        | (AnyPushRuleRef::Sender(_), AnyPushRuleRef::Sender(_)) => true,
+        _ => false,
+    }
+}
+
+/// Whether the two values have the same JSON representation.
+///
+/// This is used for types that don't implement `PartialEq`, like `Action` and `PushCondition`.
+fn has_same_json<T: Serialize + ?Sized>(a: &T, b: &T) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Get the kind of the given rule.
+fn rule_kind(rule: AnyPushRuleRef<'_>) -> RuleKind {
+    match rule {
+        AnyPushRuleRef::Override(_) => RuleKind::Override,
+        AnyPushRuleRef::Content(_) => RuleKind::Content,
+        AnyPushRuleRef::Room(_) => RuleKind::Room,
+        AnyPushRuleRef::Sender(_) => RuleKind::Sender,
+        AnyPushRuleRef::Underride(_) => RuleKind::Underride,
+    }
+}
+
+/// Convert the given rule to a `NewPushRule`.
+fn to_new_push_rule(rule: AnyPushRuleRef<'_>) -> NewPushRule {
+    match rule {
+        AnyPushRuleRef::Override(r) => NewPushRule::Override(NewConditionalPushRule::new(
+            r.rule_id.clone(),
+            r.conditions.clone(),
+            r.actions.clone(),
+        )),
+        AnyPushRuleRef::Content(r) => NewPushRule::Content(NewPatternedPushRule::new(
+            r.rule_id.clone(),
+            r.pattern.clone(),
+            r.actions.clone(),
+        )),
+        AnyPushRuleRef::Room(r) => {
+            NewPushRule::Room(NewSimplePushRule::new(r.rule_id.clone(), r.actions.clone()))
+        }
+        AnyPushRuleRef::Sender(r) => {
+            NewPushRule::Sender(NewSimplePushRule::new(r.rule_id.clone(), r.actions.clone()))
+        }
+        AnyPushRuleRef::Underride(r) => NewPushRule::Underride(NewConditionalPushRule::new(
+            r.rule_id.clone(),
+            r.conditions.clone(),
+            r.actions.clone(),
+        )),
+    }
+}
+
+/// The differences between two push rulesets.
+///
+/// To construct this, use [`Ruleset::diff()`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RulesetDiff {
+    /// The changes to apply, in order.
+    pub changes: Vec<PushRuleChange>,
+
+    /// The user-defined rules of the new ruleset that shadow a server-default rule that was
+    /// renamed or removed.
+    ///
+    /// These rules are not changed by the diff. Clients should check whether they are still
+    /// needed, and remove them or replace them with a rule matching the new server-default rules.
+    pub shadowing_rules: Vec<ShadowingPushRule>,
+}
+
+impl RulesetDiff {
+    /// Whether there are no changes between the two rulesets.
+    ///
+    /// This doesn't take the [`shadowing_rules`](Self::shadowing_rules) into account, since they
+    /// don't need to be sent to the homeserver.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply the changes of this diff to the given ruleset, in order.
+    ///
+    /// Stops at the first change that can't be applied, in which case the ruleset is left with
+    /// the changes that were applied before it.
+    pub fn apply(&self, ruleset: &mut Ruleset) -> Result<(), ApplyPushRuleChangeError> {
+        self.changes.iter().try_for_each(|change| change.apply(ruleset))
+    }
+}
+
+impl IntoIterator for RulesetDiff {
+    type Item = PushRuleChange;
+    type IntoIter = std::vec::IntoIter<PushRuleChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+/// A user-defined rule that shadows a server-default rule that was renamed or removed.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct ShadowingPushRule {
+    /// The kind of the rules.
+    pub kind: RuleKind,
+
+    /// The ID of the user-defined rule.
+    pub rule_id: String,
+
+    /// The ID of the server-default rule that is shadowed, in the old ruleset.
+    pub server_default_rule_id: String,
+}
+
+/// A change to a single push rule.
+///
+/// Each change corresponds to a single request to the homeserver.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum PushRuleChange {
+    /// A user-defined rule was added.
+    ///
+    /// Corresponds to a `set_pushrule` request.
+    Added {
+        /// The rule to add.
+        rule: NewPushRule,
+
+        /// The ID of the user-defined rule that should have a higher priority than this rule.
+        after: Option<String>,
+
+        /// The ID of the user-defined rule that should have a lower priority than this rule.
+        before: Option<String>,
+    },
+
+    /// The conditions or pattern of a user-defined rule were modified.
+    ///
+    /// Corresponds to a `set_pushrule` request.
+    Modified {
+        /// The new version of the rule.
+        rule: NewPushRule,
+
+        /// The ID of the user-defined rule that should have a higher priority than this rule.
+        after: Option<String>,
+
+        /// The ID of the user-defined rule that should have a lower priority than this rule.
+        before: Option<String>,
+    },
+
+    /// A user-defined rule was moved.
+    ///
+    /// Corresponds to a `set_pushrule` request.
+    Moved {
+        /// The rule to move.
+        rule: NewPushRule,
+
+        /// The ID of the user-defined rule that should have a higher priority than this rule.
+        after: Option<String>,
+
+        /// The ID of the user-defined rule that should have a lower priority than this rule.
+        before: Option<String>,
+    },
+
+    /// A user-defined rule was removed.
+    ///
+    /// Corresponds to a `delete_pushrule` request.
+    Removed {
+        /// The kind of the rule.
+        kind: RuleKind,
+
+        /// The ID of the rule.
+        rule_id: String,
+    },
+
+    /// A rule was enabled or disabled.
+    ///
+    /// Corresponds to a `set_pushrule_enabled` request.
+    EnabledChanged {
+        /// The kind of the rule.
+        kind: RuleKind,
+
+        /// The ID of the rule.
+        rule_id: String,
+
+        /// Whether the rule is enabled.
+        enabled: bool,
+    },
+
+    /// The actions of a rule were changed.
+    ///
+    /// Corresponds to a `set_pushrule_actions` request.
+    ActionsChanged {
+        /// The kind of the rule.
+        kind: RuleKind,
+
+        /// The ID of the rule.
+        rule_id: String,
+
+        /// The new actions of the rule.
+        actions: Vec<Action>,
+    },
+}
+
+impl PushRuleChange {
+    /// The kind of the rule affected by this change.
+    pub fn kind(&self) -> RuleKind {
+        match self {
+            Self::Added { rule, .. } | Self::Modified { rule, .. } | Self::Moved { rule, .. } => {
+                rule.kind()
+            }
+            Self::Removed { kind, .. }
+            | Self::EnabledChanged { kind, .. }
+            | Self::ActionsChanged { kind, .. } => kind.clone(),
+        }
+    }
+
+    /// The ID of the rule affected by this change.
+    pub fn rule_id(&self) -> &str {
+        match self {
+            Self::Added { rule, .. } | Self::Modified { rule, .. } | Self::Moved { rule, .. } => {
+                rule.rule_id()
+            }
+            Self::Removed { rule_id, .. }
+            | Self::EnabledChanged { rule_id, .. }
+            | Self::ActionsChanged { rule_id, .. } => rule_id,
+        }
+    }
+
+    /// Apply this change to the given ruleset.
+    ///
+    /// This has the same effect on the ruleset as the corresponding request would have on the
+    /// homeserver.
+    pub fn apply(&self, ruleset: &mut Ruleset) -> Result<(), ApplyPushRuleChangeError> {
+        match self {
+            Self::Added { rule, after, before }
+            | Self::Modified { rule, after, before }
+            | Self::Moved { rule, after, before } => {
+                ruleset.insert(rule.clone(), after.as_deref(), before.as_deref())?;
+            }
+            Self::Removed { kind, rule_id } => ruleset.remove(kind.clone(), rule_id)?,
+            Self::EnabledChanged { kind, rule_id, enabled } => {
+                ruleset.set_enabled(kind.clone(), rule_id, *enabled)?;
+            }
+            Self::ActionsChanged { kind, rule_id, actions } => {
+                ruleset.set_actions(kind.clone(), rule_id, actions.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The error type returned when a [`PushRuleChange`] can't be applied to a `Ruleset`.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ApplyPushRuleChangeError {
+    /// The rule could not be inserted.
+    #[error(transparent)]
+    Insert(#[from] InsertPushRuleError),
+
+    /// The rule could not be removed.
+    #[error(transparent)]
+    Remove(#[from] RemovePushRuleError),
+
+    /// The rule to modify could not be found.
+    #[error(transparent)]
+    NotFound(#[from] RuleNotFoundError),
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches2::assert_matches;
+
+    use super::PushRuleChange;
+    use crate::{
+        owned_room_id, owned_user_id,
+        push::{
+            Action, ConditionalPushRule, NewConditionalPushRule, NewPatternedPushRule, NewPushRule,
+            NewSimplePushRule, PushCondition, RuleKind, Ruleset, Tweak,
+        },
+    };
+
+    fn user_rule_ids(ruleset: &Ruleset, kind: RuleKind) -> Vec<String> {
+        ruleset
+            .iter()
+            .filter(|rule| !rule.is_server_default() && super::rule_kind(*rule) == kind)
+            .map(|rule| rule.rule_id().to_owned())
+            .collect()
+    }
+
+    fn insert_room_rules(ruleset: &mut Ruleset, ids: &[&str]) {
+        for id in ids.iter().rev() {
+            let rule = NewSimplePushRule::new(
+                format!("!{id}:localhost").try_into().unwrap(),
+                vec![Action::Notify],
+            );
+            ruleset.insert(NewPushRule::Room(rule), None, None).unwrap();
+        }
+    }
+
+    fn assert_diff_applies(from: &Ruleset, to: &Ruleset) {
+        let diff = from.diff(to);
+        let mut ruleset = from.clone();
+        diff.apply(&mut ruleset).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&ruleset).unwrap(),
+            serde_json::to_value(to).unwrap(),
+            "{diff:#?}"
+        );
+        assert!(ruleset.diff(to).is_empty());
+    }
+
+    #[test]
+    fn no_changes() {
+        let ruleset = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        assert!(ruleset.diff(&ruleset.clone()).is_empty());
+    }
+
+    #[test]
+    fn server_default_changes() {
+        let from = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        let mut to = from.clone();
+        to.set_enabled(RuleKind::Override, ".m.rule.master", true).unwrap();
+        to.set_actions(RuleKind::Underride, ".m.rule.message", vec![]).unwrap();
+
+        let diff = from.diff(&to);
+        assert_eq!(diff.changes.len(), 2);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::EnabledChanged { kind: RuleKind::Override, rule_id, enabled: true }
+        );
+        assert_eq!(rule_id, ".m.rule.master");
+        assert_matches!(
+            &diff.changes[1],
+            PushRuleChange::ActionsChanged { kind: RuleKind::Underride, rule_id, actions }
+        );
+        assert_eq!(rule_id, ".m.rule.message");
+        assert!(actions.is_empty());
+
+        assert_diff_applies(&from, &to);
+    }
+
+    #[test]
+    fn user_defined_changes() {
+        let from = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        let mut to = from.clone();
+
+        let keyword = NewPatternedPushRule::new(
+            "keyword".to_owned(),
+            "rustacean".to_owned(),
+            vec![Action::Notify, Action::SetTweak(Tweak::Highlight(true))],
+        );
+        to.insert(NewPushRule::Content(keyword), None, None).unwrap();
+        let muted = NewConditionalPushRule::new(
+            "muted".to_owned(),
+            vec![PushCondition::EventMatch {
+                key: "room_id".to_owned(),
+                pattern: "!room:localhost".to_owned(),
+            }],
+            vec![],
+        );
+        to.insert(NewPushRule::Override(muted), None, None).unwrap();
+        to.set_enabled(RuleKind::Override, "muted", false).unwrap();
+
+        let diff = from.diff(&to);
+        assert_eq!(diff.changes.len(), 3);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::Added { rule, after: None, before: None }
+        );
+        assert_eq!(rule.rule_id(), "muted");
+        assert_matches!(
+            &diff.changes[1],
+            PushRuleChange::EnabledChanged { kind: RuleKind::Override, enabled: false, .. }
+        );
+        assert_matches!(
+            &diff.changes[2],
+            PushRuleChange::Added { rule, after: None, before: None }
+        );
+        assert_eq!(rule.rule_id(), "keyword");
+        assert_diff_applies(&from, &to);
+
+        // Modify and remove the rules.
+        let mut modified = to.clone();
+        let keyword = NewPatternedPushRule::new(
+            "keyword".to_owned(),
+            "ferris".to_owned(),
+            vec![Action::Notify],
+        );
+        modified.insert(NewPushRule::Content(keyword), None, None).unwrap();
+        modified.remove(RuleKind::Override, "muted").unwrap();
+
+        let diff = to.diff(&modified);
+        assert_eq!(diff.changes.len(), 2);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::Removed { kind: RuleKind::Override, rule_id }
+        );
+        assert_eq!(rule_id, "muted");
+        assert_matches!(&diff.changes[1], PushRuleChange::Modified { rule, .. });
+        assert_eq!(rule.rule_id(), "keyword");
+        assert_diff_applies(&to, &modified);
+
+        // Only change the actions.
+        let mut actions_only = to.clone();
+        actions_only.set_actions(RuleKind::Content, "keyword", vec![]).unwrap();
+
+        let diff = to.diff(&actions_only);
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::ActionsChanged { kind: RuleKind::Content, .. }
+        );
+        assert_diff_applies(&to, &actions_only);
+    }
+
+    #[test]
+    fn reordered_rules() {
+        let mut from = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        insert_room_rules(&mut from, &["a", "b", "c", "d"]);
+        assert_eq!(
+            user_rule_ids(&from, RuleKind::Room),
+            ["!a:localhost", "!b:localhost", "!c:localhost", "!d:localhost"]
+        );
+
+        // Move a single rule to the end.
+        let mut to = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        insert_room_rules(&mut to, &["b", "c", "d", "a"]);
+
+        let diff = from.diff(&to);
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(&diff.changes[0], PushRuleChange::Moved { rule, after, before: None });
+        assert_eq!(rule.rule_id(), "!a:localhost");
+        assert_eq!(after.as_deref(), Some("!d:localhost"));
+        assert_diff_applies(&from, &to);
+
+        // Move a single rule to the start.
+        let mut to = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        insert_room_rules(&mut to, &["d", "a", "b", "c"]);
+
+        let diff = from.diff(&to);
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(&diff.changes[0], PushRuleChange::Moved { rule, after: None, before });
+        assert_eq!(rule.rule_id(), "!d:localhost");
+        assert_eq!(before.as_deref(), Some("!a:localhost"));
+        assert_diff_applies(&from, &to);
+
+        // Shuffle, add and remove rules.
+        for order in [
+            &["d", "c", "b", "a"][..],
+            &["c", "a", "d", "b"],
+            &["e", "b", "a"],
+            &["c", "e", "f", "a"],
+            &["f"],
+            &[],
+        ] {
+            let mut to = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+            insert_room_rules(&mut to, order);
+            assert_diff_applies(&from, &to);
+            assert_diff_applies(&to, &from);
+        }
+    }
+
+    #[test]
+    fn apply_to_other_ruleset() {
+        let base = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        let mut customized = base.clone();
+        customized
+            .insert(
+                NewPushRule::Room(NewSimplePushRule::new(
+                    owned_room_id!("!room:localhost"),
+                    vec![Action::Notify],
+                )),
+                None,
+                None,
+            )
+            .unwrap();
+        customized.set_enabled(RuleKind::Override, ".m.rule.suppress_notices", false).unwrap();
+
+        let mut other = Ruleset::server_default(&owned_user_id!("@lucky_luke:server.name"));
+        base.diff(&customized).apply(&mut other).unwrap();
+
+        assert_eq!(user_rule_ids(&other, RuleKind::Room), ["!room:localhost"]);
+        assert!(!other.get(RuleKind::Override, ".m.rule.suppress_notices").unwrap().enabled());
+
+        // The diff can't be applied if a rule doesn't exist.
+        let diff = customized.diff(&base);
+        diff.apply(&mut other).unwrap();
+        diff.apply(&mut other).unwrap_err();
+    }
+
+    #[test]
+    fn shadowing_rules() {
+        let mut from = Ruleset::server_default(&owned_user_id!("@jolly_jumper:server.name"));
+        #[allow(deprecated)]
+        let display_name = ConditionalPushRule::contains_display_name();
+        let shadowing = NewConditionalPushRule::new(
+            "display_name".to_owned(),
+            display_name.conditions.clone(),
+            vec![Action::Notify],
+        );
+        from.override_.insert(display_name);
+        from.insert(NewPushRule::Override(shadowing), None, None).unwrap();
+
+        // The server-default rule was removed in the new ruleset, but not the user-defined rule.
+        let mut to = from.clone();
+        to.override_.shift_remove(".m.rule.contains_display_name");
+
+        let diff = from.diff(&to);
+        assert!(diff.is_empty());
+        assert_eq!(diff.shadowing_rules.len(), 1);
+        let shadowing = &diff.shadowing_rules[0];
+        assert_eq!(shadowing.kind, RuleKind::Override);
+        assert_eq!(shadowing.rule_id, "display_name");
+        assert_eq!(shadowing.server_default_rule_id, ".m.rule.contains_display_name");
+
+        // Rules that don't shadow a removed server-default rule are not reported.
+        assert!(from.diff(&from.clone()).shadowing_rules.is_empty());
+        assert!(to.diff(&from).shadowing_rules.is_empty());
+    }
+}
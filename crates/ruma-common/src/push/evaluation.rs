@@ -0,0 +1,499 @@
+//! Types to trace the evaluation of push rules.
+
+use tracing::instrument;
+
+use super::{
+    condition, Action, AnyPushRuleRef, FlattenedJson, FlattenedJsonValue, PushCondition,
+    PushConditionRoomCtx, Ruleset,
+};
+use crate::serde::Raw;
+
+impl Ruleset {
+    /// Evaluate all the push rules of this `Ruleset` against this event.
+    ///
+    /// Contrary to [`Ruleset::get_match()`], this doesn't stop at the first push rule that applies
+    /// to the event, and it records the result of each condition of each push rule, which is
+    /// useful to understand why a push rule did or did not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The raw JSON of a room message event.
+    /// * `context` - The context of the message and room at the time of the event.
+    #[instrument(skip_all, fields(context.room_id = %context.room_id))]
+    pub fn evaluate<T>(
+        &self,
+        event: &Raw<T>,
+        context: &PushConditionRoomCtx,
+    ) -> RulesetEvaluation<'_> {
+        self.evaluate_flattened(&FlattenedJson::from_raw(event), context)
+    }
+
+    /// Get the first push rule that applies to this event, for each of the given rulesets.
+    ///
+    /// This is equivalent to calling [`Ruleset::get_match()`] for each ruleset, but the event is
+    /// only flattened once. It is meant for servers that need to evaluate the push rules of all the
+    /// members of a room for each event.
+    ///
+    /// The results are in the same order as `rulesets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The raw JSON of a room message event.
+    /// * `rulesets` - The rulesets to evaluate, with the context of the message and room at the
+    ///   time of the event for their owner.
+    pub fn get_match_batch<'a, 'b, T>(
+        event: &Raw<T>,
+        rulesets: impl IntoIterator<Item = (&'a Ruleset, &'b PushConditionRoomCtx)>,
+    ) -> Vec<Option<AnyPushRuleRef<'a>>> {
+        let event = FlattenedJson::from_raw(event);
+
+        rulesets
+            .into_iter()
+            .map(|(ruleset, context)| {
+                if event.get_str("sender").is_some_and(|sender| sender == context.user_id) {
+                    None
+                } else {
+                    ruleset.iter().find(|rule| rule.applies(&event, context))
+                }
+            })
+            .collect()
+    }
+
+    /// Evaluate all the push rules of each of the given rulesets against this event.
+    ///
+    /// This is equivalent to calling [`Ruleset::evaluate()`] for each ruleset, but the event is
+    /// only flattened once.
+    ///
+    /// The results are in the same order as `rulesets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The raw JSON of a room message event.
+    /// * `rulesets` - The rulesets to evaluate, with the context of the message and room at the
+    ///   time of the event for their owner.
+    pub fn evaluate_batch<'a, 'b, T>(
+        event: &Raw<T>,
+        rulesets: impl IntoIterator<Item = (&'a Ruleset, &'b PushConditionRoomCtx)>,
+    ) -> Vec<RulesetEvaluation<'a>> {
+        let event = FlattenedJson::from_raw(event);
+
+        rulesets
+            .into_iter()
+            .map(|(ruleset, context)| ruleset.evaluate_flattened(&event, context))
+            .collect()
+    }
+
+    fn evaluate_flattened(
+        &self,
+        event: &FlattenedJson,
+        context: &PushConditionRoomCtx,
+    ) -> RulesetEvaluation<'_> {
+        let own_event = event.get_str("sender").is_some_and(|sender| sender == context.user_id);
+
+        let rules = self
+            .iter()
+            .map(|rule| {
+                let evaluation = RuleEvaluation::new(rule, event, context);
+
+                if own_event {
+                    RuleEvaluation { outcome: RuleOutcome::OwnEvent, ..evaluation }
+                } else {
+                    evaluation
+                }
+            })
+            .collect();
+
+        RulesetEvaluation { rules }
+    }
+}
+
+/// The result of the evaluation of all the push rules of a [`Ruleset`] against an event.
+///
+/// To get an instance of this type, use [`Ruleset::evaluate()`] or [`Ruleset::evaluate_batch()`].
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RulesetEvaluation<'a> {
+    /// The evaluation of each push rule of the ruleset, by order of priority.
+    pub rules: Vec<RuleEvaluation<'a>>,
+}
+
+impl<'a> RulesetEvaluation<'a> {
+    /// Get the first push rule that applies to the event, if any.
+    ///
+    /// This is the same rule that is returned by [`Ruleset::get_match()`].
+    pub fn matched_rule(&self) -> Option<AnyPushRuleRef<'a>> {
+        self.rules
+            .iter()
+            .find(|evaluation| evaluation.outcome == RuleOutcome::Matched)
+            .map(|evaluation| evaluation.rule)
+    }
+
+    /// Get the push actions that apply to the event.
+    ///
+    /// Returns an empty slice if no push rule applies.
+    pub fn actions(&self) -> &'a [Action] {
+        self.matched_rule().map(|rule| rule.actions()).unwrap_or(&[])
+    }
+
+    /// Get the evaluation of the push rule with the given ID, if any.
+    ///
+    /// If several rules of different kinds have the same ID, the one with the highest priority is
+    /// returned.
+    pub fn get(&self, rule_id: &str) -> Option<&RuleEvaluation<'a>> {
+        self.rules.iter().find(|evaluation| evaluation.rule.rule_id() == rule_id)
+    }
+}
+
+/// The result of the evaluation of a push rule against an event.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RuleEvaluation<'a> {
+    /// The push rule that was evaluated.
+    pub rule: AnyPushRuleRef<'a>,
+
+    /// The outcome of the evaluation.
+    pub outcome: RuleOutcome,
+
+    /// The evaluation of each condition of the push rule.
+    ///
+    /// Content, room and sender rules don't have explicit conditions, so they have a single
+    /// `event_match` condition matching respectively the `content.body`, `room_id` or `sender`
+    /// with their pattern or rule ID.
+    ///
+    /// All the conditions are evaluated, even if the push rule is skipped or an earlier condition
+    /// doesn't apply.
+    pub conditions: Vec<ConditionEvaluation>,
+}
+
+impl<'a> RuleEvaluation<'a> {
+    fn new(
+        rule: AnyPushRuleRef<'a>,
+        event: &FlattenedJson,
+        context: &PushConditionRoomCtx,
+    ) -> Self {
+        let (skip_reason, conditions) = match rule {
+            AnyPushRuleRef::Override(rule) | AnyPushRuleRef::Underride(rule) => (
+                rule.skip_reason(event, context),
+                rule.conditions
+                    .iter()
+                    .map(|condition| ConditionEvaluation::new(condition.clone(), event, context))
+                    .collect(),
+            ),
+            AnyPushRuleRef::Content(rule) => (
+                rule.skip_reason(event),
+                vec![ConditionEvaluation::new(
+                    PushCondition::EventMatch {
+                        key: "content.body".to_owned(),
+                        pattern: rule.pattern.clone(),
+                    },
+                    event,
+                    context,
+                )],
+            ),
+            AnyPushRuleRef::Room(rule) => (
+                (!rule.enabled).then_some(RuleOutcome::Disabled),
+                vec![ConditionEvaluation::new(
+                    PushCondition::EventMatch {
+                        key: "room_id".to_owned(),
+                        pattern: rule.rule_id.to_string(),
+                    },
+                    event,
+                    context,
+                )],
+            ),
+            AnyPushRuleRef::Sender(rule) => (
+                (!rule.enabled).then_some(RuleOutcome::Disabled),
+                vec![ConditionEvaluation::new(
+                    PushCondition::EventMatch {
+                        key: "sender".to_owned(),
+                        pattern: rule.rule_id.to_string(),
+                    },
+                    event,
+                    context,
+                )],
+            ),
+        };
+
+        let outcome = skip_reason.unwrap_or_else(|| {
+            if conditions.iter().all(|condition| condition.applies) {
+                RuleOutcome::Matched
+            } else {
+                RuleOutcome::ConditionsFailed
+            }
+        });
+
+        Self { rule, outcome, conditions }
+    }
+}
+
+/// The outcome of the evaluation of a push rule against an event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum RuleOutcome {
+    /// The push rule applies to the event.
+    Matched,
+
+    /// The push rule is disabled.
+    Disabled,
+
+    /// The event was sent by the owner of the ruleset, so no push rule applies.
+    OwnEvent,
+
+    /// The push rule is one of the deprecated mention rules and the event has an `m.mentions`
+    /// property.
+    SupersededByMentions,
+
+    /// The room supports extensible events and the push rule doesn't have a
+    /// `room_version_supports` condition.
+    #[cfg(feature = "unstable-msc3932")]
+    UnsupportedRoomVersion,
+
+    /// At least one of the conditions of the push rule doesn't apply.
+    ConditionsFailed,
+}
+
+/// The result of the evaluation of a push condition against an event.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct ConditionEvaluation {
+    /// The condition that was evaluated.
+    pub condition: PushCondition,
+
+    /// Whether the condition applies to the event.
+    pub applies: bool,
+
+    /// The value that was checked by the condition, if any.
+    ///
+    /// Depending on the kind of condition, this is:
+    ///
+    /// * `event_match`, `event_property_is`, `event_property_contains`: the value of the property
+    ///   of the flattened event at `key`, or the room ID if the `key` is `room_id`.
+    /// * `contains_display_name`: the value of the `content.body` property of the flattened event.
+    /// * `room_member_count`: the number of members in the room.
+    /// * `sender_notification_permission`: the power level of the sender of the event.
+    ///
+    /// It is `None` if the value is missing.
+    pub value: Option<FlattenedJsonValue>,
+}
+
+impl ConditionEvaluation {
+    fn new(
+        condition: PushCondition,
+        event: &FlattenedJson,
+        context: &PushConditionRoomCtx,
+    ) -> Self {
+        let applies = match &condition {
+            // Don't check the sender, it is reported as the outcome of the rule.
+            PushCondition::EventMatch { key, pattern } => {
+                condition::check_event_match(event, key, pattern, context)
+            }
+            condition => condition.applies(event, context),
+        };
+        let value = condition.checked_value(event, context);
+
+        Self { condition, applies, value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_matches2::assert_matches;
+    use js_int::{int, uint};
+    use serde_json::{json, to_value as to_json_value, value::RawValue as RawJsonValue};
+
+    use super::{RuleOutcome, RulesetEvaluation};
+    use crate::{
+        owned_room_id, owned_user_id,
+        power_levels::NotificationPowerLevels,
+        push::{
+            AnyPushRuleRef, FlattenedJsonValue, PredefinedContentRuleId, PredefinedOverrideRuleId,
+            PredefinedUnderrideRuleId, PushCondition, PushConditionPowerLevelsCtx,
+            PushConditionRoomCtx, Ruleset,
+        },
+        serde::Raw,
+        user_id,
+    };
+
+    fn context() -> PushConditionRoomCtx {
+        PushConditionRoomCtx {
+            room_id: owned_room_id!("!dm:server.name"),
+            member_count: uint!(2),
+            user_id: owned_user_id!("@jolly_jumper:server.name"),
+            user_display_name: "Jolly Jumper".into(),
+            power_levels: Some(PushConditionPowerLevelsCtx {
+                users: BTreeMap::new(),
+                users_default: int!(50),
+                notifications: NotificationPowerLevels { room: int!(50) },
+            }),
+            #[cfg(feature = "unstable-msc3931")]
+            supported_features: Default::default(),
+        }
+    }
+
+    fn message(sender: &str, body: &str) -> Raw<serde_json::Value> {
+        Raw::from_json(
+            RawJsonValue::from_string(
+                json!({
+                    "type": "m.room.message",
+                    "sender": sender,
+                    "content": {
+                        "msgtype": "m.text",
+                        "body": body,
+                    },
+                })
+                .to_string(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn assert_same_rule(evaluation: &RulesetEvaluation<'_>, rule: Option<AnyPushRuleRef<'_>>) {
+        assert_eq!(
+            evaluation.matched_rule().map(|rule| rule.rule_id()),
+            rule.map(|rule| rule.rule_id())
+        );
+    }
+
+    #[test]
+    fn evaluate_matches_get_match() {
+        let set = Ruleset::server_default(user_id!("@jolly_jumper:server.name"));
+        let context = context();
+
+        for event in [
+            message("@rantanplan:server.name", "Hello"),
+            message("@rantanplan:server.name", "Hello Jolly Jumper"),
+            message("@rantanplan:server.name", "Hello @room"),
+            message("@jolly_jumper:server.name", "Hello Jolly Jumper"),
+        ] {
+            let evaluation = set.evaluate(&event, &context);
+            assert_eq!(evaluation.rules.len(), set.iter().count());
+            assert_same_rule(&evaluation, set.get_match(&event, &context));
+            assert_eq!(
+                to_json_value(evaluation.actions()).unwrap(),
+                to_json_value(set.get_actions(&event, &context)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn condition_trace() {
+        let set = Ruleset::server_default(user_id!("@jolly_jumper:server.name"));
+        let context = context();
+        let event = message("@rantanplan:server.name", "Hello Jolly Jumper");
+
+        let evaluation = set.evaluate(&event, &context);
+        assert_eq!(
+            evaluation.matched_rule().unwrap().rule_id(),
+            PredefinedOverrideRuleId::ContainsDisplayName.as_ref()
+        );
+
+        // The master rule is disabled by default.
+        let master = evaluation.get(PredefinedOverrideRuleId::Master.as_ref()).unwrap();
+        assert_eq!(master.outcome, RuleOutcome::Disabled);
+        assert_eq!(master.conditions.len(), 0);
+
+        // The event is not a notice.
+        let suppress_notices =
+            evaluation.get(PredefinedOverrideRuleId::SuppressNotices.as_ref()).unwrap();
+        assert_eq!(suppress_notices.outcome, RuleOutcome::ConditionsFailed);
+        assert_eq!(suppress_notices.conditions.len(), 1);
+        assert_matches!(
+            &suppress_notices.conditions[0].condition,
+            PushCondition::EventMatch { key, .. }
+        );
+        assert_eq!(key, "content.msgtype");
+        assert!(!suppress_notices.conditions[0].applies);
+        assert_eq!(suppress_notices.conditions[0].value, Some(FlattenedJsonValue::from("m.text")));
+
+        // The user name doesn't appear in the body.
+        let contains_user_name =
+            evaluation.get(PredefinedContentRuleId::ContainsUserName.as_ref()).unwrap();
+        assert_eq!(contains_user_name.outcome, RuleOutcome::ConditionsFailed);
+        assert_matches!(
+            &contains_user_name.conditions[0].condition,
+            PushCondition::EventMatch { key, pattern }
+        );
+        assert_eq!(key, "content.body");
+        assert_eq!(pattern, "jolly_jumper");
+        assert_eq!(
+            contains_user_name.conditions[0].value,
+            Some(FlattenedJsonValue::from("Hello Jolly Jumper"))
+        );
+
+        // All the conditions are traced, even after the first failing one.
+        let room_one_to_one =
+            evaluation.get(PredefinedUnderrideRuleId::RoomOneToOne.as_ref()).unwrap();
+        assert_eq!(room_one_to_one.outcome, RuleOutcome::Matched);
+        assert_eq!(room_one_to_one.conditions.len(), 2);
+        assert!(room_one_to_one.conditions.iter().all(|condition| condition.applies));
+        assert_eq!(room_one_to_one.conditions[0].value, Some(FlattenedJsonValue::from(int!(2))));
+    }
+
+    #[test]
+    fn own_event() {
+        let set = Ruleset::server_default(user_id!("@jolly_jumper:server.name"));
+        let event = message("@jolly_jumper:server.name", "Hello Jolly Jumper");
+
+        let evaluation = set.evaluate(&event, &context());
+        assert_matches!(evaluation.matched_rule(), None);
+        assert!(evaluation
+            .rules
+            .iter()
+            .all(|evaluation| evaluation.outcome == RuleOutcome::OwnEvent));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn superseded_by_mentions() {
+        let set = Ruleset::server_default(user_id!("@jolly_jumper:server.name"));
+        let event = Raw::new(&json!({
+            "type": "m.room.message",
+            "sender": "@rantanplan:server.name",
+            "content": {
+                "msgtype": "m.text",
+                "body": "Hello Jolly Jumper",
+                "m.mentions": {},
+            },
+        }))
+        .unwrap();
+
+        let evaluation = set.evaluate(&event, &context());
+        let contains_display_name =
+            evaluation.get(PredefinedOverrideRuleId::ContainsDisplayName.as_ref()).unwrap();
+        assert_eq!(contains_display_name.outcome, RuleOutcome::SupersededByMentions);
+        assert!(contains_display_name.conditions[0].applies);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn batch() {
+        let jolly_jumper_set = Ruleset::server_default(user_id!("@jolly_jumper:server.name"));
+        let jolly_jumper_context = context();
+        let rantanplan_set = Ruleset::server_default(user_id!("@rantanplan:server.name"));
+        let rantanplan_context = PushConditionRoomCtx {
+            user_id: owned_user_id!("@rantanplan:server.name"),
+            user_display_name: "Rantanplan".into(),
+            ..context()
+        };
+
+        let event = message("@rantanplan:server.name", "Hello Jolly Jumper");
+        let rulesets =
+            [(&jolly_jumper_set, &jolly_jumper_context), (&rantanplan_set, &rantanplan_context)];
+
+        let matches = Ruleset::get_match_batch(&event, rulesets);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            matches[0].unwrap().rule_id(),
+            PredefinedOverrideRuleId::ContainsDisplayName.as_ref()
+        );
+        assert_matches!(matches[1], None);
+
+        let evaluations = Ruleset::evaluate_batch(&event, rulesets);
+        assert_eq!(evaluations.len(), 2);
+        assert_same_rule(&evaluations[0], matches[0]);
+        assert_same_rule(&evaluations[1], matches[1]);
+    }
+}
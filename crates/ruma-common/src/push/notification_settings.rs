@@ -0,0 +1,490 @@
+//! A high-level view of the notification settings of a user, on top of their push rules.
+//!
+//! This follows the conventions used by Element clients to store the settings in the push rules.
+
+use thiserror::Error;
+
+use super::{
+    Action, ConditionalPushRule, InsertPushRuleError, NewConditionalPushRule, NewPatternedPushRule,
+    NewPushRule, NewSimplePushRule, PredefinedUnderrideRuleId, PushCondition, RuleKind, Ruleset,
+    RulesetDiff, Tweak,
+};
+use crate::RoomId;
+
+/// The notification settings of a user.
+///
+/// This allows to read and change the notification settings of rooms, the keywords and the default
+/// settings for one-to-one and group rooms, that are all stored in the push rules of the user.
+///
+/// Each change returns the [`RulesetDiff`] with the minimal list of changes that need to be sent
+/// to the homeserver to apply it.
+#[derive(Clone, Debug)]
+pub struct NotificationSettings {
+    ruleset: Ruleset,
+}
+
+impl NotificationSettings {
+    /// Creates a new `NotificationSettings` from the given push rules.
+    pub fn new(ruleset: Ruleset) -> Self {
+        Self { ruleset }
+    }
+
+    /// The push rules backing these settings.
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
+    /// Get the push rules backing these settings.
+    pub fn into_ruleset(self) -> Ruleset {
+        self.ruleset
+    }
+
+    /// The notification mode that was set explicitly for the given room, if any.
+    ///
+    /// The mode is read from:
+    ///
+    /// * An enabled override rule with the room ID as its ID that doesn't notify, for
+    ///   [`RoomNotificationMode::Mute`].
+    /// * An enabled room rule with the room ID as its ID, for [`RoomNotificationMode::AllMessages`]
+    ///   if it notifies, or [`RoomNotificationMode::MentionsAndKeywordsOnly`] otherwise.
+    pub fn room_mode(&self, room_id: &RoomId) -> Option<RoomNotificationMode> {
+        let is_muted = self
+            .ruleset
+            .get(RuleKind::Override, room_id)
+            .is_some_and(|rule| rule.enabled() && !rule.triggers_notification());
+
+        if is_muted {
+            return Some(RoomNotificationMode::Mute);
+        }
+
+        self.ruleset.get(RuleKind::Room, room_id).filter(|rule| rule.enabled()).map(|rule| {
+            if rule.triggers_notification() {
+                RoomNotificationMode::AllMessages
+            } else {
+                RoomNotificationMode::MentionsAndKeywordsOnly
+            }
+        })
+    }
+
+    /// The notification mode that applies to the given room.
+    ///
+    /// This is the mode that was set explicitly for the room, or the default mode for its type of
+    /// room.
+    pub fn effective_room_mode(
+        &self,
+        room_id: &RoomId,
+        is_one_to_one: bool,
+    ) -> RoomNotificationMode {
+        self.room_mode(room_id).unwrap_or_else(|| self.default_mode(is_one_to_one))
+    }
+
+    /// The default notification mode for one-to-one or group rooms.
+    ///
+    /// It is [`RoomNotificationMode::AllMessages`] if any of the server-default underride rules
+    /// for messages in this type of room is enabled and notifies.
+    pub fn default_mode(&self, is_one_to_one: bool) -> RoomNotificationMode {
+        let notifies = default_rule_ids(is_one_to_one).iter().any(|rule_id| {
+            self.ruleset
+                .get(RuleKind::Underride, rule_id.as_str())
+                .is_some_and(|rule| rule.enabled() && rule.triggers_notification())
+        });
+
+        if notifies {
+            RoomNotificationMode::AllMessages
+        } else {
+            RoomNotificationMode::MentionsAndKeywordsOnly
+        }
+    }
+
+    /// The keywords that trigger a notification.
+    ///
+    /// They are the patterns of the enabled user-defined content rules.
+    pub fn keywords(&self) -> impl Iterator<Item = &str> {
+        self.ruleset
+            .content
+            .iter()
+            .filter(|rule| !rule.default && rule.enabled)
+            .map(|rule| rule.pattern.as_str())
+    }
+
+    /// Set the notification mode of the given room.
+    ///
+    /// This replaces the other rules that set the mode of this room.
+    ///
+    /// Returns an error if the room ID can't be used as a push rule ID.
+    pub fn set_room_mode(
+        &mut self,
+        room_id: &RoomId,
+        mode: RoomNotificationMode,
+    ) -> Result<RulesetDiff, NotificationSettingsError> {
+        if self.room_mode(room_id) == Some(mode) {
+            return Ok(RulesetDiff::default());
+        }
+
+        self.update(|ruleset| {
+            remove_room_rules(ruleset, room_id);
+
+            let rule = match mode {
+                RoomNotificationMode::AllMessages => NewPushRule::Room(NewSimplePushRule::new(
+                    room_id.to_owned(),
+                    vec![Action::Notify, Action::SetTweak(Tweak::Sound("default".to_owned()))],
+                )),
+                RoomNotificationMode::MentionsAndKeywordsOnly => {
+                    NewPushRule::Room(NewSimplePushRule::new(room_id.to_owned(), vec![]))
+                }
+                RoomNotificationMode::Mute => NewPushRule::Override(NewConditionalPushRule::new(
+                    room_id.to_string(),
+                    vec![PushCondition::EventMatch {
+                        key: "room_id".to_owned(),
+                        pattern: room_id.to_string(),
+                    }],
+                    vec![],
+                )),
+            };
+
+            ruleset.insert(rule, None, None)?;
+            // The rule might have been disabled before.
+            ruleset.set_enabled(rule_kind(mode), room_id, true).expect("rule was just inserted");
+
+            Ok(())
+        })
+    }
+
+    /// Reset the notification mode of the given room to the default mode.
+    ///
+    /// This removes the rules that set the mode of this room.
+    pub fn reset_room_mode(&mut self, room_id: &RoomId) -> RulesetDiff {
+        self.update(|ruleset| {
+            remove_room_rules(ruleset, room_id);
+            Ok(())
+        })
+        .expect("removing rules should not fail")
+    }
+
+    /// Set the default notification mode for one-to-one or group rooms.
+    ///
+    /// Only the server-default rules whose state doesn't match the mode are changed.
+    ///
+    /// Returns an error if the mode is [`RoomNotificationMode::Mute`], which is not supported as a
+    /// default mode.
+    pub fn set_default_mode(
+        &mut self,
+        is_one_to_one: bool,
+        mode: RoomNotificationMode,
+    ) -> Result<RulesetDiff, NotificationSettingsError> {
+        let notify = match mode {
+            RoomNotificationMode::AllMessages => true,
+            RoomNotificationMode::MentionsAndKeywordsOnly => false,
+            RoomNotificationMode::Mute => return Err(NotificationSettingsError::MuteDefaultMode),
+        };
+
+        self.update(|ruleset| {
+            for rule_id in default_rule_ids(is_one_to_one) {
+                let Some(rule) = ruleset.get(RuleKind::Underride, rule_id.as_str()) else {
+                    continue;
+                };
+
+                if rule.enabled() && rule.triggers_notification() == notify {
+                    continue;
+                }
+
+                if rule.triggers_notification() != notify {
+                    let actions = if notify {
+                        server_default_rule(&rule_id).map(|rule| rule.actions).unwrap_or_default()
+                    } else {
+                        vec![]
+                    };
+                    ruleset
+                        .set_actions(RuleKind::Underride, rule_id.as_str(), actions)
+                        .expect("rule exists");
+                }
+
+                ruleset
+                    .set_enabled(RuleKind::Underride, rule_id.as_str(), true)
+                    .expect("rule exists");
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Add a keyword that triggers a notification.
+    ///
+    /// Returns an error if the keyword can't be used as a push rule ID.
+    pub fn add_keyword(
+        &mut self,
+        keyword: String,
+    ) -> Result<RulesetDiff, NotificationSettingsError> {
+        if self.keywords().any(|k| k == keyword) {
+            return Ok(RulesetDiff::default());
+        }
+
+        self.update(|ruleset| {
+            let rule = NewPatternedPushRule::new(
+                keyword.clone(),
+                keyword,
+                vec![Action::Notify, Action::SetTweak(Tweak::Sound("default".to_owned()))],
+            );
+            let rule_id = rule.rule_id.clone();
+
+            ruleset.insert(NewPushRule::Content(rule), None, None)?;
+            ruleset.set_enabled(RuleKind::Content, rule_id, true).expect("rule was just inserted");
+
+            Ok(())
+        })
+    }
+
+    /// Remove a keyword that triggers a notification.
+    ///
+    /// This removes all the user-defined content rules with the keyword as their pattern.
+    pub fn remove_keyword(&mut self, keyword: &str) -> RulesetDiff {
+        self.update(|ruleset| {
+            ruleset.content.retain(|rule| rule.default || rule.pattern != keyword);
+            Ok(())
+        })
+        .expect("removing rules should not fail")
+    }
+
+    /// Apply the given changes to a copy of the ruleset, and replace the ruleset if they succeed.
+    ///
+    /// Returns the changes between the old and the new ruleset.
+    fn update(
+        &mut self,
+        f: impl FnOnce(&mut Ruleset) -> Result<(), NotificationSettingsError>,
+    ) -> Result<RulesetDiff, NotificationSettingsError> {
+        let mut ruleset = self.ruleset.clone();
+        f(&mut ruleset)?;
+
+        let diff = self.ruleset.diff(&ruleset);
+        self.ruleset = ruleset;
+
+        Ok(diff)
+    }
+}
+
+/// Remove the user-defined rules that set the notification mode of the given room.
+fn remove_room_rules(ruleset: &mut Ruleset, room_id: &RoomId) {
+    // These can only fail if the rule doesn't exist or if it's a server-default rule.
+    let _ = ruleset.remove(RuleKind::Override, room_id);
+    let _ = ruleset.remove(RuleKind::Room, room_id);
+}
+
+/// The kind of rule used to store the given room notification mode.
+fn rule_kind(mode: RoomNotificationMode) -> RuleKind {
+    match mode {
+        RoomNotificationMode::AllMessages | RoomNotificationMode::MentionsAndKeywordsOnly => {
+            RuleKind::Room
+        }
+        RoomNotificationMode::Mute => RuleKind::Override,
+    }
+}
+
+/// The IDs of the server-default underride rules that set the default mode for one-to-one or group
+/// rooms.
+fn default_rule_ids(is_one_to_one: bool) -> Vec<PredefinedUnderrideRuleId> {
+    if is_one_to_one {
+        vec![
+            PredefinedUnderrideRuleId::RoomOneToOne,
+            PredefinedUnderrideRuleId::EncryptedRoomOneToOne,
+            #[cfg(feature = "unstable-msc3930")]
+            PredefinedUnderrideRuleId::PollStartOneToOne,
+            #[cfg(feature = "unstable-msc3930")]
+            PredefinedUnderrideRuleId::PollEndOneToOne,
+        ]
+    } else {
+        vec![
+            PredefinedUnderrideRuleId::Message,
+            PredefinedUnderrideRuleId::Encrypted,
+            #[cfg(feature = "unstable-msc3930")]
+            PredefinedUnderrideRuleId::PollStart,
+            #[cfg(feature = "unstable-msc3930")]
+            PredefinedUnderrideRuleId::PollEnd,
+        ]
+    }
+}
+
+/// The server-default version of the given rule.
+fn server_default_rule(rule_id: &PredefinedUnderrideRuleId) -> Option<ConditionalPushRule> {
+    let rule = match rule_id {
+        PredefinedUnderrideRuleId::RoomOneToOne => ConditionalPushRule::room_one_to_one(),
+        PredefinedUnderrideRuleId::EncryptedRoomOneToOne => {
+            ConditionalPushRule::encrypted_room_one_to_one()
+        }
+        PredefinedUnderrideRuleId::Message => ConditionalPushRule::message(),
+        PredefinedUnderrideRuleId::Encrypted => ConditionalPushRule::encrypted(),
+        #[cfg(feature = "unstable-msc3930")]
+        PredefinedUnderrideRuleId::PollStartOneToOne => {
+            ConditionalPushRule::poll_start_one_to_one()
+        }
+        #[cfg(feature = "unstable-msc3930")]
+        PredefinedUnderrideRuleId::PollStart => ConditionalPushRule::poll_start(),
+        #[cfg(feature = "unstable-msc3930")]
+        PredefinedUnderrideRuleId::PollEndOneToOne => ConditionalPushRule::poll_end_one_to_one(),
+        #[cfg(feature = "unstable-msc3930")]
+        PredefinedUnderrideRuleId::PollEnd => ConditionalPushRule::poll_end(),
+        _ => return None,
+    };
+
+    Some(rule)
+}
+
+/// The notification mode of a room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::exhaustive_enums)]
+pub enum RoomNotificationMode {
+    /// Receive notifications for all messages.
+    AllMessages,
+
+    /// Receive notifications for mentions and keywords only.
+    MentionsAndKeywordsOnly,
+
+    /// Don't receive any notifications.
+    Mute,
+}
+
+/// The error type returned when changing the [`NotificationSettings`] fails.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum NotificationSettingsError {
+    /// The default mode can't be set to [`RoomNotificationMode::Mute`].
+    #[error("the default notification mode can't be set to mute")]
+    MuteDefaultMode,
+
+    /// The push rule could not be inserted.
+    #[error(transparent)]
+    Insert(#[from] InsertPushRuleError),
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches2::assert_matches;
+
+    use super::{NotificationSettings, NotificationSettingsError, RoomNotificationMode};
+    use crate::{
+        owned_user_id,
+        push::{PushRuleChange, RuleKind, Ruleset},
+        room_id,
+    };
+
+    fn default_settings() -> NotificationSettings {
+        NotificationSettings::new(Ruleset::server_default(&owned_user_id!("@jj:server.name")))
+    }
+
+    #[test]
+    fn default_modes() {
+        let settings = default_settings();
+        let room_id = room_id!("!far_west:server.name");
+
+        assert_eq!(settings.room_mode(room_id), None);
+        assert_eq!(settings.default_mode(true), RoomNotificationMode::AllMessages);
+        assert_eq!(settings.default_mode(false), RoomNotificationMode::AllMessages);
+        assert_eq!(settings.effective_room_mode(room_id, false), RoomNotificationMode::AllMessages);
+        assert_eq!(settings.keywords().count(), 0);
+    }
+
+    #[test]
+    fn set_room_mode() {
+        let mut settings = default_settings();
+        let room_id = room_id!("!far_west:server.name");
+
+        let diff = settings.set_room_mode(room_id, RoomNotificationMode::Mute).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(&diff.changes[0], PushRuleChange::Added { rule, .. });
+        assert_eq!(rule.kind(), RuleKind::Override);
+        assert_eq!(rule.rule_id(), room_id);
+        assert_eq!(settings.room_mode(room_id), Some(RoomNotificationMode::Mute));
+
+        // Setting the same mode again doesn't change anything.
+        let diff = settings.set_room_mode(room_id, RoomNotificationMode::Mute).unwrap();
+        assert!(diff.is_empty());
+
+        let diff =
+            settings.set_room_mode(room_id, RoomNotificationMode::MentionsAndKeywordsOnly).unwrap();
+        assert_eq!(diff.changes.len(), 2);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::Removed { kind: RuleKind::Override, rule_id }
+        );
+        assert_eq!(rule_id, room_id);
+        assert_matches!(&diff.changes[1], PushRuleChange::Added { rule, .. });
+        assert_eq!(rule.kind(), RuleKind::Room);
+        assert_eq!(
+            settings.effective_room_mode(room_id, true),
+            RoomNotificationMode::MentionsAndKeywordsOnly
+        );
+
+        // Only the actions of the room rule need to change.
+        let diff = settings.set_room_mode(room_id, RoomNotificationMode::AllMessages).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::ActionsChanged { kind: RuleKind::Room, .. }
+        );
+        assert_eq!(settings.room_mode(room_id), Some(RoomNotificationMode::AllMessages));
+
+        let diff = settings.reset_room_mode(room_id);
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(&diff.changes[0], PushRuleChange::Removed { kind: RuleKind::Room, .. });
+        assert_eq!(settings.room_mode(room_id), None);
+        assert!(settings.reset_room_mode(room_id).is_empty());
+    }
+
+    #[test]
+    fn set_default_mode() {
+        let mut settings = default_settings();
+
+        let diff = settings
+            .set_default_mode(false, RoomNotificationMode::MentionsAndKeywordsOnly)
+            .unwrap();
+        assert_eq!(diff.changes.len(), super::default_rule_ids(false).len());
+        assert!(diff
+            .changes
+            .iter()
+            .all(|change| matches!(change, PushRuleChange::ActionsChanged { actions, .. } if actions.is_empty())));
+        assert_eq!(settings.default_mode(false), RoomNotificationMode::MentionsAndKeywordsOnly);
+        assert_eq!(settings.default_mode(true), RoomNotificationMode::AllMessages);
+
+        // Disabled rules are enabled again.
+        settings
+            .ruleset
+            .set_enabled(RuleKind::Underride, ".m.rule.room_one_to_one", false)
+            .unwrap();
+        let diff = settings.set_default_mode(true, RoomNotificationMode::AllMessages).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(
+            &diff.changes[0],
+            PushRuleChange::EnabledChanged { kind: RuleKind::Underride, enabled: true, .. }
+        );
+
+        // Going back to the server-default state.
+        settings.set_default_mode(false, RoomNotificationMode::AllMessages).unwrap();
+        assert!(settings.ruleset().diff(&default_settings().into_ruleset()).is_empty());
+
+        assert_matches!(
+            settings.set_default_mode(true, RoomNotificationMode::Mute),
+            Err(NotificationSettingsError::MuteDefaultMode)
+        );
+    }
+
+    #[test]
+    fn keywords() {
+        let mut settings = default_settings();
+
+        let diff = settings.add_keyword("rustacean".to_owned()).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(&diff.changes[0], PushRuleChange::Added { rule, .. });
+        assert_eq!(rule.kind(), RuleKind::Content);
+        assert!(settings.add_keyword("rustacean".to_owned()).unwrap().is_empty());
+        assert_eq!(settings.keywords().collect::<Vec<_>>(), ["rustacean"]);
+
+        assert_matches!(
+            settings.add_keyword(".m.rule.keyword".to_owned()),
+            Err(NotificationSettingsError::Insert(_))
+        );
+
+        let diff = settings.remove_keyword("rustacean");
+        assert_eq!(diff.changes.len(), 1);
+        assert_matches!(&diff.changes[0], PushRuleChange::Removed { kind: RuleKind::Content, .. });
+        assert_eq!(settings.keywords().count(), 0);
+    }
+}
@@ -15,6 +15,7 @@ use crate::{
 /// To create an instance of this type, first create a `ProtocolInit` and convert it via
 /// `Protocol::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Protocol {
     /// Fields which may be used to identify a third party user.
@@ -72,6 +73,7 @@ impl From<ProtocolInit> for Protocol {
 /// To create an instance of this type, first create a `ProtocolInstanceInit` and convert it via
 /// `ProtocolInstance::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ProtocolInstance {
     /// A human-readable description for the protocol, such as the name.
@@ -142,6 +144,7 @@ impl From<ProtocolInstanceInit> for ProtocolInstance {
 /// To create an instance of this type, first create a `FieldTypeInit` and convert it via
 /// `FieldType::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct FieldType {
     /// A regular expression for validation of a field's value.
@@ -174,6 +177,7 @@ impl From<FieldTypeInit> for FieldType {
 
 /// A third party network location.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Location {
     /// An alias for a matrix room.
@@ -199,6 +203,7 @@ impl Location {
 
 /// A third party network user.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct User {
     /// A matrix user ID representing a third party user.
@@ -239,6 +244,7 @@ pub enum Medium {
 /// To create an instance of this type, first create a `ThirdPartyIdentifierInit` and convert it to
 /// this type using `ThirdPartyIdentifier::Init` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(crate::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ThirdPartyIdentifier {
@@ -60,6 +60,7 @@ pub mod some_endpoint {
     }
 
     // Dummy type to avoid circular dev-dependency that rust-analyzer doesn't like
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct Event {}
 }
 
@@ -70,6 +71,7 @@ pub mod newtype_body_endpoint {
     };
 
     #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct MyCustomType {
         pub a_field: String,
     }
@@ -105,6 +107,7 @@ pub mod raw_body_endpoint {
     };
 
     #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct MyCustomType {
         pub a_field: String,
     }
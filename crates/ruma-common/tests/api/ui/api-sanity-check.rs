@@ -60,6 +60,7 @@ pub struct Response {
 }
 
 // Dummy type to avoid circular dev-dependency that rust-analyzer doesn't like
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct Event {}
 
 fn main() {
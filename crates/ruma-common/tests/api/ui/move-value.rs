@@ -9,6 +9,7 @@ mod newtype_body {
     };
 
     #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct Foo;
 
     const METADATA: Metadata = metadata! {
@@ -98,6 +99,7 @@ mod plain {
     };
 
     #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     pub struct Foo;
 
     const METADATA: Metadata = metadata! {
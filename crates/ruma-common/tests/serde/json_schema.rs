@@ -0,0 +1,211 @@
+#![cfg(feature = "json-schema")]
+
+use std::collections::BTreeMap;
+
+use ruma_common::{
+    json_schema::{schema_for, JsonSchema, SchemaGenerator},
+    serde::StringEnum,
+    OwnedUserId,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+#[derive(Debug, PartialEq)]
+struct PrivOwnedStr(Box<str>);
+
+/// The membership of a user.
+#[derive(StringEnum)]
+#[ruma_enum(rename_all = "snake_case")]
+enum Membership {
+    Join,
+    #[ruma_enum(rename = "m.leave")]
+    Leave,
+    _Custom(PrivOwnedStr),
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+struct Extra {
+    count: u32,
+}
+
+/// A member of a room.
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct Member {
+    /// The ID of the user.
+    user_id: OwnedUserId,
+
+    #[serde(rename = "m.membership")]
+    membership: Membership,
+
+    display_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+
+    #[serde(skip)]
+    #[allow(dead_code)]
+    cache: BTreeMap<String, String>,
+
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Action {
+    Notify,
+    SetTweak { value: String },
+    Custom(Extra),
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(i64),
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+struct Node {
+    children: Vec<Node>,
+}
+
+#[test]
+fn struct_schema() {
+    let schema = schema_for::<Member>();
+
+    assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+    assert_eq!(schema["title"], "Member");
+    assert_eq!(schema["description"], "A member of a room.");
+
+    let object = &schema["allOf"][0];
+    assert_eq!(object["required"], json!(["userId", "m.membership"]));
+
+    let properties = object["properties"].as_object().unwrap();
+    assert_eq!(
+        properties.keys().collect::<Vec<_>>(),
+        ["displayName", "m.membership", "tags", "userId"]
+    );
+    assert_eq!(
+        properties["userId"],
+        json!({ "allOf": [{ "$ref": "#/$defs/UserId" }], "description": "The ID of the user." })
+    );
+    assert_eq!(properties["m.membership"], json!({ "$ref": "#/$defs/Membership" }));
+    assert_eq!(
+        properties["displayName"],
+        json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+    );
+    assert_eq!(properties["tags"], json!({ "type": "array", "items": { "type": "string" } }));
+
+    assert_eq!(schema["allOf"][1], json!({ "$ref": "#/$defs/Extra" }));
+
+    let definitions = schema["$defs"].as_object().unwrap();
+    assert_eq!(definitions["UserId"], json!({ "type": "string" }));
+    assert_eq!(
+        definitions["Membership"],
+        json!({
+            "anyOf": [
+                { "type": "string", "enum": ["join", "m.leave"] },
+                { "type": "string" },
+            ],
+            "description": "The membership of a user.",
+        })
+    );
+    assert_eq!(
+        definitions["Extra"],
+        json!({
+            "type": "object",
+            "properties": { "count": { "type": "integer", "format": "uint32", "minimum": 0 } },
+            "required": ["count"],
+        })
+    );
+}
+
+#[test]
+fn internally_tagged_enum_schema() {
+    let schema = schema_for::<Action>();
+    let variants = schema["oneOf"].as_array().unwrap();
+
+    assert_eq!(variants.len(), 3);
+    assert_eq!(
+        variants[0],
+        json!({
+            "type": "object",
+            "properties": { "kind": { "type": "string", "const": "notify" } },
+            "required": ["kind"],
+        })
+    );
+    assert_eq!(
+        variants[1],
+        json!({
+            "type": "object",
+            "properties": {
+                "kind": { "type": "string", "const": "set_tweak" },
+                "value": { "type": "string" },
+            },
+            "required": ["kind", "value"],
+        })
+    );
+    assert_eq!(
+        variants[2],
+        json!({
+            "allOf": [
+                {
+                    "type": "object",
+                    "properties": { "kind": { "type": "string", "const": "custom" } },
+                    "required": ["kind"],
+                },
+                { "$ref": "#/$defs/Extra" },
+            ],
+        })
+    );
+}
+
+#[test]
+fn untagged_enum_schema() {
+    let schema = schema_for::<StringOrNumber>();
+
+    assert_eq!(
+        schema["anyOf"],
+        json!([{ "type": "string" }, { "type": "integer", "format": "int64" }])
+    );
+}
+
+#[test]
+fn recursive_schema() {
+    let mut generator = SchemaGenerator::with_definitions_path("#/components/schemas/");
+    let schema = generator.subschema_for::<Node>();
+
+    assert_eq!(schema, json!({ "$ref": "#/components/schemas/Node" }));
+    assert_eq!(
+        generator.definitions()["Node"],
+        json!({
+            "type": "object",
+            "properties": {
+                "children": { "type": "array", "items": { "$ref": "#/components/schemas/Node" } },
+            },
+            "required": ["children"],
+        })
+    );
+}
+
+#[test]
+fn with_field_schema() {
+    struct NoSchema;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct WithCustomSerde {
+        /// A field with a custom serialization.
+        #[serde(with = "no_schema")]
+        field: NoSchema,
+    }
+
+    let schema: JsonValue = schema_for::<WithCustomSerde>();
+    assert_eq!(
+        schema["properties"]["field"],
+        json!({ "description": "A field with a custom serialization." })
+    );
+    assert_eq!(schema["required"], json!(["field"]));
+}
@@ -1,2 +1,3 @@
 mod empty_strings;
 mod enum_derive;
+mod json_schema;
@@ -93,6 +93,7 @@ impl AudioEventContent {
 
 /// A block for details of audio content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AudioDetailsContentBlock {
     /// The duration of the audio in seconds.
@@ -127,6 +128,7 @@ impl AudioDetailsContentBlock {
 /// Must be an integer between 0 and 256.
 #[cfg(feature = "unstable-msc3246")]
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct Amplitude(UInt);
 
 #[cfg(feature = "unstable-msc3246")]
@@ -62,6 +62,7 @@ impl CallCandidatesEventContent {
 
 /// An ICE (Interactive Connectivity Establishment) candidate.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(rename_all = "camelCase")]
 pub struct Candidate {
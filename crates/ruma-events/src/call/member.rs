@@ -78,6 +78,7 @@ impl CallMemberEventContent {
 ///
 /// The application defines the type of the session.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Membership {
@@ -202,6 +203,7 @@ impl From<MembershipInit> for Membership {
 /// A focus can be any server powering the matrixRTC session (SFU,
 /// MCU). It serves as a node to redistribute RTC streams.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -212,6 +214,7 @@ pub enum Focus {
 
 /// The fields to describe livekit as an `active_foci`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct LivekitFocus {
@@ -242,6 +245,7 @@ impl LivekitFocus {
 /// type of matrixRTC session e.g. calling (`m.call`), third-room, whiteboard could be
 /// possible applications.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "application")]
@@ -253,6 +257,7 @@ pub enum Application {
 
 /// Call specific parameters membership parameters.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct CallApplicationContent {
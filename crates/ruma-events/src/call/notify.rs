@@ -43,6 +43,7 @@ impl CallNotifyEventContent {
 
 /// How this notify event should notify the receiver.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum NotifyType {
     /// The receiving client should ring with an audible sound.
@@ -61,6 +62,7 @@ pub enum NotifyType {
 ///
 /// An `Application` can be converted into an `ApplicationType` using `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum ApplicationType {
     /// A VoIP call.
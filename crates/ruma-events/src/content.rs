@@ -1,12 +1,15 @@
 use std::fmt;
 
 use ruma_common::serde::{CanBeEmpty, Raw};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{
+    de::{self, DeserializeOwned},
+    Serialize,
+};
 use serde_json::{from_str as from_json_str, value::RawValue as RawJsonValue};
 
 use super::{
-    EphemeralRoomEventType, GlobalAccountDataEventType, MessageLikeEventType,
-    RoomAccountDataEventType, StateEventType, ToDeviceEventType,
+    validation::ContentValidationError, EphemeralRoomEventType, GlobalAccountDataEventType,
+    MessageLikeEventType, RoomAccountDataEventType, StateEventType, ToDeviceEventType,
 };
 
 /// The base trait that all event content types implement.
@@ -20,12 +23,33 @@ pub trait EventContent: Sized + Serialize {
 
     /// Get the event's type, like `m.room.message`.
     fn event_type(&self) -> Self::EventType;
+
+    /// Validate the fields of this content.
+    ///
+    /// The checks are declared with the `#[ruma_event(validate(...))]` attribute, see the
+    /// [`validation`](crate::validation) module. This is called automatically when the content is
+    /// deserialized with [`RawExt`] or [`EventContentFromType`], but not by the `Deserialize`
+    /// implementation of the content type.
+    fn validate(&self) -> Result<(), ContentValidationError> {
+        Ok(())
+    }
 }
 
 /// Extension trait for [`Raw<T>`].
-pub trait RawExt<T: EventContentFromType> {
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait RawExt<T: EventContentFromType>: private::Sealed {
     /// Try to deserialize the JSON as an event's content with the given event type.
+    ///
+    /// The content is validated with [`EventContent::validate()`].
     fn deserialize_with_type(&self, event_type: T::EventType) -> serde_json::Result<T>;
+
+    /// Try to deserialize the JSON as an event's content.
+    ///
+    /// The content is validated with [`EventContent::validate()`].
+    fn deserialize_content(&self) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned;
 }
 
 impl<T> RawExt<T> for Raw<T>
@@ -36,6 +60,23 @@ where
     fn deserialize_with_type(&self, event_type: T::EventType) -> serde_json::Result<T> {
         T::from_parts(&event_type.to_string(), self.json())
     }
+
+    fn deserialize_content(&self) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let content: T = self.deserialize()?;
+        content.validate().map_err(de::Error::custom)?;
+        Ok(content)
+    }
+}
+
+mod private {
+    use ruma_common::serde::Raw;
+
+    pub trait Sealed {}
+
+    impl<T> Sealed for Raw<T> {}
 }
 
 /// An event content type with a statically-known event `type` value.
@@ -104,6 +145,8 @@ where
     T: EventContent + DeserializeOwned,
 {
     fn from_parts(_event_type: &str, content: &RawJsonValue) -> serde_json::Result<Self> {
-        from_json_str(content.get())
+        let content: Self = from_json_str(content.get())?;
+        content.validate().map_err(de::Error::custom)?;
+        Ok(content)
     }
 }
@@ -42,6 +42,7 @@ impl From<EncryptedEventScheme> for EncryptedEventContent {
 
 /// A block for encrypted content.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct EncryptedContentBlock {
     /// Algorithm-specific fields.
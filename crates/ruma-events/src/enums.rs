@@ -301,6 +301,45 @@ impl<'de> Deserialize<'de> for AnySyncTimelineEvent {
     }
 }
 
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::json_schema::{private::any_of, JsonSchema, SchemaGenerator};
+    use serde_json::Value as JsonValue;
+
+    use super::{
+        AnyMessageLikeEvent, AnyStateEvent, AnySyncMessageLikeEvent, AnySyncStateEvent,
+        AnySyncTimelineEvent, AnyTimelineEvent,
+    };
+
+    impl JsonSchema for AnyTimelineEvent {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("AnyTimelineEvent")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            any_of(vec![
+                generator.subschema_for::<AnyMessageLikeEvent>(),
+                generator.subschema_for::<AnyStateEvent>(),
+            ])
+        }
+    }
+
+    impl JsonSchema for AnySyncTimelineEvent {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("AnySyncTimelineEvent")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            any_of(vec![
+                generator.subschema_for::<AnySyncMessageLikeEvent>(),
+                generator.subschema_for::<AnySyncStateEvent>(),
+            ])
+        }
+    }
+}
+
 impl AnyMessageLikeEventContent {
     /// Get a copy of the event's `m.relates_to` field, if any.
     ///
@@ -125,6 +125,7 @@ impl FileEventContent {
 
 /// A block for file content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct FileContentBlock {
     /// The URL to the file.
@@ -176,6 +177,7 @@ impl FileContentBlock {
 /// To create an instance of this type, first create a `EncryptedContentInit` and convert it via
 /// `EncryptedContent::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct EncryptedContent {
     /// A [JSON Web Key](https://tools.ietf.org/html/rfc7517#appendix-A.3) object.
@@ -240,6 +242,7 @@ impl From<&EncryptedFile> for EncryptedContent {
 /// To construct a `CaptionContentBlock` with a custom [`TextContentBlock`], convert it with
 /// `CaptionContentBlock::from()` / `.into()`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct CaptionContentBlock {
     /// The text message of the caption.
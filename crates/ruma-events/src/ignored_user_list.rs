@@ -38,6 +38,7 @@ impl IgnoredUserListEventContent {
 ///
 /// This is currently empty.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct IgnoredUser {}
 
@@ -112,6 +112,7 @@ impl ImageEventContent {
 
 /// A block for details of image content.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ImageDetailsContentBlock {
     /// The height of the image in pixels.
@@ -143,6 +144,7 @@ impl ImageDetailsContentBlock {
 /// To construct a `ThumbnailContentBlock` convert a `Vec<Thumbnail>` with
 /// `ThumbnailContentBlock::from()` / `.into()`.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[allow(clippy::exhaustive_structs)]
 pub struct ThumbnailContentBlock(Vec<Thumbnail>);
 
@@ -175,6 +177,7 @@ impl Deref for ThumbnailContentBlock {
 
 /// Thumbnail content.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Thumbnail {
     /// The file info of the thumbnail.
@@ -198,6 +201,7 @@ impl Thumbnail {
 
 /// A block for thumbnail file content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ThumbnailFileContentBlock {
     /// The URL to the thumbnail.
@@ -251,6 +255,7 @@ impl ThumbnailFileContentBlock {
 
 /// A block for details of thumbnail image content.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ThumbnailImageDetailsContentBlock {
     /// The height of the image in pixels.
@@ -274,6 +279,7 @@ impl ThumbnailImageDetailsContentBlock {
 /// To construct an `AltTextContentBlock` with a custom [`TextContentBlock`], convert it with
 /// `AltTextContentBlock::from()` / `.into()`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AltTextContentBlock {
     /// The alternative text.
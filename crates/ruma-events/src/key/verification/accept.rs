@@ -65,6 +65,7 @@ impl KeyVerificationAcceptEventContent {
 
 /// An enum representing the different method specific `m.key.verification.accept` content.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(untagged)]
 pub enum AcceptMethod {
@@ -79,6 +80,7 @@ pub enum AcceptMethod {
 /// Method specific content of a unknown key verification method.
 #[doc(hidden)]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[allow(clippy::exhaustive_structs)]
 pub struct _CustomContent {
     /// The name of the method.
@@ -91,6 +93,7 @@ pub struct _CustomContent {
 
 /// The payload of an `m.key.verification.accept` event using the `m.sas.v1` method.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(rename = "m.sas.v1", tag = "method")]
 pub struct SasV1Content {
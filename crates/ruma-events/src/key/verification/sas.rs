@@ -0,0 +1,1348 @@
+//! A transport-agnostic state machine for [SAS verification].
+//!
+//! [`SasVerification`] takes care of the protocol flow of a Short Authentication String
+//! verification, from the `m.key.verification.request` to the `m.key.verification.done` event.
+//! It accepts incoming to-device or in-room events, validates them and returns the content of the
+//! events that should be sent to the other party.
+//!
+//! A to-device request is usually sent to all the devices of the other user. Only the first device
+//! that is ready takes part in the verification. When it is ready, the other devices that the
+//! request was sent to, listed in [`SasConfig::their_device_ids`], are sent a cancellation with the
+//! [`CancelCode::Accepted`] code, as well as any other device that responds later.
+//!
+//! The cryptographic primitives are not implemented here, they must be provided through the
+//! [`SasCrypto`] trait, for example with a wrapper around the [vodozemac] `Sas` type.
+//!
+//! [SAS verification]: https://spec.matrix.org/latest/client-server-api/#short-authentication-string-sas-verification
+//! [vodozemac]: https://crates.io/crates/vodozemac
+
+use std::{collections::BTreeMap, error::Error, time::Duration};
+
+use ruma_common::{
+    canonical_json::to_canonical_value,
+    serde::{Base64, Raw},
+    to_device::DeviceIdOrAllDevices,
+    CanonicalJsonObject, CanonicalJsonValue, MilliSecondsSinceUnixEpoch, OwnedDeviceId,
+    OwnedEventId, OwnedTransactionId, OwnedUserId,
+};
+
+use super::{
+    accept::{
+        AcceptMethod, KeyVerificationAcceptEventContent, SasV1Content as AcceptSasV1Content,
+        SasV1ContentInit as AcceptSasV1ContentInit, ToDeviceKeyVerificationAcceptEventContent,
+    },
+    cancel::{
+        CancelCode, KeyVerificationCancelEventContent, ToDeviceKeyVerificationCancelEventContent,
+    },
+    done::{KeyVerificationDoneEventContent, ToDeviceKeyVerificationDoneEventContent},
+    key::{KeyVerificationKeyEventContent, ToDeviceKeyVerificationKeyEventContent},
+    mac::{KeyVerificationMacEventContent, ToDeviceKeyVerificationMacEventContent},
+    ready::{KeyVerificationReadyEventContent, ToDeviceKeyVerificationReadyEventContent},
+    request::ToDeviceKeyVerificationRequestEventContent,
+    start::{
+        KeyVerificationStartEventContent, SasV1Content as StartSasV1Content,
+        SasV1ContentInit as StartSasV1ContentInit, StartMethod,
+        ToDeviceKeyVerificationStartEventContent,
+    },
+    HashAlgorithm, KeyAgreementProtocol, MessageAuthenticationCode, ShortAuthenticationString,
+    VerificationMethod, REQUEST_TIMESTAMP_TIMEOUT,
+};
+use crate::{
+    relation::Reference,
+    room::message::{
+        KeyVerificationRequestEventContent, MessageType, OriginalSyncRoomMessageEvent,
+    },
+    AnyMessageLikeEventContent, AnySyncMessageLikeEvent, AnyToDeviceEvent, AnyToDeviceEventContent,
+    SyncMessageLikeEvent, ToDeviceEvent,
+};
+
+/// The amount of time after which a verification should be cancelled if it is not done, relative
+/// to the time the request was sent or received.
+///
+/// This is defined as 10 minutes.
+pub const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// The amount of time a verification request can be in the future before it is ignored.
+///
+/// This is defined as 5 minutes.
+const REQUEST_FUTURE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The short authentication string methods supported by this implementation, in order of
+/// preference.
+const SUPPORTED_SAS: [ShortAuthenticationString; 2] =
+    [ShortAuthenticationString::Decimal, ShortAuthenticationString::Emoji];
+
+/// The cryptographic primitives needed for SAS verification.
+///
+/// An implementation must hold a fresh ephemeral Curve25519 key pair for every verification.
+pub trait SasCrypto {
+    /// The error returned when the public key of the other party is invalid.
+    type Error: Error;
+
+    /// The ephemeral public key of this device.
+    fn public_key(&self) -> Base64;
+
+    /// Compute the SHA-256 hash of the given input.
+    fn sha256(&self, input: &[u8]) -> Vec<u8>;
+
+    /// Perform the Elliptic-curve Diffie-Hellman key agreement with the ephemeral public key of
+    /// the other party.
+    ///
+    /// This is called once, before [`generate_bytes()`](Self::generate_bytes) and
+    /// [`calculate_mac()`](Self::calculate_mac).
+    fn diffie_hellman(&mut self, their_public_key: &Base64) -> Result<(), Self::Error>;
+
+    /// Generate `count` bytes from the shared secret with HKDF-SHA-256, using `info` as the info
+    /// parameter.
+    fn generate_bytes(&self, info: &str, count: usize) -> Vec<u8>;
+
+    /// Calculate the MAC of `input` with the `hkdf-hmac-sha256.v2` method, using `info` as the
+    /// info parameter.
+    fn calculate_mac(&self, input: &str, info: &str) -> Base64;
+}
+
+/// The identifier of a verification flow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::exhaustive_enums)]
+pub enum FlowId {
+    /// A verification using to-device events, identified by its transaction ID.
+    ToDevice(OwnedTransactionId),
+
+    /// A verification using room events, identified by the ID of the request event.
+    InRoom(OwnedEventId),
+}
+
+impl FlowId {
+    /// The string representation of this ID.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ToDevice(transaction_id) => transaction_id.as_str(),
+            Self::InRoom(event_id) => event_id.as_str(),
+        }
+    }
+}
+
+/// The content of an event to send to the other party of a verification.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant, clippy::exhaustive_enums)]
+pub enum OutgoingContent {
+    /// The content of a to-device event.
+    ToDevice {
+        /// The device of the other user that the event must be sent to.
+        ///
+        /// This is all the devices of the other user until one of them is ready.
+        recipient: DeviceIdOrAllDevices,
+
+        /// The content of the event.
+        content: AnyToDeviceEventContent,
+    },
+
+    /// The content of a room event.
+    InRoom(AnyMessageLikeEventContent),
+}
+
+/// The identities of the two parties of a verification.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct SasConfig {
+    /// The ID of our user.
+    pub own_user_id: OwnedUserId,
+
+    /// The ID of our device.
+    pub own_device_id: OwnedDeviceId,
+
+    /// Our keys that are verified by the other party, as a map of key ID to public key.
+    ///
+    /// This is usually the Ed25519 key of our device and our cross-signing master key.
+    pub own_keys: BTreeMap<String, String>,
+
+    /// The ID of the user we want to verify.
+    pub their_user_id: OwnedUserId,
+
+    /// The keys of the other party that we know of, as a map of key ID to public key.
+    ///
+    /// Only the keys present in this map are verified.
+    pub their_keys: BTreeMap<String, String>,
+
+    /// The devices of the other user that our to-device request was sent to.
+    ///
+    /// When one of them is ready, the others are sent a cancellation. It is not used for in-room
+    /// verifications, or for requests that we received.
+    pub their_device_ids: Vec<OwnedDeviceId>,
+}
+
+impl SasConfig {
+    /// Creates a new `SasConfig` with the given user and device IDs, empty key maps and no devices
+    /// of the other user.
+    pub fn new(
+        own_user_id: OwnedUserId,
+        own_device_id: OwnedDeviceId,
+        their_user_id: OwnedUserId,
+    ) -> Self {
+        Self {
+            own_user_id,
+            own_device_id,
+            own_keys: BTreeMap::new(),
+            their_user_id,
+            their_keys: BTreeMap::new(),
+            their_device_ids: Vec::new(),
+        }
+    }
+
+    /// Creates the content of a to-device verification request.
+    pub fn to_device_request(
+        &self,
+        transaction_id: OwnedTransactionId,
+        timestamp: MilliSecondsSinceUnixEpoch,
+    ) -> ToDeviceKeyVerificationRequestEventContent {
+        ToDeviceKeyVerificationRequestEventContent::new(
+            self.own_device_id.clone(),
+            transaction_id,
+            vec![VerificationMethod::SasV1],
+            timestamp,
+        )
+    }
+
+    /// Creates the content of an in-room verification request.
+    pub fn in_room_request(&self) -> KeyVerificationRequestEventContent {
+        KeyVerificationRequestEventContent::new(
+            format!(
+                "{} is requesting to verify your key, but your client does not support in-chat \
+                 key verification. You will need to use legacy key verification to verify keys.",
+                self.own_user_id
+            ),
+            vec![VerificationMethod::SasV1],
+            self.own_device_id.clone(),
+            self.their_user_id.clone(),
+        )
+    }
+}
+
+/// An error encountered when creating a verification from an incoming request.
+///
+/// The request should be ignored.
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum SasRequestError {
+    /// The request is too old or too far in the future.
+    #[error("the verification request has expired")]
+    Expired,
+
+    /// The request doesn't support SAS verification.
+    #[error("the verification request does not support SAS verification")]
+    UnsupportedMethod,
+
+    /// The request wasn't sent by the expected user or to our user.
+    #[error("the verification request is not between the expected users")]
+    UserMismatch,
+}
+
+/// Information about a cancelled verification.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct CancelInfo {
+    /// The code of the cancellation.
+    pub code: CancelCode,
+
+    /// The human-readable reason of the cancellation.
+    pub reason: String,
+
+    /// Whether the verification was cancelled by us.
+    pub cancelled_by_us: bool,
+}
+
+/// The state of a [`SasVerification`].
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum SasState {
+    /// We sent a request and are waiting for the other party to be ready.
+    Requested,
+
+    /// We received a request and must [accept](SasVerification::accept_request) it.
+    RequestReceived,
+
+    /// Both parties are ready, one of them must [start](SasVerification::start) the
+    /// verification.
+    Ready,
+
+    /// The verification was started, the responder must [accept](SasVerification::accept) it.
+    Started,
+
+    /// The verification was accepted, the ephemeral keys are being exchanged.
+    Accepted,
+
+    /// The keys were exchanged, the user must compare the short authentication string and
+    /// [confirm](SasVerification::confirm) that it matches.
+    KeysExchanged,
+
+    /// We confirmed that the short authentication string matches and are waiting for the other
+    /// party.
+    Confirmed,
+
+    /// The verification was successful.
+    Done,
+
+    /// The verification was cancelled.
+    Cancelled(CancelInfo),
+}
+
+/// An emoji of the short authentication string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::exhaustive_structs)]
+pub struct SasEmoji {
+    /// The emoji symbol.
+    pub symbol: &'static str,
+
+    /// The English description of the emoji.
+    ///
+    /// The translations of the descriptions are available in the [spec repository].
+    ///
+    /// [spec repository]: https://github.com/matrix-org/matrix-spec/tree/main/data-definitions
+    pub description: &'static str,
+}
+
+/// A SAS verification with another device.
+///
+/// All the methods that change the state return the content of the events that must be sent to
+/// the other party, in order.
+#[derive(Debug)]
+pub struct SasVerification<C> {
+    crypto: C,
+    config: SasConfig,
+    flow_id: FlowId,
+    their_device_id: Option<OwnedDeviceId>,
+    created_at: MilliSecondsSinceUnixEpoch,
+    state: SasState,
+
+    /// Whether we sent the `m.key.verification.start` event.
+    we_started: bool,
+
+    /// The canonical JSON of the `m.key.verification.start` event content.
+    start_json: Option<String>,
+
+    /// The short authentication string methods offered in the start event.
+    start_sas: Vec<ShortAuthenticationString>,
+
+    /// The short authentication string methods agreed upon.
+    sas_methods: Vec<ShortAuthenticationString>,
+
+    /// The commitment received in the `m.key.verification.accept` event.
+    their_commitment: Option<Base64>,
+
+    their_public_key: Option<Base64>,
+    sas_bytes: Option<Vec<u8>>,
+
+    /// The IDs of the keys verified with the MAC of the other party.
+    verified_keys: Option<Vec<String>>,
+
+    done_received: bool,
+}
+
+impl<C: SasCrypto> SasVerification<C> {
+    /// Creates a verification for a request that we sent.
+    ///
+    /// The content of the request can be created with [`SasConfig::to_device_request()`] or
+    /// [`SasConfig::in_room_request()`]. For an in-room verification, the flow ID is the ID of
+    /// the request event.
+    pub fn new(
+        crypto: C,
+        config: SasConfig,
+        flow_id: FlowId,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Self {
+        Self::with_state(crypto, config, flow_id, None, now, SasState::Requested)
+    }
+
+    /// Creates a verification from an incoming to-device request.
+    pub fn from_to_device_request(
+        crypto: C,
+        config: SasConfig,
+        request: &ToDeviceEvent<ToDeviceKeyVerificationRequestEventContent>,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Result<Self, SasRequestError> {
+        if request.sender != config.their_user_id {
+            return Err(SasRequestError::UserMismatch);
+        }
+
+        let content = &request.content;
+        check_request(&content.methods, content.timestamp, now)?;
+
+        Ok(Self::with_state(
+            crypto,
+            config,
+            FlowId::ToDevice(content.transaction_id.clone()),
+            Some(content.from_device.clone()),
+            now,
+            SasState::RequestReceived,
+        ))
+    }
+
+    /// Creates a verification from an incoming in-room request.
+    ///
+    /// Returns `Err(_)` if the event is not a verification request.
+    pub fn from_room_request(
+        crypto: C,
+        config: SasConfig,
+        request: &OriginalSyncRoomMessageEvent,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Result<Self, SasRequestError> {
+        let MessageType::VerificationRequest(content) = &request.content.msgtype else {
+            return Err(SasRequestError::UnsupportedMethod);
+        };
+
+        if request.sender != config.their_user_id || content.to != config.own_user_id {
+            return Err(SasRequestError::UserMismatch);
+        }
+
+        check_request(&content.methods, request.origin_server_ts, now)?;
+
+        Ok(Self::with_state(
+            crypto,
+            config,
+            FlowId::InRoom(request.event_id.clone()),
+            Some(content.from_device.clone()),
+            now,
+            SasState::RequestReceived,
+        ))
+    }
+
+    fn with_state(
+        crypto: C,
+        config: SasConfig,
+        flow_id: FlowId,
+        their_device_id: Option<OwnedDeviceId>,
+        now: MilliSecondsSinceUnixEpoch,
+        state: SasState,
+    ) -> Self {
+        Self {
+            crypto,
+            config,
+            flow_id,
+            their_device_id,
+            created_at: now,
+            state,
+            we_started: false,
+            start_json: None,
+            start_sas: Vec::new(),
+            sas_methods: Vec::new(),
+            their_commitment: None,
+            their_public_key: None,
+            sas_bytes: None,
+            verified_keys: None,
+            done_received: false,
+        }
+    }
+
+    /// The ID of this verification flow.
+    pub fn flow_id(&self) -> &FlowId {
+        &self.flow_id
+    }
+
+    /// The ID of the device of the other party, if it is known.
+    pub fn their_device_id(&self) -> Option<&OwnedDeviceId> {
+        self.their_device_id.as_ref()
+    }
+
+    /// The current state of the verification.
+    pub fn state(&self) -> &SasState {
+        &self.state
+    }
+
+    /// Whether the verification was successful.
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, SasState::Done)
+    }
+
+    /// Whether the verification was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.state, SasState::Cancelled(_))
+    }
+
+    /// Whether we sent the `m.key.verification.start` event.
+    pub fn we_started(&self) -> bool {
+        self.we_started
+    }
+
+    /// The IDs of the keys of the other party that were verified.
+    ///
+    /// Returns `None` until the verification is done.
+    pub fn verified_keys(&self) -> Option<&[String]> {
+        self.verified_keys.as_deref().filter(|_| self.is_done())
+    }
+
+    /// The short authentication string as three decimal numbers between 1000 and 9191.
+    ///
+    /// Returns `None` if the keys were not exchanged yet.
+    pub fn decimals(&self) -> Option<(u16, u16, u16)> {
+        let bytes = self.sas_bytes.as_deref()?;
+        let [b0, b1, b2, b3, b4, ..] = *bytes else {
+            return None;
+        };
+        let (b0, b1, b2, b3, b4) = (b0 as u16, b1 as u16, b2 as u16, b3 as u16, b4 as u16);
+
+        Some((
+            ((b0 << 5) | (b1 >> 3)) + 1000,
+            (((b1 & 0x7) << 10) | (b2 << 2) | (b3 >> 6)) + 1000,
+            (((b3 & 0x3f) << 7) | (b4 >> 1)) + 1000,
+        ))
+    }
+
+    /// The short authentication string as the indices of seven emojis in the [SAS emoji table].
+    ///
+    /// Returns `None` if the keys were not exchanged yet, or if both parties didn't agree to use
+    /// emojis.
+    ///
+    /// [SAS emoji table]: https://spec.matrix.org/latest/client-server-api/#sas-method-emoji
+    pub fn emoji_indices(&self) -> Option<[u8; 7]> {
+        if !self.sas_methods.contains(&ShortAuthenticationString::Emoji) {
+            return None;
+        }
+
+        let bytes: [u8; 6] = self.sas_bytes.as_deref()?.get(..6)?.try_into().ok()?;
+        let mut num = bytes.iter().fold(0_u64, |num, byte| (num << 8) | u64::from(*byte));
+        // Only the first 42 bits are used.
+        num >>= 6;
+
+        let mut indices = [0; 7];
+        for (i, index) in indices.iter_mut().rev().enumerate() {
+            *index = ((num >> (6 * i)) & 0x3f) as u8;
+        }
+        Some(indices)
+    }
+
+    /// The short authentication string as seven emojis.
+    ///
+    /// Returns `None` if the keys were not exchanged yet, or if both parties didn't agree to use
+    /// emojis.
+    pub fn emojis(&self) -> Option<[SasEmoji; 7]> {
+        Some(self.emoji_indices()?.map(|index| SAS_EMOJIS[usize::from(index)]))
+    }
+
+    /// Accept an incoming request.
+    pub fn accept_request(&mut self) -> Vec<OutgoingContent> {
+        if !matches!(self.state, SasState::RequestReceived) {
+            return Vec::new();
+        }
+
+        self.state = SasState::Ready;
+        let methods = vec![VerificationMethod::SasV1];
+        let own_device_id = self.config.own_device_id.clone();
+
+        vec![self.content(
+            |transaction_id| {
+                ToDeviceKeyVerificationReadyEventContent::new(
+                    own_device_id.clone(),
+                    methods.clone(),
+                    transaction_id,
+                )
+                .into()
+            },
+            |relates_to| {
+                KeyVerificationReadyEventContent::new(
+                    own_device_id.clone(),
+                    methods.clone(),
+                    relates_to,
+                )
+                .into()
+            },
+        )]
+    }
+
+    /// Start the SAS verification.
+    ///
+    /// This can only be called when both parties are ready.
+    pub fn start(&mut self) -> Vec<OutgoingContent> {
+        if !matches!(self.state, SasState::Ready) {
+            return Vec::new();
+        }
+
+        let method = StartMethod::SasV1(
+            StartSasV1ContentInit {
+                key_agreement_protocols: vec![KeyAgreementProtocol::Curve25519HkdfSha256],
+                hashes: vec![HashAlgorithm::Sha256],
+                message_authentication_codes: vec![MessageAuthenticationCode::HkdfHmacSha256V2],
+                short_authentication_string: SUPPORTED_SAS.to_vec(),
+            }
+            .into(),
+        );
+        let own_device_id = self.config.own_device_id.clone();
+
+        let content = self.content(
+            |transaction_id| {
+                ToDeviceKeyVerificationStartEventContent::new(
+                    own_device_id.clone(),
+                    transaction_id,
+                    method.clone(),
+                )
+                .into()
+            },
+            |relates_to| {
+                KeyVerificationStartEventContent::new(
+                    own_device_id.clone(),
+                    method.clone(),
+                    relates_to,
+                )
+                .into()
+            },
+        );
+
+        let start_json = match &content {
+            OutgoingContent::ToDevice {
+                content: AnyToDeviceEventContent::KeyVerificationStart(c),
+                ..
+            } => to_canonical_value(c),
+            OutgoingContent::InRoom(AnyMessageLikeEventContent::KeyVerificationStart(c)) => {
+                to_canonical_value(c)
+            }
+            _ => unreachable!("the content is a start event"),
+        };
+        self.start_json =
+            Some(start_json.expect("start content serialization to succeed").to_string());
+        self.start_sas = SUPPORTED_SAS.to_vec();
+        self.we_started = true;
+        self.state = SasState::Started;
+
+        vec![content]
+    }
+
+    /// Accept the SAS verification started by the other party.
+    pub fn accept(&mut self) -> Vec<OutgoingContent> {
+        if !matches!(self.state, SasState::Started) || self.we_started {
+            return Vec::new();
+        }
+
+        let Some(start_json) = &self.start_json else {
+            return Vec::new();
+        };
+        let commitment = self.commitment(&self.crypto.public_key(), start_json);
+
+        self.sas_methods =
+            SUPPORTED_SAS.into_iter().filter(|sas| self.start_sas.contains(sas)).collect();
+        let method = AcceptMethod::SasV1(AcceptSasV1Content::from(AcceptSasV1ContentInit {
+            key_agreement_protocol: KeyAgreementProtocol::Curve25519HkdfSha256,
+            hash: HashAlgorithm::Sha256,
+            message_authentication_code: MessageAuthenticationCode::HkdfHmacSha256V2,
+            short_authentication_string: self.sas_methods.clone(),
+            commitment,
+        }));
+
+        self.state = SasState::Accepted;
+
+        vec![self.content(
+            |transaction_id| {
+                ToDeviceKeyVerificationAcceptEventContent::new(transaction_id, method.clone())
+                    .into()
+            },
+            |relates_to| KeyVerificationAcceptEventContent::new(method.clone(), relates_to).into(),
+        )]
+    }
+
+    /// Confirm that the short authentication string matches.
+    pub fn confirm(&mut self) -> Vec<OutgoingContent> {
+        if !matches!(self.state, SasState::KeysExchanged) {
+            return Vec::new();
+        }
+
+        self.state = SasState::Confirmed;
+        let mut contents = vec![self.mac_content()];
+
+        if self.verified_keys.is_some() {
+            contents.push(self.done_content());
+            if self.done_received {
+                self.state = SasState::Done;
+            }
+        }
+
+        contents
+    }
+
+    /// Declare that the short authentication string doesn't match.
+    ///
+    /// This cancels the verification.
+    pub fn mismatch(&mut self) -> Vec<OutgoingContent> {
+        if !matches!(self.state, SasState::KeysExchanged) {
+            return Vec::new();
+        }
+
+        self.cancel_with(CancelCode::MismatchedSas)
+    }
+
+    /// Cancel the verification.
+    pub fn cancel(&mut self) -> Vec<OutgoingContent> {
+        self.cancel_with(CancelCode::User)
+    }
+
+    /// Cancel the verification if it timed out.
+    pub fn check_timeout(&mut self, now: MilliSecondsSinceUnixEpoch) -> Vec<OutgoingContent> {
+        if self.is_finished() || !self.is_timed_out(now) {
+            return Vec::new();
+        }
+
+        self.cancel_with(CancelCode::Timeout)
+    }
+
+    /// Handle an incoming to-device event.
+    ///
+    /// The event is taken in its raw form because the commitment of the verification is computed
+    /// from the JSON of the `m.key.verification.start` event, as it was received.
+    ///
+    /// Events that don't belong to this verification or that fail to deserialize are ignored.
+    pub fn receive_to_device_event(
+        &mut self,
+        raw_event: &Raw<AnyToDeviceEvent>,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Vec<OutgoingContent> {
+        let FlowId::ToDevice(transaction_id) = &self.flow_id else {
+            return Vec::new();
+        };
+        let Ok(event) = raw_event.deserialize() else {
+            return Vec::new();
+        };
+
+        let message = match &event {
+            AnyToDeviceEvent::KeyVerificationReady(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Ready {
+                    from_device: ev.content.from_device.clone(),
+                    methods: ev.content.methods.clone(),
+                }
+            }
+            AnyToDeviceEvent::KeyVerificationStart(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Start {
+                    from_device: ev.content.from_device.clone(),
+                    method: ev.content.method.clone(),
+                    canonical_json: content_canonical_json(raw_event),
+                }
+            }
+            AnyToDeviceEvent::KeyVerificationAccept(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Accept(ev.content.method.clone())
+            }
+            AnyToDeviceEvent::KeyVerificationKey(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Key(ev.content.key.clone())
+            }
+            AnyToDeviceEvent::KeyVerificationMac(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Mac { mac: ev.content.mac.clone(), keys: ev.content.keys.clone() }
+            }
+            AnyToDeviceEvent::KeyVerificationDone(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Done
+            }
+            AnyToDeviceEvent::KeyVerificationCancel(ev)
+                if ev.content.transaction_id == *transaction_id =>
+            {
+                Message::Cancel { code: ev.content.code.clone(), reason: ev.content.reason.clone() }
+            }
+            _ => return Vec::new(),
+        };
+
+        if *event.sender() != self.config.their_user_id {
+            return Vec::new();
+        }
+
+        self.receive(message, now)
+    }
+
+    /// Handle an incoming room event.
+    ///
+    /// The event is taken in its raw form because the commitment of the verification is computed
+    /// from the JSON of the `m.key.verification.start` event, as it was received.
+    ///
+    /// Events that don't belong to this verification or that fail to deserialize are ignored.
+    pub fn receive_room_event(
+        &mut self,
+        raw_event: &Raw<AnySyncMessageLikeEvent>,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Vec<OutgoingContent> {
+        let FlowId::InRoom(event_id) = &self.flow_id else {
+            return Vec::new();
+        };
+        let Ok(event) = raw_event.deserialize() else {
+            return Vec::new();
+        };
+
+        let message =
+            match &event {
+                AnySyncMessageLikeEvent::KeyVerificationReady(SyncMessageLikeEvent::Original(
+                    ev,
+                )) if ev.content.relates_to.event_id == *event_id => Message::Ready {
+                    from_device: ev.content.from_device.clone(),
+                    methods: ev.content.methods.clone(),
+                },
+                AnySyncMessageLikeEvent::KeyVerificationStart(SyncMessageLikeEvent::Original(
+                    ev,
+                )) if ev.content.relates_to.event_id == *event_id => Message::Start {
+                    from_device: ev.content.from_device.clone(),
+                    method: ev.content.method.clone(),
+                    canonical_json: content_canonical_json(raw_event),
+                },
+                AnySyncMessageLikeEvent::KeyVerificationAccept(SyncMessageLikeEvent::Original(
+                    ev,
+                )) if ev.content.relates_to.event_id == *event_id => {
+                    Message::Accept(ev.content.method.clone())
+                }
+                AnySyncMessageLikeEvent::KeyVerificationKey(SyncMessageLikeEvent::Original(ev))
+                    if ev.content.relates_to.event_id == *event_id =>
+                {
+                    Message::Key(ev.content.key.clone())
+                }
+                AnySyncMessageLikeEvent::KeyVerificationMac(SyncMessageLikeEvent::Original(ev))
+                    if ev.content.relates_to.event_id == *event_id =>
+                {
+                    Message::Mac { mac: ev.content.mac.clone(), keys: ev.content.keys.clone() }
+                }
+                AnySyncMessageLikeEvent::KeyVerificationDone(SyncMessageLikeEvent::Original(
+                    ev,
+                )) if ev.content.relates_to.event_id == *event_id => Message::Done,
+                AnySyncMessageLikeEvent::KeyVerificationCancel(SyncMessageLikeEvent::Original(
+                    ev,
+                )) if ev.content.relates_to.event_id == *event_id => Message::Cancel {
+                    code: ev.content.code.clone(),
+                    reason: ev.content.reason.clone(),
+                },
+                _ => return Vec::new(),
+            };
+
+        // Our own events are echoed back in the room.
+        if *event.sender() != self.config.their_user_id {
+            return Vec::new();
+        }
+
+        self.receive(message, now)
+    }
+
+    fn receive(
+        &mut self,
+        message: Message,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Vec<OutgoingContent> {
+        if self.is_finished() {
+            return Vec::new();
+        }
+
+        if let Message::Cancel { code, reason } = message {
+            self.state = SasState::Cancelled(CancelInfo { code, reason, cancelled_by_us: false });
+            return Vec::new();
+        }
+
+        // Another device of the other user responded to the request after one was accepted, the
+        // verification continues with the accepted device.
+        if let Message::Ready { from_device, .. } | Message::Start { from_device, .. } = &message {
+            if self.their_device_id.as_ref().is_some_and(|device_id| device_id != from_device) {
+                return self.cancel_other_device(from_device.clone());
+            }
+        }
+
+        if self.is_timed_out(now) {
+            return self.cancel_with(CancelCode::Timeout);
+        }
+
+        match (&self.state, message) {
+            (SasState::Requested, Message::Ready { from_device, methods }) => {
+                if !methods.contains(&VerificationMethod::SasV1) {
+                    return self.cancel_with(CancelCode::UnknownMethod);
+                }
+
+                // The other devices that received the request don't need to wait for a response.
+                let other_devices = self
+                    .config
+                    .their_device_ids
+                    .iter()
+                    .filter(|device_id| **device_id != from_device)
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                self.their_device_id = Some(from_device);
+                self.state = SasState::Ready;
+                other_devices
+                    .into_iter()
+                    .flat_map(|device_id| self.cancel_other_device(device_id))
+                    .collect()
+            }
+            (SasState::Ready, Message::Start { from_device, method, canonical_json }) => {
+                self.receive_start(from_device, method, canonical_json)
+            }
+            (SasState::Started, Message::Start { from_device, method, canonical_json })
+                if self.we_started =>
+            {
+                // Both parties started the verification at the same time, the start event of the
+                // party with the lowest user ID, or device ID for the same user, is used.
+                let own = (&self.config.own_user_id, &self.config.own_device_id);
+                let theirs = (&self.config.their_user_id, &from_device);
+                if own < theirs {
+                    return Vec::new();
+                }
+
+                self.we_started = false;
+                self.receive_start(from_device, method, canonical_json)
+            }
+            (SasState::Started, Message::Accept(method)) if self.we_started => {
+                self.receive_accept(method)
+            }
+            (SasState::Accepted, Message::Key(key)) => self.receive_key(key),
+            (SasState::KeysExchanged | SasState::Confirmed, Message::Mac { mac, keys })
+                if self.verified_keys.is_none() =>
+            {
+                self.receive_mac(&mac, &keys)
+            }
+            (SasState::Confirmed, Message::Done) if self.verified_keys.is_some() => {
+                self.done_received = true;
+                self.state = SasState::Done;
+                Vec::new()
+            }
+            (SasState::KeysExchanged, Message::Done) if self.verified_keys.is_some() => {
+                self.done_received = true;
+                Vec::new()
+            }
+            _ => self.cancel_with(CancelCode::UnexpectedMessage),
+        }
+    }
+
+    fn receive_start(
+        &mut self,
+        from_device: OwnedDeviceId,
+        method: StartMethod,
+        canonical_json: Option<String>,
+    ) -> Vec<OutgoingContent> {
+        let Some(canonical_json) = canonical_json else {
+            return self.cancel_with(CancelCode::InvalidMessage);
+        };
+
+        let StartMethod::SasV1(content) = method else {
+            return self.cancel_with(CancelCode::UnknownMethod);
+        };
+
+        if !is_supported_start(&content) {
+            return self.cancel_with(CancelCode::UnknownMethod);
+        }
+
+        self.their_device_id = Some(from_device);
+        self.start_json = Some(canonical_json);
+        self.start_sas = content.short_authentication_string;
+        self.state = SasState::Started;
+        Vec::new()
+    }
+
+    fn receive_accept(&mut self, method: AcceptMethod) -> Vec<OutgoingContent> {
+        let AcceptMethod::SasV1(content) = method else {
+            return self.cancel_with(CancelCode::UnknownMethod);
+        };
+
+        let sas_methods: Vec<_> = SUPPORTED_SAS
+            .into_iter()
+            .filter(|sas| content.short_authentication_string.contains(sas))
+            .collect();
+        if content.key_agreement_protocol != KeyAgreementProtocol::Curve25519HkdfSha256
+            || content.hash != HashAlgorithm::Sha256
+            || content.message_authentication_code != MessageAuthenticationCode::HkdfHmacSha256V2
+            || sas_methods.is_empty()
+        {
+            return self.cancel_with(CancelCode::UnknownMethod);
+        }
+
+        self.sas_methods = sas_methods;
+        self.their_commitment = Some(content.commitment);
+        self.state = SasState::Accepted;
+
+        vec![self.key_content()]
+    }
+
+    fn receive_key(&mut self, key: Base64) -> Vec<OutgoingContent> {
+        let mut contents = Vec::new();
+
+        if self.we_started {
+            // We sent our key after receiving the accept event, check that their key matches the
+            // commitment.
+            let (Some(their_commitment), Some(start_json)) =
+                (&self.their_commitment, &self.start_json)
+            else {
+                return self.cancel_with(CancelCode::UnexpectedMessage);
+            };
+
+            if self.commitment(&key, start_json) != *their_commitment {
+                return self.cancel_with(CancelCode::MismatchedCommitment);
+            }
+        } else {
+            contents.push(self.key_content());
+        }
+
+        if self.crypto.diffie_hellman(&key).is_err() {
+            return self.cancel_with(CancelCode::InvalidMessage);
+        }
+        self.their_public_key = Some(key);
+        self.sas_bytes = Some(self.crypto.generate_bytes(&self.sas_info(), 6));
+        self.state = SasState::KeysExchanged;
+
+        contents
+    }
+
+    fn receive_mac(
+        &mut self,
+        mac: &BTreeMap<String, Base64>,
+        keys: &Base64,
+    ) -> Vec<OutgoingContent> {
+        let Some(their_device_id) = &self.their_device_id else {
+            return self.cancel_with(CancelCode::UnexpectedMessage);
+        };
+        let base_info = mac_base_info(
+            &self.config.their_user_id,
+            their_device_id.as_str(),
+            &self.config.own_user_id,
+            self.config.own_device_id.as_str(),
+            &self.flow_id,
+        );
+
+        let key_ids = mac.keys().map(String::as_str).collect::<Vec<_>>().join(",");
+        if self.crypto.calculate_mac(&key_ids, &format!("{base_info}KEY_IDS")) != *keys {
+            return self.cancel_with(CancelCode::KeyMismatch);
+        }
+
+        let mut verified_keys = Vec::new();
+        for (key_id, key_mac) in mac {
+            // Keys that we don't know of can't be verified.
+            let Some(key) = self.config.their_keys.get(key_id) else {
+                continue;
+            };
+
+            if self.crypto.calculate_mac(key, &format!("{base_info}{key_id}")) != *key_mac {
+                return self.cancel_with(CancelCode::KeyMismatch);
+            }
+
+            verified_keys.push(key_id.clone());
+        }
+
+        if verified_keys.is_empty() {
+            return self.cancel_with(CancelCode::KeyMismatch);
+        }
+
+        self.verified_keys = Some(verified_keys);
+
+        if matches!(self.state, SasState::Confirmed) {
+            vec![self.done_content()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(self.state, SasState::Done | SasState::Cancelled(_))
+    }
+
+    fn is_timed_out(&self, now: MilliSecondsSinceUnixEpoch) -> bool {
+        let elapsed = Duration::from_millis(now.get().saturating_sub(self.created_at.get()).into());
+        elapsed > VERIFICATION_TIMEOUT
+    }
+
+    fn cancel_with(&mut self, code: CancelCode) -> Vec<OutgoingContent> {
+        if self.is_finished() {
+            return Vec::new();
+        }
+
+        let reason = cancel_reason(&code).to_owned();
+        self.state = SasState::Cancelled(CancelInfo {
+            code: code.clone(),
+            reason: reason.clone(),
+            cancelled_by_us: true,
+        });
+
+        vec![self.content(
+            |transaction_id| {
+                ToDeviceKeyVerificationCancelEventContent::new(
+                    transaction_id,
+                    reason.clone(),
+                    code.clone(),
+                )
+                .into()
+            },
+            |relates_to| {
+                KeyVerificationCancelEventContent::new(reason.clone(), code.clone(), relates_to)
+                    .into()
+            },
+        )]
+    }
+
+    /// Cancel the verification with a device of the other user that is not the one taking part in
+    /// the verification.
+    ///
+    /// This doesn't change the state of the verification. In-room verifications are ignored
+    /// because all the devices see which one responded first.
+    fn cancel_other_device(&self, device_id: OwnedDeviceId) -> Vec<OutgoingContent> {
+        let FlowId::ToDevice(transaction_id) = &self.flow_id else {
+            return Vec::new();
+        };
+
+        let code = CancelCode::Accepted;
+        let content = ToDeviceKeyVerificationCancelEventContent::new(
+            transaction_id.clone(),
+            cancel_reason(&code).to_owned(),
+            code,
+        );
+
+        vec![OutgoingContent::ToDevice {
+            recipient: DeviceIdOrAllDevices::DeviceId(device_id),
+            content: content.into(),
+        }]
+    }
+
+    fn commitment(&self, public_key: &Base64, start_json: &str) -> Base64 {
+        let input = format!("{}{start_json}", public_key.encode());
+        Base64::new(self.crypto.sha256(input.as_bytes()))
+    }
+
+    /// The info parameter to generate the short authentication string, for the
+    /// `curve25519-hkdf-sha256` key agreement protocol.
+    fn sas_info(&self) -> String {
+        let own_key = self.crypto.public_key().encode();
+        let their_key = self.their_public_key.as_ref().map(Base64::encode).unwrap_or_default();
+        let their_device_id = self.their_device_id.as_ref().map(|d| d.as_str()).unwrap_or_default();
+
+        let own = format!("{}|{}|{own_key}", self.config.own_user_id, self.config.own_device_id);
+        let theirs = format!("{}|{their_device_id}|{their_key}", self.config.their_user_id);
+        let (initiator, acceptor) = if self.we_started { (own, theirs) } else { (theirs, own) };
+
+        format!("MATRIX_KEY_VERIFICATION_SAS|{initiator}|{acceptor}|{}", self.flow_id.as_str())
+    }
+
+    fn key_content(&self) -> OutgoingContent {
+        let key = self.crypto.public_key();
+
+        self.content(
+            |transaction_id| {
+                ToDeviceKeyVerificationKeyEventContent::new(transaction_id, key.clone()).into()
+            },
+            |relates_to| KeyVerificationKeyEventContent::new(key.clone(), relates_to).into(),
+        )
+    }
+
+    fn mac_content(&self) -> OutgoingContent {
+        let their_device_id = self.their_device_id.as_ref().map(|d| d.as_str()).unwrap_or_default();
+        let base_info = mac_base_info(
+            &self.config.own_user_id,
+            self.config.own_device_id.as_str(),
+            &self.config.their_user_id,
+            their_device_id,
+            &self.flow_id,
+        );
+
+        let mac: BTreeMap<_, _> = self
+            .config
+            .own_keys
+            .iter()
+            .map(|(key_id, key)| {
+                (key_id.clone(), self.crypto.calculate_mac(key, &format!("{base_info}{key_id}")))
+            })
+            .collect();
+        let key_ids = mac.keys().map(String::as_str).collect::<Vec<_>>().join(",");
+        let keys = self.crypto.calculate_mac(&key_ids, &format!("{base_info}KEY_IDS"));
+
+        self.content(
+            |transaction_id| {
+                ToDeviceKeyVerificationMacEventContent::new(
+                    transaction_id,
+                    mac.clone(),
+                    keys.clone(),
+                )
+                .into()
+            },
+            |relates_to| {
+                KeyVerificationMacEventContent::new(mac.clone(), keys.clone(), relates_to).into()
+            },
+        )
+    }
+
+    fn done_content(&self) -> OutgoingContent {
+        self.content(
+            |transaction_id| ToDeviceKeyVerificationDoneEventContent::new(transaction_id).into(),
+            |relates_to| KeyVerificationDoneEventContent::new(relates_to).into(),
+        )
+    }
+
+    fn content(
+        &self,
+        to_device: impl FnOnce(OwnedTransactionId) -> AnyToDeviceEventContent,
+        in_room: impl FnOnce(Reference) -> AnyMessageLikeEventContent,
+    ) -> OutgoingContent {
+        match &self.flow_id {
+            FlowId::ToDevice(transaction_id) => OutgoingContent::ToDevice {
+                recipient: self
+                    .their_device_id
+                    .clone()
+                    .map_or(DeviceIdOrAllDevices::AllDevices, DeviceIdOrAllDevices::DeviceId),
+                content: to_device(transaction_id.clone()),
+            },
+            FlowId::InRoom(event_id) => {
+                OutgoingContent::InRoom(in_room(Reference::new(event_id.clone())))
+            }
+        }
+    }
+}
+
+/// A normalized verification event.
+enum Message {
+    Ready {
+        from_device: OwnedDeviceId,
+        methods: Vec<VerificationMethod>,
+    },
+    /// The canonical JSON of the content is `None` if it could not be converted from the received
+    /// JSON.
+    Start {
+        from_device: OwnedDeviceId,
+        method: StartMethod,
+        canonical_json: Option<String>,
+    },
+    Accept(AcceptMethod),
+    Key(Base64),
+    Mac {
+        mac: BTreeMap<String, Base64>,
+        keys: Base64,
+    },
+    Done,
+    Cancel {
+        code: CancelCode,
+        reason: String,
+    },
+}
+
+fn check_request(
+    methods: &[VerificationMethod],
+    timestamp: MilliSecondsSinceUnixEpoch,
+    now: MilliSecondsSinceUnixEpoch,
+) -> Result<(), SasRequestError> {
+    let (now, timestamp) = (u64::from(now.get()), u64::from(timestamp.get()));
+    if Duration::from_millis(now.saturating_sub(timestamp)) > REQUEST_TIMESTAMP_TIMEOUT
+        || Duration::from_millis(timestamp.saturating_sub(now)) > REQUEST_FUTURE_TIMEOUT
+    {
+        return Err(SasRequestError::Expired);
+    }
+
+    if !methods.contains(&VerificationMethod::SasV1) {
+        return Err(SasRequestError::UnsupportedMethod);
+    }
+
+    Ok(())
+}
+
+fn is_supported_start(content: &StartSasV1Content) -> bool {
+    content.key_agreement_protocols.contains(&KeyAgreementProtocol::Curve25519HkdfSha256)
+        && content.hashes.contains(&HashAlgorithm::Sha256)
+        && content
+            .message_authentication_codes
+            .contains(&MessageAuthenticationCode::HkdfHmacSha256V2)
+        && SUPPORTED_SAS.iter().any(|sas| content.short_authentication_string.contains(sas))
+}
+
+/// The canonical JSON of the content of the given event, as it was received.
+fn content_canonical_json<T>(raw_event: &Raw<T>) -> Option<String> {
+    let content = raw_event.get_field::<CanonicalJsonObject>("content").ok()??;
+    Some(CanonicalJsonValue::Object(content).to_string())
+}
+
+/// The beginning of the info parameter to calculate a MAC, for the `hkdf-hmac-sha256.v2` method.
+fn mac_base_info(
+    sender_user_id: &OwnedUserId,
+    sender_device_id: &str,
+    receiver_user_id: &OwnedUserId,
+    receiver_device_id: &str,
+    flow_id: &FlowId,
+) -> String {
+    format!(
+        "MATRIX_KEY_VERIFICATION_MAC{sender_user_id}{sender_device_id}{receiver_user_id}\
+         {receiver_device_id}{}",
+        flow_id.as_str()
+    )
+}
+
+fn cancel_reason(code: &CancelCode) -> &'static str {
+    match code {
+        CancelCode::User => "The user cancelled the verification.",
+        CancelCode::Timeout => "The verification process timed out.",
+        CancelCode::UnknownMethod => "The device does not know how to handle the requested method.",
+        CancelCode::UnexpectedMessage => "The device received an unexpected message.",
+        CancelCode::KeyMismatch => "The key was not verified.",
+        CancelCode::UserMismatch => "The expected user did not match the user verified.",
+        CancelCode::InvalidMessage => "The message received was invalid.",
+        CancelCode::MismatchedCommitment => "The hash commitment did not match.",
+        CancelCode::MismatchedSas => "The short authentication string did not match.",
+        CancelCode::Accepted => "The verification was accepted by another device.",
+        _ => "The verification was cancelled.",
+    }
+}
+
+/// The [SAS emoji table].
+///
+/// [SAS emoji table]: https://spec.matrix.org/latest/client-server-api/#sas-method-emoji
+pub static SAS_EMOJIS: [SasEmoji; 64] = [
+    SasEmoji { symbol: "🐶", description: "Dog" },
+    SasEmoji { symbol: "🐱", description: "Cat" },
+    SasEmoji { symbol: "🦁", description: "Lion" },
+    SasEmoji { symbol: "🐎", description: "Horse" },
+    SasEmoji { symbol: "🦄", description: "Unicorn" },
+    SasEmoji { symbol: "🐷", description: "Pig" },
+    SasEmoji { symbol: "🐘", description: "Elephant" },
+    SasEmoji { symbol: "🐰", description: "Rabbit" },
+    SasEmoji { symbol: "🐼", description: "Panda" },
+    SasEmoji { symbol: "🐓", description: "Rooster" },
+    SasEmoji { symbol: "🐧", description: "Penguin" },
+    SasEmoji { symbol: "🐢", description: "Turtle" },
+    SasEmoji { symbol: "🐟", description: "Fish" },
+    SasEmoji { symbol: "🐙", description: "Octopus" },
+    SasEmoji { symbol: "🦋", description: "Butterfly" },
+    SasEmoji { symbol: "🌷", description: "Flower" },
+    SasEmoji { symbol: "🌳", description: "Tree" },
+    SasEmoji { symbol: "🌵", description: "Cactus" },
+    SasEmoji { symbol: "🍄", description: "Mushroom" },
+    SasEmoji { symbol: "🌏", description: "Globe" },
+    SasEmoji { symbol: "🌙", description: "Moon" },
+    SasEmoji { symbol: "☁️", description: "Cloud" },
+    SasEmoji { symbol: "🔥", description: "Fire" },
+    SasEmoji { symbol: "🍌", description: "Banana" },
+    SasEmoji { symbol: "🍎", description: "Apple" },
+    SasEmoji { symbol: "🍓", description: "Strawberry" },
+    SasEmoji { symbol: "🌽", description: "Corn" },
+    SasEmoji { symbol: "🍕", description: "Pizza" },
+    SasEmoji { symbol: "🎂", description: "Cake" },
+    SasEmoji { symbol: "❤️", description: "Heart" },
+    SasEmoji { symbol: "😀", description: "Smiley" },
+    SasEmoji { symbol: "🤖", description: "Robot" },
+    SasEmoji { symbol: "🎩", description: "Hat" },
+    SasEmoji { symbol: "👓", description: "Glasses" },
+    SasEmoji { symbol: "🔧", description: "Spanner" },
+    SasEmoji { symbol: "🎅", description: "Santa" },
+    SasEmoji { symbol: "👍", description: "Thumbs Up" },
+    SasEmoji { symbol: "☂️", description: "Umbrella" },
+    SasEmoji { symbol: "⌛", description: "Hourglass" },
+    SasEmoji { symbol: "⏰", description: "Clock" },
+    SasEmoji { symbol: "🎁", description: "Gift" },
+    SasEmoji { symbol: "💡", description: "Light Bulb" },
+    SasEmoji { symbol: "📕", description: "Book" },
+    SasEmoji { symbol: "✏️", description: "Pencil" },
+    SasEmoji { symbol: "📎", description: "Paperclip" },
+    SasEmoji { symbol: "✂️", description: "Scissors" },
+    SasEmoji { symbol: "🔒", description: "Lock" },
+    SasEmoji { symbol: "🔑", description: "Key" },
+    SasEmoji { symbol: "🔨", description: "Hammer" },
+    SasEmoji { symbol: "☎️", description: "Telephone" },
+    SasEmoji { symbol: "🏁", description: "Flag" },
+    SasEmoji { symbol: "🚂", description: "Train" },
+    SasEmoji { symbol: "🚲", description: "Bicycle" },
+    SasEmoji { symbol: "✈️", description: "Aeroplane" },
+    SasEmoji { symbol: "🚀", description: "Rocket" },
+    SasEmoji { symbol: "🏆", description: "Trophy" },
+    SasEmoji { symbol: "⚽", description: "Ball" },
+    SasEmoji { symbol: "🎸", description: "Guitar" },
+    SasEmoji { symbol: "🎺", description: "Trumpet" },
+    SasEmoji { symbol: "🔔", description: "Bell" },
+    SasEmoji { symbol: "⚓", description: "Anchor" },
+    SasEmoji { symbol: "🎧", description: "Headphones" },
+    SasEmoji { symbol: "📁", description: "Folder" },
+    SasEmoji { symbol: "📌", description: "Pin" },
+];
@@ -77,6 +77,7 @@ impl KeyVerificationStartEventContent {
 
 /// An enum representing the different method specific `m.key.verification.start` content.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(untagged)]
 pub enum StartMethod {
@@ -98,6 +99,7 @@ pub enum StartMethod {
 /// Method specific content of a unknown key verification method.
 #[doc(hidden)]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[allow(clippy::exhaustive_structs)]
 pub struct _CustomContent {
     /// The name of the method.
@@ -110,6 +112,7 @@ pub struct _CustomContent {
 
 /// The payload of an `m.key.verification.start` event using the `m.sas.v1` method.
 #[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(rename = "m.reciprocate.v1", tag = "method")]
 pub struct ReciprocateV1Content {
@@ -137,6 +140,7 @@ impl fmt::Debug for ReciprocateV1Content {
 /// To create an instance of this type, first create a `SasV1ContentInit` and convert it via
 /// `SasV1Content::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(rename = "m.sas.v1", tag = "method")]
 pub struct SasV1Content {
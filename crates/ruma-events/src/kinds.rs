@@ -519,6 +519,7 @@ pub struct DecryptedOlmV1Event<C: MessageLikeEventContent> {
 
 /// Public keys used for an `m.olm.v1.curve25519-aes-sha2` event.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct OlmV1Keys {
     /// An ed25519 key.
     pub ed25519: String,
@@ -773,3 +774,66 @@ impl_sync_from_full!(
     StaticStateEventContent,
     RedactedStateEventContent
 );
+
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::json_schema::{private::any_of, JsonSchema, SchemaGenerator};
+    use serde_json::Value as JsonValue;
+
+    use super::{
+        MessageLikeEvent, MessageLikeEventContent, OriginalMessageLikeEvent, OriginalStateEvent,
+        OriginalSyncMessageLikeEvent, OriginalSyncStateEvent, RedactContent,
+        RedactedMessageLikeEvent, RedactedMessageLikeEventContent, RedactedStateEvent,
+        RedactedStateEventContent, RedactedSyncMessageLikeEvent, RedactedSyncStateEvent,
+        StateEvent, StaticStateEventContent, SyncMessageLikeEvent, SyncStateEvent,
+    };
+
+    macro_rules! impl_possibly_redacted_event_json_schema {
+        (
+            $ty:ident ( $original:ident, $redacted:ident ),
+            $content_trait:ident, $redacted_content_trait:ident
+        ) => {
+            impl<C> JsonSchema for $ty<C>
+            where
+                C: $content_trait + RedactContent + JsonSchema,
+                C::Redacted: $redacted_content_trait,
+                $original<C>: JsonSchema,
+                $redacted<C::Redacted>: JsonSchema,
+            {
+                fn schema_name() -> Cow<'static, str> {
+                    format!("{}_{}", stringify!($ty), C::schema_name()).into()
+                }
+
+                fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+                    any_of(vec![
+                        generator.subschema_for::<$original<C>>(),
+                        generator.subschema_for::<$redacted<C::Redacted>>(),
+                    ])
+                }
+            }
+        };
+    }
+
+    impl_possibly_redacted_event_json_schema!(
+        MessageLikeEvent(OriginalMessageLikeEvent, RedactedMessageLikeEvent),
+        MessageLikeEventContent,
+        RedactedMessageLikeEventContent
+    );
+    impl_possibly_redacted_event_json_schema!(
+        SyncMessageLikeEvent(OriginalSyncMessageLikeEvent, RedactedSyncMessageLikeEvent),
+        MessageLikeEventContent,
+        RedactedMessageLikeEventContent
+    );
+    impl_possibly_redacted_event_json_schema!(
+        StateEvent(OriginalStateEvent, RedactedStateEvent),
+        StaticStateEventContent,
+        RedactedStateEventContent
+    );
+    impl_possibly_redacted_event_json_schema!(
+        SyncStateEvent(OriginalSyncStateEvent, RedactedSyncStateEvent),
+        StaticStateEventContent,
+        RedactedStateEventContent
+    );
+}
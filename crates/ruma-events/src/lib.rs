@@ -48,6 +48,7 @@
 //! use serde::{Deserialize, Serialize};
 //!
 //! #[derive(Clone, Debug, Deserialize, Serialize)]
+//! # #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 //! #[serde(tag = "rel_type")]
 //! pub enum RelatesTo {
 //!     #[serde(rename = "m.annotation")]
@@ -180,6 +181,7 @@ pub mod space;
 pub mod sticker;
 pub mod tag;
 pub mod typing;
+pub mod validation;
 #[cfg(feature = "unstable-msc3553")]
 pub mod video;
 #[cfg(feature = "unstable-msc3245")]
@@ -246,6 +248,7 @@ pub fn serialize_custom_event_error<T, S: Serializer>(_: &T, _: S) -> Result<S::
 
 /// Describes whether the event mentions other users or the room.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[non_exhaustive]
 pub struct Mentions {
     /// The list of mentioned users.
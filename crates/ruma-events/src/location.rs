@@ -89,6 +89,7 @@ impl LocationEventContent {
 
 /// Location content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct LocationContent {
     /// A `geo:` URI representing the location.
@@ -129,6 +130,7 @@ pub enum ZoomLevelError {
 ///
 /// [OpenStreetMap Wiki]: https://wiki.openstreetmap.org/wiki/Zoom_levels
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct ZoomLevel(UInt);
 
 impl ZoomLevel {
@@ -163,6 +165,7 @@ impl TryFrom<u8> for ZoomLevel {
 
 /// Asset content.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AssetContent {
     /// The type of asset being referred to.
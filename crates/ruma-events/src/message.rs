@@ -178,6 +178,7 @@ impl From<TextContentBlock> for MessageEventContent {
 /// To construct a `TextContentBlock` with custom MIME types, construct a `Vec<TextRepresentation>`
 /// first and use its `::from()` / `.into()` implementation.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct TextContentBlock(Vec<TextRepresentation>);
 
 impl TextContentBlock {
@@ -247,6 +248,7 @@ impl Deref for TextContentBlock {
 
 /// Text content with optional markup.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct TextRepresentation {
     /// The MIME type of the `body`.
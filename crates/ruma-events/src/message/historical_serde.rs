@@ -8,6 +8,7 @@ use super::{TextContentBlock, TextRepresentation};
 
 /// Historical `m.message` text content block from MSC1767.
 #[derive(Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(try_from = "MessageContentBlockSerDeHelper")]
 #[serde(into = "MessageContentBlockSerDeHelper")]
 pub(crate) struct MessageContentBlock(Vec<TextRepresentation>);
@@ -25,6 +26,7 @@ impl From<TextContentBlock> for MessageContentBlock {
 }
 
 #[derive(Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub(crate) struct MessageContentBlockSerDeHelper {
     /// Plain text short form.
     #[serde(rename = "org.matrix.msc1767.text", skip_serializing_if = "Option::is_none")]
@@ -11,6 +11,7 @@ pub mod user;
 
 /// The payload for policy rule events.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PolicyRuleEventContent {
     /// The entity affected by this rule.
@@ -37,6 +38,7 @@ impl PolicyRuleEventContent {
 ///
 /// This type is used when it's not obvious whether the content is redacted or not.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PossiblyRedactedPolicyRuleEventContent {
     /// The entity affected by this rule.
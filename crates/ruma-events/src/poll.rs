@@ -22,6 +22,10 @@ pub mod unstable_end;
 pub mod unstable_response;
 pub mod unstable_start;
 
+mod state;
+
+pub use self::state::PollState;
+
 /// The data from a poll response necessary to compile poll results.
 #[derive(Debug, Clone, Copy)]
 #[allow(clippy::exhaustive_structs)]
@@ -81,6 +81,7 @@ impl PollEndEventContent {
 ///
 /// This is a map of answer ID to number of votes.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PollResultsContentBlock(BTreeMap<String, UInt>);
 
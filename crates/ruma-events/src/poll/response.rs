@@ -77,6 +77,7 @@ impl OriginalPollResponseEvent {
 
 /// A block for selections content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SelectionsContentBlock(Vec<String>);
 
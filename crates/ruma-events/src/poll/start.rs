@@ -126,6 +126,7 @@ impl OriginalSyncPollStartEvent {
 
 /// A block for poll content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PollContentBlock {
     /// The question of the poll.
@@ -172,6 +173,7 @@ impl PollContentBlock {
 
 /// The question of a poll.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PollQuestion {
     /// The text representation of the question.
@@ -209,6 +211,7 @@ pub enum PollKind {
 ///
 /// To build this, use the `TryFrom` implementations.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(try_from = "PollAnswersDeHelper")]
 pub struct PollAnswers(Vec<PollAnswer>);
 
@@ -264,6 +267,7 @@ impl Deref for PollAnswers {
 
 /// Poll answer.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PollAnswer {
     /// The ID of the answer.
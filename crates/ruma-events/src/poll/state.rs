@@ -0,0 +1,433 @@
+//! Incremental computation of the state of a poll.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use indexmap::IndexMap;
+use js_int::UInt;
+use ruma_common::{
+    EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomVersionId, UserId,
+};
+
+use super::{
+    aggregate_results, filter_selections, start::PollKind,
+    unstable_start::UnstablePollStartEventContent, PollResponseData,
+};
+use crate::{room::message::Relation, AnySyncMessageLikeEvent};
+
+/// The state of a poll, updated incrementally as the events of the poll are received.
+///
+/// This is the incremental counterpart of [`compile_poll_results()`] and
+/// [`compile_unstable_poll_results()`]. It accepts the stable and unstable poll start, response
+/// and end events, and redactions, in any order. The results are computed according to the rules
+/// of [MSC3381]:
+///
+/// * Only the latest response of each user is taken into account.
+/// * A response containing an unknown answer is spoiled.
+/// * Only the first `max_selections` answers of a response are taken into account.
+/// * Responses sent after the poll was closed are ignored.
+/// * A poll can only be closed by its creator.
+///
+/// [`compile_poll_results()`]: super::compile_poll_results
+/// [`compile_unstable_poll_results()`]: super::compile_unstable_poll_results
+/// [MSC3381]: https://github.com/matrix-org/matrix-spec-proposals/pull/3381
+#[derive(Clone, Debug)]
+pub struct PollState {
+    /// The version of the room, used to get the target of redactions.
+    room_version: RoomVersionId,
+
+    /// The ID of the poll start event.
+    start_event_id: OwnedEventId,
+
+    /// The content of the poll start event, if it was received.
+    start: Option<PollStartInfo>,
+
+    /// Whether the poll start event was redacted.
+    is_redacted: bool,
+
+    /// The responses to the poll, by event ID.
+    responses: BTreeMap<OwnedEventId, PollResponseInfo>,
+
+    /// The end events of the poll, by event ID.
+    ends: BTreeMap<OwnedEventId, PollEndInfo>,
+
+    /// The IDs of the events that were redacted, to ignore them if they are received later.
+    redacted: BTreeSet<OwnedEventId>,
+}
+
+impl PollState {
+    /// Creates a new `PollState` for the poll started by the event with the given ID, in a room
+    /// with the given version.
+    pub fn new(room_version: RoomVersionId, start_event_id: OwnedEventId) -> Self {
+        Self {
+            room_version,
+            start_event_id,
+            start: None,
+            is_redacted: false,
+            responses: BTreeMap::new(),
+            ends: BTreeMap::new(),
+            redacted: BTreeSet::new(),
+        }
+    }
+
+    /// The ID of the poll start event.
+    pub fn start_event_id(&self) -> &EventId {
+        &self.start_event_id
+    }
+
+    /// Update the state of the poll with the given event.
+    ///
+    /// Returns `true` if the event is part of this poll and was taken into account, `false` if it
+    /// was ignored.
+    pub fn apply_event(&mut self, event: &AnySyncMessageLikeEvent) -> bool {
+        let event_id = event.event_id();
+        let sender = event.sender();
+        let origin_server_ts = event.origin_server_ts();
+
+        match event {
+            AnySyncMessageLikeEvent::PollStart(ev) => {
+                if event_id != self.start_event_id {
+                    return false;
+                }
+
+                let Some(ev) = ev.as_original() else {
+                    self.is_redacted = true;
+                    self.start = None;
+                    return true;
+                };
+
+                // The original event might be received after the redaction.
+                if self.is_redacted {
+                    return false;
+                }
+
+                // Ignore edits of the poll.
+                if matches!(ev.content.relates_to, Some(Relation::Replacement(_))) {
+                    return false;
+                }
+
+                let poll = &ev.content.poll;
+                self.start = Some(PollStartInfo {
+                    sender: sender.to_owned(),
+                    origin_server_ts,
+                    question: poll.question.text.find_plain().unwrap_or_default().to_owned(),
+                    kind: poll.kind.clone(),
+                    max_selections: poll.max_selections,
+                    answers: poll
+                        .answers
+                        .iter()
+                        .map(|a| PollAnswerInfo {
+                            id: a.id.clone(),
+                            text: a.text.find_plain().unwrap_or(&a.id).to_owned(),
+                        })
+                        .collect(),
+                });
+                true
+            }
+            AnySyncMessageLikeEvent::UnstablePollStart(ev) => {
+                if event_id != self.start_event_id {
+                    return false;
+                }
+
+                let Some(ev) = ev.as_original() else {
+                    self.is_redacted = true;
+                    self.start = None;
+                    return true;
+                };
+
+                // The original event might be received after the redaction.
+                if self.is_redacted {
+                    return false;
+                }
+
+                let UnstablePollStartEventContent::New(content) = &ev.content else {
+                    // Ignore edits of the poll.
+                    return false;
+                };
+
+                let poll = &content.poll_start;
+                self.start = Some(PollStartInfo {
+                    sender: sender.to_owned(),
+                    origin_server_ts,
+                    question: poll.question.text.clone(),
+                    kind: poll.kind.clone(),
+                    max_selections: poll.max_selections,
+                    answers: poll
+                        .answers
+                        .iter()
+                        .map(|a| PollAnswerInfo { id: a.id.clone(), text: a.text.clone() })
+                        .collect(),
+                });
+                true
+            }
+            AnySyncMessageLikeEvent::PollResponse(ev) => {
+                let Some(ev) = ev.as_original() else {
+                    return self.redact_event(event_id.to_owned());
+                };
+                if ev.content.relates_to.event_id != self.start_event_id {
+                    return false;
+                }
+
+                self.add_response(
+                    event_id,
+                    sender,
+                    origin_server_ts,
+                    ev.content.selections.to_vec(),
+                )
+            }
+            AnySyncMessageLikeEvent::UnstablePollResponse(ev) => {
+                let Some(ev) = ev.as_original() else {
+                    return self.redact_event(event_id.to_owned());
+                };
+                if ev.content.relates_to.event_id != self.start_event_id {
+                    return false;
+                }
+
+                self.add_response(
+                    event_id,
+                    sender,
+                    origin_server_ts,
+                    ev.content.poll_response.answers.clone(),
+                )
+            }
+            AnySyncMessageLikeEvent::PollEnd(ev) => {
+                let Some(ev) = ev.as_original() else {
+                    return self.redact_event(event_id.to_owned());
+                };
+                if ev.content.relates_to.event_id != self.start_event_id {
+                    return false;
+                }
+
+                self.add_end(event_id, sender, origin_server_ts)
+            }
+            AnySyncMessageLikeEvent::UnstablePollEnd(ev) => {
+                let Some(ev) = ev.as_original() else {
+                    return self.redact_event(event_id.to_owned());
+                };
+                if ev.content.relates_to.event_id != self.start_event_id {
+                    return false;
+                }
+
+                self.add_end(event_id, sender, origin_server_ts)
+            }
+            AnySyncMessageLikeEvent::RoomRedaction(ev) => {
+                let Some(redacts) = ev.redacts(&self.room_version) else {
+                    return false;
+                };
+
+                if redacts == self.start_event_id {
+                    self.is_redacted = true;
+                    self.start = None;
+                    true
+                } else {
+                    self.redact_event(redacts.to_owned())
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Update the state of the poll with the given events.
+    ///
+    /// Returns `true` if at least one of the events was taken into account.
+    pub fn apply_events<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a AnySyncMessageLikeEvent>,
+    ) -> bool {
+        let mut changed = false;
+        for event in events {
+            changed |= self.apply_event(event);
+        }
+        changed
+    }
+
+    /// Whether the poll start event was received.
+    ///
+    /// If this is `false`, the responses that were already received are kept, but no results can
+    /// be computed.
+    pub fn is_started(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Whether the poll start event was redacted.
+    pub fn is_redacted(&self) -> bool {
+        self.is_redacted
+    }
+
+    /// The sender of the poll start event, if it was received.
+    pub fn creator(&self) -> Option<&UserId> {
+        self.start.as_ref().map(|start| &*start.sender)
+    }
+
+    /// The question of the poll, if the poll start event was received.
+    pub fn question(&self) -> Option<&str> {
+        self.start.as_ref().map(|start| start.question.as_str())
+    }
+
+    /// The kind of the poll, if the poll start event was received.
+    pub fn kind(&self) -> Option<&PollKind> {
+        self.start.as_ref().map(|start| &start.kind)
+    }
+
+    /// The maximum number of answers a user can select, if the poll start event was received.
+    pub fn max_selections(&self) -> Option<UInt> {
+        self.start.as_ref().map(|start| start.max_selections)
+    }
+
+    /// The possible answers of the poll, as `(answer ID, plain text representation)` tuples.
+    pub fn answers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.start
+            .iter()
+            .flat_map(|start| start.answers.iter().map(|a| (a.id.as_str(), a.text.as_str())))
+    }
+
+    /// The timestamp of the valid poll end event, if the poll was closed.
+    ///
+    /// If the poll was closed several times, the earliest end event is used.
+    pub fn end_timestamp(&self) -> Option<MilliSecondsSinceUnixEpoch> {
+        let start = self.start.as_ref()?;
+
+        self.ends
+            .values()
+            .filter(|end| end.sender == start.sender)
+            .map(|end| end.origin_server_ts)
+            .min()
+    }
+
+    /// Whether the poll was closed.
+    pub fn is_ended(&self) -> bool {
+        self.end_timestamp().is_some()
+    }
+
+    /// The current results of the poll.
+    ///
+    /// Returns a map of answer ID to a set of user IDs that voted for them. When using `.iter()`
+    /// or `.into_iter()` on the map, the results are sorted from the highest number of votes to
+    /// the lowest.
+    ///
+    /// Returns an empty map if the poll start event was not received or was redacted.
+    pub fn results(&self) -> IndexMap<&str, BTreeSet<&UserId>> {
+        let Some(start) = &self.start else {
+            return IndexMap::new();
+        };
+
+        let answer_ids = start.answers.iter().map(|a| a.id.as_str()).collect();
+        let users_selections = filter_selections(
+            answer_ids,
+            start.max_selections,
+            self.valid_responses(start),
+            self.end_timestamp(),
+        );
+
+        aggregate_results(start.answers.iter().map(|a| a.id.as_str()), users_selections)
+    }
+
+    /// The number of users whose latest response counts towards the results.
+    pub fn voters_count(&self) -> usize {
+        self.results().into_values().flatten().collect::<BTreeSet<_>>().len()
+    }
+
+    /// The answers selected by the given user that count towards the results.
+    pub fn user_selections(&self, user_id: &UserId) -> Vec<&str> {
+        self.results()
+            .into_iter()
+            .filter(|(_, users)| users.contains(user_id))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// The responses that were sent after the poll start event, sorted by timestamp.
+    fn valid_responses<'a>(
+        &'a self,
+        start: &PollStartInfo,
+    ) -> impl Iterator<Item = PollResponseData<'a>> {
+        let mut responses = self
+            .responses
+            .values()
+            .filter(|response| response.origin_server_ts >= start.origin_server_ts)
+            .map(|response| PollResponseData {
+                sender: &response.sender,
+                origin_server_ts: response.origin_server_ts,
+                selections: &response.selections,
+            })
+            .collect::<Vec<_>>();
+        responses.sort_by_key(|response| response.origin_server_ts);
+
+        responses.into_iter()
+    }
+
+    fn add_response(
+        &mut self,
+        event_id: &EventId,
+        sender: &UserId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+        selections: Vec<String>,
+    ) -> bool {
+        if self.redacted.contains(event_id) {
+            return false;
+        }
+
+        self.responses.insert(
+            event_id.to_owned(),
+            PollResponseInfo { sender: sender.to_owned(), origin_server_ts, selections },
+        );
+        true
+    }
+
+    fn add_end(
+        &mut self,
+        event_id: &EventId,
+        sender: &UserId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+    ) -> bool {
+        if self.redacted.contains(event_id) {
+            return false;
+        }
+
+        self.ends.insert(
+            event_id.to_owned(),
+            PollEndInfo { sender: sender.to_owned(), origin_server_ts },
+        );
+        true
+    }
+
+    /// Remove the response or end event with the given ID, and ignore it if it is received later.
+    fn redact_event(&mut self, event_id: OwnedEventId) -> bool {
+        let removed =
+            self.responses.remove(&event_id).is_some() || self.ends.remove(&event_id).is_some();
+        self.redacted.insert(event_id);
+        removed
+    }
+}
+
+/// The content of a poll start event necessary to compute the state of the poll.
+#[derive(Clone, Debug)]
+struct PollStartInfo {
+    sender: OwnedUserId,
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+    question: String,
+    kind: PollKind,
+    max_selections: UInt,
+    answers: Vec<PollAnswerInfo>,
+}
+
+/// An answer of a poll.
+#[derive(Clone, Debug)]
+struct PollAnswerInfo {
+    id: String,
+    text: String,
+}
+
+/// A response to a poll.
+#[derive(Clone, Debug)]
+struct PollResponseInfo {
+    sender: OwnedUserId,
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+    selections: Vec<String>,
+}
+
+/// An end event of a poll.
+#[derive(Clone, Debug)]
+struct PollEndInfo {
+    sender: OwnedUserId,
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
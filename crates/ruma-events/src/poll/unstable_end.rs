@@ -53,5 +53,6 @@ impl UnstablePollEndEventContent {
 ///
 /// This is currently an empty struct.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstablePollEndContentBlock {}
@@ -66,6 +66,7 @@ impl OriginalUnstablePollResponseEvent {
 
 /// An unstable block for poll response content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstablePollResponseContentBlock {
     /// The selected answers for the response.
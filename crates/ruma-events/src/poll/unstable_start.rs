@@ -110,6 +110,7 @@ impl OriginalSyncUnstablePollStartEvent {
 
 /// A new unstable poll start event.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct NewUnstablePollStartEventContent {
     /// The poll content of the message.
@@ -157,6 +158,7 @@ impl MessageLikeEventContent for NewUnstablePollStartEventContent {}
 /// To construct this type, construct a [`NewUnstablePollStartEventContent`] and then use one of its
 /// `::from()` / `.into()` methods.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct NewUnstablePollStartEventContentWithoutRelation {
     /// The poll content of the message.
@@ -241,6 +243,7 @@ impl MessageLikeEventContent for ReplacementUnstablePollStartEventContent {}
 
 /// Redacted form of UnstablePollStartEventContent
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedUnstablePollStartEventContent {}
 
@@ -267,6 +270,7 @@ impl RedactedMessageLikeEventContent for RedactedUnstablePollStartEventContent {
 
 /// An unstable block for poll start content.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstablePollStartContentBlock {
     /// The question of the poll.
@@ -302,6 +306,7 @@ impl UnstablePollStartContentBlock {
 
 /// An unstable poll question.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstablePollQuestion {
     /// The text representation of the question.
@@ -322,6 +327,7 @@ impl UnstablePollQuestion {
 ///
 /// To build this, use one of the `TryFrom` implementations.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(try_from = "UnstablePollAnswersDeHelper")]
 pub struct UnstablePollAnswers(Vec<UnstablePollAnswer>);
 
@@ -357,6 +363,7 @@ impl Deref for UnstablePollAnswers {
 
 /// Unstable poll answer.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstablePollAnswer {
     /// The ID of the answer.
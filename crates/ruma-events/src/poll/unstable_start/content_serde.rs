@@ -80,3 +80,32 @@ impl Serialize for ReplacementUnstablePollStartEventContent {
 struct ReplacementRelatesTo<'a> {
     event_id: &'a EventId,
 }
+
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for ReplacementUnstablePollStartEventContent {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("ReplacementUnstablePollStartEventContent")
+    }
+
+    fn json_schema(generator: &mut ruma_common::json_schema::SchemaGenerator) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "org.matrix.msc3381.poll.start":
+                    generator.subschema_for::<UnstablePollStartContentBlock>(),
+                "org.matrix.msc1767.text": { "type": "string" },
+                "m.new_content":
+                    generator.subschema_for::<NewUnstablePollStartEventContentWithoutRelation>(),
+                "m.relates_to": {
+                    "type": "object",
+                    "properties": {
+                        "rel_type": { "type": "string", "const": "m.replace" },
+                        "event_id": { "type": "string" },
+                    },
+                    "required": ["rel_type", "event_id"],
+                },
+            },
+            "required": ["m.new_content", "m.relates_to"],
+        })
+    }
+}
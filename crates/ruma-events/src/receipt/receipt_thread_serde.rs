@@ -20,3 +20,19 @@ impl<'de> Deserialize<'de> for ReceiptThread {
         Self::try_from(Some(s)).map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for ReceiptThread {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("ReceiptThread")
+    }
+
+    fn json_schema(
+        _generator: &mut ruma_common::json_schema::SchemaGenerator,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "type": "string",
+            "description": "`main` or the ID of the root of a thread.",
+        })
+    }
+}
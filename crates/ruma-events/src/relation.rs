@@ -14,12 +14,16 @@ use serde::{Deserialize, Serialize};
 use super::AnyMessageLikeEvent;
 use crate::PrivOwnedStr;
 
+mod aggregation;
 mod rel_serde;
 
+pub use self::aggregation::{EventRelations, ThreadSummary, TimelineAggregator};
+
 /// Information about the event a [rich reply] is replying to.
 ///
 /// [rich reply]: https://spec.matrix.org/latest/client-server-api/#rich-replies
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct InReplyTo {
     /// The event being replied to.
@@ -37,6 +41,7 @@ impl InReplyTo {
 ///
 /// [annotation]: https://spec.matrix.org/latest/client-server-api/#event-annotations-and-reactions
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "rel_type", rename = "m.annotation")]
 pub struct Annotation {
@@ -83,6 +88,7 @@ impl<C> Replacement<C> {
 ///
 /// [thread]: https://spec.matrix.org/latest/client-server-api/#threading
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "rel_type", rename = "m.thread")]
 pub struct Thread {
@@ -132,6 +138,7 @@ impl Thread {
 
 /// A bundled thread.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct BundledThread {
     /// The latest event in the thread.
@@ -159,6 +166,7 @@ impl BundledThread {
 ///
 /// [reference]: https://spec.matrix.org/latest/client-server-api/#reference-relations
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "rel_type", rename = "m.reference")]
 pub struct Reference {
@@ -175,6 +183,7 @@ impl Reference {
 
 /// A bundled reference.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct BundledReference {
     /// The ID of the event referencing this event.
@@ -190,6 +199,7 @@ impl BundledReference {
 
 /// A chunk of references.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ReferenceChunk {
     /// A batch of bundled references.
@@ -207,6 +217,7 @@ impl ReferenceChunk {
 ///
 /// [Bundled aggregations]: https://spec.matrix.org/latest/client-server-api/#aggregations-of-child-events
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct BundledMessageLikeRelations<E> {
     /// Replacement relation.
@@ -268,6 +279,7 @@ impl<E> Default for BundledMessageLikeRelations<E> {
 ///
 /// [Bundled aggregations]: https://spec.matrix.org/latest/client-server-api/#aggregations-of-child-events
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct BundledStateRelations {
     /// Thread relation.
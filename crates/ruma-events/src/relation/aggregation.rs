@@ -0,0 +1,437 @@
+//! Client-side aggregation of related events.
+
+use std::collections::{btree_map, BTreeMap, BTreeSet};
+
+use ruma_common::{
+    EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomVersionId, UserId,
+};
+
+use crate::{
+    room::encrypted::Relation, AnySyncMessageLikeEvent, AnySyncTimelineEvent, TimelineEventType,
+};
+
+/// An engine to aggregate the [relations] between events of a room timeline.
+///
+/// Events can be added to the aggregator in any order, for example as they are received from
+/// `/sync` and from back-pagination. Relations that arrive before the event they relate to are kept
+/// and taken into account as soon as the related event is added.
+///
+/// The aggregator supports the following relations:
+///
+/// * [Replacements], the latest valid edit of an event can be obtained with
+///   [`EventRelations::latest_edit()`].
+/// * [Annotations], mostly used for reactions, grouped by key and by sender.
+/// * [Threads], summarized with [`EventRelations::thread_summary()`].
+/// * [Rich replies], including replies inside a thread.
+///
+/// Redactions are applied to relations: redacting a relation event removes it from the
+/// aggregation, and redacting an event hides its edits.
+///
+/// [relations]: https://spec.matrix.org/latest/client-server-api/#forming-relationships-between-events
+/// [Replacements]: https://spec.matrix.org/latest/client-server-api/#event-replacements
+/// [Annotations]: https://spec.matrix.org/latest/client-server-api/#event-annotations-and-reactions
+/// [Threads]: https://spec.matrix.org/latest/client-server-api/#threading
+/// [Rich replies]: https://spec.matrix.org/latest/client-server-api/#rich-replies
+#[derive(Clone, Debug)]
+pub struct TimelineAggregator {
+    /// The version of the room, used to get the target of redactions.
+    room_version: RoomVersionId,
+
+    /// The relations of the events, by event ID.
+    events: BTreeMap<OwnedEventId, EventRelations>,
+
+    /// The relation of each relation event, by event ID of the relation event.
+    relations: BTreeMap<OwnedEventId, AggregatedRelation>,
+
+    /// The IDs of the events that were redacted.
+    redacted: BTreeSet<OwnedEventId>,
+}
+
+impl TimelineAggregator {
+    /// Creates a new empty `TimelineAggregator` for a room with the given version.
+    pub fn new(room_version: RoomVersionId) -> Self {
+        Self {
+            room_version,
+            events: BTreeMap::new(),
+            relations: BTreeMap::new(),
+            redacted: BTreeSet::new(),
+        }
+    }
+
+    /// Add the given event to the aggregation.
+    ///
+    /// Adding the same event several times has no further effect.
+    pub fn add_event(&mut self, event: &AnySyncTimelineEvent) {
+        let event_id = event.event_id();
+
+        let entry = self.events.entry(event_id.to_owned()).or_default();
+        if entry.original.is_none() {
+            entry.original = Some(OriginalInfo {
+                sender: event.sender().to_owned(),
+                event_type: event.event_type(),
+            });
+        }
+
+        let AnySyncTimelineEvent::MessageLike(event) = event else {
+            return;
+        };
+
+        if let AnySyncMessageLikeEvent::RoomRedaction(redaction) = event {
+            if let Some(redacts) = redaction.redacts(&self.room_version) {
+                let redacts = redacts.to_owned();
+                self.redact(redacts);
+            }
+            return;
+        }
+
+        if self.redacted.contains(event_id) {
+            self.redact(event_id.to_owned());
+            return;
+        }
+
+        let Some(content) = event.original_content() else {
+            // The event was redacted before we received it, or we received its original form
+            // before, so it must be removed from the aggregations.
+            self.redact(event_id.to_owned());
+            return;
+        };
+        let Some(relation) = content.relation() else {
+            return;
+        };
+
+        let sender = event.sender().to_owned();
+        let origin_server_ts = event.origin_server_ts();
+
+        match relation {
+            Relation::Replacement(replacement) => {
+                self.events
+                    .entry(replacement.event_id.clone())
+                    .or_default()
+                    .edits
+                    .insert(event_id.to_owned(), event.clone());
+                self.relations.insert(
+                    event_id.to_owned(),
+                    AggregatedRelation::Replacement { target: replacement.event_id },
+                );
+            }
+            Relation::Annotation(annotation) => {
+                self.events
+                    .entry(annotation.event_id.clone())
+                    .or_default()
+                    .reactions
+                    .entry(annotation.key.clone())
+                    .or_default()
+                    .entry(sender.clone())
+                    .or_default()
+                    .insert(event_id.to_owned());
+                self.relations.insert(
+                    event_id.to_owned(),
+                    AggregatedRelation::Annotation {
+                        target: annotation.event_id,
+                        key: annotation.key,
+                        sender,
+                    },
+                );
+            }
+            Relation::Thread(thread) => {
+                self.events.entry(thread.event_id.clone()).or_default().thread.insert(
+                    event_id.to_owned(),
+                    ThreadEntry { sender: sender.clone(), origin_server_ts },
+                );
+
+                let reply_target = thread
+                    .in_reply_to
+                    .filter(|_| !thread.is_falling_back)
+                    .map(|in_reply_to| in_reply_to.event_id);
+                if let Some(target) = &reply_target {
+                    self.events
+                        .entry(target.clone())
+                        .or_default()
+                        .replies
+                        .insert(event_id.to_owned());
+                }
+
+                let entry = self.events.entry(event_id.to_owned()).or_default();
+                entry.thread_root = Some(thread.event_id.clone());
+                entry.in_reply_to.clone_from(&reply_target);
+
+                self.relations.insert(
+                    event_id.to_owned(),
+                    AggregatedRelation::Thread { root: thread.event_id, reply_target },
+                );
+            }
+            Relation::Reply { in_reply_to } => {
+                self.events
+                    .entry(in_reply_to.event_id.clone())
+                    .or_default()
+                    .replies
+                    .insert(event_id.to_owned());
+                self.events.entry(event_id.to_owned()).or_default().in_reply_to =
+                    Some(in_reply_to.event_id.clone());
+                self.relations.insert(
+                    event_id.to_owned(),
+                    AggregatedRelation::Reply { target: in_reply_to.event_id },
+                );
+            }
+            Relation::Reference(_) | Relation::_Custom(_) => {}
+        }
+    }
+
+    /// Add the given events to the aggregation.
+    pub fn add_events<'a>(&mut self, events: impl IntoIterator<Item = &'a AnySyncTimelineEvent>) {
+        for event in events {
+            self.add_event(event);
+        }
+    }
+
+    /// Get the aggregated relations of the event with the given ID.
+    ///
+    /// Returns `None` if neither the event nor any event relating to it was added to the
+    /// aggregation.
+    pub fn relations(&self, event_id: &EventId) -> Option<&EventRelations> {
+        self.events.get(event_id)
+    }
+
+    /// Whether the event with the given ID was redacted.
+    pub fn is_redacted(&self, event_id: &EventId) -> bool {
+        self.redacted.contains(event_id)
+    }
+
+    /// Apply the redaction of the event with the given ID.
+    fn redact(&mut self, event_id: OwnedEventId) {
+        self.mark_redacted(&event_id);
+
+        let Some(relation) = self.relations.remove(&event_id) else {
+            return;
+        };
+
+        match relation {
+            AggregatedRelation::Replacement { target } => {
+                if let Some(entry) = self.events.get_mut(&target) {
+                    entry.edits.remove(&event_id);
+                }
+            }
+            AggregatedRelation::Annotation { target, key, sender } => {
+                let Some(entry) = self.events.get_mut(&target) else {
+                    return;
+                };
+                let btree_map::Entry::Occupied(mut senders) = entry.reactions.entry(key) else {
+                    return;
+                };
+                if let btree_map::Entry::Occupied(mut events) = senders.get_mut().entry(sender) {
+                    events.get_mut().remove(&event_id);
+                    if events.get().is_empty() {
+                        events.remove();
+                    }
+                }
+                if senders.get().is_empty() {
+                    senders.remove();
+                }
+            }
+            AggregatedRelation::Thread { root, reply_target } => {
+                if let Some(entry) = self.events.get_mut(&root) {
+                    entry.thread.remove(&event_id);
+                }
+                if let Some(target) = reply_target {
+                    self.remove_reply(&target, &event_id);
+                }
+            }
+            AggregatedRelation::Reply { target } => {
+                self.remove_reply(&target, &event_id);
+            }
+        }
+    }
+
+    /// Mark the event with the given ID as redacted.
+    fn mark_redacted(&mut self, event_id: &EventId) {
+        self.redacted.insert(event_id.to_owned());
+
+        let entry = self.events.entry(event_id.to_owned()).or_default();
+        entry.is_redacted = true;
+        entry.thread_root = None;
+        entry.in_reply_to = None;
+    }
+
+    /// Remove the reply with the given ID from the replies of the given target.
+    fn remove_reply(&mut self, target: &EventId, reply: &EventId) {
+        if let Some(entry) = self.events.get_mut(target) {
+            entry.replies.remove(reply);
+        }
+    }
+}
+
+/// The aggregated relations of an event.
+#[derive(Clone, Debug, Default)]
+pub struct EventRelations {
+    /// Information about the event, if it was added to the aggregation.
+    original: Option<OriginalInfo>,
+
+    /// Whether the event was redacted.
+    is_redacted: bool,
+
+    /// The thread this event belongs to.
+    thread_root: Option<OwnedEventId>,
+
+    /// The event this event replies to.
+    in_reply_to: Option<OwnedEventId>,
+
+    /// The replacements of this event, by event ID.
+    edits: BTreeMap<OwnedEventId, AnySyncMessageLikeEvent>,
+
+    /// The annotations of this event, by key and by sender.
+    reactions: BTreeMap<String, BTreeMap<OwnedUserId, BTreeSet<OwnedEventId>>>,
+
+    /// The events in the thread started by this event, by event ID.
+    thread: BTreeMap<OwnedEventId, ThreadEntry>,
+
+    /// The replies to this event.
+    replies: BTreeSet<OwnedEventId>,
+}
+
+impl EventRelations {
+    /// Whether the event itself was added to the aggregation.
+    ///
+    /// Returns `false` if only events relating to it were added.
+    pub fn is_known(&self) -> bool {
+        self.original.is_some()
+    }
+
+    /// Whether the event was redacted.
+    pub fn is_redacted(&self) -> bool {
+        self.is_redacted
+    }
+
+    /// The sender of the event, if it is known.
+    pub fn sender(&self) -> Option<&UserId> {
+        self.original.as_ref().map(|original| &*original.sender)
+    }
+
+    /// The root of the thread this event belongs to, if any.
+    pub fn thread_root(&self) -> Option<&EventId> {
+        self.thread_root.as_deref()
+    }
+
+    /// The event this event replies to, if any.
+    ///
+    /// Events that are in a thread but only have a reply fallback are not considered as replies.
+    pub fn in_reply_to(&self) -> Option<&EventId> {
+        self.in_reply_to.as_deref()
+    }
+
+    /// The latest valid edit of this event, if any.
+    ///
+    /// According to the spec, an edit is only valid if it has the same sender and type as the
+    /// original event. If there are several valid edits, the one with the highest
+    /// `origin_server_ts` wins, and ties are broken by using the event with the lexicographically
+    /// largest event ID.
+    ///
+    /// Returns `None` if the original event is unknown or was redacted.
+    pub fn latest_edit(&self) -> Option<&AnySyncMessageLikeEvent> {
+        if self.is_redacted {
+            return None;
+        }
+
+        let original = self.original.as_ref()?;
+
+        self.edits
+            .values()
+            .filter(|edit| {
+                edit.sender() == original.sender
+                    && TimelineEventType::from(edit.event_type()) == original.event_type
+            })
+            .max_by(|a, b| {
+                a.origin_server_ts()
+                    .cmp(&b.origin_server_ts())
+                    .then_with(|| a.event_id().cmp(b.event_id()))
+            })
+    }
+
+    /// Whether this event has at least one valid edit.
+    pub fn is_edited(&self) -> bool {
+        self.latest_edit().is_some()
+    }
+
+    /// The keys of the reactions to this event.
+    pub fn reaction_keys(&self) -> impl Iterator<Item = &str> {
+        self.reactions.keys().map(String::as_str)
+    }
+
+    /// The number of senders that reacted with the given key to this event.
+    pub fn reaction_count(&self, key: &str) -> usize {
+        self.reactions.get(key).map_or(0, BTreeMap::len)
+    }
+
+    /// The senders that reacted with the given key to this event.
+    pub fn reaction_senders(&self, key: &str) -> impl Iterator<Item = &UserId> {
+        self.reactions.get(key).into_iter().flat_map(|senders| senders.keys().map(|s| &**s))
+    }
+
+    /// The ID of the reaction event that the given user sent with the given key, if any.
+    ///
+    /// If the user sent several reactions with the same key, the one with the lexicographically
+    /// smallest event ID is returned.
+    pub fn reaction_by(&self, key: &str, user_id: &UserId) -> Option<&EventId> {
+        self.reactions.get(key)?.get(user_id)?.first().map(|id| &**id)
+    }
+
+    /// The summary of the thread started by this event, if any.
+    pub fn thread_summary(&self) -> Option<ThreadSummary> {
+        let (latest_event, _) = self.thread.iter().max_by(|(a_id, a), (b_id, b)| {
+            a.origin_server_ts.cmp(&b.origin_server_ts).then_with(|| a_id.cmp(b_id))
+        })?;
+
+        Some(ThreadSummary {
+            count: self.thread.len(),
+            latest_event: latest_event.clone(),
+            participants: self.thread.values().map(|entry| entry.sender.clone()).collect(),
+        })
+    }
+
+    /// The IDs of the events replying to this event.
+    pub fn replies(&self) -> impl Iterator<Item = &EventId> {
+        self.replies.iter().map(|id| &**id)
+    }
+}
+
+/// A summary of a thread.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct ThreadSummary {
+    /// The number of events in the thread, excluding the thread root.
+    pub count: usize,
+
+    /// The ID of the latest event in the thread.
+    pub latest_event: OwnedEventId,
+
+    /// The users that sent events in the thread.
+    pub participants: BTreeSet<OwnedUserId>,
+}
+
+/// Information about an event added to the aggregation.
+#[derive(Clone, Debug)]
+struct OriginalInfo {
+    /// The sender of the event.
+    sender: OwnedUserId,
+
+    /// The type of the event.
+    event_type: TimelineEventType,
+}
+
+/// An event in a thread.
+#[derive(Clone, Debug)]
+struct ThreadEntry {
+    /// The sender of the event.
+    sender: OwnedUserId,
+
+    /// The timestamp of the event.
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+/// A relation that was aggregated, used to undo it when the relation event is redacted.
+#[derive(Clone, Debug)]
+enum AggregatedRelation {
+    Replacement { target: OwnedEventId },
+    Annotation { target: OwnedEventId, key: String, sender: OwnedUserId },
+    Thread { root: OwnedEventId, reply_target: Option<OwnedEventId> },
+    Reply { target: OwnedEventId },
+}
@@ -12,9 +12,12 @@ use ruma_common::{
 use serde::{de, Deserialize, Serialize};
 
 pub mod aliases;
+#[cfg(feature = "attachment-encryption")]
+mod attachment;
 pub mod avatar;
 pub mod canonical_alias;
 pub mod create;
+mod display_name;
 pub mod encrypted;
 pub mod encryption;
 pub mod guest_access;
@@ -27,13 +30,25 @@ pub mod pinned_events;
 pub mod power_levels;
 pub mod redaction;
 pub mod server_acl;
+mod state;
 pub mod third_party_invite;
 mod thumbnail_source_serde;
 pub mod tombstone;
 pub mod topic;
 
+#[cfg(feature = "attachment-encryption")]
+pub use self::attachment::{AttachmentDecryptionError, AttachmentDecryptor, AttachmentEncryptor};
+pub use self::{
+    display_name::{
+        member_display_names, room_display_name, DisplayNameLocalizer, EnglishLocalizer,
+        MemberDisplayName, RoomDisplayName, RoomSummary,
+    },
+    state::{RoomMember, RoomState},
+};
+
 /// The source of a media file.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[allow(clippy::exhaustive_enums)]
 pub enum MediaSource {
     /// The MXC URI to the unencrypted media file.
@@ -71,6 +86,7 @@ impl<'de> Deserialize<'de> for MediaSource {
 
 /// Metadata about an image.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ImageInfo {
     /// The height of the image in pixels.
@@ -115,6 +131,7 @@ impl ImageInfo {
 
 /// Metadata about a thumbnail.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ThumbnailInfo {
     /// The height of the thumbnail in pixels.
@@ -146,6 +163,7 @@ impl ThumbnailInfo {
 /// To create an instance of this type, first create a `EncryptedFileInit` and convert it via
 /// `EncryptedFile::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct EncryptedFile {
     /// The URL to the file.
@@ -207,6 +225,7 @@ impl From<EncryptedFileInit> for EncryptedFile {
 /// To create an instance of this type, first create a `JsonWebKeyInit` and convert it via
 /// `JsonWebKey::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct JsonWebKey {
     /// Key type.
@@ -4,7 +4,9 @@ use ruma_common::{OwnedRoomAliasId, OwnedServerName, RoomVersionId};
 use ruma_macros::EventContent;
 use serde::{Deserialize, Serialize};
 
-use crate::{EventContent, RedactContent, RedactedStateEventContent, StateEventType};
+use crate::{
+    EventContent, RedactContent, RedactedStateEventContent, StateEventType, StaticEventContent,
+};
 
 /// The content of an `m.room.aliases` event.
 ///
@@ -45,6 +47,7 @@ impl RedactContent for RoomAliasesEventContent {
 
 /// An aliases event that has been redacted.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedRoomAliasesEventContent {
     /// A list of room aliases.
@@ -82,3 +85,7 @@ impl EventContent for RedactedRoomAliasesEventContent {
 impl RedactedStateEventContent for RedactedRoomAliasesEventContent {
     type StateKey = OwnedServerName;
 }
+
+impl StaticEventContent for RedactedRoomAliasesEventContent {
+    const TYPE: &'static str = "m.room.aliases";
+}
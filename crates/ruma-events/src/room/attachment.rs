@@ -0,0 +1,227 @@
+//! Encryption and decryption of attachments.
+//!
+//! See the [spec] for the details of the algorithm.
+//!
+//! [spec]: https://spec.matrix.org/latest/client-server-api/#sending-encrypted-attachments
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Read},
+};
+
+use aes::{
+    cipher::{KeyIvInit, StreamCipher},
+    Aes256,
+};
+use rand::{thread_rng, RngCore};
+use ruma_common::{serde::Base64, OwnedMxcUri};
+use sha2::{Digest, Sha256};
+
+use super::{EncryptedFile, EncryptedFileInit, JsonWebKeyInit};
+
+type Aes256Ctr = ctr::Ctr128BE<Aes256>;
+
+/// The only version of the encrypted attachment format that is supported.
+const VERSION: &str = "v2";
+
+const KEY_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+
+/// A reader that encrypts the data of an attachment while reading it.
+///
+/// The key and IV are generated randomly according to the rules of the `v2` format: the 64 lower
+/// bits of the IV, used as the AES-CTR counter, are set to zero to avoid an overflow of the
+/// counter. The SHA-256 hash of the ciphertext is computed as it is read.
+///
+/// Once all the data was read and uploaded, [`finish()`](Self::finish) returns the
+/// [`EncryptedFile`] to send in the event.
+pub struct AttachmentEncryptor<R> {
+    inner: R,
+    key: [u8; KEY_SIZE],
+    iv: [u8; IV_SIZE],
+    cipher: Aes256Ctr,
+    sha256: Sha256,
+}
+
+impl<R: Read> AttachmentEncryptor<R> {
+    /// Creates a new `AttachmentEncryptor` that encrypts the data of the given reader with a
+    /// random key and IV.
+    pub fn new(reader: R) -> Self {
+        let mut rng = thread_rng();
+        let mut key = [0; KEY_SIZE];
+        let mut iv = [0; IV_SIZE / 2];
+        rng.fill_bytes(&mut key);
+        rng.fill_bytes(&mut iv);
+
+        Self::with_key_and_iv(reader, key, iv)
+    }
+
+    /// Creates a new `AttachmentEncryptor` that encrypts the data of the given reader with the
+    /// given key and the given 64 upper bits of the IV.
+    ///
+    /// The key and IV must never be reused, this is only meant to check the encryption against
+    /// known vectors. Use [`AttachmentEncryptor::new()`] instead.
+    pub fn with_key_and_iv(reader: R, key: [u8; KEY_SIZE], iv: [u8; IV_SIZE / 2]) -> Self {
+        let mut full_iv = [0; IV_SIZE];
+        full_iv[..IV_SIZE / 2].copy_from_slice(&iv);
+
+        Self {
+            inner: reader,
+            key,
+            iv: full_iv,
+            cipher: Aes256Ctr::new(&key.into(), &full_iv.into()),
+            sha256: Sha256::new(),
+        }
+    }
+
+    /// Consumes this encryptor and returns the [`EncryptedFile`] needed to decrypt the data.
+    ///
+    /// This should only be called after all the data was read.
+    pub fn finish(self, url: OwnedMxcUri) -> EncryptedFile {
+        let key = JsonWebKeyInit {
+            kty: "oct".to_owned(),
+            key_ops: vec!["encrypt".to_owned(), "decrypt".to_owned()],
+            alg: "A256CTR".to_owned(),
+            k: Base64::new(self.key.to_vec()),
+            ext: true,
+        };
+        let hashes =
+            BTreeMap::from([("sha256".to_owned(), Base64::new(self.sha256.finalize().to_vec()))]);
+
+        EncryptedFileInit {
+            url,
+            key: key.into(),
+            iv: Base64::new(self.iv.to_vec()),
+            hashes,
+            v: VERSION.to_owned(),
+        }
+        .into()
+    }
+}
+
+impl<R: Read> Read for AttachmentEncryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let data = &mut buf[..read];
+
+        self.cipher.apply_keystream(data);
+        self.sha256.update(data);
+
+        Ok(read)
+    }
+}
+
+impl<R> fmt::Debug for AttachmentEncryptor<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AttachmentEncryptor").finish_non_exhaustive()
+    }
+}
+
+/// A reader that decrypts the data of an attachment while reading it.
+///
+/// The SHA-256 hash of the ciphertext is checked when the end of the data is reached. If it
+/// doesn't match, the last read returns an error of kind [`io::ErrorKind::InvalidData`] wrapping
+/// [`AttachmentDecryptionError::HashMismatch`], and the decrypted data must be discarded.
+pub struct AttachmentDecryptor<R> {
+    inner: R,
+    cipher: Aes256Ctr,
+    sha256: Sha256,
+    expected_hash: Vec<u8>,
+}
+
+impl<R: Read> AttachmentDecryptor<R> {
+    /// Creates a new `AttachmentDecryptor` that decrypts the data of the given reader with the
+    /// given encryption info.
+    ///
+    /// Only the `v2` format is supported.
+    pub fn new(reader: R, file: &EncryptedFile) -> Result<Self, AttachmentDecryptionError> {
+        if file.v != VERSION {
+            return Err(AttachmentDecryptionError::UnsupportedVersion(file.v.clone()));
+        }
+
+        if file.key.kty != "oct" || file.key.alg != "A256CTR" {
+            return Err(AttachmentDecryptionError::UnsupportedAlgorithm(file.key.alg.clone()));
+        }
+
+        let key: [u8; KEY_SIZE] = file
+            .key
+            .k
+            .as_bytes()
+            .try_into()
+            .map_err(|_| AttachmentDecryptionError::InvalidKeyLength)?;
+        let iv: [u8; IV_SIZE] = file
+            .iv
+            .as_bytes()
+            .try_into()
+            .map_err(|_| AttachmentDecryptionError::InvalidIvLength)?;
+        let expected_hash =
+            file.hashes.get("sha256").ok_or(AttachmentDecryptionError::MissingHash)?;
+
+        Ok(Self {
+            inner: reader,
+            cipher: Aes256Ctr::new(&key.into(), &iv.into()),
+            sha256: Sha256::new(),
+            expected_hash: expected_hash.as_bytes().to_vec(),
+        })
+    }
+}
+
+impl<R: Read> Read for AttachmentDecryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+
+        if read == 0 && !buf.is_empty() {
+            let hash = self.sha256.finalize_reset();
+            if hash.as_slice() != self.expected_hash {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    AttachmentDecryptionError::HashMismatch,
+                ));
+            }
+
+            return Ok(0);
+        }
+
+        let data = &mut buf[..read];
+        self.sha256.update(&*data);
+        self.cipher.apply_keystream(data);
+
+        Ok(read)
+    }
+}
+
+impl<R> fmt::Debug for AttachmentDecryptor<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AttachmentDecryptor").finish_non_exhaustive()
+    }
+}
+
+/// An error encountered when decrypting an attachment.
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum AttachmentDecryptionError {
+    /// The version of the encrypted attachment format is not supported.
+    #[error("unsupported encrypted attachment version: {0}")]
+    UnsupportedVersion(String),
+
+    /// The algorithm of the key is not supported.
+    #[error("unsupported encrypted attachment algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+
+    /// The key doesn't have a length of 256 bits.
+    #[error("the key of the encrypted attachment has an invalid length")]
+    InvalidKeyLength,
+
+    /// The IV doesn't have a length of 128 bits.
+    #[error("the IV of the encrypted attachment has an invalid length")]
+    InvalidIvLength,
+
+    /// The SHA-256 hash of the ciphertext is missing.
+    #[error("the SHA-256 hash of the encrypted attachment is missing")]
+    MissingHash,
+
+    /// The SHA-256 hash of the ciphertext doesn't match.
+    #[error("the SHA-256 hash of the encrypted attachment doesn't match")]
+    HashMismatch,
+}
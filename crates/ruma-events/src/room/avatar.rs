@@ -36,6 +36,7 @@ impl RoomAvatarEventContent {
 
 /// Metadata about an image (specific to avatars).
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ImageInfo {
     /// The height of the image in pixels.
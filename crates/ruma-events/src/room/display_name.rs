@@ -0,0 +1,369 @@
+//! Computation of the display names of rooms and members.
+
+use std::{collections::BTreeMap, fmt};
+
+use js_int::UInt;
+use ruma_common::{OwnedRoomAliasId, OwnedUserId, RoomAliasId, UserId};
+
+use super::{
+    canonical_alias::RoomCanonicalAliasEventContent, member::MembershipState,
+    name::RoomNameEventContent, RoomMember,
+};
+
+/// The maximum number of heroes used to compute the display name of a room, according to the spec.
+const MAX_HEROES: usize = 5;
+
+/// The data of a room summary used to compute the display name of a room.
+///
+/// This matches the `summary` of a joined room in a `/sync` response. All the fields are optional,
+/// if they are not set, they are computed from the known members of the room.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RoomSummary {
+    /// The users that can be used to generate a room name if the room does not have one.
+    pub heroes: Option<Vec<OwnedUserId>>,
+
+    /// The number of users with `membership` of `join`, including the client's own user ID.
+    pub joined_member_count: Option<UInt>,
+
+    /// The number of users with `membership` of `invite`.
+    pub invited_member_count: Option<UInt>,
+}
+
+impl RoomSummary {
+    /// Creates an empty `RoomSummary`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update this summary with the fields that are set in the given summary.
+    ///
+    /// According to the spec, fields that are omitted in a room summary have not changed.
+    pub fn update(&mut self, summary: RoomSummary) {
+        let RoomSummary { heroes, joined_member_count, invited_member_count } = summary;
+
+        if heroes.is_some() {
+            self.heroes = heroes;
+        }
+        if joined_member_count.is_some() {
+            self.joined_member_count = joined_member_count;
+        }
+        if invited_member_count.is_some() {
+            self.invited_member_count = invited_member_count;
+        }
+    }
+}
+
+/// Compute the display name of a room, according to the [spec].
+///
+/// The name is computed from the `m.room.name` and `m.room.canonical_alias` events if they are
+/// set, or from the heroes of the room otherwise.
+///
+/// `members` is the list of known members of the room. It is used to get the display names of the
+/// heroes, and to compute the data that is missing from the `summary`.
+///
+/// [spec]: https://spec.matrix.org/latest/client-server-api/#calculating-the-display-name-for-a-room
+pub fn room_display_name(
+    own_user_id: &UserId,
+    name: Option<&RoomNameEventContent>,
+    canonical_alias: Option<&RoomCanonicalAliasEventContent>,
+    summary: &RoomSummary,
+    members: &BTreeMap<OwnedUserId, RoomMember>,
+) -> RoomDisplayName {
+    compute_room_display_name(
+        own_user_id,
+        name.map(|c| c.name.as_str()),
+        canonical_alias.and_then(|c| c.alias.as_deref()),
+        summary,
+        members,
+    )
+}
+
+pub(super) fn compute_room_display_name(
+    own_user_id: &UserId,
+    name: Option<&str>,
+    canonical_alias: Option<&RoomAliasId>,
+    summary: &RoomSummary,
+    members: &BTreeMap<OwnedUserId, RoomMember>,
+) -> RoomDisplayName {
+    if let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) {
+        return RoomDisplayName::Named(name.to_owned());
+    }
+
+    if let Some(alias) = canonical_alias {
+        return RoomDisplayName::Aliased(alias.to_owned());
+    }
+
+    let heroes = heroes(own_user_id, summary, members);
+
+    let joined_member_count = summary
+        .joined_member_count
+        .map(u64::from)
+        .unwrap_or_else(|| count_members(members, &MembershipState::Join));
+    let invited_member_count = summary
+        .invited_member_count
+        .map(u64::from)
+        .unwrap_or_else(|| count_members(members, &MembershipState::Invite));
+
+    // The heroes don't include the current user, so the member count shouldn't either.
+    let members_count = (joined_member_count + invited_member_count).saturating_sub(1);
+    let others = members_count.saturating_sub(heroes.len().try_into().unwrap_or(u64::MAX));
+
+    let member_names = member_display_names(members);
+    let heroes = heroes
+        .into_iter()
+        .map(|user_id| {
+            member_names
+                .get(user_id)
+                .cloned()
+                .unwrap_or_else(|| MemberDisplayName::UserId(user_id.to_owned()))
+        })
+        .collect::<Vec<_>>();
+
+    if members_count > 0 {
+        RoomDisplayName::Calculated { heroes, others }
+    } else if heroes.is_empty() {
+        RoomDisplayName::Empty
+    } else {
+        RoomDisplayName::EmptyWas { heroes }
+    }
+}
+
+/// The heroes of the room, excluding the given user.
+///
+/// Uses the heroes from the room summary if they are known, otherwise computes them from the
+/// joined and invited members, or from the members that left if there are none.
+fn heroes<'a>(
+    own_user_id: &UserId,
+    summary: &'a RoomSummary,
+    members: &'a BTreeMap<OwnedUserId, RoomMember>,
+) -> Vec<&'a UserId> {
+    if let Some(heroes) = &summary.heroes {
+        return heroes
+            .iter()
+            .filter(|user_id| *user_id != own_user_id)
+            .take(MAX_HEROES)
+            .map(|user_id| &**user_id)
+            .collect();
+    }
+
+    let heroes_with = |filter: fn(&MembershipState) -> bool| {
+        members
+            .iter()
+            .filter(|(user_id, member)| *user_id != own_user_id && filter(&member.membership))
+            .map(|(user_id, _)| &**user_id)
+            .take(MAX_HEROES)
+            .collect::<Vec<_>>()
+    };
+
+    let heroes = heroes_with(|m| matches!(m, MembershipState::Join | MembershipState::Invite));
+    if !heroes.is_empty() {
+        return heroes;
+    }
+
+    heroes_with(|m| matches!(m, MembershipState::Leave | MembershipState::Ban))
+}
+
+pub(super) fn count_members(
+    members: &BTreeMap<OwnedUserId, RoomMember>,
+    membership: &MembershipState,
+) -> u64 {
+    members
+        .values()
+        .filter(|member| member.membership == *membership)
+        .count()
+        .try_into()
+        .unwrap_or(u64::MAX)
+}
+
+/// Compute the display names of the given members of a room, according to the [spec].
+///
+/// If a member's display name is not unique among the joined and invited members of the room, it
+/// is disambiguated with the member's user ID. If a member doesn't have a display name, their user
+/// ID is used.
+///
+/// [spec]: https://spec.matrix.org/latest/client-server-api/#calculating-the-display-name-for-a-user
+pub fn member_display_names(
+    members: &BTreeMap<OwnedUserId, RoomMember>,
+) -> BTreeMap<&UserId, MemberDisplayName> {
+    // Count the number of joined and invited members using each display name.
+    let mut name_counts = BTreeMap::<&str, usize>::new();
+    for member in members.values().filter(|member| member.is_active()) {
+        if let Some(name) = member.non_empty_display_name() {
+            *name_counts.entry(name).or_default() += 1;
+        }
+    }
+
+    members
+        .iter()
+        .map(|(user_id, member)| {
+            let display_name = match member.non_empty_display_name() {
+                Some(name) => {
+                    let count = name_counts.get(name).copied().unwrap_or_default();
+                    // Don't count the member themselves.
+                    let others = if member.is_active() { count - 1 } else { count };
+
+                    if others > 0 {
+                        MemberDisplayName::Disambiguated {
+                            name: name.to_owned(),
+                            user_id: user_id.clone(),
+                        }
+                    } else {
+                        MemberDisplayName::Name(name.to_owned())
+                    }
+                }
+                None => MemberDisplayName::UserId(user_id.clone()),
+            };
+
+            (&**user_id, display_name)
+        })
+        .collect()
+}
+
+/// The computed display name of a room.
+///
+/// The `Display` implementation of this type generates an English representation of the name. To
+/// use another language, use [`RoomDisplayName::localize()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum RoomDisplayName {
+    /// The name set in the `m.room.name` event.
+    Named(String),
+
+    /// The canonical alias of the room.
+    Aliased(OwnedRoomAliasId),
+
+    /// A name calculated from the members of the room.
+    Calculated {
+        /// The names of the heroes of the room.
+        heroes: Vec<MemberDisplayName>,
+
+        /// The number of other members in the room.
+        others: u64,
+    },
+
+    /// The room is empty, but it had other members.
+    EmptyWas {
+        /// The names of the members that the room had.
+        heroes: Vec<MemberDisplayName>,
+    },
+
+    /// The room is empty.
+    Empty,
+}
+
+impl RoomDisplayName {
+    /// Get the representation of this name with the given localizer.
+    pub fn localize<L: DisplayNameLocalizer + ?Sized>(&self, localizer: &L) -> String {
+        let localize_heroes = |heroes: &[MemberDisplayName]| {
+            heroes.iter().map(|hero| hero.localize(localizer)).collect::<Vec<_>>()
+        };
+
+        match self {
+            Self::Named(name) => name.clone(),
+            Self::Aliased(alias) => alias.as_str().to_owned(),
+            Self::Calculated { heroes, others } => {
+                localizer.room_name_from_heroes(&localize_heroes(heroes), *others)
+            }
+            Self::EmptyWas { heroes } => localizer.empty_room_was(&localize_heroes(heroes)),
+            Self::Empty => localizer.empty_room(),
+        }
+    }
+}
+
+impl fmt::Display for RoomDisplayName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(&EnglishLocalizer))
+    }
+}
+
+/// The computed display name of a member of a room.
+///
+/// The `Display` implementation of this type generates an English representation of the name. To
+/// use another language, use [`MemberDisplayName::localize()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum MemberDisplayName {
+    /// The display name of the member, which is unique in the room.
+    Name(String),
+
+    /// The display name of the member, which is not unique in the room.
+    Disambiguated {
+        /// The display name of the member.
+        name: String,
+
+        /// The user ID of the member.
+        user_id: OwnedUserId,
+    },
+
+    /// The member doesn't have a display name, so their user ID is used.
+    UserId(OwnedUserId),
+}
+
+impl MemberDisplayName {
+    /// Get the representation of this name with the given localizer.
+    pub fn localize<L: DisplayNameLocalizer + ?Sized>(&self, localizer: &L) -> String {
+        match self {
+            Self::Name(name) => name.clone(),
+            Self::Disambiguated { name, user_id } => {
+                localizer.disambiguated_member_name(name, user_id)
+            }
+            Self::UserId(user_id) => user_id.as_str().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for MemberDisplayName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(&EnglishLocalizer))
+    }
+}
+
+/// Hooks to localize the computed display names of rooms and members.
+///
+/// All the methods have a default implementation that generates the English representation, so
+/// only the ones that need to be localized have to be implemented.
+pub trait DisplayNameLocalizer {
+    /// The name of a room computed from its heroes.
+    ///
+    /// `heroes` are the localized names of the heroes, and `others` is the number of other members
+    /// in the room. For example, "Alice, Bob and 3 others".
+    fn room_name_from_heroes(&self, heroes: &[String], others: u64) -> String {
+        match (heroes, others) {
+            ([], 0) => String::new(),
+            ([], 1) => "1 other".to_owned(),
+            ([], others) => format!("{others} others"),
+            ([hero], 0) => hero.clone(),
+            ([first @ .., last], 0) => format!("{} and {last}", first.join(", ")),
+            (heroes, 1) => format!("{} and 1 other", heroes.join(", ")),
+            (heroes, others) => format!("{} and {others} others", heroes.join(", ")),
+        }
+    }
+
+    /// The name of an empty room that had other members.
+    ///
+    /// `heroes` are the localized names of the former members. For example, "Empty Room (was
+    /// Alice)".
+    fn empty_room_was(&self, heroes: &[String]) -> String {
+        format!("Empty Room (was {})", self.room_name_from_heroes(heroes, 0))
+    }
+
+    /// The name of an empty room.
+    fn empty_room(&self) -> String {
+        "Empty Room".to_owned()
+    }
+
+    /// The display name of a member that is not unique in the room.
+    ///
+    /// For example, "Alice (@alice:example.org)".
+    fn disambiguated_member_name(&self, name: &str, user_id: &UserId) -> String {
+        format!("{name} ({user_id})")
+    }
+}
+
+/// A [`DisplayNameLocalizer`] that generates English representations.
+#[derive(Clone, Copy, Debug, Default)]
+#[allow(clippy::exhaustive_structs)]
+pub struct EnglishLocalizer;
+
+impl DisplayNameLocalizer for EnglishLocalizer {}
@@ -98,3 +98,54 @@ struct RelationSerHelper<'a, T> {
     #[serde(flatten)]
     data: &'a T,
 }
+
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::json_schema::{JsonSchema, SchemaGenerator};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{InReplyTo, Relation, Thread};
+    use crate::{
+        relation::{Annotation, Reference},
+        room::encrypted::Replacement,
+    };
+
+    impl JsonSchema for Relation {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("EncryptedRelation")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            json!({
+                "anyOf": [
+                    {
+                        "type": "object",
+                        "properties": {
+                            "m.in_reply_to": generator.subschema_for::<InReplyTo>(),
+                        },
+                        "required": ["m.in_reply_to"],
+                    },
+                    {
+                        "allOf": [
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "rel_type": { "type": "string", "const": "m.replace" },
+                                },
+                                "required": ["rel_type"],
+                            },
+                            generator.subschema_for::<Replacement>(),
+                        ],
+                    },
+                    generator.subschema_for::<Reference>(),
+                    generator.subschema_for::<Annotation>(),
+                    generator.subschema_for::<Thread>(),
+                    // Custom relations.
+                    { "type": "object" },
+                ],
+            })
+        }
+    }
+}
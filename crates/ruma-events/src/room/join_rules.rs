@@ -81,6 +81,7 @@ impl SyncRoomJoinRulesEvent {
 /// This type can hold an arbitrary string. To check for values that are not available as a
 /// documented variant here, use its string representation, obtained through `.as_str()`.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "join_rule", rename_all = "snake_case")]
 pub enum JoinRule {
@@ -165,6 +166,7 @@ impl From<JoinRule> for SpaceRoomJoinRule {
 
 /// Configuration of the `Restricted` join rule.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct Restricted {
     /// Allow rules which describe conditions that allow joining a room.
@@ -180,6 +182,7 @@ impl Restricted {
 
 /// An allow rule which defines a condition that allows joining a room.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(untagged)]
 pub enum AllowRule {
@@ -199,6 +202,7 @@ impl AllowRule {
 
 /// Allow rule which grants permission to join based on the membership of another room.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "type", rename = "m.room_membership")]
 pub struct RoomMembership {
@@ -215,6 +219,7 @@ impl RoomMembership {
 
 #[doc(hidden)]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct CustomAllowRule {
     #[serde(rename = "type")]
@@ -15,13 +15,20 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     AnyStrippedStateEvent, BundledStateRelations, EventContent, PossiblyRedactedStateEventContent,
-    PrivOwnedStr, RedactContent, RedactedStateEventContent, StateEventType,
+    PrivOwnedStr, RedactContent, RedactedStateEventContent, StateEventType, StaticEventContent,
 };
 
 mod change;
+mod summary;
 
 use self::change::membership_change;
-pub use self::change::{Change, MembershipChange, MembershipDetails};
+pub use self::{
+    change::{Change, MembershipChange, MembershipDetails},
+    summary::{
+        CollapsedChanges, MembershipChangeKind, MembershipSummary, MembershipSummaryGroup,
+        ProfileChange, UserMembershipSummary,
+    },
+};
 
 /// The content of an `m.room.member` event.
 ///
@@ -193,6 +200,7 @@ impl PossiblyRedactedStateEventContent for RoomMemberEventContent {
 
 /// A member event that has been redacted.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedRoomMemberEventContent {
     /// The membership state of this user.
@@ -261,6 +269,10 @@ impl RedactedStateEventContent for RedactedRoomMemberEventContent {
     type StateKey = OwnedUserId;
 }
 
+impl StaticEventContent for RedactedRoomMemberEventContent {
+    const TYPE: &'static str = "m.room.member";
+}
+
 impl RoomMemberEvent {
     /// Obtain the membership state, regardless of whether this event is redacted.
     pub fn membership(&self) -> &MembershipState {
@@ -308,6 +320,7 @@ pub enum MembershipState {
 
 /// Information about a third party invitation.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ThirdPartyInvite {
     /// A name which can be displayed to represent the user instead of their third party
@@ -349,6 +362,7 @@ impl ThirdPartyInvite {
 
 /// Redacted information about a third party invitation.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedThirdPartyInvite {
     /// A block of content which has been signed, which servers can use to verify the event.
@@ -360,6 +374,7 @@ pub struct RedactedThirdPartyInvite {
 /// A block of content which has been signed, which servers can use to verify a third party
 /// invitation.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SignedContent {
     /// The invited Matrix user ID.
@@ -526,6 +541,7 @@ impl StrippedRoomMemberEvent {
 
 /// Extra information about a message event that is not incorporated into the event's hash.
 #[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomMemberUnsigned {
     /// The time in milliseconds that has elapsed since the event was sent.
@@ -0,0 +1,372 @@
+//! Summarisation of runs of `m.room.member` events.
+
+use std::collections::BTreeMap;
+
+use ruma_common::{MxcUri, OwnedMxcUri, OwnedUserId, UserId};
+
+use super::{
+    membership_change, Change, MembershipChange, MembershipDetails, MembershipState,
+    SyncRoomMemberEvent,
+};
+
+/// The kind of a [`MembershipChange`], without the associated data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum MembershipChangeKind {
+    /// User joined the room.
+    Joined,
+
+    /// User left the room.
+    Left,
+
+    /// User was banned.
+    Banned,
+
+    /// User was unbanned.
+    Unbanned,
+
+    /// User was kicked.
+    Kicked,
+
+    /// User was invited.
+    Invited,
+
+    /// User was kicked and banned.
+    KickedAndBanned,
+
+    /// User accepted the invite.
+    InvitationAccepted,
+
+    /// User rejected the invite.
+    InvitationRejected,
+
+    /// User had their invite revoked.
+    InvitationRevoked,
+
+    /// User knocked.
+    Knocked,
+
+    /// User had their knock accepted.
+    KnockAccepted,
+
+    /// User retracted their knock.
+    KnockRetracted,
+
+    /// User had their knock denied.
+    KnockDenied,
+
+    /// `displayname` or `avatar_url` changed.
+    ProfileChanged,
+}
+
+impl MembershipChangeKind {
+    /// Get the kind of the given membership change.
+    ///
+    /// Returns `None` if the change is [`MembershipChange::None`], [`MembershipChange::Error`] or
+    /// [`MembershipChange::NotImplemented`].
+    pub fn from_change(change: &MembershipChange<'_>) -> Option<Self> {
+        use MembershipChange as Ch;
+
+        Some(match change {
+            Ch::Joined => Self::Joined,
+            Ch::Left => Self::Left,
+            Ch::Banned => Self::Banned,
+            Ch::Unbanned => Self::Unbanned,
+            Ch::Kicked => Self::Kicked,
+            Ch::Invited => Self::Invited,
+            Ch::KickedAndBanned => Self::KickedAndBanned,
+            Ch::InvitationAccepted => Self::InvitationAccepted,
+            Ch::InvitationRejected => Self::InvitationRejected,
+            Ch::InvitationRevoked => Self::InvitationRevoked,
+            Ch::Knocked => Self::Knocked,
+            Ch::KnockAccepted => Self::KnockAccepted,
+            Ch::KnockRetracted => Self::KnockRetracted,
+            Ch::KnockDenied => Self::KnockDenied,
+            Ch::ProfileChanged { .. } => Self::ProfileChanged,
+            Ch::None | Ch::Error | Ch::NotImplemented => return None,
+        })
+    }
+}
+
+/// A sequence of membership changes, collapsed into a pattern that is repeated a number of times.
+///
+/// For example, a user that joined and left a room three times has the pattern
+/// `[Joined, Left]` repeated 3 times.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(clippy::exhaustive_structs)]
+pub struct CollapsedChanges {
+    /// The changes of the pattern, in chronological order.
+    pub pattern: Vec<MembershipChangeKind>,
+
+    /// The number of times the pattern is repeated.
+    pub repeat: usize,
+}
+
+impl CollapsedChanges {
+    fn new(changes: &[MembershipChangeKind]) -> Self {
+        // Find the smallest pattern that, repeated, gives the whole sequence.
+        let len = changes.len();
+        let period = (1..len)
+            .filter(|period| len % period == 0)
+            .find(|&period| changes.chunks(period).all(|chunk| chunk == &changes[..period]))
+            .unwrap_or(len);
+
+        Self { pattern: changes[..period].to_vec(), repeat: len.checked_div(period).unwrap_or(0) }
+    }
+}
+
+/// The net change of the profile of a user over a run of `m.room.member` events.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct ProfileChange {
+    /// The details of the displayname change, if applicable.
+    pub displayname_change: Option<Change<Option<String>>>,
+
+    /// The details of the avatar url change, if applicable.
+    pub avatar_url_change: Option<Change<Option<OwnedMxcUri>>>,
+}
+
+impl ProfileChange {
+    /// Whether the profile is the same at the end of the run as at the beginning.
+    pub fn is_empty(&self) -> bool {
+        self.displayname_change.is_none() && self.avatar_url_change.is_none()
+    }
+
+    fn update(
+        &mut self,
+        displayname_change: Option<Change<Option<&str>>>,
+        avatar_url_change: Option<Change<Option<&MxcUri>>>,
+    ) {
+        if let Some(change) = displayname_change {
+            let old = match self.displayname_change.take() {
+                Some(prev) => prev.old,
+                None => change.old.map(ToOwned::to_owned),
+            };
+            let new = change.new.map(ToOwned::to_owned);
+            self.displayname_change = (old != new).then_some(Change { old, new });
+        }
+
+        if let Some(change) = avatar_url_change {
+            let old = match self.avatar_url_change.take() {
+                Some(prev) => prev.old,
+                None => change.old.map(ToOwned::to_owned),
+            };
+            let new = change.new.map(ToOwned::to_owned);
+            self.avatar_url_change = (old != new).then_some(Change { old, new });
+        }
+    }
+}
+
+/// The summary of the membership changes of a single user over a run of `m.room.member` events.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct UserMembershipSummary {
+    /// The user whose membership changed.
+    pub user_id: OwnedUserId,
+
+    /// The membership changes of the user, in chronological order.
+    ///
+    /// Consecutive profile changes are merged into a single
+    /// [`MembershipChangeKind::ProfileChanged`].
+    pub changes: Vec<MembershipChangeKind>,
+
+    /// The net change of the profile of the user.
+    pub profile_change: ProfileChange,
+}
+
+impl UserMembershipSummary {
+    fn new(user_id: OwnedUserId) -> Self {
+        Self { user_id, changes: Vec::new(), profile_change: ProfileChange::default() }
+    }
+
+    /// The membership changes of the user, collapsed into a repeated pattern.
+    ///
+    /// Profile changes are not part of the pattern, unless the user only changed their profile.
+    pub fn collapsed_changes(&self) -> CollapsedChanges {
+        if self.is_profile_change_only() {
+            return CollapsedChanges::new(&self.changes);
+        }
+
+        let changes: Vec<_> = self
+            .changes
+            .iter()
+            .copied()
+            .filter(|kind| *kind != MembershipChangeKind::ProfileChanged)
+            .collect();
+        CollapsedChanges::new(&changes)
+    }
+
+    /// Whether the user only changed their profile.
+    pub fn is_profile_change_only(&self) -> bool {
+        self.changes.iter().all(|kind| *kind == MembershipChangeKind::ProfileChanged)
+    }
+}
+
+/// A group of users that had the same membership changes.
+#[derive(Clone, Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct MembershipSummaryGroup<'a> {
+    /// The membership changes shared by the users.
+    pub changes: CollapsedChanges,
+
+    /// The users of the group, in the order they first appeared.
+    pub users: Vec<&'a UserId>,
+}
+
+/// A summary of a run of consecutive `m.room.member` events.
+///
+/// The changes are collapsed per user, so that a user that joined and then left is summarised as
+/// "joined and left". The summary only contains structured data, it is up to clients to
+/// localise it.
+#[derive(Clone, Debug, Default)]
+pub struct MembershipSummary {
+    users: Vec<UserMembershipSummary>,
+}
+
+impl MembershipSummary {
+    /// Summarise the given `m.room.member` events, in chronological order.
+    ///
+    /// The previous membership of a user is taken from the `unsigned.prev_content` of the event.
+    /// If it is missing, for example because the event was redacted, the state of the previous
+    /// event of the same user in the run is used.
+    pub fn new<'a>(events: impl IntoIterator<Item = &'a SyncRoomMemberEvent>) -> Self {
+        let mut summary = Self::default();
+        let mut last_details: BTreeMap<OwnedUserId, OwnedMembershipDetails> = BTreeMap::new();
+
+        for event in events {
+            let user_id = event.state_key();
+            let last = last_details.get(user_id);
+
+            let (details, change) = match event {
+                SyncRoomMemberEvent::Original(ev) => {
+                    let prev_details = ev
+                        .prev_content()
+                        .map(|content| content.details())
+                        .or_else(|| last.map(OwnedMembershipDetails::as_details));
+                    let change =
+                        membership_change(ev.details(), prev_details, &ev.sender, &ev.state_key);
+                    (OwnedMembershipDetails::from_details(ev.details()), change)
+                }
+                SyncRoomMemberEvent::Redacted(ev) => {
+                    let prev_details = last.map(OwnedMembershipDetails::as_details);
+                    let change =
+                        membership_change(ev.details(), prev_details, &ev.sender, &ev.state_key);
+                    (OwnedMembershipDetails::from_details(ev.details()), change)
+                }
+            };
+
+            summary.push_change(user_id, &change);
+            last_details.insert(user_id.to_owned(), details);
+        }
+
+        summary
+    }
+
+    fn push_change(&mut self, user_id: &UserId, change: &MembershipChange<'_>) {
+        let Some(kind) = MembershipChangeKind::from_change(change) else {
+            return;
+        };
+
+        let user = match self.users.iter().position(|user| user.user_id == user_id) {
+            Some(pos) => &mut self.users[pos],
+            None => {
+                self.users.push(UserMembershipSummary::new(user_id.to_owned()));
+                self.users.last_mut().expect("a user was just pushed")
+            }
+        };
+
+        if let MembershipChange::ProfileChanged { displayname_change, avatar_url_change } = change {
+            user.profile_change.update(displayname_change.clone(), avatar_url_change.clone());
+
+            if user.changes.last() == Some(&MembershipChangeKind::ProfileChanged) {
+                return;
+            }
+        }
+
+        user.changes.push(kind);
+    }
+
+    /// Whether this summary doesn't contain any change.
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// The summaries of the users whose membership changed, in the order they first appeared.
+    pub fn users(&self) -> &[UserMembershipSummary] {
+        &self.users
+    }
+
+    /// The summary of the given user, if their membership changed.
+    pub fn user(&self, user_id: &UserId) -> Option<&UserMembershipSummary> {
+        self.users.iter().find(|user| user.user_id == user_id)
+    }
+
+    /// The users grouped by their collapsed membership changes.
+    ///
+    /// The groups are in the order of the first appearance of one of their users. Users that only
+    /// changed their profile are grouped together.
+    pub fn groups(&self) -> Vec<MembershipSummaryGroup<'_>> {
+        let mut groups: Vec<MembershipSummaryGroup<'_>> = Vec::new();
+
+        for user in &self.users {
+            let changes = user.collapsed_changes();
+
+            match groups.iter_mut().find(|group| group.changes == changes) {
+                Some(group) => group.users.push(&user.user_id),
+                None => groups.push(MembershipSummaryGroup { changes, users: vec![&user.user_id] }),
+            }
+        }
+
+        groups
+    }
+
+    /// The number of users for each kind of change.
+    ///
+    /// A user is counted once per kind, even if the change happened several times.
+    pub fn counts(&self) -> BTreeMap<MembershipChangeKind, usize> {
+        let mut counts = BTreeMap::new();
+
+        for user in &self.users {
+            let mut kinds = user.changes.clone();
+            kinds.sort_unstable();
+            kinds.dedup();
+
+            for kind in kinds {
+                *counts.entry(kind).or_default() += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// The summaries of the users that only changed their profile.
+    pub fn profile_only_changes(&self) -> impl Iterator<Item = &UserMembershipSummary> {
+        self.users.iter().filter(|user| user.is_profile_change_only())
+    }
+}
+
+/// An owned version of [`MembershipDetails`], to keep track of the latest state of a user.
+#[derive(Clone, Debug)]
+struct OwnedMembershipDetails {
+    avatar_url: Option<OwnedMxcUri>,
+    displayname: Option<String>,
+    membership: MembershipState,
+}
+
+impl OwnedMembershipDetails {
+    fn from_details(details: MembershipDetails<'_>) -> Self {
+        Self {
+            avatar_url: details.avatar_url.map(ToOwned::to_owned),
+            displayname: details.displayname.map(ToOwned::to_owned),
+            membership: details.membership.clone(),
+        }
+    }
+
+    fn as_details(&self) -> MembershipDetails<'_> {
+        MembershipDetails {
+            avatar_url: self.avatar_url.as_deref(),
+            displayname: self.displayname.as_deref(),
+            membership: &self.membership,
+        }
+    }
+}
@@ -8,14 +8,18 @@ use ruma_common::{
     serde::{JsonObject, Raw, StringEnum},
     OwnedEventId, RoomId,
 };
+#[cfg(feature = "html-matrix")]
+use ruma_html::{matrix::MentionPill, Html};
 #[cfg(feature = "html")]
-use ruma_html::{sanitize_html, HtmlSanitizerMode, RemoveReplyFallback};
+use ruma_html::{
+    remove_html_reply_fallback, sanitize_html, HtmlSanitizerMode, RemoveReplyFallback,
+};
 use ruma_macros::EventContent;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use tracing::warn;
 
-use self::reply::OriginalEventData;
+use self::reply::{OriginalEventData, ReplyFallback};
 #[cfg(feature = "html")]
 use self::sanitize::remove_plain_reply_fallback;
 use crate::{
@@ -30,10 +34,12 @@ mod file;
 mod image;
 mod key_verification_request;
 mod location;
+#[cfg(all(feature = "markdown", feature = "html"))]
+mod markdown;
 mod notice;
 mod relation;
 pub(crate) mod relation_serde;
-mod reply;
+pub mod reply;
 pub mod sanitize;
 mod server_notice;
 mod text;
@@ -44,6 +50,8 @@ mod without_relation;
 pub use self::audio::{
     UnstableAmplitude, UnstableAudioDetailsContentBlock, UnstableVoiceContentBlock,
 };
+#[cfg(all(feature = "markdown", feature = "html"))]
+pub use self::markdown::{MarkdownRenderer, RenderedMarkdown};
 pub use self::{
     audio::{AudioInfo, AudioMessageEventContent},
     emote::EmoteMessageEventContent,
@@ -167,6 +175,28 @@ impl RoomMessageEventContent {
         self.without_relation().make_reply_to(original_message, forward_thread, add_mentions)
     }
 
+    /// Turns `self` into a reply to the given message, without a [rich reply fallback].
+    ///
+    /// Sets the `in_reply_to` field inside `relates_to`, and optionally the `rel_type` to
+    /// `m.thread` if the `original_message is in a thread and thread forwarding is enabled. The
+    /// `body` / `formatted_body` (if any) in `self` are left untouched.
+    ///
+    /// This should be used by clients that don't rely on reply fallbacks anymore.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub fn make_reply_to_without_fallback(
+        self,
+        original_message: &OriginalRoomMessageEvent,
+        forward_thread: ForwardThread,
+        add_mentions: AddMentions,
+    ) -> Self {
+        self.without_relation().make_reply_to_without_fallback(
+            original_message,
+            forward_thread,
+            add_mentions,
+        )
+    }
+
     /// Turns `self` into a reply to the given raw event.
     ///
     /// Takes the `body` / `formatted_body` (if any) in `self` for the main text and prepends a
@@ -428,6 +458,7 @@ pub enum ReplyWithinThread {
 
 /// The content that is specific to each message type variant.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(untagged)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub enum MessageType {
@@ -656,6 +687,56 @@ impl MessageType {
         }
     }
 
+    /// Extract the [rich reply fallback] of this message.
+    ///
+    /// Returns `None` if this message doesn't start with a reply fallback. Note that you should be
+    /// sure that the message is a reply, as there is no way to differentiate plain text reply
+    /// fallbacks and markdown quotes.
+    ///
+    /// This method is only effective on text, notice and emote messages.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub fn reply_fallback(&self) -> Option<ReplyFallback> {
+        match self {
+            MessageType::Emote(EmoteMessageEventContent { body, formatted, .. })
+            | MessageType::Notice(NoticeMessageEventContent { body, formatted, .. })
+            | MessageType::Text(TextMessageEventContent { body, formatted, .. }) => {
+                ReplyFallback::parse(body, formatted.as_ref())
+            }
+            _ => None,
+        }
+    }
+
+    /// Remove the [rich reply fallback] from the plain text and HTML message.
+    ///
+    /// The quoted lines are removed from the plain text `body`, and the `<mx-reply>` element is
+    /// removed from the HTML `formatted` body. Unlike [`MessageType::sanitize()`], no other tags or
+    /// attributes are removed from the HTML, but since it is parsed, malformed HTML and comments
+    /// are stripped from it. Note that you should be sure that the message is a reply, as there
+    /// is no way to differentiate plain text reply fallbacks and markdown quotes.
+    ///
+    /// This method is only effective on text, notice and emote messages.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    #[cfg(feature = "html")]
+    pub fn remove_reply_fallback(&mut self) {
+        if let MessageType::Emote(EmoteMessageEventContent { body, formatted, .. })
+        | MessageType::Notice(NoticeMessageEventContent { body, formatted, .. })
+        | MessageType::Text(TextMessageEventContent { body, formatted, .. }) = self
+        {
+            if let Some(formatted) =
+                formatted.as_mut().filter(|formatted| formatted.format == MessageFormat::Html)
+            {
+                formatted.body = remove_html_reply_fallback(&formatted.body);
+            }
+            // This is a false positive, see <https://github.com/rust-lang/rust-clippy/issues/12444>
+            #[allow(clippy::assigning_clones)]
+            {
+                *body = remove_plain_reply_fallback(body).to_owned();
+            }
+        }
+    }
+
     #[track_caller]
     fn add_reply_fallback(&mut self, original_event: OriginalEventData<'_>) {
         let empty_formatted_body = || FormattedBody::html(String::new());
@@ -795,6 +876,7 @@ pub enum MessageFormat {
 /// Common message event content fields for message types that have separate plain-text and
 /// formatted representations.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[allow(clippy::exhaustive_structs)]
 pub struct FormattedBody {
     /// The format used in the `formatted_body`.
@@ -839,11 +921,69 @@ impl FormattedBody {
             self.body = sanitize_html(&self.body, mode, remove_reply_fallback);
         }
     }
+
+    /// Get the mention pills in this `FormattedBody`, if its format is `MessageFormat::Html`.
+    ///
+    /// The mention pills in the [rich reply fallback] are ignored.
+    ///
+    /// Returns an empty list if the format is not `MessageFormat::Html`.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    #[cfg(feature = "html-matrix")]
+    pub fn mention_pills(&self) -> Vec<MentionPill> {
+        if self.format != MessageFormat::Html {
+            return Vec::new();
+        }
+
+        Html::parse(&self.body).mention_pills()
+    }
+
+    /// Construct the [`Mentions`] of the users mentioned with pills in this `FormattedBody`.
+    ///
+    /// Only user mentions are taken into account, a room mention must be added separately.
+    #[cfg(feature = "html-matrix")]
+    pub fn mentions(&self) -> Mentions {
+        Mentions::with_user_ids(
+            self.mention_pills().iter().filter_map(|pill| pill.user_id()).map(ToOwned::to_owned),
+        )
+    }
+
+    /// Rewrite the display text of the mention pills in this `FormattedBody`, if its format is
+    /// `MessageFormat::Html`.
+    ///
+    /// The given closure is called for every mention pill. If it returns a new text, the content of
+    /// the pill is replaced by it. This can be used for example to update the display names of
+    /// users.
+    ///
+    /// The HTML is only serialized again if at least one pill was rewritten. Due to the fact that
+    /// the HTML is parsed, note that malformed HTML and comments will be stripped from the output
+    /// in this case.
+    ///
+    /// Returns the number of pills that were rewritten.
+    #[cfg(feature = "html-matrix")]
+    pub fn rewrite_mention_pills<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&MentionPill) -> Option<String>,
+    {
+        if self.format != MessageFormat::Html {
+            return 0;
+        }
+
+        let mut html = Html::parse(&self.body);
+        let count = html.rewrite_mention_pills(f);
+
+        if count > 0 {
+            self.body = html.to_string();
+        }
+
+        count
+    }
 }
 
 /// The payload for a custom message event.
 #[doc(hidden)]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct CustomEventContent {
     /// A custom msgtype.
     msgtype: String,
@@ -858,19 +998,36 @@ pub struct CustomEventContent {
 
 #[cfg(feature = "markdown")]
 pub(crate) fn parse_markdown(text: &str) -> Option<String> {
-    use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+    use pulldown_cmark::{Event, Options, Parser};
 
     const OPTIONS: Options = Options::ENABLE_TABLES.union(Options::ENABLE_STRIKETHROUGH);
 
-    let mut found_first_paragraph = false;
-
     let parser_events: Vec<_> = Parser::new_ext(text, OPTIONS)
         .map(|event| match event {
             Event::SoftBreak => Event::HardBreak,
             _ => event,
         })
         .collect();
-    let has_markdown = parser_events.iter().any(|ref event| {
+
+    if !has_markdown(&parser_events) {
+        return None;
+    }
+
+    let mut html_body = String::new();
+    pulldown_cmark::html::push_html(&mut html_body, parser_events.into_iter());
+
+    Some(html_body)
+}
+
+/// Whether the given Markdown events contain formatting, other than a single paragraph and line
+/// breaks.
+#[cfg(feature = "markdown")]
+pub(crate) fn has_markdown(events: &[pulldown_cmark::Event<'_>]) -> bool {
+    use pulldown_cmark::{Event, Tag, TagEnd};
+
+    let mut found_first_paragraph = false;
+
+    events.iter().any(|event| {
         let is_text = matches!(event, Event::Text(_));
         let is_break = matches!(event, Event::HardBreak);
         let is_first_paragraph_start = if matches!(event, Event::Start(Tag::Paragraph)) {
@@ -886,14 +1043,5 @@ pub(crate) fn parse_markdown(text: &str) -> Option<String> {
         let is_paragraph_end = matches!(event, Event::End(TagEnd::Paragraph));
 
         !is_text && !is_break && !is_first_paragraph_start && !is_paragraph_end
-    });
-
-    if !has_markdown {
-        return None;
-    }
-
-    let mut html_body = String::new();
-    pulldown_cmark::html::push_html(&mut html_body, parser_events.into_iter());
-
-    Some(html_body)
+    })
 }
@@ -9,6 +9,7 @@ use crate::room::{EncryptedFile, MediaSource};
 
 /// The payload for an audio message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "msgtype", rename = "m.audio")]
 pub struct AudioMessageEventContent {
@@ -92,6 +93,7 @@ impl AudioMessageEventContent {
 
 /// Metadata about an audio clip.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct AudioInfo {
     /// The duration of the audio in milliseconds.
@@ -124,6 +126,7 @@ impl AudioInfo {
 /// [msc]: https://github.com/matrix-org/matrix-spec-proposals/blob/83f6c5b469c1d78f714e335dcaa25354b255ffa5/proposals/3245-voice-messages.md
 #[cfg(feature = "unstable-msc3245-v1-compat")]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstableAudioDetailsContentBlock {
     /// The duration of the audio in milliseconds.
@@ -154,6 +157,7 @@ impl UnstableAudioDetailsContentBlock {
 /// [msc]: https://github.com/matrix-org/matrix-spec-proposals/blob/83f6c5b469c1d78f714e335dcaa25354b255ffa5/proposals/3245-voice-messages.md
 #[cfg(feature = "unstable-msc3245-v1-compat")]
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnstableVoiceContentBlock {}
 
@@ -170,6 +174,7 @@ impl UnstableVoiceContentBlock {
 /// Must be an integer between 0 and 1024.
 #[cfg(feature = "unstable-msc3245-v1-compat")]
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 pub struct UnstableAmplitude(UInt);
 
 #[cfg(feature = "unstable-msc3245-v1-compat")]
@@ -90,6 +90,7 @@ pub(in super::super) mod msc3488 {
 
     /// Deserialize helper type for `LocationMessageEventContent` with unstable fields from msc3488.
     #[derive(Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[serde(tag = "msgtype", rename = "m.location")]
     pub(in super::super) struct LocationMessageEventContentSerDeHelper {
         pub body: String,
@@ -7,6 +7,7 @@ use crate::room::{EncryptedFile, MediaSource, ThumbnailInfo};
 
 /// The payload for a file message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "msgtype", rename = "m.file")]
 pub struct FileMessageEventContent {
@@ -64,6 +65,7 @@ impl FileMessageEventContent {
 
 /// Metadata about a file.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct FileInfo {
     /// The mimetype of the file, e.g. "application/msword".
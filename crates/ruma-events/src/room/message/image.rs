@@ -6,6 +6,7 @@ use crate::room::{EncryptedFile, ImageInfo, MediaSource};
 
 /// The payload for an image message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "msgtype", rename = "m.image")]
 pub struct ImageMessageEventContent {
@@ -12,6 +12,7 @@ use crate::{
 /// The payload for a location message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[serde(tag = "msgtype", rename = "m.location")]
 #[cfg_attr(
     feature = "unstable-msc3488",
@@ -114,6 +115,7 @@ impl LocationMessageEventContent {
 
 /// Thumbnail info associated with a location.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct LocationInfo {
     /// The source of a thumbnail of the location.
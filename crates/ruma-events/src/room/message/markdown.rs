@@ -0,0 +1,385 @@
+//! A Markdown renderer with Matrix-specific extensions.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd, TextMergeStream};
+use ruma_common::{
+    matrix_uri::MatrixId, MatrixToUri, MatrixUri, OwnedMxcUri, OwnedUserId, RoomAliasId, UserId,
+};
+use ruma_html::{sanitize_html, HtmlSanitizerMode, RemoveReplyFallback};
+
+use super::{has_markdown, FormattedBody};
+use crate::Mentions;
+
+/// The Markdown extensions enabled in the renderer.
+const OPTIONS: Options = Options::ENABLE_TABLES.union(Options::ENABLE_STRIKETHROUGH);
+
+/// The HTML tag to open a spoiler.
+const SPOILER_START: &str = "<span data-mx-spoiler>";
+
+/// The HTML tag to close a spoiler.
+const SPOILER_END: &str = "</span>";
+
+/// The height of the emotes, in pixels.
+const EMOTE_HEIGHT: u32 = 32;
+
+/// A Markdown renderer with Matrix-specific extensions.
+///
+/// In addition to the [CommonMark] syntax, tables and strikethrough, it supports:
+///
+/// * Spoilers, with text between `||`.
+/// * Mention pills, generated for user IDs and room aliases in the text.
+/// * Emotes, with their shortcode between `:`, for example `:party:`.
+///
+/// The generated HTML is sanitized with the [`HtmlSanitizerMode::Strict`] mode and the [rich reply
+/// fallback] is removed.
+///
+/// [CommonMark]: https://commonmark.org/
+/// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+#[derive(Clone, Debug)]
+pub struct MarkdownRenderer {
+    /// Whether to render spoilers.
+    spoilers: bool,
+
+    /// Whether to generate mention pills.
+    pills: bool,
+
+    /// The emotes, as a map of shortcode to URI.
+    emotes: BTreeMap<String, OwnedMxcUri>,
+}
+
+impl MarkdownRenderer {
+    /// Creates a new `MarkdownRenderer` with spoilers and mention pills enabled, and no emotes.
+    pub fn new() -> Self {
+        Self { spoilers: true, pills: true, emotes: BTreeMap::new() }
+    }
+
+    /// Set whether text between `||` should be rendered as a spoiler.
+    pub fn spoilers(mut self, enabled: bool) -> Self {
+        self.spoilers = enabled;
+        self
+    }
+
+    /// Set whether mention pills should be generated for user IDs and room aliases in the text.
+    pub fn pills(mut self, enabled: bool) -> Self {
+        self.pills = enabled;
+        self
+    }
+
+    /// Add an emote with the given shortcode, without the surrounding `:`, and the given URI.
+    pub fn emote(mut self, shortcode: impl Into<String>, url: OwnedMxcUri) -> Self {
+        self.emotes.insert(shortcode.into(), url);
+        self
+    }
+
+    /// Add the given emotes, as `(shortcode, url)` tuples.
+    ///
+    /// The shortcodes must not include the surrounding `:`.
+    pub fn emotes(mut self, emotes: impl IntoIterator<Item = (String, OwnedMxcUri)>) -> Self {
+        self.emotes.extend(emotes);
+        self
+    }
+
+    /// Render the given Markdown.
+    ///
+    /// The returned [`RenderedMarkdown`] contains the HTML, if any formatting was found, and the
+    /// [`Mentions`] of the users mentioned with pills or links and of the room, if `@room` is in
+    /// the text.
+    pub fn render(&self, markdown: &str) -> RenderedMarkdown {
+        let mut mentions = Mentions::new();
+        let mut pieces = Vec::new();
+        let mut code_block_depth = 0;
+        let mut link_depth = 0;
+
+        let events = TextMergeStream::new(Parser::new_ext(markdown, OPTIONS));
+
+        for event in events {
+            match &event {
+                Event::Start(Tag::CodeBlock(_)) => code_block_depth += 1,
+                Event::End(TagEnd::CodeBlock) => code_block_depth -= 1,
+                Event::Start(Tag::Link { dest_url, .. }) => {
+                    link_depth += 1;
+
+                    if let Some(user_id) = link_user_id(dest_url) {
+                        mentions.user_ids.insert(user_id);
+                    }
+                }
+                Event::Start(Tag::Image { .. }) => link_depth += 1,
+                Event::End(TagEnd::Link | TagEnd::Image) => link_depth -= 1,
+                Event::Text(text) if code_block_depth == 0 => {
+                    self.split_text(text, link_depth > 0, &mut pieces, &mut mentions);
+                    continue;
+                }
+                Event::SoftBreak => {
+                    pieces.push(Piece::Event(Event::HardBreak));
+                    continue;
+                }
+                _ => {}
+            }
+
+            pieces.push(Piece::Event(event));
+        }
+
+        let events = resolve_spoilers(pieces);
+
+        let formatted = has_markdown(&events).then(|| {
+            let mut html_body = String::new();
+            pulldown_cmark::html::push_html(&mut html_body, events.into_iter());
+
+            FormattedBody::html(sanitize_html(
+                &html_body,
+                HtmlSanitizerMode::Strict,
+                RemoveReplyFallback::Yes,
+            ))
+        });
+
+        RenderedMarkdown { formatted, mentions }
+    }
+
+    /// Split the given text into events for the extensions.
+    fn split_text<'a>(
+        &self,
+        text: &CowStr<'a>,
+        in_link: bool,
+        pieces: &mut Vec<Piece<'a>>,
+        mentions: &mut Mentions,
+    ) {
+        let mut start = 0;
+        let mut pos = 0;
+
+        let flush = |pieces: &mut Vec<Piece<'a>>, start: usize, end: usize| {
+            if start < end {
+                pieces.push(Piece::Event(Event::Text(text[start..end].to_owned().into())));
+            }
+        };
+
+        while let Some(c) = text[pos..].chars().next() {
+            let rest = &text[pos..];
+            let at_word_start = text[..pos].chars().next_back().map_or(true, is_word_separator);
+
+            if self.spoilers && rest.starts_with("||") {
+                flush(pieces, start, pos);
+                pieces.push(Piece::SpoilerMarker);
+                pos += 2;
+                start = pos;
+                continue;
+            }
+
+            if at_word_start && c == '@' && is_room_mention(rest) {
+                mentions.room = true;
+            }
+
+            if self.pills && !in_link && at_word_start && matches!(c, '@' | '#') {
+                if let Some((len, html)) = pill(rest, mentions) {
+                    flush(pieces, start, pos);
+                    pieces.push(Piece::Event(Event::InlineHtml(html.into())));
+                    pos += len;
+                    start = pos;
+                    continue;
+                }
+            }
+
+            if c == ':' && !self.emotes.is_empty() {
+                if let Some((len, html)) = self.emote_html(rest) {
+                    flush(pieces, start, pos);
+                    pieces.push(Piece::Event(Event::InlineHtml(html.into())));
+                    pos += len;
+                    start = pos;
+                    continue;
+                }
+            }
+
+            pos += c.len_utf8();
+        }
+
+        flush(pieces, start, pos);
+    }
+
+    /// Get the HTML for the emote at the start of the given string.
+    ///
+    /// Returns the length of the emote shortcode, with the surrounding `:`, and the HTML.
+    fn emote_html(&self, s: &str) -> Option<(usize, String)> {
+        let (shortcode, _) = s[1..].split_once(':')?;
+        let url = self.emotes.get(shortcode)?;
+
+        let mut html = String::new();
+        write!(
+            html,
+            "<img src=\"{}\" alt=\":{shortcode}:\" title=\":{shortcode}:\" height=\"{EMOTE_HEIGHT}\">",
+            EscapeHtml(url.as_str()),
+            shortcode = EscapeHtml(shortcode),
+        )
+        .expect("writing to a String should succeed");
+
+        Some((shortcode.len() + 2, html))
+    }
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of rendering Markdown with a [`MarkdownRenderer`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RenderedMarkdown {
+    /// The rendered HTML, if any formatting was found in the Markdown.
+    pub formatted: Option<FormattedBody>,
+
+    /// The users and room mentioned in the Markdown.
+    pub mentions: Mentions,
+}
+
+/// A piece of the rendered Markdown.
+enum Piece<'a> {
+    /// A Markdown event.
+    Event(Event<'a>),
+
+    /// A `||` that might start or end a spoiler.
+    SpoilerMarker,
+}
+
+/// Replace the spoiler markers by the HTML tags of spoilers.
+///
+/// Spoilers must be closed in the same block, otherwise the marker is kept as text.
+fn resolve_spoilers(pieces: Vec<Piece<'_>>) -> Vec<Event<'_>> {
+    let mut events = Vec::with_capacity(pieces.len());
+    let mut open_spoiler = None;
+
+    for piece in pieces {
+        match piece {
+            Piece::SpoilerMarker => {
+                if open_spoiler.take().is_some() {
+                    events.push(Event::InlineHtml(SPOILER_END.into()));
+                } else {
+                    open_spoiler = Some(events.len());
+                    events.push(Event::InlineHtml(SPOILER_START.into()));
+                }
+            }
+            Piece::Event(event) => {
+                if !is_inline(&event) {
+                    if let Some(index) = open_spoiler.take() {
+                        events[index] = Event::Text("||".into());
+                    }
+                }
+
+                events.push(event);
+            }
+        }
+    }
+
+    if let Some(index) = open_spoiler {
+        events[index] = Event::Text("||".into());
+    }
+
+    events
+}
+
+/// Whether the given event doesn't end a block.
+fn is_inline(event: &Event<'_>) -> bool {
+    match event {
+        Event::Start(tag) => matches!(
+            tag,
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+        ),
+        Event::End(tag) => matches!(
+            tag,
+            TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Link
+                | TagEnd::Image
+        ),
+        _ => true,
+    }
+}
+
+/// Get the HTML for the mention pill at the start of the given string, if any.
+///
+/// The mentioned user is added to the given `Mentions`.
+///
+/// Returns the length of the mentioned ID and the HTML.
+fn pill(s: &str, mentions: &mut Mentions) -> Option<(usize, String)> {
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    let mut candidate = &s[..end];
+
+    // Trailing punctuation is probably not part of the ID, but it can be valid in server names, so
+    // try to parse the ID with less and less of it.
+    loop {
+        let uri = if candidate.starts_with('@') {
+            UserId::parse(candidate).ok().map(|user_id| {
+                let uri = user_id.matrix_to_uri();
+                mentions.user_ids.insert(user_id);
+                uri
+            })
+        } else {
+            <&RoomAliasId>::try_from(candidate).ok().map(RoomAliasId::matrix_to_uri)
+        };
+
+        if let Some(uri) = uri {
+            let html = format!(
+                "<a href=\"{}\">{}</a>",
+                EscapeHtml(&uri.to_string()),
+                EscapeHtml(candidate)
+            );
+            return Some((candidate.len(), html));
+        }
+
+        candidate = candidate.strip_suffix(is_trailing_punctuation)?;
+    }
+}
+
+/// Whether the given string starts with a room mention.
+fn is_room_mention(s: &str) -> bool {
+    s.strip_prefix("@room")
+        .is_some_and(|rest| rest.chars().next().map_or(true, |c| !c.is_alphanumeric()))
+}
+
+/// Get the ID of the user mentioned by the given link URL, if any.
+fn link_user_id(url: &str) -> Option<OwnedUserId> {
+    let id = if let Ok(uri) = MatrixToUri::parse(url) {
+        uri.id().clone()
+    } else {
+        MatrixUri::parse(url).ok()?.id().clone()
+    };
+
+    match id {
+        MatrixId::User(user_id) => Some(user_id),
+        _ => None,
+    }
+}
+
+/// Whether the given character separates words.
+fn is_word_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | '[' | '{' | '<' | '"' | '\'')
+}
+
+/// Whether the given character is a punctuation character that can end a sentence or a
+/// parenthesis.
+fn is_trailing_punctuation(c: char) -> bool {
+    matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']' | '}' | '>' | '"' | '\'')
+}
+
+/// Escape the reserved HTML characters of a string.
+struct EscapeHtml<'a>(&'a str);
+
+impl fmt::Display for EscapeHtml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                _ => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -251,3 +251,67 @@ impl RelationWithoutReplacement {
         }
     }
 }
+
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::json_schema::{JsonSchema, SchemaGenerator};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{InReplyTo, Relation, RelationWithoutReplacement, Thread};
+
+    impl<C: JsonSchema> JsonSchema for Relation<C> {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("Relation")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            json!({
+                "type": "object",
+                "properties": {
+                    "m.relates_to": relates_to_schema(generator, true),
+                    "m.new_content": generator.subschema_for::<C>(),
+                },
+            })
+        }
+    }
+
+    impl JsonSchema for RelationWithoutReplacement {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("RelationWithoutReplacement")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            relates_to_schema(generator, false)
+        }
+    }
+
+    /// The schema of the `m.relates_to` object.
+    fn relates_to_schema(generator: &mut SchemaGenerator, with_replacement: bool) -> JsonValue {
+        let mut schemas = Vec::new();
+
+        if with_replacement {
+            schemas.push(json!({
+                "type": "object",
+                "properties": {
+                    "rel_type": { "type": "string", "const": "m.replace" },
+                    "event_id": { "type": "string" },
+                },
+                "required": ["rel_type", "event_id"],
+            }));
+        }
+
+        schemas.push(generator.subschema_for::<Thread>());
+
+        // Replies and custom relations.
+        schemas.push(json!({
+            "type": "object",
+            "properties": {
+                "m.in_reply_to": generator.subschema_for::<InReplyTo>(),
+            },
+        }));
+
+        json!({ "anyOf": schemas })
+    }
+}
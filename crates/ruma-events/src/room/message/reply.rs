@@ -1,13 +1,150 @@
+//! Types and functions to work with [rich reply fallbacks].
+//!
+//! [rich reply fallbacks]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+
 use std::fmt::{self, Write};
 
-use ruma_common::{EventId, RoomId, UserId};
+use ruma_common::{EventId, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId, UserId};
+#[cfg(feature = "html")]
+pub use ruma_html::{remove_html_reply_fallback, RemoveReplyFallback};
+#[cfg(feature = "html")]
+use ruma_html::{Html, NodeData, NodeId, NodeRef};
+
+pub use super::sanitize::remove_plain_reply_fallback;
+#[cfg(feature = "html")]
+use super::MessageFormat;
+use super::{FormattedBody, MessageType, OriginalRoomMessageEvent, Relation};
+
+/// The quoted original message of a [rich reply fallback].
+///
+/// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ReplyFallback {
+    /// The sender of the original message.
+    pub sender: OwnedUserId,
+
+    /// Whether the original message is an emote.
+    pub is_emote: bool,
+
+    /// The plain text body of the original message.
+    pub body: String,
+
+    /// The ID of the room of the original message, if it was found in the HTML fallback.
+    pub room_id: Option<OwnedRoomId>,
+
+    /// The ID of the original message, if it was found in the HTML fallback.
+    pub event_id: Option<OwnedEventId>,
+
+    /// The HTML body of the original message, if it was found in the HTML fallback.
+    pub formatted_body: Option<String>,
+}
+
+impl ReplyFallback {
+    /// Extract the rich reply fallback from the given plain text body and optional formatted
+    /// body.
+    ///
+    /// Returns `None` if the plain text body doesn't start with a reply fallback. Note that there
+    /// is no way to differentiate a plain text reply fallback and a markdown quote starting
+    /// with a user ID, so this should only be used on messages that are replies.
+    ///
+    /// The HTML fallback is only parsed with the `html` feature, if the format of the formatted
+    /// body is `MessageFormat::Html`.
+    pub fn parse(body: &str, formatted: Option<&FormattedBody>) -> Option<Self> {
+        let (sender, is_emote, body) = parse_plain_reply_fallback(body)?;
+
+        #[cfg(feature = "html")]
+        let (room_id, event_id, formatted_body) = formatted
+            .filter(|formatted| formatted.format == MessageFormat::Html)
+            .and_then(|formatted| parse_html_reply_fallback(&formatted.body))
+            .map(|(room_id, event_id, formatted_body)| (room_id, event_id, Some(formatted_body)))
+            .unwrap_or_default();
+        #[cfg(not(feature = "html"))]
+        let (room_id, event_id, formatted_body) = {
+            let _ = formatted;
+            (None, None, None)
+        };
+
+        Some(Self { sender, is_emote, body, room_id, event_id, formatted_body })
+    }
+}
+
+/// Parse the plain text reply fallback at the start of the given body.
+///
+/// Returns a `(sender, is_emote, quoted_body)` tuple.
+fn parse_plain_reply_fallback(body: &str) -> Option<(OwnedUserId, bool, String)> {
+    let mut lines = body.split('\n').map_while(|line| line.strip_prefix("> "));
+
+    let first_line = lines.next()?;
+    let (is_emote, first_line) = match first_line.strip_prefix("* ") {
+        Some(rest) => (true, rest),
+        None => (false, first_line),
+    };
+    let (sender, first_line) = first_line.strip_prefix('<')?.split_once('>')?;
+    let sender = UserId::parse(sender).ok()?;
+    let first_line = first_line.strip_prefix(' ').unwrap_or(first_line);
+
+    let mut quoted_body = first_line.to_owned();
+    for line in lines {
+        quoted_body.push('\n');
+        quoted_body.push_str(line);
+    }
+
+    Some((sender, is_emote, quoted_body))
+}
+
+/// Parse the HTML reply fallback at the start of the given HTML body.
+///
+/// Returns a `(room_id, event_id, quoted_html)` tuple.
 #[cfg(feature = "html")]
-use ruma_html::Html;
+fn parse_html_reply_fallback(
+    formatted_body: &str,
+) -> Option<(Option<OwnedRoomId>, Option<OwnedEventId>, String)> {
+    use ruma_common::{matrix_uri::MatrixId, MatrixToUri};
+
+    let mut html = Html::parse(formatted_body);
+
+    let mx_reply = html.children().find(|node| is_element(node, "mx-reply"))?;
+    let blockquote = mx_reply.children().find(|node| is_element(node, "blockquote"))?;
+
+    let mut room_id = None;
+    let mut event_id = None;
+    let mut quoted_nodes: Option<Vec<NodeId>> = None;
 
-use super::{
-    sanitize::remove_plain_reply_fallback, FormattedBody, MessageType, OriginalRoomMessageEvent,
-    Relation,
-};
+    for node in blockquote.children() {
+        if let Some(nodes) = &mut quoted_nodes {
+            nodes.push(node.id());
+        } else if is_element(&node, "br") {
+            quoted_nodes = Some(Vec::new());
+        } else if let Some(href) = node.as_element().and_then(|element| element.attribute("href")) {
+            if let Ok(uri) = MatrixToUri::parse(href) {
+                if let MatrixId::Event(room_or_alias_id, id) = uri.id() {
+                    room_id = room_or_alias_id.clone().try_into().ok();
+                    event_id = Some(id.clone());
+                }
+            }
+        }
+    }
+
+    let quoted_nodes = quoted_nodes?;
+
+    // Only keep the quoted nodes in the tree to serialize them.
+    let root_nodes = html.children().map(|node| node.id()).collect::<Vec<_>>();
+    for node in root_nodes {
+        html.detach(node);
+    }
+    for node in quoted_nodes {
+        html.append_to_root(node);
+    }
+
+    Some((room_id, event_id, html.to_string()))
+}
+
+/// Whether the given node is an element with the given local name.
+#[cfg(feature = "html")]
+fn is_element(node: &NodeRef<'_>, name: &str) -> bool {
+    matches!(node.data(), NodeData::Element(element) if element.name.local.as_ref() == name)
+}
 
 pub(super) struct OriginalEventData<'a> {
     pub(super) body: &'a str,
@@ -146,7 +283,7 @@ pub(super) fn plain_and_formatted_reply_body(
 mod tests {
     use ruma_common::{owned_event_id, owned_room_id, owned_user_id, MilliSecondsSinceUnixEpoch};
 
-    use super::OriginalRoomMessageEvent;
+    use super::{OriginalRoomMessageEvent, ReplyFallback};
     use crate::{room::message::RoomMessageEventContent, MessageLikeUnsigned};
 
     #[test]
@@ -176,4 +313,55 @@ mod tests {
             </mx-reply>",
         );
     }
+
+    #[test]
+    fn parse_plain_fallback() {
+        let fallback =
+            ReplyFallback::parse("> <@alice:example.com> multi\n> line\n\nThis is my reply", None)
+                .unwrap();
+
+        assert_eq!(fallback.sender, "@alice:example.com");
+        assert!(!fallback.is_emote);
+        assert_eq!(fallback.body, "multi\nline");
+        assert_eq!(fallback.event_id, None);
+        assert_eq!(fallback.formatted_body, None);
+
+        let fallback = ReplyFallback::parse("> * <@alice:example.com> waves\n\nHi!", None).unwrap();
+        assert_eq!(fallback.sender, "@alice:example.com");
+        assert!(fallback.is_emote);
+        assert_eq!(fallback.body, "waves");
+
+        assert!(ReplyFallback::parse("No reply here", None).is_none());
+        assert!(ReplyFallback::parse("> A simple quote\n\nNo reply here", None).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "html")]
+    fn parse_html_fallback() {
+        use crate::room::message::FormattedBody;
+
+        let formatted = FormattedBody::html(
+            "<mx-reply>\
+                <blockquote>\
+                    <a href=\"https://matrix.to/#/!n8f893n9:example.com/$1598361704261elfgc:localhost\">In reply to</a> \
+                    <a href=\"https://matrix.to/#/@alice:example.com\">@alice:example.com</a>\
+                    <br>\
+                    <strong>multi</strong><br>line\
+                </blockquote>\
+            </mx-reply>\
+            This is my <em>reply</em>",
+        );
+
+        let fallback = ReplyFallback::parse(
+            "> <@alice:example.com> **multi**\n> line\n\nThis is my _reply_",
+            Some(&formatted),
+        )
+        .unwrap();
+
+        assert_eq!(fallback.sender, "@alice:example.com");
+        assert_eq!(fallback.body, "**multi**\nline");
+        assert_eq!(fallback.room_id.unwrap(), "!n8f893n9:example.com");
+        assert_eq!(fallback.event_id.unwrap(), "$1598361704261elfgc:localhost");
+        assert_eq!(fallback.formatted_body.unwrap(), "<strong>multi</strong><br>line");
+    }
 }
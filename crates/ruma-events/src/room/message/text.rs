@@ -4,6 +4,7 @@ use super::FormattedBody;
 
 /// The payload for a text message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "msgtype", rename = "m.text")]
 pub struct TextMessageEventContent {
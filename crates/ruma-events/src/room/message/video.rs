@@ -9,6 +9,7 @@ use crate::room::{EncryptedFile, MediaSource, ThumbnailInfo};
 
 /// The payload for a video message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "msgtype", rename = "m.video")]
 pub struct VideoMessageEventContent {
@@ -66,6 +67,7 @@ impl VideoMessageEventContent {
 
 /// Metadata about a video.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct VideoInfo {
     /// The duration of the video in milliseconds.
@@ -14,6 +14,7 @@ use crate::{
 
 /// Form of [`RoomMessageEventContent`] without relation.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomMessageEventContentWithoutRelation {
     /// A key which identifies the type of message being sent.
@@ -111,6 +112,24 @@ impl RoomMessageEventContentWithoutRelation {
         add_mentions: AddMentions,
     ) -> RoomMessageEventContent {
         self.msgtype.add_reply_fallback(original_message.into());
+        self.make_reply_to_without_fallback(original_message, forward_thread, add_mentions)
+    }
+
+    /// Turns `self` into a reply to the given message, without a [rich reply fallback].
+    ///
+    /// Sets the `in_reply_to` field inside `relates_to`, and optionally the `rel_type` to
+    /// `m.thread` if the `original_message is in a thread and thread forwarding is enabled. The
+    /// `body` / `formatted_body` (if any) in `self` are left untouched.
+    ///
+    /// This should be used by clients that don't rely on reply fallbacks anymore.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub fn make_reply_to_without_fallback(
+        self,
+        original_message: &OriginalRoomMessageEvent,
+        forward_thread: ForwardThread,
+        add_mentions: AddMentions,
+    ) -> RoomMessageEventContent {
         let original_event_id = original_message.event_id.clone();
 
         let original_thread_id = if forward_thread == ForwardThread::Yes {
@@ -214,6 +214,7 @@ impl StrippedRoomPowerLevelsEvent {
 
 /// Redacted form of [`RoomPowerLevelsEventContent`].
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedRoomPowerLevelsEventContent {
     /// The level required to ban a user.
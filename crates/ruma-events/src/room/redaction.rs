@@ -235,6 +235,7 @@ impl RedactContent for RoomRedactionEventContent {
 
 /// A redacted redaction event.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedRoomRedactionEventContent {
     /// The ID of the event that was redacted.
@@ -430,6 +431,7 @@ impl RedactionEvent for SyncRoomRedactionEvent {}
 
 /// Extra information about a redaction that is not incorporated into the event's hash.
 #[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomRedactionUnsigned {
     /// The time in milliseconds that has elapsed since the event was sent.
@@ -511,3 +513,90 @@ fn redacts<'a>(
                 .expect("At least one redacts field is set"),
         }
 }
+
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use ruma_common::{
+        json_schema::{
+            private::{any_of, event_type, ObjectSchema},
+            JsonSchema, SchemaGenerator,
+        },
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId,
+    };
+    use serde_json::Value as JsonValue;
+
+    use super::{
+        OriginalRoomRedactionEvent, OriginalSyncRoomRedactionEvent, RedactedRoomRedactionEvent,
+        RedactedSyncRoomRedactionEvent, RoomRedactionEvent, RoomRedactionEventContent,
+        RoomRedactionUnsigned, SyncRoomRedactionEvent,
+    };
+    use crate::StaticEventContent;
+
+    /// The schema of an original redaction event, with or without a `room_id`.
+    fn original_schema(generator: &mut SchemaGenerator, with_room_id: bool) -> JsonValue {
+        let mut object = ObjectSchema::new();
+        object.property("type", event_type(RoomRedactionEventContent::TYPE), true);
+        object.property("content", generator.subschema_for::<RoomRedactionEventContent>(), true);
+        object.property("redacts", generator.subschema_for::<OwnedEventId>(), false);
+        object.property("event_id", generator.subschema_for::<OwnedEventId>(), true);
+        object.property("sender", generator.subschema_for::<OwnedUserId>(), true);
+        object.property(
+            "origin_server_ts",
+            generator.subschema_for::<MilliSecondsSinceUnixEpoch>(),
+            true,
+        );
+        if with_room_id {
+            object.property("room_id", generator.subschema_for::<OwnedRoomId>(), true);
+        }
+        object.property("unsigned", generator.subschema_for::<RoomRedactionUnsigned>(), false);
+        object.finish()
+    }
+
+    impl JsonSchema for OriginalRoomRedactionEvent {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("OriginalRoomRedactionEvent")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            original_schema(generator, true)
+        }
+    }
+
+    impl JsonSchema for OriginalSyncRoomRedactionEvent {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("OriginalSyncRoomRedactionEvent")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            original_schema(generator, false)
+        }
+    }
+
+    impl JsonSchema for RoomRedactionEvent {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("RoomRedactionEvent")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            any_of(vec![
+                generator.subschema_for::<OriginalRoomRedactionEvent>(),
+                generator.subschema_for::<RedactedRoomRedactionEvent>(),
+            ])
+        }
+    }
+
+    impl JsonSchema for SyncRoomRedactionEvent {
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("SyncRoomRedactionEvent")
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            any_of(vec![
+                generator.subschema_for::<OriginalSyncRoomRedactionEvent>(),
+                generator.subschema_for::<RedactedSyncRoomRedactionEvent>(),
+            ])
+        }
+    }
+}
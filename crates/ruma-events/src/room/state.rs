@@ -0,0 +1,412 @@
+//! A container for the state of a room.
+
+use std::collections::BTreeMap;
+
+use js_int::int;
+use ruma_common::{
+    EventEncryptionAlgorithm, OwnedMxcUri, OwnedRoomAliasId, OwnedRoomId, OwnedUserId,
+    RoomVersionId, UserId,
+};
+
+use super::{
+    display_name::{compute_room_display_name, count_members},
+    join_rules::JoinRule,
+    member::MembershipState,
+    member_display_names,
+    power_levels::{RoomPowerLevels, RoomPowerLevelsEventContent},
+    MemberDisplayName, RoomDisplayName, RoomSummary,
+};
+use crate::{AnyStrippedStateEvent, AnySyncStateEvent, SyncStateEvent};
+
+/// The state of a room, folded from its state events.
+///
+/// This type accepts state events as received in the `state` and `timeline` of a joined or left
+/// room, with [`RoomState::apply_event()`], or as stripped state events received for an invited or
+/// knocked room, with [`RoomState::apply_stripped_event()`]. Events must be applied in the order
+/// of the room's timeline: the last event wins.
+///
+/// It can then be used to answer common questions about the room, like its [display name], its
+/// avatar, whether it is encrypted, its join rule, the power levels and the list of members.
+///
+/// [display name]: https://spec.matrix.org/latest/client-server-api/#calculating-the-display-name-for-a-room
+#[derive(Clone, Debug, Default)]
+pub struct RoomState {
+    /// The sender of the `m.room.create` event.
+    creator: Option<OwnedUserId>,
+
+    /// The version of the room.
+    room_version: Option<RoomVersionId>,
+
+    /// The room that this room replaces.
+    predecessor: Option<OwnedRoomId>,
+
+    /// The room that replaces this room.
+    successor: Option<OwnedRoomId>,
+
+    /// The name of the room.
+    name: Option<String>,
+
+    /// The topic of the room.
+    topic: Option<String>,
+
+    /// The avatar of the room.
+    avatar_url: Option<OwnedMxcUri>,
+
+    /// The canonical alias of the room.
+    canonical_alias: Option<OwnedRoomAliasId>,
+
+    /// The alternative aliases of the room.
+    alt_aliases: Vec<OwnedRoomAliasId>,
+
+    /// Whether the room is encrypted.
+    is_encrypted: bool,
+
+    /// The encryption algorithm of the room, if it is known.
+    encryption_algorithm: Option<EventEncryptionAlgorithm>,
+
+    /// The join rule of the room.
+    join_rule: Option<JoinRule>,
+
+    /// The power levels of the room.
+    power_levels: Option<RoomPowerLevels>,
+
+    /// The members of the room.
+    members: BTreeMap<OwnedUserId, RoomMember>,
+
+    /// The room summary.
+    summary: RoomSummary,
+}
+
+impl RoomState {
+    /// Creates a new empty `RoomState`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the given state event.
+    pub fn apply_event(&mut self, event: &AnySyncStateEvent) {
+        match event {
+            AnySyncStateEvent::RoomCreate(ev) => {
+                self.creator = Some(ev.sender().to_owned());
+                if let SyncStateEvent::Original(ev) = ev {
+                    self.room_version = Some(ev.content.room_version.clone());
+                    self.predecessor = ev.content.predecessor.as_ref().map(|p| p.room_id.clone());
+                }
+            }
+            AnySyncStateEvent::RoomTombstone(ev) => {
+                self.successor = ev.as_original().map(|ev| ev.content.replacement_room.clone());
+            }
+            AnySyncStateEvent::RoomName(ev) => {
+                self.name = ev.as_original().map(|ev| ev.content.name.clone());
+            }
+            AnySyncStateEvent::RoomTopic(ev) => {
+                self.topic = ev.as_original().map(|ev| ev.content.topic.clone());
+            }
+            AnySyncStateEvent::RoomAvatar(ev) => {
+                self.avatar_url = ev.as_original().and_then(|ev| ev.content.url.clone());
+            }
+            AnySyncStateEvent::RoomCanonicalAlias(ev) => {
+                let content = ev.as_original().map(|ev| &ev.content);
+                self.canonical_alias = content.and_then(|c| c.alias.clone());
+                self.alt_aliases = content.map(|c| c.alt_aliases.clone()).unwrap_or_default();
+            }
+            AnySyncStateEvent::RoomEncryption(ev) => {
+                // Encryption cannot be disabled once it was enabled.
+                self.is_encrypted = true;
+                if let Some(ev) = ev.as_original() {
+                    self.encryption_algorithm = Some(ev.content.algorithm.clone());
+                }
+            }
+            AnySyncStateEvent::RoomJoinRules(ev) => {
+                self.join_rule = Some(ev.join_rule().clone());
+            }
+            AnySyncStateEvent::RoomPowerLevels(ev) => {
+                self.power_levels = Some(ev.power_levels());
+            }
+            AnySyncStateEvent::RoomMember(ev) => {
+                let member = match ev {
+                    SyncStateEvent::Original(ev) => RoomMember {
+                        membership: ev.content.membership.clone(),
+                        display_name: ev.content.displayname.clone(),
+                        avatar_url: ev.content.avatar_url.clone(),
+                        is_direct: ev.content.is_direct.unwrap_or_default(),
+                    },
+                    SyncStateEvent::Redacted(ev) => RoomMember::new(ev.content.membership.clone()),
+                };
+                self.members.insert(ev.state_key().clone(), member);
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply the given state events, in order.
+    pub fn apply_events<'a>(&mut self, events: impl IntoIterator<Item = &'a AnySyncStateEvent>) {
+        for event in events {
+            self.apply_event(event);
+        }
+    }
+
+    /// Apply the given stripped state event.
+    pub fn apply_stripped_event(&mut self, event: &AnyStrippedStateEvent) {
+        match event {
+            AnyStrippedStateEvent::RoomCreate(ev) => {
+                self.creator = Some(ev.sender.clone());
+                self.room_version = Some(ev.content.room_version.clone());
+                self.predecessor = ev.content.predecessor.as_ref().map(|p| p.room_id.clone());
+            }
+            AnyStrippedStateEvent::RoomTombstone(ev) => {
+                self.successor.clone_from(&ev.content.replacement_room);
+            }
+            AnyStrippedStateEvent::RoomName(ev) => {
+                self.name.clone_from(&ev.content.name);
+            }
+            AnyStrippedStateEvent::RoomTopic(ev) => {
+                self.topic.clone_from(&ev.content.topic);
+            }
+            AnyStrippedStateEvent::RoomAvatar(ev) => {
+                self.avatar_url.clone_from(&ev.content.url);
+            }
+            AnyStrippedStateEvent::RoomCanonicalAlias(ev) => {
+                self.canonical_alias.clone_from(&ev.content.alias);
+                self.alt_aliases.clone_from(&ev.content.alt_aliases);
+            }
+            AnyStrippedStateEvent::RoomEncryption(ev) => {
+                self.is_encrypted = true;
+                if let Some(algorithm) = &ev.content.algorithm {
+                    self.encryption_algorithm = Some(algorithm.clone());
+                }
+            }
+            AnyStrippedStateEvent::RoomJoinRules(ev) => {
+                self.join_rule = Some(ev.content.join_rule.clone());
+            }
+            AnyStrippedStateEvent::RoomPowerLevels(ev) => {
+                self.power_levels = Some(ev.power_levels());
+            }
+            AnyStrippedStateEvent::RoomMember(ev) => {
+                self.members.insert(
+                    ev.state_key.clone(),
+                    RoomMember {
+                        membership: ev.content.membership.clone(),
+                        display_name: ev.content.displayname.clone(),
+                        avatar_url: ev.content.avatar_url.clone(),
+                        is_direct: ev.content.is_direct.unwrap_or_default(),
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply the given stripped state events, in order.
+    pub fn apply_stripped_events<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a AnyStrippedStateEvent>,
+    ) {
+        for event in events {
+            self.apply_stripped_event(event);
+        }
+    }
+
+    /// Update the room summary, as received in a `/sync` response.
+    ///
+    /// The heroes and the counts of joined and invited members are used to compute the display
+    /// name of the room. If they are not set, they are computed from the known members of the room.
+    /// Fields that are `None` leave the previous value untouched, as specified for the room
+    /// summary.
+    pub fn update_summary(&mut self, summary: RoomSummary) {
+        self.summary.update(summary);
+    }
+
+    /// The creator of the room, if the `m.room.create` event is known.
+    pub fn creator(&self) -> Option<&UserId> {
+        self.creator.as_deref()
+    }
+
+    /// The version of the room, if the `m.room.create` event is known.
+    pub fn room_version(&self) -> Option<&RoomVersionId> {
+        self.room_version.as_ref()
+    }
+
+    /// The ID of the room that this room replaces, if any.
+    pub fn predecessor(&self) -> Option<&OwnedRoomId> {
+        self.predecessor.as_ref()
+    }
+
+    /// The ID of the room that replaces this room, if it was upgraded.
+    pub fn successor(&self) -> Option<&OwnedRoomId> {
+        self.successor.as_ref()
+    }
+
+    /// The name of the room, as set in the `m.room.name` event.
+    ///
+    /// Use [`RoomState::display_name()`] to get the name of the room to display.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The topic of the room.
+    pub fn topic(&self) -> Option<&str> {
+        self.topic.as_deref()
+    }
+
+    /// The URL of the avatar of the room.
+    pub fn avatar_url(&self) -> Option<&OwnedMxcUri> {
+        self.avatar_url.as_ref()
+    }
+
+    /// The canonical alias of the room.
+    pub fn canonical_alias(&self) -> Option<&OwnedRoomAliasId> {
+        self.canonical_alias.as_ref()
+    }
+
+    /// The alternative aliases of the room.
+    pub fn alt_aliases(&self) -> &[OwnedRoomAliasId] {
+        &self.alt_aliases
+    }
+
+    /// Whether the room is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.is_encrypted
+    }
+
+    /// The algorithm used to encrypt the events of the room, if it is known.
+    pub fn encryption_algorithm(&self) -> Option<&EventEncryptionAlgorithm> {
+        self.encryption_algorithm.as_ref()
+    }
+
+    /// The join rule of the room.
+    ///
+    /// Defaults to [`JoinRule::Invite`] if the `m.room.join_rules` event is unknown, as specified
+    /// in the authorization rules.
+    pub fn join_rule(&self) -> &JoinRule {
+        self.join_rule.as_ref().unwrap_or(&JoinRule::Invite)
+    }
+
+    /// The power levels of the room.
+    ///
+    /// If the `m.room.power_levels` event is unknown, this returns the default power levels of the
+    /// [spec], where the creator of the room has a power level of `100`, every other user has a
+    /// power level of `0` and sending state events requires a power level of `0`. Returns `None`
+    /// if neither the `m.room.power_levels` nor the `m.room.create` event is known.
+    ///
+    /// [spec]: https://spec.matrix.org/latest/client-server-api/#mroompower_levels
+    pub fn power_levels(&self) -> Option<RoomPowerLevels> {
+        if let Some(power_levels) = &self.power_levels {
+            return Some(power_levels.clone());
+        }
+
+        let creator = self.creator.clone()?;
+        let mut power_levels = RoomPowerLevels::from(RoomPowerLevelsEventContent::new());
+        power_levels.users.insert(creator, int!(100));
+        power_levels.state_default = int!(0);
+
+        Some(power_levels)
+    }
+
+    /// The member of the room with the given user ID, if it is known.
+    pub fn member(&self, user_id: &UserId) -> Option<&RoomMember> {
+        self.members.get(user_id)
+    }
+
+    /// The known members of the room, regardless of their membership.
+    pub fn members(&self) -> impl Iterator<Item = (&UserId, &RoomMember)> {
+        self.members.iter().map(|(user_id, member)| (&**user_id, member))
+    }
+
+    /// The known members of the room with the given membership.
+    pub fn members_with_membership<'a>(
+        &'a self,
+        membership: &'a MembershipState,
+    ) -> impl Iterator<Item = (&'a UserId, &'a RoomMember)> {
+        self.members().filter(move |(_, member)| member.membership == *membership)
+    }
+
+    /// The number of joined members of the room.
+    ///
+    /// Uses the count from the room summary if it is known.
+    pub fn joined_member_count(&self) -> u64 {
+        self.summary
+            .joined_member_count
+            .map(u64::from)
+            .unwrap_or_else(|| count_members(&self.members, &MembershipState::Join))
+    }
+
+    /// The number of invited members of the room.
+    ///
+    /// Uses the count from the room summary if it is known.
+    pub fn invited_member_count(&self) -> u64 {
+        self.summary
+            .invited_member_count
+            .map(u64::from)
+            .unwrap_or_else(|| count_members(&self.members, &MembershipState::Invite))
+    }
+
+    /// The display name of the room for the given user, computed according to the [spec].
+    ///
+    /// See [`room_display_name()`] for more details.
+    ///
+    /// [spec]: https://spec.matrix.org/latest/client-server-api/#calculating-the-display-name-for-a-room
+    /// [`room_display_name()`]: super::room_display_name
+    pub fn display_name(&self, own_user_id: &UserId) -> RoomDisplayName {
+        compute_room_display_name(
+            own_user_id,
+            self.name.as_deref(),
+            self.canonical_alias.as_deref(),
+            &self.summary,
+            &self.members,
+        )
+    }
+
+    /// The display name of the member with the given user ID, disambiguated if necessary.
+    ///
+    /// Returns `None` if the member is unknown. See [`member_display_names()`] for more details.
+    ///
+    /// [`member_display_names()`]: super::member_display_names
+    pub fn member_display_name(&self, user_id: &UserId) -> Option<MemberDisplayName> {
+        let mut names = member_display_names(&self.members);
+        names.remove(user_id)
+    }
+
+    /// The display names of all the known members, disambiguated if necessary.
+    ///
+    /// See [`member_display_names()`] for more details.
+    ///
+    /// [`member_display_names()`]: super::member_display_names
+    pub fn member_display_names(&self) -> BTreeMap<&UserId, MemberDisplayName> {
+        member_display_names(&self.members)
+    }
+}
+
+/// A member of a room.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RoomMember {
+    /// The membership state of the member.
+    pub membership: MembershipState,
+
+    /// The display name of the member in the room, if any.
+    pub display_name: Option<String>,
+
+    /// The avatar of the member in the room, if any.
+    pub avatar_url: Option<OwnedMxcUri>,
+
+    /// Whether the room is a direct chat, as set by the sender of the invite.
+    pub is_direct: bool,
+}
+
+impl RoomMember {
+    /// Creates a new `RoomMember` with the given membership.
+    pub fn new(membership: MembershipState) -> Self {
+        Self { membership, display_name: None, avatar_url: None, is_direct: false }
+    }
+
+    /// Whether this member is joined or invited.
+    pub(super) fn is_active(&self) -> bool {
+        matches!(self.membership, MembershipState::Join | MembershipState::Invite)
+    }
+
+    /// The display name of this member, if it is set and not empty.
+    pub(super) fn non_empty_display_name(&self) -> Option<&str> {
+        self.display_name.as_deref().filter(|name| !name.trim().is_empty())
+    }
+}
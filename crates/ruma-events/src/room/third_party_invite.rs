@@ -53,6 +53,7 @@ impl RoomThirdPartyInviteEventContent {
 
 /// A public key for signing a third party invite token.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PublicKey {
     /// An optional URL which can be fetched to validate whether the key has been revoked.
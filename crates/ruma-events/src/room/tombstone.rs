@@ -46,6 +46,7 @@ impl RoomTombstoneEventContent {
 ///
 /// This type is used when it's not obvious whether the content is redacted or not.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PossiblyRedactedRoomTombstoneEventContent {
     /// A server-defined message.
@@ -65,6 +65,7 @@ pub enum Action {
 
 /// Information about a requested key.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RequestedKeyInfo {
     /// The encryption algorithm the requested key in this event is to be used with.
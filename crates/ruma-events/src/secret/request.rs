@@ -84,6 +84,43 @@ impl Serialize for RequestAction {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for RequestAction {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("RequestAction")
+    }
+
+    fn json_schema(generator: &mut ruma_common::json_schema::SchemaGenerator) -> serde_json::Value {
+        serde_json::json!({
+            "anyOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "action": { "type": "string", "const": "request" },
+                        "name": generator.subschema_for::<SecretName>(),
+                    },
+                    "required": ["action", "name"],
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "action": { "type": "string", "const": "request_cancellation" },
+                    },
+                    "required": ["action"],
+                },
+                // Custom actions.
+                {
+                    "type": "object",
+                    "properties": {
+                        "action": { "type": "string" },
+                    },
+                    "required": ["action"],
+                },
+            ],
+        })
+    }
+}
+
 #[derive(Deserialize)]
 struct RequestActionJsonRepr {
     action: String,
@@ -0,0 +1,279 @@
+//! Encryption of secrets with the `m.secret_storage.v1.aes-hmac-sha2` algorithm.
+//!
+//! See the [spec] for the details of the algorithm.
+//!
+//! [spec]: https://spec.matrix.org/latest/client-server-api/#msecret_storagev1aes-hmac-sha2
+
+use std::fmt;
+
+use aes::{
+    cipher::{KeyIvInit, StreamCipher},
+    Aes256,
+};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, RngCore};
+use ruma_common::{serde::Base64, KeyDerivationAlgorithm};
+use sha2::{Sha256, Sha512};
+
+use super::{
+    key::{PassPhrase, SecretStorageV1AesHmacSha2Properties},
+    secret::SecretEncryptedData,
+};
+
+type Aes256Ctr = ctr::Ctr128BE<Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+const KEY_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+
+/// The prefix of a recovery key, before base58 encoding.
+const RECOVERY_KEY_PREFIX: [u8; 2] = [0x8B, 0x01];
+
+/// The length of a decoded recovery key, with the prefix and the parity byte.
+const RECOVERY_KEY_LENGTH: usize = RECOVERY_KEY_PREFIX.len() + KEY_SIZE + 1;
+
+/// A secret storage key for the `m.secret_storage.v1.aes-hmac-sha2` algorithm.
+///
+/// The key can be generated randomly, derived from a passphrase or parsed from a recovery key.
+#[derive(Clone)]
+pub struct SecretStorageKey {
+    key: [u8; KEY_SIZE],
+}
+
+impl SecretStorageKey {
+    /// Creates a new `SecretStorageKey` from the given bytes.
+    pub fn new(key: [u8; KEY_SIZE]) -> Self {
+        Self { key }
+    }
+
+    /// Generates a new random `SecretStorageKey`.
+    pub fn generate() -> Self {
+        let mut key = [0; KEY_SIZE];
+        thread_rng().fill_bytes(&mut key);
+        Self { key }
+    }
+
+    /// Derives a `SecretStorageKey` from the given passphrase, with the parameters of the key
+    /// description.
+    pub fn from_passphrase(
+        passphrase: &str,
+        params: &PassPhrase,
+    ) -> Result<Self, SecretStorageError> {
+        if params.algorithm != KeyDerivationAlgorithm::Pbkfd2 {
+            return Err(SecretStorageError::UnsupportedKeyDerivation(params.algorithm.clone()));
+        }
+
+        if params.bits != js_int::uint!(256) {
+            return Err(SecretStorageError::UnsupportedKeyLength);
+        }
+
+        let iterations =
+            u32::try_from(params.iterations).map_err(|_| SecretStorageError::TooManyIterations)?;
+        let mut key = [0; KEY_SIZE];
+        pbkdf2::pbkdf2_hmac::<Sha512>(
+            passphrase.as_bytes(),
+            params.salt.as_bytes(),
+            iterations,
+            &mut key,
+        );
+
+        Ok(Self { key })
+    }
+
+    /// Parses a `SecretStorageKey` from the given recovery key.
+    ///
+    /// Whitespace in the recovery key is ignored.
+    pub fn from_recovery_key(recovery_key: &str) -> Result<Self, SecretStorageError> {
+        let recovery_key: String = recovery_key.split_whitespace().collect();
+        let decoded = bs58::decode(recovery_key)
+            .with_alphabet(bs58::Alphabet::BITCOIN)
+            .into_vec()
+            .map_err(|_| SecretStorageError::InvalidRecoveryKey)?;
+
+        if decoded.len() != RECOVERY_KEY_LENGTH
+            || decoded[..RECOVERY_KEY_PREFIX.len()] != RECOVERY_KEY_PREFIX
+        {
+            return Err(SecretStorageError::InvalidRecoveryKey);
+        }
+
+        if decoded.iter().fold(0, |parity, byte| parity ^ byte) != 0 {
+            return Err(SecretStorageError::InvalidRecoveryKeyParity);
+        }
+
+        let mut key = [0; KEY_SIZE];
+        key.copy_from_slice(&decoded[RECOVERY_KEY_PREFIX.len()..RECOVERY_KEY_LENGTH - 1]);
+        Ok(Self { key })
+    }
+
+    /// Encodes this key as a recovery key, with a space every four characters.
+    pub fn to_recovery_key(&self) -> String {
+        let mut bytes = Vec::with_capacity(RECOVERY_KEY_LENGTH);
+        bytes.extend_from_slice(&RECOVERY_KEY_PREFIX);
+        bytes.extend_from_slice(&self.key);
+        bytes.push(bytes.iter().fold(0, |parity, byte| parity ^ byte));
+
+        let encoded = bs58::encode(bytes).with_alphabet(bs58::Alphabet::BITCOIN).into_string();
+        encoded
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).expect("base58 is ASCII"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The bytes of this key.
+    pub fn as_bytes(&self) -> &[u8; KEY_SIZE] {
+        &self.key
+    }
+
+    /// Compute the properties to store in the key description, to be able to
+    /// [check](Self::check) this key later.
+    pub fn properties(&self) -> SecretStorageV1AesHmacSha2Properties {
+        let SecretEncryptedData::AesHmacSha2EncryptedData { iv, mac, .. } =
+            self.encrypt_bytes(&[0; KEY_SIZE], "", random_iv());
+        SecretStorageV1AesHmacSha2Properties::new(Some(iv), Some(mac))
+    }
+
+    /// Check whether this key matches the properties of a key description.
+    ///
+    /// Returns `false` if the properties don't contain an IV and a MAC.
+    pub fn check(&self, properties: &SecretStorageV1AesHmacSha2Properties) -> bool {
+        let (Some(iv), Some(mac)) = (&properties.iv, &properties.mac) else {
+            return false;
+        };
+        let Ok(iv) = <[u8; IV_SIZE]>::try_from(iv.as_bytes()) else {
+            return false;
+        };
+
+        let (aes_key, mac_key) = self.derive_keys("");
+        let mut ciphertext = [0; KEY_SIZE];
+        Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+        hmac(&mac_key, &ciphertext).verify_slice(mac.as_bytes()).is_ok()
+    }
+
+    /// Encrypt the given secret with this key.
+    ///
+    /// The name of the secret is the type of the account data event where the secret is stored,
+    /// like `m.cross_signing.master`.
+    pub fn encrypt(&self, secret: &str, secret_name: &str) -> SecretEncryptedData {
+        self.encrypt_bytes(secret.as_bytes(), secret_name, random_iv())
+    }
+
+    /// Decrypt the given secret with this key.
+    ///
+    /// The name of the secret is the type of the account data event where the secret is stored,
+    /// like `m.cross_signing.master`.
+    pub fn decrypt(
+        &self,
+        data: &SecretEncryptedData,
+        secret_name: &str,
+    ) -> Result<String, SecretStorageError> {
+        let SecretEncryptedData::AesHmacSha2EncryptedData { iv, ciphertext, mac } = data;
+
+        let iv: [u8; IV_SIZE] =
+            iv.as_bytes().try_into().map_err(|_| SecretStorageError::InvalidIvLength)?;
+        let (aes_key, mac_key) = self.derive_keys(secret_name);
+
+        hmac(&mac_key, ciphertext.as_bytes())
+            .verify_slice(mac.as_bytes())
+            .map_err(|_| SecretStorageError::MacMismatch)?;
+
+        let mut plaintext = ciphertext.as_bytes().to_vec();
+        Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut plaintext);
+
+        String::from_utf8(plaintext).map_err(|_| SecretStorageError::InvalidUtf8)
+    }
+
+    fn encrypt_bytes(
+        &self,
+        plaintext: &[u8],
+        secret_name: &str,
+        iv: [u8; IV_SIZE],
+    ) -> SecretEncryptedData {
+        let (aes_key, mac_key) = self.derive_keys(secret_name);
+
+        let mut ciphertext = plaintext.to_vec();
+        Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut ciphertext);
+        let mac = hmac(&mac_key, &ciphertext).finalize().into_bytes();
+
+        SecretEncryptedData::AesHmacSha2EncryptedData {
+            iv: Base64::new(iv.to_vec()),
+            ciphertext: Base64::new(ciphertext),
+            mac: Base64::new(mac.to_vec()),
+        }
+    }
+
+    /// Derive the AES and MAC keys for the secret with the given name.
+    fn derive_keys(&self, secret_name: &str) -> ([u8; KEY_SIZE], [u8; KEY_SIZE]) {
+        let mut okm = [0; 2 * KEY_SIZE];
+        Hkdf::<Sha256>::new(Some(&[0; KEY_SIZE]), &self.key)
+            .expand(secret_name.as_bytes(), &mut okm)
+            .expect("64 bytes is a valid length for HKDF-SHA-256");
+
+        let mut aes_key = [0; KEY_SIZE];
+        let mut mac_key = [0; KEY_SIZE];
+        aes_key.copy_from_slice(&okm[..KEY_SIZE]);
+        mac_key.copy_from_slice(&okm[KEY_SIZE..]);
+        (aes_key, mac_key)
+    }
+}
+
+impl fmt::Debug for SecretStorageKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretStorageKey").finish_non_exhaustive()
+    }
+}
+
+/// Generate a random IV, with bit 63 set to zero to work around differences in AES-CTR
+/// implementations.
+fn random_iv() -> [u8; IV_SIZE] {
+    let mut iv = [0; IV_SIZE];
+    thread_rng().fill_bytes(&mut iv);
+    iv[8] &= 0x7f;
+    iv
+}
+
+fn hmac(key: &[u8; KEY_SIZE], data: &[u8]) -> HmacSha256 {
+    let mut hmac = HmacSha256::new_from_slice(key).expect("HMAC can take a key of any size");
+    hmac.update(data);
+    hmac
+}
+
+/// An error encountered when using a [`SecretStorageKey`].
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum SecretStorageError {
+    /// The key derivation algorithm of the passphrase is not supported.
+    #[error("unsupported key derivation algorithm: {0}")]
+    UnsupportedKeyDerivation(KeyDerivationAlgorithm),
+
+    /// The passphrase should generate a key that is not 256 bits long.
+    #[error("only 256-bit keys are supported")]
+    UnsupportedKeyLength,
+
+    /// The number of iterations of the passphrase is too big.
+    #[error("too many iterations for PBKDF2")]
+    TooManyIterations,
+
+    /// The recovery key is not valid base58, or doesn't have the right prefix or length.
+    #[error("invalid recovery key")]
+    InvalidRecoveryKey,
+
+    /// The parity byte of the recovery key doesn't match.
+    #[error("invalid recovery key parity")]
+    InvalidRecoveryKeyParity,
+
+    /// The IV of the encrypted secret doesn't have a length of 128 bits.
+    #[error("the IV of the encrypted secret has an invalid length")]
+    InvalidIvLength,
+
+    /// The MAC of the encrypted secret doesn't match, which usually means that the key is wrong.
+    #[error("the MAC of the encrypted secret doesn't match")]
+    MacMismatch,
+
+    /// The decrypted secret is not valid UTF-8.
+    #[error("the decrypted secret is not valid UTF-8")]
+    InvalidUtf8,
+}
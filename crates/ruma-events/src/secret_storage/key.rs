@@ -18,6 +18,7 @@ use crate::macros::EventContent;
 
 /// A passphrase from which a key is to be derived.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PassPhrase {
     /// The algorithm to use to generate the key from the passphrase.
@@ -139,6 +140,7 @@ impl SecretStorageEncryptionAlgorithm {
 /// Corresponds to the AES-specific properties of `AesHmacSha2KeyDescription` in the
 /// [spec](https://spec.matrix.org/latest/client-server-api/#msecret_storagev1aes-hmac-sha2).
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SecretStorageV1AesHmacSha2Properties {
     /// The 16-byte initialization vector, encoded as base64.
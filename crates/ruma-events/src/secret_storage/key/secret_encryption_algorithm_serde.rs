@@ -62,3 +62,40 @@ impl Serialize for SecretStorageEncryptionAlgorithm {
         }
     }
 }
+
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for SecretStorageEncryptionAlgorithm {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("SecretStorageEncryptionAlgorithm")
+    }
+
+    fn json_schema(generator: &mut ruma_common::json_schema::SchemaGenerator) -> serde_json::Value {
+        serde_json::json!({
+            "anyOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "algorithm": {
+                                    "type": "string",
+                                    "const": "m.secret_storage.v1.aes-hmac-sha2",
+                                },
+                            },
+                            "required": ["algorithm"],
+                        },
+                        generator.subschema_for::<SecretStorageV1AesHmacSha2Properties>(),
+                    ],
+                },
+                // Custom algorithms.
+                {
+                    "type": "object",
+                    "properties": {
+                        "algorithm": { "type": "string" },
+                    },
+                    "required": ["algorithm"],
+                },
+            ],
+        })
+    }
+}
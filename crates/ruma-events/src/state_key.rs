@@ -38,3 +38,20 @@ impl Serialize for EmptyStateKey {
         serializer.serialize_str("")
     }
 }
+
+#[cfg(feature = "json-schema")]
+impl ruma_common::json_schema::JsonSchema for EmptyStateKey {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("EmptyStateKey")
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(
+        _generator: &mut ruma_common::json_schema::SchemaGenerator,
+    ) -> serde_json::Value {
+        ruma_common::json_schema::private::constant("")
+    }
+}
@@ -12,6 +12,7 @@ use crate::room::{ImageInfo, MediaSource};
 
 /// The source of a sticker media file.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[non_exhaustive]
 pub enum StickerMediaSource {
     /// The MXC URI to the unencrypted media file.
@@ -111,6 +111,7 @@ impl<C: PossiblyRedactedStateEventContent> Default for StateUnsigned<C> {
 
 /// Extra information about a redacted event that is not incorporated into the event's hash.
 #[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RedactedUnsigned {
     /// The event that redacted this event, if any.
@@ -133,6 +134,7 @@ impl RedactedUnsigned {
 /// It is intentionally not possible to create an instance of this type other than through `Clone`
 /// or `Deserialize`.
 #[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[non_exhaustive]
 pub struct UnsignedRoomRedactionEvent {
     /// Data specific to the event type.
@@ -151,3 +153,77 @@ pub struct UnsignedRoomRedactionEvent {
     #[serde(default)]
     pub unsigned: MessageLikeUnsigned<RoomRedactionEventContent>,
 }
+
+#[cfg(feature = "json-schema")]
+mod json_schema {
+    use std::borrow::Cow;
+
+    use js_int::Int;
+    use ruma_common::{
+        json_schema::{private::ObjectSchema, JsonSchema, SchemaGenerator},
+        OwnedTransactionId,
+    };
+    use serde_json::Value as JsonValue;
+
+    use super::{MessageLikeUnsigned, StateUnsigned};
+    use crate::{
+        relation::{BundledMessageLikeRelations, BundledStateRelations},
+        MessageLikeEventContent, OriginalSyncMessageLikeEvent, PossiblyRedactedStateEventContent,
+        StaticEventContent,
+    };
+
+    /// Add the fields common to all unsigned data to the given object.
+    fn common_properties(object: &mut ObjectSchema, generator: &mut SchemaGenerator) {
+        object.property("age", generator.subschema_for::<Option<Int>>(), false);
+        object.property(
+            "transaction_id",
+            generator.subschema_for::<Option<OwnedTransactionId>>(),
+            false,
+        );
+    }
+
+    // The bounds of the derived implementation would not include `StaticEventContent`, which is
+    // needed for the schema of the bundled replacement.
+    impl<C> JsonSchema for MessageLikeUnsigned<C>
+    where
+        C: MessageLikeEventContent + StaticEventContent + JsonSchema,
+    {
+        fn schema_name() -> Cow<'static, str> {
+            format!("MessageLikeUnsigned_{}", C::schema_name()).into()
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            let mut object = ObjectSchema::new();
+            common_properties(&mut object, generator);
+            object.property(
+                "m.relations",
+                generator
+                    .subschema_for::<BundledMessageLikeRelations<OriginalSyncMessageLikeEvent<C>>>(
+                    ),
+                false,
+            );
+            object.finish()
+        }
+    }
+
+    impl<C> JsonSchema for StateUnsigned<C>
+    where
+        C: PossiblyRedactedStateEventContent + JsonSchema,
+    {
+        fn schema_name() -> Cow<'static, str> {
+            format!("StateUnsigned_{}", C::schema_name()).into()
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+            let mut object = ObjectSchema::new();
+            common_properties(&mut object, generator);
+            object.property("prev_content", generator.subschema_for::<Option<C>>(), false);
+            object.property(
+                "m.relations",
+                generator.subschema_for::<BundledStateRelations>(),
+                false,
+            );
+            object.finish()
+        }
+    }
+}
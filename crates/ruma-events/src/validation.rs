@@ -0,0 +1,235 @@
+//! Validation of event content.
+//!
+//! The fields of a type deriving [`EventContent`](crate::macros::EventContent) can be validated
+//! with the `#[ruma_event(validate(...))]` attribute. The following validations are supported:
+//!
+//! * `max_length = N`: the length of the field must be at most `N`. For strings, the length is the
+//!   number of bytes. For lists and maps, it is the number of items.
+//! * `required`: the field must be present. This is only useful for `Option` fields.
+//! * `url_scheme = "scheme"`: the field must be a URL with the given scheme. This can be repeated
+//!   to accept several schemes.
+//!
+//! Validations of `Option` fields only apply when the field is present, except for `required`.
+//! The errors use the name of the field in the JSON, taking `#[serde(rename)]` and
+//! `#[serde(rename_all)]` into account.
+//!
+//! # Entry points
+//!
+//! The checks are implemented in the generated
+//! [`EventContent::validate()`](crate::EventContent::validate) method, which is called
+//! automatically by:
+//!
+//! * [`RawExt::deserialize_content()`](crate::RawExt::deserialize_content) and
+//!   [`RawExt::deserialize_with_type()`](crate::RawExt::deserialize_with_type),
+//! * [`EventContentFromType::from_parts()`](crate::EventContentFromType::from_parts), which is used
+//!   to deserialize the content of the generic event types like
+//!   [`OriginalMessageLikeEvent`](crate::OriginalMessageLikeEvent).
+//!
+//! The `Deserialize` implementation of the content type is derived separately and does **not**
+//! validate the content, so deserializing it directly, with [`Raw::deserialize()`] or
+//! `serde_json::from_*` for example, skips the checks. `validate()` must be called manually in
+//! that case.
+//!
+//! [`Raw::deserialize()`]: ruma_common::serde::Raw::deserialize
+//!
+//! # Example
+//!
+//! ```
+//! use ruma_events::macros::EventContent;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+//! #[ruma_event(type = "com.acme.link", kind = MessageLike)]
+//! pub struct LinkEventContent {
+//!     #[ruma_event(validate(max_length = 255))]
+//!     pub title: String,
+//!
+//!     #[ruma_event(validate(url_scheme = "https", url_scheme = "mxc"))]
+//!     pub url: String,
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use ruma_common::{MxcUri, OwnedMxcUri};
+
+/// An error encountered when validating event content.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("invalid field `{field}`: {kind}")]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct ContentValidationError {
+    /// The name of the invalid field.
+    pub field: &'static str,
+
+    /// The reason why the field is invalid.
+    pub kind: ContentValidationErrorKind,
+}
+
+impl ContentValidationError {
+    /// Creates a new `ContentValidationError` for the given field.
+    pub fn new(field: &'static str, kind: ContentValidationErrorKind) -> Self {
+        Self { field, kind }
+    }
+}
+
+/// The reason why a field of event content is invalid.
+#[derive(Clone, Debug, thiserror::Error)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum ContentValidationErrorKind {
+    /// The field is longer than the maximum length.
+    #[error("length {len} is greater than the maximum of {max}")]
+    TooLong {
+        /// The maximum length.
+        max: usize,
+
+        /// The length of the field.
+        len: usize,
+    },
+
+    /// The field is missing.
+    #[error("the field is required")]
+    Missing,
+
+    /// The field is not a URL with one of the allowed schemes.
+    #[error("expected a URL with one of the schemes {expected:?}")]
+    InvalidUrlScheme {
+        /// The allowed schemes.
+        expected: &'static [&'static str],
+    },
+}
+
+/// Check that the given value is not longer than `max`.
+#[doc(hidden)]
+pub fn max_length<T: ValidateLength + ?Sized>(
+    field: &'static str,
+    value: &T,
+    max: usize,
+) -> Result<(), ContentValidationError> {
+    match value.validation_len() {
+        Some(len) if len > max => Err(ContentValidationError::new(
+            field,
+            ContentValidationErrorKind::TooLong { max, len },
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the given value is present.
+#[doc(hidden)]
+pub fn required<T>(field: &'static str, value: &Option<T>) -> Result<(), ContentValidationError> {
+    match value {
+        Some(_) => Ok(()),
+        None => Err(ContentValidationError::new(field, ContentValidationErrorKind::Missing)),
+    }
+}
+
+/// Check that the given value is a URL with one of the given schemes.
+#[doc(hidden)]
+pub fn url_scheme<T: ValidateUrl + ?Sized>(
+    field: &'static str,
+    value: &T,
+    schemes: &'static [&'static str],
+) -> Result<(), ContentValidationError> {
+    let Some(url) = value.validation_url() else {
+        return Ok(());
+    };
+
+    let has_scheme = url.split_once(':').is_some_and(|(scheme, _)| {
+        schemes.iter().any(|expected| scheme.eq_ignore_ascii_case(expected))
+    });
+
+    if has_scheme {
+        Ok(())
+    } else {
+        Err(ContentValidationError::new(
+            field,
+            ContentValidationErrorKind::InvalidUrlScheme { expected: schemes },
+        ))
+    }
+}
+
+/// A type whose length can be validated.
+#[doc(hidden)]
+pub trait ValidateLength {
+    /// The length to validate, or `None` if there is nothing to validate.
+    fn validation_len(&self) -> Option<usize>;
+}
+
+impl ValidateLength for str {
+    fn validation_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl ValidateLength for String {
+    fn validation_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> ValidateLength for [T] {
+    fn validation_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> ValidateLength for Vec<T> {
+    fn validation_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, V> ValidateLength for BTreeMap<K, V> {
+    fn validation_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: ValidateLength> ValidateLength for Option<T> {
+    fn validation_len(&self) -> Option<usize> {
+        self.as_ref().and_then(T::validation_len)
+    }
+}
+
+impl<T: ValidateLength + ?Sized> ValidateLength for Box<T> {
+    fn validation_len(&self) -> Option<usize> {
+        (**self).validation_len()
+    }
+}
+
+/// A type whose URL scheme can be validated.
+#[doc(hidden)]
+pub trait ValidateUrl {
+    /// The URL to validate, or `None` if there is nothing to validate.
+    fn validation_url(&self) -> Option<&str>;
+}
+
+impl ValidateUrl for str {
+    fn validation_url(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl ValidateUrl for String {
+    fn validation_url(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl ValidateUrl for MxcUri {
+    fn validation_url(&self) -> Option<&str> {
+        Some(self.as_str())
+    }
+}
+
+impl ValidateUrl for OwnedMxcUri {
+    fn validation_url(&self) -> Option<&str> {
+        Some(self.as_str())
+    }
+}
+
+impl<T: ValidateUrl> ValidateUrl for Option<T> {
+    fn validation_url(&self) -> Option<&str> {
+        self.as_ref().and_then(T::validation_url)
+    }
+}
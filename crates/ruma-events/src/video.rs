@@ -103,6 +103,7 @@ impl VideoEventContent {
 
 /// A block for details of video content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct VideoDetailsContentBlock {
     /// The width of the video in pixels.
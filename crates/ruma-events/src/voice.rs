@@ -91,6 +91,7 @@ impl VoiceEventContent {
 
 /// A block for details of voice audio content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct VoiceAudioDetailsContentBlock {
     /// The duration of the audio in seconds.
@@ -0,0 +1,305 @@
+use assert_matches2::assert_let;
+use ruma_common::{event_id, owned_event_id, user_id, RoomVersionId};
+use ruma_events::{
+    relation::TimelineAggregator, room::message::MessageType, AnySyncMessageLikeEvent,
+    AnySyncTimelineEvent, SyncMessageLikeEvent,
+};
+use serde_json::{from_value as from_json_value, json, Value as JsonValue};
+
+fn timeline_event(json: JsonValue) -> AnySyncTimelineEvent {
+    from_json_value(json).unwrap()
+}
+
+fn message(event_id: &str, sender: &str, ts: u64, body: &str) -> AnySyncTimelineEvent {
+    timeline_event(json!({
+        "type": "m.room.message",
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": ts,
+        "content": {
+            "msgtype": "m.text",
+            "body": body,
+        },
+    }))
+}
+
+fn edit(event_id: &str, sender: &str, ts: u64, target: &str, body: &str) -> AnySyncTimelineEvent {
+    timeline_event(json!({
+        "type": "m.room.message",
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": ts,
+        "content": {
+            "msgtype": "m.text",
+            "body": format!("* {body}"),
+            "m.new_content": {
+                "msgtype": "m.text",
+                "body": body,
+            },
+            "m.relates_to": {
+                "rel_type": "m.replace",
+                "event_id": target,
+            },
+        },
+    }))
+}
+
+fn reaction(event_id: &str, sender: &str, target: &str, key: &str) -> AnySyncTimelineEvent {
+    timeline_event(json!({
+        "type": "m.reaction",
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": 1,
+        "content": {
+            "m.relates_to": {
+                "rel_type": "m.annotation",
+                "event_id": target,
+                "key": key,
+            },
+        },
+    }))
+}
+
+fn redaction(event_id: &str, sender: &str, redacts: &str) -> AnySyncTimelineEvent {
+    timeline_event(json!({
+        "type": "m.room.redaction",
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": 1,
+        "redacts": redacts,
+        "content": {},
+    }))
+}
+
+fn redacted(event_type: &str, event_id: &str, sender: &str) -> AnySyncTimelineEvent {
+    timeline_event(json!({
+        "type": event_type,
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": 1,
+        "content": {},
+        "unsigned": {
+            "redacted_because": {
+                "type": "m.room.redaction",
+                "event_id": "$redaction",
+                "sender": sender,
+                "origin_server_ts": 2,
+                "redacts": event_id,
+                "content": {},
+            },
+        },
+    }))
+}
+
+fn edit_body(event: &AnySyncMessageLikeEvent) -> &str {
+    assert_let!(AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(ev)) = event);
+    assert_let!(
+        Some(ruma_events::room::message::Relation::Replacement(replacement)) =
+            &ev.content.relates_to
+    );
+    assert_let!(MessageType::Text(text) = &replacement.new_content.msgtype);
+    &text.body
+}
+
+#[test]
+fn edits_out_of_order() {
+    let mut aggregator = TimelineAggregator::new(RoomVersionId::V10);
+
+    aggregator.add_events(&[
+        edit("$edit2", "@alice:localhost", 30, "$original", "third"),
+        edit("$edit1", "@alice:localhost", 20, "$original", "second"),
+        // Edits from other users are ignored.
+        edit("$edit3", "@mallory:localhost", 40, "$original", "evil"),
+    ]);
+
+    // The original event is unknown, so the edits cannot be validated yet.
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert!(!relations.is_known());
+    assert!(relations.latest_edit().is_none());
+
+    aggregator.add_event(&message("$original", "@alice:localhost", 10, "first"));
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert!(relations.is_known());
+    assert_eq!(relations.sender(), Some(user_id!("@alice:localhost")));
+    assert!(relations.is_edited());
+    assert_eq!(edit_body(relations.latest_edit().unwrap()), "third");
+
+    // Redacting the latest edit falls back to the previous one.
+    aggregator.add_event(&redaction("$redaction1", "@alice:localhost", "$edit2"));
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert_eq!(edit_body(relations.latest_edit().unwrap()), "second");
+
+    // Redacting the original event hides the edits.
+    aggregator.add_event(&redaction("$redaction2", "@alice:localhost", "$original"));
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert!(relations.is_redacted());
+    assert!(relations.latest_edit().is_none());
+}
+
+#[test]
+fn edits_tie_breaking() {
+    let mut aggregator = TimelineAggregator::new(RoomVersionId::V10);
+
+    aggregator.add_events(&[
+        message("$original", "@alice:localhost", 10, "first"),
+        edit("$b", "@alice:localhost", 20, "$original", "b"),
+        edit("$a", "@alice:localhost", 20, "$original", "a"),
+    ]);
+
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert_eq!(edit_body(relations.latest_edit().unwrap()), "b");
+}
+
+#[test]
+fn reactions() {
+    let mut aggregator = TimelineAggregator::new(RoomVersionId::V10);
+    let alice = user_id!("@alice:localhost");
+    let bob = user_id!("@bob:localhost");
+
+    aggregator.add_events(&[
+        reaction("$r1", "@alice:localhost", "$original", "👍"),
+        message("$original", "@alice:localhost", 10, "Hello"),
+        reaction("$r2", "@bob:localhost", "$original", "👍"),
+        reaction("$r3", "@bob:localhost", "$original", "🎉"),
+        // The redaction arrives before the event it redacts.
+        redaction("$redaction1", "@bob:localhost", "$r4"),
+        reaction("$r4", "@bob:localhost", "$original", "😀"),
+    ]);
+
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert_eq!(relations.reaction_keys().collect::<Vec<_>>(), ["🎉", "👍"]);
+    assert_eq!(relations.reaction_count("👍"), 2);
+    assert_eq!(relations.reaction_count("🎉"), 1);
+    assert_eq!(relations.reaction_count("😀"), 0);
+    assert_eq!(relations.reaction_senders("👍").collect::<Vec<_>>(), [alice, bob]);
+    assert_eq!(relations.reaction_by("🎉", bob), Some(event_id!("$r3")));
+    assert!(aggregator.is_redacted(event_id!("$r4")));
+
+    aggregator.add_event(&redaction("$redaction2", "@bob:localhost", "$r3"));
+    // Adding the same event again has no effect.
+    aggregator.add_event(&reaction("$r2", "@bob:localhost", "$original", "👍"));
+
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert_eq!(relations.reaction_keys().collect::<Vec<_>>(), ["👍"]);
+    assert_eq!(relations.reaction_count("👍"), 2);
+    assert_eq!(relations.reaction_by("🎉", bob), None);
+}
+
+#[test]
+fn redacted_copy_of_aggregated_events() {
+    let mut aggregator = TimelineAggregator::new(RoomVersionId::V10);
+
+    aggregator.add_events(&[
+        message("$original", "@alice:localhost", 10, "first"),
+        edit("$edit", "@alice:localhost", 20, "$original", "second"),
+        reaction("$reaction", "@bob:localhost", "$original", "👍"),
+    ]);
+
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert!(relations.is_edited());
+    assert_eq!(relations.reaction_count("👍"), 1);
+
+    // The redacted forms of the events are received, for example after a back-pagination.
+    aggregator.add_events(&[
+        redacted("m.room.message", "$edit", "@alice:localhost"),
+        redacted("m.reaction", "$reaction", "@bob:localhost"),
+    ]);
+
+    let relations = aggregator.relations(event_id!("$original")).unwrap();
+    assert!(!relations.is_edited());
+    assert!(relations.latest_edit().is_none());
+    assert_eq!(relations.reaction_count("👍"), 0);
+    assert!(aggregator.is_redacted(event_id!("$edit")));
+    assert!(aggregator.is_redacted(event_id!("$reaction")));
+}
+
+#[test]
+fn threads_and_replies() {
+    let mut aggregator = TimelineAggregator::new(RoomVersionId::V11);
+
+    aggregator.add_events(&[
+        timeline_event(json!({
+            "type": "m.room.message",
+            "event_id": "$thread2",
+            "sender": "@bob:localhost",
+            "origin_server_ts": 30,
+            "content": {
+                "msgtype": "m.text",
+                "body": "In thread, replying",
+                "m.relates_to": {
+                    "rel_type": "m.thread",
+                    "event_id": "$root",
+                    "m.in_reply_to": { "event_id": "$thread1" },
+                    "is_falling_back": false,
+                },
+            },
+        })),
+        timeline_event(json!({
+            "type": "m.room.message",
+            "event_id": "$thread1",
+            "sender": "@alice:localhost",
+            "origin_server_ts": 20,
+            "content": {
+                "msgtype": "m.text",
+                "body": "In thread",
+                "m.relates_to": {
+                    "rel_type": "m.thread",
+                    "event_id": "$root",
+                    "m.in_reply_to": { "event_id": "$root" },
+                    "is_falling_back": true,
+                },
+            },
+        })),
+        message("$root", "@alice:localhost", 10, "Root"),
+        timeline_event(json!({
+            "type": "m.room.message",
+            "event_id": "$reply",
+            "sender": "@carl:localhost",
+            "origin_server_ts": 40,
+            "content": {
+                "msgtype": "m.text",
+                "body": "Reply",
+                "m.relates_to": {
+                    "m.in_reply_to": { "event_id": "$root" },
+                },
+            },
+        })),
+    ]);
+
+    let root = aggregator.relations(event_id!("$root")).unwrap();
+    let summary = root.thread_summary().unwrap();
+    assert_eq!(summary.count, 2);
+    assert_eq!(summary.latest_event, owned_event_id!("$thread2"));
+    assert_eq!(summary.participants.len(), 2);
+    assert_eq!(root.replies().collect::<Vec<_>>(), [event_id!("$reply")]);
+
+    let thread1 = aggregator.relations(event_id!("$thread1")).unwrap();
+    assert_eq!(thread1.thread_root(), Some(event_id!("$root")));
+    assert_eq!(thread1.in_reply_to(), None);
+    assert_eq!(thread1.replies().collect::<Vec<_>>(), [event_id!("$thread2")]);
+
+    let thread2 = aggregator.relations(event_id!("$thread2")).unwrap();
+    assert_eq!(thread2.thread_root(), Some(event_id!("$root")));
+    assert_eq!(thread2.in_reply_to(), Some(event_id!("$thread1")));
+
+    // In room version 11, `redacts` is in the content.
+    aggregator.add_event(&timeline_event(json!({
+        "type": "m.room.redaction",
+        "event_id": "$redaction",
+        "sender": "@bob:localhost",
+        "origin_server_ts": 50,
+        "content": { "redacts": "$thread2" },
+    })));
+
+    let root = aggregator.relations(event_id!("$root")).unwrap();
+    let summary = root.thread_summary().unwrap();
+    assert_eq!(summary.count, 1);
+    assert_eq!(summary.latest_event, owned_event_id!("$thread1"));
+
+    let thread1 = aggregator.relations(event_id!("$thread1")).unwrap();
+    assert_eq!(thread1.replies().count(), 0);
+
+    let thread2 = aggregator.relations(event_id!("$thread2")).unwrap();
+    assert!(thread2.is_redacted());
+    assert_eq!(thread2.thread_root(), None);
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "attachment-encryption")]
+
+use std::io::{ErrorKind, Read};
+
+use assert_matches2::assert_matches;
+use ruma_common::{mxc_uri, owned_mxc_uri};
+use ruma_events::room::{
+    AttachmentDecryptionError, AttachmentDecryptor, AttachmentEncryptor, EncryptedFile,
+};
+use serde_json::{
+    from_value as from_json_value, json, to_value as to_json_value, Value as JsonValue,
+};
+
+const PLAINTEXT: &[u8] = b"Hello, World! This is an encrypted attachment.";
+const KEY: [u8; 32] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+];
+const IV: [u8; 8] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+const CIPHERTEXT: &[u8] = &[
+    0xa5, 0x5b, 0xdd, 0x7e, 0x53, 0x90, 0x98, 0xfa, 0x6e, 0x16, 0x8a, 0xa7, 0xca, 0x2d, 0x91, 0x56,
+    0xa0, 0xe7, 0x0f, 0x59, 0x43, 0x47, 0xe5, 0xbe, 0xc9, 0x8f, 0xe9, 0x9c, 0xa3, 0xaa, 0x4a, 0xf6,
+    0x51, 0x9b, 0x2b, 0x72, 0x22, 0x77, 0x52, 0xce, 0xd0, 0x47, 0xbc, 0xe4, 0x7f, 0x12,
+];
+
+fn encrypted_file_json() -> JsonValue {
+    json!({
+        "url": "mxc://localhost/encrypted",
+        "key": {
+            "kty": "oct",
+            "key_ops": ["encrypt", "decrypt"],
+            "alg": "A256CTR",
+            "k": "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8",
+            "ext": true,
+        },
+        "iv": "ABEiM0RVZncAAAAAAAAAAA",
+        "hashes": {
+            "sha256": "gYeC06Cd58tOcyNBWxwYhvTUCByMs362x/bE/bB7Bak",
+        },
+        "v": "v2",
+    })
+}
+
+#[test]
+fn encrypt_known_answer() {
+    let mut encryptor = AttachmentEncryptor::with_key_and_iv(PLAINTEXT, KEY, IV);
+
+    // Read in small chunks to check that the stream cipher keeps its state between reads.
+    let mut ciphertext = Vec::new();
+    let mut buf = [0; 5];
+    loop {
+        let read = encryptor.read(&mut buf).unwrap();
+        if read == 0 {
+            break;
+        }
+        ciphertext.extend_from_slice(&buf[..read]);
+    }
+    assert_eq!(ciphertext, CIPHERTEXT);
+
+    let file = encryptor.finish(owned_mxc_uri!("mxc://localhost/encrypted"));
+    assert_eq!(to_json_value(&file).unwrap(), encrypted_file_json());
+}
+
+#[test]
+fn decrypt_known_answer() {
+    let file: EncryptedFile = from_json_value(encrypted_file_json()).unwrap();
+    assert_eq!(file.url, mxc_uri!("mxc://localhost/encrypted"));
+
+    let mut decryptor = AttachmentDecryptor::new(CIPHERTEXT, &file).unwrap();
+    let mut plaintext = Vec::new();
+    decryptor.read_to_end(&mut plaintext).unwrap();
+    assert_eq!(plaintext, PLAINTEXT);
+}
+
+#[test]
+fn decrypt_nist_vector() {
+    // AES-256-CTR vector from NIST SP 800-38A, F.5.5. The counter of the IV is not zero, which is
+    // still accepted when decrypting.
+    let ciphertext = [
+        0x60, 0x1e, 0xc3, 0x13, 0x77, 0x57, 0x89, 0xa5, 0xb7, 0xa7, 0xf5, 0x04, 0xbb, 0xf3, 0xd2,
+        0x28,
+    ];
+    let file: EncryptedFile = from_json_value(json!({
+        "url": "mxc://localhost/nist",
+        "key": {
+            "kty": "oct",
+            "key_ops": ["encrypt", "decrypt"],
+            "alg": "A256CTR",
+            "k": "YD3rEBXKcb4rc67whX13gR81LAc7YQjXLZgQowkU3_Q",
+            "ext": true,
+        },
+        "iv": "8PHy8/T19vf4+fr7/P3+/w",
+        "hashes": {
+            "sha256": "tkEAvV+cSsTWQNMQu3a21Y87/8yAiyTOaF9Bj4Es+VQ",
+        },
+        "v": "v2",
+    }))
+    .unwrap();
+
+    let mut decryptor = AttachmentDecryptor::new(ciphertext.as_slice(), &file).unwrap();
+    let mut plaintext = Vec::new();
+    decryptor.read_to_end(&mut plaintext).unwrap();
+    assert_eq!(
+        plaintext,
+        [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93,
+            0x17, 0x2a
+        ]
+    );
+}
+
+#[test]
+fn roundtrip_random_key() {
+    let data = vec![42; 10_000];
+
+    let mut encryptor = AttachmentEncryptor::new(data.as_slice());
+    let mut ciphertext = Vec::new();
+    encryptor.read_to_end(&mut ciphertext).unwrap();
+    assert_ne!(ciphertext, data);
+
+    let file = encryptor.finish(owned_mxc_uri!("mxc://localhost/random"));
+    assert_eq!(file.v, "v2");
+    // The counter half of the IV is zero.
+    assert_eq!(file.iv.as_bytes()[8..], [0; 8]);
+
+    let mut decryptor = AttachmentDecryptor::new(ciphertext.as_slice(), &file).unwrap();
+    let mut plaintext = Vec::new();
+    decryptor.read_to_end(&mut plaintext).unwrap();
+    assert_eq!(plaintext, data);
+}
+
+#[test]
+fn decrypt_errors() {
+    let file: EncryptedFile = from_json_value(encrypted_file_json()).unwrap();
+
+    // The ciphertext was tampered with.
+    let mut ciphertext = CIPHERTEXT.to_vec();
+    ciphertext[0] ^= 1;
+    let mut decryptor = AttachmentDecryptor::new(ciphertext.as_slice(), &file).unwrap();
+    let err = decryptor.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_matches!(
+        err.into_inner().unwrap().downcast::<AttachmentDecryptionError>().map(|err| *err),
+        Ok(AttachmentDecryptionError::HashMismatch)
+    );
+
+    let mut json = encrypted_file_json();
+    json["v"] = "v1".into();
+    let file: EncryptedFile = from_json_value(json).unwrap();
+    assert_matches!(
+        AttachmentDecryptor::new(CIPHERTEXT, &file),
+        Err(AttachmentDecryptionError::UnsupportedVersion(version))
+    );
+    assert_eq!(version, "v1");
+
+    let mut json = encrypted_file_json();
+    json["iv"] = "ABEiM0RVZnc".into();
+    let file: EncryptedFile = from_json_value(json).unwrap();
+    assert_matches!(
+        AttachmentDecryptor::new(CIPHERTEXT, &file),
+        Err(AttachmentDecryptionError::InvalidIvLength)
+    );
+
+    let mut json = encrypted_file_json();
+    json["hashes"] = json!({});
+    let file: EncryptedFile = from_json_value(json).unwrap();
+    assert_matches!(
+        AttachmentDecryptor::new(CIPHERTEXT, &file),
+        Err(AttachmentDecryptionError::MissingHash)
+    );
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "json-schema")]
+
+use ruma_common::json_schema::schema_for;
+use ruma_events::{
+    reaction::ReactionEventContent,
+    room::{
+        member::RoomMemberEventContent, message::RoomMessageEventContent,
+        topic::RoomTopicEventContent,
+    },
+    AnySyncTimelineEvent,
+};
+use serde_json::json;
+
+#[test]
+fn topic_content_schema() {
+    let schema = schema_for::<RoomTopicEventContent>();
+
+    assert_eq!(schema["title"], "RoomTopicEventContent");
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], json!(["topic"]));
+    assert_eq!(schema["properties"]["topic"]["type"], "string");
+}
+
+#[test]
+fn member_content_schema() {
+    let schema = schema_for::<RoomMemberEventContent>();
+
+    assert_eq!(schema["required"], json!(["membership"]));
+    assert_eq!(schema["properties"]["membership"]["allOf"][0]["$ref"], "#/$defs/MembershipState");
+    assert_eq!(
+        schema["properties"]["join_authorised_via_users_server"]["anyOf"],
+        json!([{ "$ref": "#/$defs/UserId" }, { "type": "null" }])
+    );
+
+    let membership = &schema["$defs"]["MembershipState"];
+    assert_eq!(membership["anyOf"][0]["enum"], json!(["ban", "invite", "join", "knock", "leave"]));
+}
+
+#[test]
+fn message_content_schema() {
+    let schema = schema_for::<RoomMessageEventContent>();
+
+    assert_eq!(schema["title"], "RoomMessageEventContent");
+    assert_eq!(schema["allOf"][1]["allOf"][0]["$ref"], "#/$defs/MessageType");
+    assert!(schema["allOf"][2]["properties"]["m.relates_to"]["anyOf"].is_array());
+
+    let text = &schema["$defs"]["TextMessageEventContent"];
+    assert_eq!(text["allOf"][0]["properties"]["msgtype"]["const"], "m.text");
+    assert_eq!(text["allOf"][0]["required"], json!(["msgtype", "body"]));
+}
+
+#[test]
+fn reaction_content_schema() {
+    let schema = schema_for::<ReactionEventContent>();
+
+    assert_eq!(schema["required"], json!(["m.relates_to"]));
+    assert_eq!(schema["properties"]["m.relates_to"]["allOf"][0]["$ref"], "#/$defs/Annotation");
+
+    let annotation = &schema["$defs"]["Annotation"];
+    assert_eq!(annotation["properties"]["rel_type"]["const"], "m.annotation");
+    assert_eq!(annotation["required"], json!(["rel_type", "event_id", "key"]));
+}
+
+#[test]
+fn sync_timeline_event_schema() {
+    let schema = schema_for::<AnySyncTimelineEvent>();
+    let defs = schema["$defs"].as_object().unwrap();
+
+    let message = &defs["OriginalSyncMessageLikeEvent_RoomMessageEventContent"];
+    assert_eq!(message["properties"]["type"]["const"], "m.room.message");
+    assert_eq!(
+        message["properties"]["content"]["allOf"][0]["$ref"],
+        "#/$defs/RoomMessageEventContent"
+    );
+    assert_eq!(
+        message["required"],
+        json!(["type", "content", "event_id", "sender", "origin_server_ts"])
+    );
+
+    assert_eq!(
+        defs["SyncMessageLikeEvent_RoomMessageEventContent"]["anyOf"],
+        json!([
+            { "$ref": "#/$defs/OriginalSyncMessageLikeEvent_RoomMessageEventContent" },
+            { "$ref": "#/$defs/RedactedSyncMessageLikeEvent_RedactedRoomMessageEventContent" },
+        ])
+    );
+
+    // Every type used by an event has a real schema.
+    assert!(!defs.values().any(|def| def.as_object().is_some_and(|def| def.is_empty())));
+}
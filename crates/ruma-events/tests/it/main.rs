@@ -1,3 +1,5 @@
+mod aggregation;
+mod attachment;
 mod audio;
 mod call;
 mod encrypted;
@@ -9,7 +11,9 @@ mod event_enums;
 mod file;
 mod image;
 mod initial_state;
+mod json_schema;
 mod location;
+mod member_summary;
 mod message;
 mod pdu;
 mod poll;
@@ -17,10 +21,14 @@ mod redacted;
 mod redaction;
 mod relations;
 mod room_message;
+mod room_state;
+mod sas;
+mod secret_storage;
 mod state_event;
 mod sticker;
 mod stripped;
 mod to_device;
+mod validation;
 mod video;
 mod voice;
 mod without_relation;
@@ -0,0 +1,152 @@
+use ruma_common::{mxc_uri, owned_user_id, user_id};
+use ruma_events::room::member::{
+    CollapsedChanges, MembershipChangeKind as Kind, MembershipSummary, SyncRoomMemberEvent,
+};
+use serde_json::{from_value as from_json_value, json, Value as JsonValue};
+
+fn member_event(
+    state_key: &str,
+    sender: &str,
+    content: JsonValue,
+    prev_content: Option<JsonValue>,
+) -> SyncRoomMemberEvent {
+    let mut event = json!({
+        "type": "m.room.member",
+        "state_key": state_key,
+        "sender": sender,
+        "event_id": "$event",
+        "origin_server_ts": 1,
+        "content": content,
+    });
+    if let Some(prev_content) = prev_content {
+        event["unsigned"] = json!({ "prev_content": prev_content });
+    }
+    from_json_value(event).unwrap()
+}
+
+fn membership(membership: &str) -> JsonValue {
+    json!({ "membership": membership })
+}
+
+#[test]
+fn collapse_per_user() {
+    let events = [
+        member_event("@alice:localhost", "@alice:localhost", membership("join"), None),
+        member_event("@bob:localhost", "@bob:localhost", membership("join"), None),
+        member_event(
+            "@alice:localhost",
+            "@alice:localhost",
+            membership("leave"),
+            Some(membership("join")),
+        ),
+        member_event("@carl:localhost", "@carl:localhost", membership("join"), None),
+        // Without `prev_content`, the previous event of the user in the run is used.
+        member_event("@alice:localhost", "@alice:localhost", membership("join"), None),
+        member_event("@alice:localhost", "@alice:localhost", membership("leave"), None),
+        member_event("@bob:localhost", "@bob:localhost", membership("leave"), None),
+        member_event("@dan:localhost", "@alice:localhost", membership("invite"), None),
+        member_event("@dan:localhost", "@dan:localhost", membership("join"), None),
+    ];
+
+    let summary = MembershipSummary::new(&events);
+    assert!(!summary.is_empty());
+    assert_eq!(summary.users().len(), 4);
+
+    let alice = summary.user(user_id!("@alice:localhost")).unwrap();
+    assert_eq!(alice.changes, [Kind::Joined, Kind::Left, Kind::Joined, Kind::Left]);
+    assert_eq!(
+        alice.collapsed_changes(),
+        CollapsedChanges { pattern: vec![Kind::Joined, Kind::Left], repeat: 2 }
+    );
+
+    let groups = summary.groups();
+    assert_eq!(groups.len(), 4);
+    assert_eq!(groups[0].changes.pattern, [Kind::Joined, Kind::Left]);
+    assert_eq!(groups[0].changes.repeat, 2);
+    assert_eq!(groups[0].users, [user_id!("@alice:localhost")]);
+    assert_eq!(groups[1].changes.pattern, [Kind::Joined, Kind::Left]);
+    assert_eq!(groups[1].changes.repeat, 1);
+    assert_eq!(groups[1].users, [user_id!("@bob:localhost")]);
+    assert_eq!(groups[2].changes.pattern, [Kind::Joined]);
+    assert_eq!(groups[2].users, [user_id!("@carl:localhost")]);
+    assert_eq!(groups[3].changes.pattern, [Kind::Invited, Kind::InvitationAccepted]);
+
+    let dan = summary.user(user_id!("@dan:localhost")).unwrap();
+    assert_eq!(dan.changes, [Kind::Invited, Kind::InvitationAccepted]);
+
+    let counts = summary.counts();
+    assert_eq!(counts[&Kind::Joined], 3);
+    assert_eq!(counts[&Kind::Left], 2);
+    assert_eq!(counts[&Kind::Invited], 1);
+    assert_eq!(counts.get(&Kind::Banned), None);
+}
+
+#[test]
+fn profile_changes() {
+    let events = [
+        member_event(
+            "@alice:localhost",
+            "@alice:localhost",
+            json!({ "membership": "join", "displayname": "Alice" }),
+            Some(json!({ "membership": "join", "displayname": "alice" })),
+        ),
+        member_event(
+            "@alice:localhost",
+            "@alice:localhost",
+            json!({
+                "membership": "join",
+                "displayname": "Alice 🦀",
+                "avatar_url": "mxc://localhost/alice",
+            }),
+            None,
+        ),
+        // Changing back to the original value is not a net change.
+        member_event(
+            "@bob:localhost",
+            "@bob:localhost",
+            json!({ "membership": "join", "displayname": "Bobby" }),
+            Some(json!({ "membership": "join", "displayname": "Bob" })),
+        ),
+        member_event(
+            "@bob:localhost",
+            "@bob:localhost",
+            json!({ "membership": "join", "displayname": "Bob" }),
+            Some(json!({ "membership": "join", "displayname": "Bobby" })),
+        ),
+        member_event("@carl:localhost", "@mod:localhost", membership("ban"), None),
+        // No change.
+        member_event(
+            "@dan:localhost",
+            "@dan:localhost",
+            membership("join"),
+            Some(membership("join")),
+        ),
+    ];
+
+    let summary = MembershipSummary::new(&events);
+    assert_eq!(summary.users().len(), 3);
+    assert!(summary.user(user_id!("@dan:localhost")).is_none());
+
+    let profile_only = summary.profile_only_changes().collect::<Vec<_>>();
+    assert_eq!(profile_only.len(), 2);
+
+    let alice = profile_only[0];
+    assert_eq!(alice.user_id, owned_user_id!("@alice:localhost"));
+    assert_eq!(alice.changes, [Kind::ProfileChanged]);
+    let displayname_change = alice.profile_change.displayname_change.as_ref().unwrap();
+    assert_eq!(displayname_change.old.as_deref(), Some("alice"));
+    assert_eq!(displayname_change.new.as_deref(), Some("Alice 🦀"));
+    let avatar_url_change = alice.profile_change.avatar_url_change.as_ref().unwrap();
+    assert_eq!(avatar_url_change.old, None);
+    assert_eq!(avatar_url_change.new.as_deref(), Some(mxc_uri!("mxc://localhost/alice")));
+
+    let bob = profile_only[1];
+    assert_eq!(bob.user_id, owned_user_id!("@bob:localhost"));
+    assert!(bob.profile_change.is_empty());
+
+    let groups = summary.groups();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].changes.pattern, [Kind::ProfileChanged]);
+    assert_eq!(groups[0].users, [user_id!("@alice:localhost"), user_id!("@bob:localhost")]);
+    assert_eq!(groups[1].changes.pattern, [Kind::Banned]);
+}
@@ -4,7 +4,7 @@ use std::{collections::BTreeMap, ops::Range};
 
 use assert_matches2::assert_matches;
 use js_int::{uint, UInt};
-use ruma_common::{owned_event_id, MilliSecondsSinceUnixEpoch};
+use ruma_common::{owned_event_id, user_id, MilliSecondsSinceUnixEpoch, RoomVersionId};
 use ruma_events::{
     message::TextContentBlock,
     poll::{
@@ -24,12 +24,15 @@ use ruma_events::{
             ReplacementUnstablePollStartEventContent, UnstablePollAnswer,
             UnstablePollStartContentBlock, UnstablePollStartEventContent,
         },
+        PollState,
     },
     relation::Reference,
     room::message::{Relation, RelationWithoutReplacement},
-    AnyMessageLikeEvent, MessageLikeEvent,
+    AnyMessageLikeEvent, AnySyncMessageLikeEvent, MessageLikeEvent,
+};
+use serde_json::{
+    from_value as from_json_value, json, to_value as to_json_value, Value as JsonValue,
 };
-use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
 
 #[test]
 fn poll_answers_deserialization_valid() {
@@ -1095,3 +1098,341 @@ fn compute_unstable_results() {
     let poll_end = poll.compile_results(responses.iter().map(|r| r.data()));
     assert_eq!(poll_end.text, "The poll has closed. Top answer: Pizza 🍕");
 }
+
+fn sync_event(json: JsonValue) -> AnySyncMessageLikeEvent {
+    from_json_value(json).unwrap()
+}
+
+fn poll_response_event(
+    event_id: &str,
+    sender: &str,
+    ts: u64,
+    answers: &[&str],
+    unstable: bool,
+) -> AnySyncMessageLikeEvent {
+    let content = if unstable {
+        json!({
+            "org.matrix.msc3381.poll.response": { "answers": answers },
+            "m.relates_to": { "rel_type": "m.reference", "event_id": "$poll" },
+        })
+    } else {
+        json!({
+            "m.selections": answers,
+            "m.relates_to": { "rel_type": "m.reference", "event_id": "$poll" },
+        })
+    };
+
+    sync_event(json!({
+        "type": if unstable { "org.matrix.msc3381.poll.response" } else { "m.poll.response" },
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": ts,
+        "content": content,
+    }))
+}
+
+#[test]
+fn poll_state() {
+    let mut state = PollState::new(RoomVersionId::V10, owned_event_id!("$poll"));
+
+    // Responses received before the poll start are kept.
+    assert!(state.apply_event(&poll_response_event(
+        "$r1",
+        "@alice:localhost",
+        20,
+        &["pizza"],
+        false
+    )));
+    assert!(!state.is_started());
+    assert!(state.results().is_empty());
+
+    assert!(state.apply_event(&sync_event(json!({
+        "type": "m.poll.start",
+        "event_id": "$poll",
+        "sender": "@alice:localhost",
+        "origin_server_ts": 10,
+        "content": {
+            "m.text": [{ "body": "What should we eat?" }],
+            "m.poll": {
+                "question": { "m.text": [{ "body": "What should we eat?" }] },
+                "max_selections": 2,
+                "answers": [
+                    { "m.id": "pizza", "m.text": [{ "body": "Pizza" }] },
+                    { "m.id": "poutine", "m.text": [{ "body": "Poutine" }] },
+                    { "m.id": "wings", "m.text": [{ "body": "Wings" }] },
+                ],
+            },
+        },
+    }))));
+    assert!(state.is_started());
+    assert_eq!(state.question(), Some("What should we eat?"));
+    assert_eq!(state.max_selections(), Some(uint!(2)));
+    assert_eq!(
+        state.answers().collect::<Vec<_>>(),
+        [("pizza", "Pizza"), ("poutine", "Poutine"), ("wings", "Wings")]
+    );
+
+    let changed = state.apply_events(&[
+        poll_response_event("$r2", "@bob:localhost", 30, &["poutine"], false),
+        // Bob changes their mind, only their latest response counts.
+        poll_response_event("$r3", "@bob:localhost", 40, &["wings"], false),
+        // Only the first `max_selections` answers count.
+        poll_response_event("$r4", "@carl:localhost", 50, &["wings", "pizza", "poutine"], false),
+        // Responses with unknown answers are spoiled.
+        poll_response_event("$r5", "@dan:localhost", 50, &["pasta"], false),
+        // Responses to other polls are ignored.
+        sync_event(json!({
+            "type": "m.poll.response",
+            "event_id": "$r6",
+            "sender": "@erin:localhost",
+            "origin_server_ts": 50,
+            "content": {
+                "m.selections": ["pizza"],
+                "m.relates_to": { "rel_type": "m.reference", "event_id": "$other_poll" },
+            },
+        })),
+    ]);
+    assert!(changed);
+
+    let results = state.results();
+    assert_eq!(results.get("wings").unwrap().len(), 2);
+    assert_eq!(results.get("pizza").unwrap().len(), 2);
+    assert_eq!(results.get("poutine").unwrap().len(), 0);
+    assert_eq!(results.keys().last(), Some(&"poutine"));
+    assert_eq!(state.voters_count(), 3);
+    assert_eq!(state.user_selections(user_id!("@bob:localhost")), ["wings"]);
+
+    // Redacting Bob's latest response restores the previous one.
+    assert!(state.apply_event(&sync_event(json!({
+        "type": "m.room.redaction",
+        "event_id": "$redaction",
+        "sender": "@bob:localhost",
+        "origin_server_ts": 60,
+        "redacts": "$r3",
+        "content": {},
+    }))));
+    assert_eq!(state.user_selections(user_id!("@bob:localhost")), ["poutine"]);
+
+    // Only the creator can end the poll.
+    assert!(state.apply_event(&sync_event(json!({
+        "type": "m.poll.end",
+        "event_id": "$end1",
+        "sender": "@bob:localhost",
+        "origin_server_ts": 70,
+        "content": {
+            "m.text": [{ "body": "The poll has closed." }],
+            "m.relates_to": { "rel_type": "m.reference", "event_id": "$poll" },
+        },
+    }))));
+    assert!(!state.is_ended());
+
+    assert!(state.apply_event(&sync_event(json!({
+        "type": "m.poll.end",
+        "event_id": "$end2",
+        "sender": "@alice:localhost",
+        "origin_server_ts": 80,
+        "content": {
+            "m.text": [{ "body": "The poll has closed." }],
+            "m.relates_to": { "rel_type": "m.reference", "event_id": "$poll" },
+        },
+    }))));
+    assert!(state.is_ended());
+    assert_eq!(state.end_timestamp(), Some(MilliSecondsSinceUnixEpoch(uint!(80))));
+
+    // Responses after the end are ignored.
+    state.apply_event(&poll_response_event("$r7", "@bob:localhost", 90, &["pizza"], false));
+    assert_eq!(state.user_selections(user_id!("@bob:localhost")), ["poutine"]);
+}
+
+#[test]
+fn unstable_poll_state() {
+    let mut state = PollState::new(RoomVersionId::V10, owned_event_id!("$poll"));
+
+    state.apply_events(&[
+        sync_event(json!({
+            "type": "org.matrix.msc3381.poll.start",
+            "event_id": "$poll",
+            "sender": "@alice:localhost",
+            "origin_server_ts": 10,
+            "content": {
+                "org.matrix.msc1767.text": "What should we eat?",
+                "org.matrix.msc3381.poll.start": {
+                    "question": { "org.matrix.msc1767.text": "What should we eat?" },
+                    "kind": "org.matrix.msc3381.poll.disclosed",
+                    "answers": [
+                        { "id": "pizza", "org.matrix.msc1767.text": "Pizza" },
+                        { "id": "wings", "org.matrix.msc1767.text": "Wings" },
+                    ],
+                },
+            },
+        })),
+        poll_response_event("$r1", "@alice:localhost", 20, &["pizza"], true),
+        poll_response_event("$r2", "@bob:localhost", 30, &["wings", "pizza"], true),
+    ]);
+
+    assert_eq!(state.kind(), Some(&PollKind::Disclosed));
+    assert_eq!(state.max_selections(), Some(uint!(1)));
+    let results = state.results();
+    assert_eq!(results.get("pizza").unwrap().len(), 1);
+    assert_eq!(results.get("wings").unwrap().len(), 1);
+
+    // Redacting the poll start clears the results.
+    state.apply_event(&sync_event(json!({
+        "type": "m.room.redaction",
+        "event_id": "$redaction",
+        "sender": "@alice:localhost",
+        "origin_server_ts": 40,
+        "redacts": "$poll",
+        "content": {},
+    })));
+    assert!(state.is_redacted());
+    assert!(state.results().is_empty());
+}
+
+fn redacted_poll_start_event(unstable: bool) -> AnySyncMessageLikeEvent {
+    sync_event(json!({
+        "type": if unstable { "org.matrix.msc3381.poll.start" } else { "m.poll.start" },
+        "event_id": "$poll",
+        "sender": "@alice:localhost",
+        "origin_server_ts": 10,
+        "content": {},
+        "unsigned": {
+            "redacted_because": {
+                "type": "m.room.redaction",
+                "event_id": "$redaction",
+                "sender": "@alice:localhost",
+                "origin_server_ts": 40,
+                "redacts": "$poll",
+                "content": {},
+            },
+        },
+    }))
+}
+
+#[test]
+fn poll_state_redacted_start_after_original() {
+    let mut state = PollState::new(RoomVersionId::V10, owned_event_id!("$poll"));
+
+    state.apply_events(&[
+        sync_event(json!({
+            "type": "m.poll.start",
+            "event_id": "$poll",
+            "sender": "@alice:localhost",
+            "origin_server_ts": 10,
+            "content": {
+                "m.text": [{ "body": "What should we eat?" }],
+                "m.poll": {
+                    "question": { "m.text": [{ "body": "What should we eat?" }] },
+                    "answers": [
+                        { "m.id": "pizza", "m.text": [{ "body": "Pizza" }] },
+                        { "m.id": "wings", "m.text": [{ "body": "Wings" }] },
+                    ],
+                },
+            },
+        })),
+        poll_response_event("$r1", "@bob:localhost", 20, &["pizza"], false),
+    ]);
+    assert_eq!(state.results().get("pizza").unwrap().len(), 1);
+
+    // The redacted form of the poll start clears the results.
+    assert!(state.apply_event(&redacted_poll_start_event(false)));
+    assert!(state.is_redacted());
+    assert!(!state.is_started());
+    assert!(state.results().is_empty());
+}
+
+#[test]
+fn poll_state_original_start_after_redacted() {
+    let mut state = PollState::new(RoomVersionId::V10, owned_event_id!("$poll"));
+
+    assert!(state.apply_event(&redacted_poll_start_event(true)));
+    assert!(state.is_redacted());
+
+    // The original form of the poll start is ignored once the poll was redacted.
+    assert!(!state.apply_events(&[sync_event(json!({
+        "type": "org.matrix.msc3381.poll.start",
+        "event_id": "$poll",
+        "sender": "@alice:localhost",
+        "origin_server_ts": 10,
+        "content": {
+            "org.matrix.msc1767.text": "What should we eat?",
+            "org.matrix.msc3381.poll.start": {
+                "question": { "org.matrix.msc1767.text": "What should we eat?" },
+                "answers": [
+                    { "id": "pizza", "org.matrix.msc1767.text": "Pizza" },
+                    { "id": "wings", "org.matrix.msc1767.text": "Wings" },
+                ],
+            },
+        },
+    })),]));
+    state.apply_event(&poll_response_event("$r1", "@bob:localhost", 20, &["pizza"], true));
+    assert!(!state.is_started());
+    assert!(state.results().is_empty());
+}
+
+fn redacted_event(event_type: &str, event_id: &str, sender: &str) -> AnySyncMessageLikeEvent {
+    sync_event(json!({
+        "type": event_type,
+        "event_id": event_id,
+        "sender": sender,
+        "origin_server_ts": 20,
+        "content": {},
+        "unsigned": {
+            "redacted_because": {
+                "type": "m.room.redaction",
+                "event_id": "$redaction",
+                "sender": sender,
+                "origin_server_ts": 40,
+                "redacts": event_id,
+                "content": {},
+            },
+        },
+    }))
+}
+
+#[test]
+fn poll_state_original_response_and_end_after_redacted() {
+    let mut state = PollState::new(RoomVersionId::V10, owned_event_id!("$poll"));
+
+    state.apply_event(&sync_event(json!({
+        "type": "m.poll.start",
+        "event_id": "$poll",
+        "sender": "@alice:localhost",
+        "origin_server_ts": 10,
+        "content": {
+            "m.text": [{ "body": "What should we eat?" }],
+            "m.poll": {
+                "question": { "m.text": [{ "body": "What should we eat?" }] },
+                "answers": [
+                    { "m.id": "pizza", "m.text": [{ "body": "Pizza" }] },
+                    { "m.id": "wings", "m.text": [{ "body": "Wings" }] },
+                ],
+            },
+        },
+    })));
+
+    // The redacted forms of the response and end events are received first, for example when
+    // paginating backwards.
+    state.apply_events(&[
+        redacted_event("m.poll.response", "$r1", "@bob:localhost"),
+        redacted_event("org.matrix.msc3381.poll.end", "$end", "@alice:localhost"),
+    ]);
+
+    // The original forms of the events are ignored.
+    assert!(!state.apply_events(&[
+        poll_response_event("$r1", "@bob:localhost", 20, &["pizza"], false),
+        sync_event(json!({
+            "type": "org.matrix.msc3381.poll.end",
+            "event_id": "$end",
+            "sender": "@alice:localhost",
+            "origin_server_ts": 30,
+            "content": {
+                "org.matrix.msc1767.text": "The poll has closed.",
+                "m.relates_to": { "rel_type": "m.reference", "event_id": "$poll" },
+            },
+        })),
+    ]));
+    assert!(!state.is_ended());
+    assert_eq!(state.voters_count(), 0);
+    assert!(state.results().get("pizza").unwrap().is_empty());
+}
@@ -235,6 +235,74 @@ fn markdown_options() {
     assert_eq!(formatted_body.unwrap().body, "<p>A message with a <del>strike</del></p>\n");
 }
 
+#[test]
+#[cfg(all(feature = "markdown", feature = "html"))]
+fn markdown_renderer() {
+    use ruma_events::room::message::MarkdownRenderer;
+
+    let renderer =
+        MarkdownRenderer::new().emote("party", mxc_uri!("mxc://localhost/party").to_owned());
+
+    // No formatting.
+    let rendered = renderer.render("A simple message with a || and a :unknown: emote.");
+    assert_matches!(rendered.formatted, None);
+    assert!(rendered.mentions.user_ids.is_empty());
+    assert!(!rendered.mentions.room);
+
+    // Spoilers.
+    let rendered = renderer.render("This is ||**very** secret||, `||not this||`.");
+    assert_eq!(
+        rendered.formatted.unwrap().body,
+        "<p>This is <span data-mx-spoiler=\"\"><strong>very</strong> secret</span>, \
+        <code>||not this||</code>.</p>\n"
+    );
+
+    // Unclosed spoiler in a block.
+    let rendered = renderer.render("*Unclosed || spoiler*\n\nin paragraph||");
+    assert_eq!(
+        rendered.formatted.unwrap().body,
+        "<p><em>Unclosed || spoiler</em></p>\n<p>in paragraph||</p>\n"
+    );
+
+    // Pills.
+    let rendered = renderer.render(
+        "Hi @alice:localhost, welcome to #room:localhost! Tell [Bob](https://matrix.to/#/@bob:localhost) and @room.",
+    );
+    assert_eq!(
+        rendered.formatted.unwrap().body,
+        "<p>Hi <a href=\"https://matrix.to/#/@alice:localhost\">@alice:localhost</a>, \
+        welcome to <a href=\"https://matrix.to/#/%23room:localhost\">#room:localhost</a>! \
+        Tell <a href=\"https://matrix.to/#/@bob:localhost\">Bob</a> and @room.</p>\n"
+    );
+    assert_eq!(
+        rendered.mentions.user_ids,
+        [owned_user_id!("@alice:localhost"), owned_user_id!("@bob:localhost")].into()
+    );
+    assert!(rendered.mentions.room);
+
+    // Emotes.
+    let rendered = renderer.render("Let's :party:!");
+    assert_eq!(
+        rendered.formatted.unwrap().body,
+        "<p>Let's <img alt=\":party:\" height=\"32\" src=\"mxc://localhost/party\" \
+        title=\":party:\">!</p>\n"
+    );
+
+    // Disabled extensions.
+    let renderer = MarkdownRenderer::new().spoilers(false).pills(false);
+    let rendered = renderer.render("Hi @alice:localhost, ||this|| is *visible*.");
+    assert_eq!(
+        rendered.formatted.unwrap().body,
+        "<p>Hi @alice:localhost, ||this|| is <em>visible</em>.</p>\n"
+    );
+    assert!(rendered.mentions.user_ids.is_empty());
+
+    // Unsafe HTML is sanitized and reply fallbacks are removed.
+    let rendered =
+        renderer.render("<mx-reply>Fake reply</mx-reply>Hello <script>alert(1)</script>");
+    assert_eq!(rendered.formatted.unwrap().body, "<p>Hello alert(1)</p>\n");
+}
+
 #[test]
 fn verification_request_msgtype_deserialization() {
     let user_id = user_id!("@example2:localhost");
@@ -521,6 +589,66 @@ fn reply_add_mentions() {
     assert!(mentions.room);
 }
 
+#[test]
+fn reply_without_fallback() {
+    let first_message = OriginalRoomMessageEvent {
+        content: RoomMessageEventContent::text_html("Hi **there**", "Hi <strong>there</strong>"),
+        event_id: owned_event_id!("$143273582443PhrSn"),
+        origin_server_ts: MilliSecondsSinceUnixEpoch(uint!(10_000)),
+        room_id: owned_room_id!("!testroomid:example.org"),
+        sender: owned_user_id!("@user:example.org"),
+        unsigned: MessageLikeUnsigned::default(),
+    };
+    let reply = RoomMessageEventContent::text_html("Hello _you_", "Hello <em>you</em>")
+        .make_reply_to_without_fallback(&first_message, ForwardThread::Yes, AddMentions::Yes);
+
+    assert_matches!(
+        reply.msgtype,
+        MessageType::Text(TextMessageEventContent { body, formatted, .. })
+    );
+    assert_eq!(body, "Hello _you_");
+    assert_eq!(formatted.unwrap().body, "Hello <em>you</em>");
+
+    assert_matches!(reply.relates_to, Some(Relation::Reply { in_reply_to }));
+    assert_eq!(in_reply_to.event_id, "$143273582443PhrSn");
+    assert_eq!(reply.mentions.unwrap().user_ids, [owned_user_id!("@user:example.org")].into());
+}
+
+#[test]
+fn reply_fallback_roundtrip() {
+    let first_message = OriginalRoomMessageEvent {
+        content: RoomMessageEventContent::text_html("Hi **there**", "Hi <strong>there</strong>"),
+        event_id: owned_event_id!("$143273582443PhrSn"),
+        origin_server_ts: MilliSecondsSinceUnixEpoch(uint!(10_000)),
+        room_id: owned_room_id!("!testroomid:example.org"),
+        sender: owned_user_id!("@user:example.org"),
+        unsigned: MessageLikeUnsigned::default(),
+    };
+    let reply = RoomMessageEventContent::text_html("Hello _you_", "Hello <em>you</em>")
+        .make_reply_to(&first_message, ForwardThread::Yes, AddMentions::No);
+
+    let fallback = reply.msgtype.reply_fallback().unwrap();
+    assert_eq!(fallback.sender, "@user:example.org");
+    assert!(!fallback.is_emote);
+    assert_eq!(fallback.body, "Hi **there**");
+
+    #[cfg(feature = "html")]
+    {
+        assert_eq!(fallback.room_id.unwrap(), "!testroomid:example.org");
+        assert_eq!(fallback.event_id.unwrap(), "$143273582443PhrSn");
+        assert_eq!(fallback.formatted_body.unwrap(), "Hi <strong>there</strong>");
+
+        let mut msgtype = reply.msgtype;
+        msgtype.remove_reply_fallback();
+        assert_matches!(
+            msgtype,
+            MessageType::Text(TextMessageEventContent { body, formatted, .. })
+        );
+        assert_eq!(body, "Hello _you_");
+        assert_eq!(formatted.unwrap().body, "Hello <em>you</em>");
+    }
+}
+
 #[test]
 fn reply_to_raw() {
     let room_id = room_id!("!roomid:notareal.hs");
@@ -1287,3 +1415,53 @@ fn invalid_replacement() {
     assert_matches!(&data, Cow::Borrowed(_)); // data is stored in JSON form because it's invalid
     assert_eq!(JsonValue::Object(data.into_owned()), relation);
 }
+
+#[test]
+#[cfg(feature = "html-matrix")]
+fn formatted_body_mention_pills() {
+    use ruma_events::room::message::FormattedBody;
+
+    let mut formatted = FormattedBody::html(
+        "\
+        <mx-reply><blockquote>\
+            <a href=\"https://matrix.to/#/@bob:example.org\">Bob</a> said hi\
+        </blockquote></mx-reply>\
+        Hi <a href=\"https://matrix.to/#/@alice:example.org\">Alice</a> and \
+        <a href=\"matrix:u/carl:example.org\">Carl</a>, welcome to \
+        <a href=\"https://matrix.to/#/#room:example.org\">#room</a>!\
+        ",
+    );
+
+    let pills = formatted.mention_pills();
+    assert_eq!(pills.len(), 3);
+    assert_eq!(pills[0].text, "Alice");
+    assert_eq!(pills[0].position, 3..8);
+    assert_eq!(pills[2].text, "#room");
+
+    let mentions = formatted.mentions();
+    assert_eq!(
+        mentions.user_ids.into_iter().collect::<Vec<_>>(),
+        [owned_user_id!("@alice:example.org"), owned_user_id!("@carl:example.org")]
+    );
+    assert!(!mentions.room);
+
+    let count = formatted.rewrite_mention_pills(|pill| {
+        (pill.user_id()? == user_id!("@carl:example.org")).then(|| "Carlos".to_owned())
+    });
+    assert_eq!(count, 1);
+    assert_eq!(
+        formatted.body,
+        "\
+        <mx-reply><blockquote>\
+            <a href=\"https://matrix.to/#/@bob:example.org\">Bob</a> said hi\
+        </blockquote></mx-reply>\
+        Hi <a href=\"https://matrix.to/#/@alice:example.org\">Alice</a> and \
+        <a href=\"matrix:u/carl:example.org\">Carlos</a>, welcome to \
+        <a href=\"https://matrix.to/#/#room:example.org\">#room</a>!\
+        "
+    );
+
+    let mut plain = FormattedBody { format: "text/plain".into(), body: "Hi Alice".to_owned() };
+    assert!(plain.mention_pills().is_empty());
+    assert_eq!(plain.rewrite_mention_pills(|_| Some("Bob".to_owned())), 0);
+}
@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+
+use assign::assign;
+use js_int::{int, uint};
+use ruma_common::{owned_room_alias_id, owned_user_id, user_id, EventEncryptionAlgorithm, UserId};
+use ruma_events::{
+    room::{
+        canonical_alias::RoomCanonicalAliasEventContent, join_rules::JoinRule,
+        member::MembershipState, member_display_names, name::RoomNameEventContent,
+        room_display_name, DisplayNameLocalizer, MemberDisplayName, RoomDisplayName, RoomMember,
+        RoomState, RoomSummary,
+    },
+    AnyStrippedStateEvent, AnySyncStateEvent, StateEventType,
+};
+use serde_json::{from_value as from_json_value, json, Value as JsonValue};
+
+fn state_event(event_type: &str, state_key: &str, sender: &str, content: JsonValue) -> JsonValue {
+    json!({
+        "type": event_type,
+        "state_key": state_key,
+        "sender": sender,
+        "event_id": format!("${event_type}{state_key}"),
+        "origin_server_ts": 1,
+        "content": content,
+    })
+}
+
+fn sync_state_event(
+    event_type: &str,
+    state_key: &str,
+    sender: &str,
+    content: JsonValue,
+) -> AnySyncStateEvent {
+    from_json_value(state_event(event_type, state_key, sender, content)).unwrap()
+}
+
+fn member_event(user_id: &str, membership: &str, displayname: Option<&str>) -> AnySyncStateEvent {
+    sync_state_event(
+        "m.room.member",
+        user_id,
+        user_id,
+        json!({ "membership": membership, "displayname": displayname }),
+    )
+}
+
+#[test]
+fn room_state_info() {
+    let mut state = RoomState::new();
+
+    state.apply_events(&[
+        sync_state_event("m.room.create", "", "@alice:localhost", json!({ "room_version": "11" })),
+        sync_state_event(
+            "m.room.encryption",
+            "",
+            "@alice:localhost",
+            json!({ "algorithm": "m.megolm.v1.aes-sha2" }),
+        ),
+        sync_state_event(
+            "m.room.join_rules",
+            "",
+            "@alice:localhost",
+            json!({ "join_rule": "public" }),
+        ),
+        sync_state_event(
+            "m.room.avatar",
+            "",
+            "@alice:localhost",
+            json!({ "url": "mxc://localhost/avatar" }),
+        ),
+        sync_state_event("m.room.topic", "", "@alice:localhost", json!({ "topic": "Chatting" })),
+        member_event("@alice:localhost", "join", Some("Alice")),
+        member_event("@bob:localhost", "invite", None),
+    ]);
+
+    assert_eq!(state.creator(), Some(user_id!("@alice:localhost")));
+    assert!(state.is_encrypted());
+    assert_eq!(state.encryption_algorithm(), Some(&EventEncryptionAlgorithm::MegolmV1AesSha2));
+    assert_eq!(*state.join_rule(), JoinRule::Public);
+    assert_eq!(state.avatar_url().unwrap(), "mxc://localhost/avatar");
+    assert_eq!(state.topic(), Some("Chatting"));
+
+    // Without a power levels event, the creator has the highest power level.
+    let power_levels = state.power_levels().unwrap();
+    assert_eq!(power_levels.for_user(user_id!("@alice:localhost")), int!(100));
+    assert_eq!(power_levels.for_user(user_id!("@bob:localhost")), int!(0));
+    assert_eq!(power_levels.state_default, int!(0));
+    assert!(power_levels.user_can_send_state(user_id!("@bob:localhost"), StateEventType::RoomTopic));
+
+    state.apply_event(&sync_state_event(
+        "m.room.power_levels",
+        "",
+        "@alice:localhost",
+        json!({ "users": { "@bob:localhost": 50 } }),
+    ));
+    let power_levels = state.power_levels().unwrap();
+    assert_eq!(power_levels.for_user(user_id!("@alice:localhost")), int!(0));
+    assert_eq!(power_levels.for_user(user_id!("@bob:localhost")), int!(50));
+
+    assert_eq!(state.joined_member_count(), 1);
+    assert_eq!(state.invited_member_count(), 1);
+    assert_eq!(
+        state
+            .members_with_membership(&MembershipState::Invite)
+            .map(|(user_id, _)| user_id)
+            .collect::<Vec<_>>(),
+        [user_id!("@bob:localhost")]
+    );
+    assert_eq!(
+        state.member(user_id!("@alice:localhost")).unwrap().display_name.as_deref(),
+        Some("Alice")
+    );
+
+    state.apply_event(&member_event("@bob:localhost", "join", Some("Bob")));
+    assert_eq!(state.joined_member_count(), 2);
+    assert_eq!(state.invited_member_count(), 0);
+}
+
+#[test]
+fn room_state_display_name() {
+    let own_user_id = user_id!("@me:localhost");
+    let mut state = RoomState::new();
+    state.apply_event(&member_event("@me:localhost", "join", None));
+
+    assert_eq!(state.display_name(own_user_id), RoomDisplayName::Empty);
+    assert_eq!(state.display_name(own_user_id).to_string(), "Empty Room");
+
+    state.apply_events(&[
+        member_event("@alice:localhost", "join", Some("Alice")),
+        member_event("@bob:localhost", "invite", None),
+    ]);
+    assert_eq!(state.display_name(own_user_id).to_string(), "Alice and @bob:localhost");
+
+    // Heroes from the room summary take precedence.
+    state.update_summary(assign!(RoomSummary::new(), {
+        heroes: Some(vec![owned_user_id!("@alice:localhost")]),
+        joined_member_count: Some(uint!(4)),
+        invited_member_count: Some(uint!(1)),
+    }));
+    assert_eq!(
+        state.display_name(own_user_id),
+        RoomDisplayName::Calculated {
+            heroes: vec![MemberDisplayName::Name("Alice".to_owned())],
+            others: 3
+        }
+    );
+    assert_eq!(state.display_name(own_user_id).to_string(), "Alice and 3 others");
+
+    // The room is empty but had members.
+    state.update_summary(assign!(RoomSummary::new(), {
+        joined_member_count: Some(uint!(1)),
+        invited_member_count: Some(uint!(0)),
+    }));
+    assert_eq!(state.display_name(own_user_id).to_string(), "Empty Room (was Alice)");
+
+    state.apply_event(&sync_state_event(
+        "m.room.canonical_alias",
+        "",
+        "@alice:localhost",
+        json!({ "alias": "#room:localhost" }),
+    ));
+    assert_eq!(state.display_name(own_user_id).to_string(), "#room:localhost");
+
+    state.apply_event(&sync_state_event(
+        "m.room.name",
+        "",
+        "@alice:localhost",
+        json!({ "name": "The Room" }),
+    ));
+    assert_eq!(state.display_name(own_user_id), RoomDisplayName::Named("The Room".to_owned()));
+
+    // An empty name is ignored.
+    state.apply_event(&sync_state_event(
+        "m.room.name",
+        "",
+        "@alice:localhost",
+        json!({ "name": "" }),
+    ));
+    assert_eq!(state.display_name(own_user_id).to_string(), "#room:localhost");
+}
+
+#[test]
+fn room_state_stripped() {
+    let events = [
+        json!({
+            "type": "m.room.name",
+            "state_key": "",
+            "sender": "@alice:localhost",
+            "content": { "name": "Invited Room" },
+        }),
+        json!({
+            "type": "m.room.join_rules",
+            "state_key": "",
+            "sender": "@alice:localhost",
+            "content": { "join_rule": "knock" },
+        }),
+        json!({
+            "type": "m.room.member",
+            "state_key": "@me:localhost",
+            "sender": "@alice:localhost",
+            "content": { "membership": "invite", "is_direct": true },
+        }),
+    ]
+    .into_iter()
+    .map(|json| from_json_value::<AnyStrippedStateEvent>(json).unwrap())
+    .collect::<Vec<_>>();
+
+    let mut state = RoomState::new();
+    state.apply_stripped_events(&events);
+
+    assert_eq!(state.name(), Some("Invited Room"));
+    assert_eq!(*state.join_rule(), JoinRule::Knock);
+    let member = state.member(user_id!("@me:localhost")).unwrap();
+    assert_eq!(member.membership, MembershipState::Invite);
+    assert!(member.is_direct);
+    assert!(!state.is_encrypted());
+    assert!(state.power_levels().is_none());
+}
+
+fn room_member(membership: MembershipState, display_name: Option<&str>) -> RoomMember {
+    assign!(RoomMember::new(membership), { display_name: display_name.map(ToOwned::to_owned) })
+}
+
+#[test]
+fn member_display_names_disambiguation() {
+    let members = BTreeMap::from([
+        (owned_user_id!("@alice:localhost"), room_member(MembershipState::Join, Some("Alice"))),
+        (owned_user_id!("@alice:example.org"), room_member(MembershipState::Invite, Some("Alice"))),
+        (owned_user_id!("@bob:localhost"), room_member(MembershipState::Join, Some("Bob"))),
+        // Members that left don't make the name ambiguous, but are disambiguated themselves.
+        (owned_user_id!("@bob:example.org"), room_member(MembershipState::Leave, Some("Bob"))),
+        (owned_user_id!("@carl:localhost"), room_member(MembershipState::Join, Some(" "))),
+    ]);
+
+    let names = member_display_names(&members);
+    assert_eq!(
+        names[user_id!("@alice:localhost")],
+        MemberDisplayName::Disambiguated {
+            name: "Alice".to_owned(),
+            user_id: owned_user_id!("@alice:localhost")
+        }
+    );
+    assert_eq!(names[user_id!("@alice:example.org")].to_string(), "Alice (@alice:example.org)");
+    assert_eq!(names[user_id!("@bob:localhost")], MemberDisplayName::Name("Bob".to_owned()));
+    assert_eq!(names[user_id!("@bob:example.org")].to_string(), "Bob (@bob:example.org)");
+    assert_eq!(names[user_id!("@carl:localhost")].to_string(), "@carl:localhost");
+}
+
+struct FrenchLocalizer;
+
+impl DisplayNameLocalizer for FrenchLocalizer {
+    fn room_name_from_heroes(&self, heroes: &[String], others: u64) -> String {
+        match others {
+            0 => heroes.join(", "),
+            1 => format!("{} et 1 autre", heroes.join(", ")),
+            _ => format!("{} et {others} autres", heroes.join(", ")),
+        }
+    }
+
+    fn empty_room(&self) -> String {
+        "Salon vide".to_owned()
+    }
+
+    fn disambiguated_member_name(&self, name: &str, user_id: &UserId) -> String {
+        format!("{name} [{user_id}]")
+    }
+}
+
+#[test]
+fn room_display_name_localized() {
+    let own_user_id = user_id!("@me:localhost");
+    let members = BTreeMap::from([
+        (owned_user_id!("@me:localhost"), room_member(MembershipState::Join, Some("Alice"))),
+        (owned_user_id!("@alice:localhost"), room_member(MembershipState::Join, Some("Alice"))),
+        (owned_user_id!("@bob:localhost"), room_member(MembershipState::Join, None)),
+    ]);
+    let summary = assign!(RoomSummary::new(), { joined_member_count: Some(uint!(5)) });
+
+    let name = room_display_name(own_user_id, None, None, &summary, &members);
+    assert_eq!(name.to_string(), "Alice (@alice:localhost), @bob:localhost and 2 others");
+    assert_eq!(
+        name.localize(&FrenchLocalizer),
+        "Alice [@alice:localhost], @bob:localhost et 2 autres"
+    );
+
+    let name = room_display_name(own_user_id, None, None, &RoomSummary::new(), &BTreeMap::new());
+    assert_eq!(name.localize(&FrenchLocalizer), "Salon vide");
+    // Methods that are not overridden use the English representation.
+    let name =
+        RoomDisplayName::EmptyWas { heroes: vec![MemberDisplayName::Name("Bob".to_owned())] };
+    assert_eq!(name.localize(&FrenchLocalizer), "Empty Room (was Bob)");
+
+    let alias_content = assign!(RoomCanonicalAliasEventContent::new(), {
+        alias: Some(owned_room_alias_id!("#room:localhost")),
+    });
+    let name = room_display_name(own_user_id, None, Some(&alias_content), &summary, &members);
+    assert_eq!(name, RoomDisplayName::Aliased(owned_room_alias_id!("#room:localhost")));
+
+    let name_content = RoomNameEventContent::new("Name".to_owned());
+    let name = room_display_name(
+        own_user_id,
+        Some(&name_content),
+        Some(&alias_content),
+        &summary,
+        &members,
+    );
+    assert_eq!(name.localize(&FrenchLocalizer), "Name");
+}
@@ -0,0 +1,605 @@
+#![cfg(feature = "canonical-json")]
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use assert_matches2::assert_let;
+use ruma_common::{
+    event_id, owned_device_id, owned_event_id, owned_user_id,
+    serde::{Base64, Raw},
+    to_device::DeviceIdOrAllDevices,
+    MilliSecondsSinceUnixEpoch,
+};
+use ruma_events::{
+    key::verification::{
+        accept::AcceptMethod,
+        cancel::CancelCode,
+        sas::{
+            FlowId, OutgoingContent, SasConfig, SasCrypto, SasRequestError, SasState,
+            SasVerification,
+        },
+    },
+    room::message::{MessageType, OriginalSyncRoomMessageEvent},
+    AnyMessageLikeEventContent, AnySyncMessageLikeEvent, AnyToDeviceEvent, AnyToDeviceEventContent,
+    EventContent, ToDeviceEvent,
+};
+use serde_json::{from_value as from_json_value, json};
+
+#[derive(Debug)]
+struct InvalidKey;
+
+impl fmt::Display for InvalidKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid key")
+    }
+}
+
+impl std::error::Error for InvalidKey {}
+
+/// A fake implementation of the cryptographic primitives, where the shared secret is the
+/// concatenation of the sorted public keys.
+#[derive(Debug)]
+struct MockCrypto {
+    public_key: Vec<u8>,
+    shared_secret: Vec<u8>,
+}
+
+impl MockCrypto {
+    fn new(public_key: &[u8]) -> Self {
+        Self { public_key: public_key.to_vec(), shared_secret: Vec::new() }
+    }
+
+    fn hash(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = DefaultHasher::new();
+        self.shared_secret.hash(&mut hasher);
+        parts.hash(&mut hasher);
+        hasher.finish().to_be_bytes().to_vec()
+    }
+}
+
+impl SasCrypto for MockCrypto {
+    type Error = InvalidKey;
+
+    fn public_key(&self) -> Base64 {
+        Base64::new(self.public_key.clone())
+    }
+
+    fn sha256(&self, input: &[u8]) -> Vec<u8> {
+        let mut hasher = DefaultHasher::new();
+        input.hash(&mut hasher);
+        hasher.finish().to_be_bytes().to_vec()
+    }
+
+    fn diffie_hellman(&mut self, their_public_key: &Base64) -> Result<(), Self::Error> {
+        let mut keys = [self.public_key.as_slice(), their_public_key.as_bytes()];
+        if keys[1].len() != 4 {
+            return Err(InvalidKey);
+        }
+        keys.sort();
+        self.shared_secret = keys.concat();
+        Ok(())
+    }
+
+    fn generate_bytes(&self, info: &str, count: usize) -> Vec<u8> {
+        let mut bytes = self.hash(&[info.as_bytes()]);
+        bytes.truncate(count);
+        bytes
+    }
+
+    fn calculate_mac(&self, input: &str, info: &str) -> Base64 {
+        Base64::new(self.hash(&[input.as_bytes(), info.as_bytes()]))
+    }
+}
+
+fn ts(secs: u64) -> MilliSecondsSinceUnixEpoch {
+    MilliSecondsSinceUnixEpoch((1_700_000_000_000 + secs * 1000).try_into().unwrap())
+}
+
+fn alice_config() -> SasConfig {
+    let mut config = SasConfig::new(
+        owned_user_id!("@alice:localhost"),
+        owned_device_id!("ALICEDEVICE"),
+        owned_user_id!("@bob:localhost"),
+    );
+    config.own_keys = BTreeMap::from([("ed25519:ALICEDEVICE".to_owned(), "alicekey".to_owned())]);
+    config.their_keys = BTreeMap::from([("ed25519:BOBDEVICE".to_owned(), "bobkey".to_owned())]);
+    config
+}
+
+fn bob_config() -> SasConfig {
+    let mut config = SasConfig::new(
+        owned_user_id!("@bob:localhost"),
+        owned_device_id!("BOBDEVICE"),
+        owned_user_id!("@alice:localhost"),
+    );
+    config.own_keys = BTreeMap::from([
+        ("ed25519:BOBDEVICE".to_owned(), "bobkey".to_owned()),
+        ("ed25519:bobmasterkey".to_owned(), "bobmasterkey".to_owned()),
+    ]);
+    config.their_keys = BTreeMap::from([("ed25519:ALICEDEVICE".to_owned(), "alicekey".to_owned())]);
+    config
+}
+
+fn to_device_event(sender: &str, content: OutgoingContent) -> Raw<AnyToDeviceEvent> {
+    assert_let!(OutgoingContent::ToDevice { content, .. } = content);
+    from_json_value(json!({
+        "type": content.event_type().to_string(),
+        "sender": sender,
+        "content": content,
+    }))
+    .unwrap()
+}
+
+fn room_event(sender: &str, content: OutgoingContent) -> Raw<AnySyncMessageLikeEvent> {
+    assert_let!(OutgoingContent::InRoom(content) = content);
+    from_json_value(json!({
+        "type": content.event_type().to_string(),
+        "sender": sender,
+        "event_id": "$event",
+        "origin_server_ts": 1,
+        "content": content,
+    }))
+    .unwrap()
+}
+
+/// Deliver all the to-device contents to the other party, and return its responses.
+fn deliver(
+    sender: &str,
+    contents: Vec<OutgoingContent>,
+    receiver: &mut SasVerification<MockCrypto>,
+) -> Vec<OutgoingContent> {
+    contents
+        .into_iter()
+        .flat_map(|content| {
+            receiver.receive_to_device_event(&to_device_event(sender, content), ts(10))
+        })
+        .collect()
+}
+
+fn to_device_request(
+    config: &SasConfig,
+) -> ToDeviceEvent<
+    ruma_events::key::verification::request::ToDeviceKeyVerificationRequestEventContent,
+> {
+    from_json_value(json!({
+        "type": "m.key.verification.request",
+        "sender": config.own_user_id,
+        "content": config.to_device_request("txn".into(), ts(0)),
+    }))
+    .unwrap()
+}
+
+/// Exchange the request, ready, start and accept events between Alice and Bob.
+fn start_to_device() -> (SasVerification<MockCrypto>, SasVerification<MockCrypto>) {
+    let alice_config = alice_config();
+    let request = to_device_request(&alice_config);
+    let mut alice = SasVerification::new(
+        MockCrypto::new(b"alic"),
+        alice_config,
+        FlowId::ToDevice("txn".into()),
+        ts(0),
+    );
+    let mut bob = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob!"),
+        bob_config(),
+        &request,
+        ts(1),
+    )
+    .unwrap();
+    assert_let!(SasState::RequestReceived = bob.state());
+
+    let ready = bob.accept_request();
+    assert!(deliver("@bob:localhost", ready, &mut alice).is_empty());
+    assert_let!(SasState::Ready = alice.state());
+    assert_eq!(alice.their_device_id().unwrap(), "BOBDEVICE");
+
+    let start = alice.start();
+    assert!(deliver("@alice:localhost", start, &mut bob).is_empty());
+    assert_let!(SasState::Started = bob.state());
+
+    (alice, bob)
+}
+
+#[test]
+fn to_device_verification() {
+    let (mut alice, mut bob) = start_to_device();
+
+    let accept = bob.accept();
+    let alice_key = deliver("@bob:localhost", accept, &mut alice);
+    assert_let!(SasState::Accepted = alice.state());
+    let bob_key = deliver("@alice:localhost", alice_key, &mut bob);
+    assert_let!(SasState::KeysExchanged = bob.state());
+    assert!(deliver("@bob:localhost", bob_key, &mut alice).is_empty());
+    assert_let!(SasState::KeysExchanged = alice.state());
+
+    // Both parties display the same short authentication string.
+    assert!(alice.decimals().is_some());
+    assert_eq!(alice.decimals(), bob.decimals());
+    assert_eq!(alice.emojis(), bob.emojis());
+    assert!(alice.emoji_indices().unwrap().iter().all(|index| *index < 64));
+
+    // Bob confirms first, Alice receives his MAC before confirming.
+    let bob_mac = bob.confirm();
+    assert_let!(SasState::Confirmed = bob.state());
+    assert!(deliver("@bob:localhost", bob_mac, &mut alice).is_empty());
+
+    let alice_mac_and_done = alice.confirm();
+    assert_eq!(alice_mac_and_done.len(), 2);
+    assert_let!(SasState::Confirmed = alice.state());
+
+    let bob_done = deliver("@alice:localhost", alice_mac_and_done, &mut bob);
+    assert!(bob.is_done());
+    assert_eq!(bob.verified_keys().unwrap(), ["ed25519:ALICEDEVICE"]);
+
+    assert!(deliver("@bob:localhost", bob_done, &mut alice).is_empty());
+    assert!(alice.is_done());
+    // Only the known keys are verified.
+    assert_eq!(alice.verified_keys().unwrap(), ["ed25519:BOBDEVICE"]);
+}
+
+#[test]
+fn in_room_verification() {
+    let alice_config = alice_config();
+    let request_content = alice_config.in_room_request();
+    let request: OriginalSyncRoomMessageEvent = from_json_value(json!({
+        "type": "m.room.message",
+        "sender": "@alice:localhost",
+        "event_id": "$request",
+        "origin_server_ts": ts(0),
+        "content": request_content,
+    }))
+    .unwrap();
+    assert_let!(MessageType::VerificationRequest(_) = &request.content.msgtype);
+
+    let mut alice = SasVerification::new(
+        MockCrypto::new(b"alic"),
+        alice_config,
+        FlowId::InRoom(owned_event_id!("$request")),
+        ts(0),
+    );
+    let mut bob =
+        SasVerification::from_room_request(MockCrypto::new(b"bob!"), bob_config(), &request, ts(1))
+            .unwrap();
+    assert_eq!(*bob.flow_id(), FlowId::InRoom(owned_event_id!("$request")));
+
+    // Bob starts the verification this time.
+    let mut contents = bob.accept_request();
+    contents.extend(bob.start());
+    assert!(bob.we_started());
+
+    for content in contents {
+        let event = room_event("@bob:localhost", content.clone());
+        assert!(alice.receive_room_event(&event, ts(10)).is_empty());
+        // Our own events are ignored.
+        assert!(bob.receive_room_event(&event, ts(10)).is_empty());
+    }
+    assert_let!(SasState::Started = alice.state());
+
+    let send = |sender: &str,
+                contents: Vec<OutgoingContent>,
+                receiver: &mut SasVerification<MockCrypto>| {
+        contents
+            .into_iter()
+            .flat_map(|content| receiver.receive_room_event(&room_event(sender, content), ts(10)))
+            .collect::<Vec<_>>()
+    };
+
+    let accept = alice.accept();
+    assert_let!(
+        Some(OutgoingContent::InRoom(AnyMessageLikeEventContent::KeyVerificationAccept(content))) =
+            accept.first()
+    );
+    assert_eq!(content.relates_to.event_id, event_id!("$request"));
+
+    let bob_key = send("@alice:localhost", accept, &mut bob);
+    let alice_key = send("@bob:localhost", bob_key, &mut alice);
+    assert!(send("@alice:localhost", alice_key, &mut bob).is_empty());
+    assert_eq!(alice.decimals(), bob.decimals());
+
+    let alice_mac = alice.confirm();
+    assert!(send("@alice:localhost", alice_mac, &mut bob).is_empty());
+    let bob_mac_and_done = bob.confirm();
+    let alice_done = send("@bob:localhost", bob_mac_and_done, &mut alice);
+    assert!(send("@alice:localhost", alice_done, &mut bob).is_empty());
+
+    assert!(alice.is_done());
+    assert!(bob.is_done());
+}
+
+#[test]
+fn mismatched_commitment() {
+    let (mut alice, mut bob) = start_to_device();
+
+    let accept = bob.accept();
+    let alice_key = deliver("@bob:localhost", accept, &mut alice);
+    assert_eq!(alice_key.len(), 1);
+
+    // Bob sends a different key than the one used for the commitment.
+    let key = json!({
+        "type": "m.key.verification.key",
+        "sender": "@bob:localhost",
+        "content": { "transaction_id": "txn", "key": "bWFsIQ" },
+    });
+    let cancel = alice.receive_to_device_event(&from_json_value(key).unwrap(), ts(10));
+    assert_let!(
+        [OutgoingContent::ToDevice {
+            content: AnyToDeviceEventContent::KeyVerificationCancel(content),
+            ..
+        }] = cancel.as_slice()
+    );
+    assert_eq!(content.code, CancelCode::MismatchedCommitment);
+    assert_let!(SasState::Cancelled(info) = alice.state());
+    assert!(info.cancelled_by_us);
+
+    // The cancellation is received by Bob.
+    deliver("@alice:localhost", cancel, &mut bob);
+    assert_let!(SasState::Cancelled(info) = bob.state());
+    assert_eq!(info.code, CancelCode::MismatchedCommitment);
+    assert!(!info.cancelled_by_us);
+}
+
+#[test]
+fn protocol_violations() {
+    // Requests that are too old are ignored.
+    let request = to_device_request(&alice_config());
+    let res = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob!"),
+        bob_config(),
+        &request,
+        ts(11 * 60),
+    );
+    assert_let!(Err(SasRequestError::Expired) = res);
+
+    // Requests from another user are ignored.
+    let mut config = bob_config();
+    config.their_user_id = owned_user_id!("@carl:localhost");
+    let res =
+        SasVerification::from_to_device_request(MockCrypto::new(b"bob!"), config, &request, ts(1));
+    assert_let!(Err(SasRequestError::UserMismatch) = res);
+
+    // A key before the verification started is unexpected.
+    let (mut alice, _) = start_to_device();
+    let key = json!({
+        "type": "m.key.verification.key",
+        "sender": "@bob:localhost",
+        "content": { "transaction_id": "txn", "key": "Ym9iIQ" },
+    });
+    let key: Raw<AnyToDeviceEvent> = from_json_value(key).unwrap();
+    let cancel = alice.receive_to_device_event(&key, ts(10));
+    assert_let!(
+        [OutgoingContent::ToDevice {
+            content: AnyToDeviceEventContent::KeyVerificationCancel(content),
+            ..
+        }] = cancel.as_slice()
+    );
+    assert_eq!(content.code, CancelCode::UnexpectedMessage);
+    // Further events are ignored.
+    assert!(alice.receive_to_device_event(&key, ts(10)).is_empty());
+
+    // Events of other flows are ignored.
+    let (_, mut bob) = start_to_device();
+    let other_flow = json!({
+        "type": "m.key.verification.cancel",
+        "sender": "@alice:localhost",
+        "content": { "transaction_id": "other", "code": "m.user", "reason": "Cancelled" },
+    });
+    assert!(bob.receive_to_device_event(&from_json_value(other_flow).unwrap(), ts(10)).is_empty());
+    assert_let!(SasState::Started = bob.state());
+
+    // The verification times out.
+    assert!(bob.check_timeout(ts(5 * 60)).is_empty());
+    let cancel = bob.check_timeout(ts(11 * 60));
+    assert_let!(
+        [OutgoingContent::ToDevice {
+            content: AnyToDeviceEventContent::KeyVerificationCancel(content),
+            ..
+        }] = cancel.as_slice()
+    );
+    assert_eq!(content.code, CancelCode::Timeout);
+}
+
+#[test]
+fn concurrent_start() {
+    let (mut alice, _) = start_to_device();
+    // Bob's verification was already started by Alice, simulate a concurrent start with a new one.
+    let mut bob2 = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob!"),
+        bob_config(),
+        &to_device_request(&alice_config()),
+        ts(1),
+    )
+    .unwrap();
+    let ready = bob2.accept_request();
+    let bob_start = bob2.start();
+    let alice_start = {
+        // Alice already sent her start event, get it again from a fresh verification.
+        let mut alice2 = SasVerification::new(
+            MockCrypto::new(b"alic"),
+            alice_config(),
+            FlowId::ToDevice("txn".into()),
+            ts(0),
+        );
+        deliver("@bob:localhost", ready, &mut alice2);
+        alice2.start()
+    };
+
+    // Alice's user ID is lower, so Bob's start event is ignored by Alice.
+    assert!(deliver("@bob:localhost", bob_start, &mut alice).is_empty());
+    assert!(alice.we_started());
+    assert_let!(SasState::Started = alice.state());
+
+    // Bob uses Alice's start event.
+    assert!(deliver("@alice:localhost", alice_start, &mut bob2).is_empty());
+    assert!(!bob2.we_started());
+    assert!(!bob2.accept().is_empty());
+    assert_let!(SasState::Accepted = bob2.state());
+}
+
+#[test]
+fn other_devices_after_ready() {
+    let request = to_device_request(&alice_config());
+    let mut alice = SasVerification::new(
+        MockCrypto::new(b"alic"),
+        alice_config(),
+        FlowId::ToDevice("txn".into()),
+        ts(0),
+    );
+    let mut bob = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob!"),
+        bob_config(),
+        &request,
+        ts(1),
+    )
+    .unwrap();
+    let mut other_config = bob_config();
+    other_config.own_device_id = owned_device_id!("BOBOTHERDEVICE");
+    let mut bob_other = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob?"),
+        other_config,
+        &request,
+        ts(1),
+    )
+    .unwrap();
+
+    // Before a device is ready, the events are sent to all the devices.
+    let mut alice_cancelled = SasVerification::new(
+        MockCrypto::new(b"alic"),
+        alice_config(),
+        FlowId::ToDevice("txn".into()),
+        ts(0),
+    );
+    let cancel = alice_cancelled.cancel();
+    assert_let!(
+        [OutgoingContent::ToDevice { recipient: DeviceIdOrAllDevices::AllDevices, .. }] =
+            cancel.as_slice()
+    );
+
+    // Both devices of Bob are ready, the first one is used.
+    assert!(deliver("@bob:localhost", bob.accept_request(), &mut alice).is_empty());
+    let cancel = deliver("@bob:localhost", bob_other.accept_request(), &mut alice);
+    assert_let!(SasState::Ready = alice.state());
+    assert_eq!(alice.their_device_id().unwrap(), "BOBDEVICE");
+
+    // The other device receives a cancellation for itself only.
+    assert_let!(
+        [OutgoingContent::ToDevice {
+            recipient: DeviceIdOrAllDevices::DeviceId(recipient),
+            content: AnyToDeviceEventContent::KeyVerificationCancel(content),
+        }] = cancel.as_slice()
+    );
+    assert_eq!(recipient, "BOBOTHERDEVICE");
+    assert_eq!(content.code, CancelCode::Accepted);
+
+    // A start event from the other device is not used either.
+    bob_other.accept_request();
+    let cancel = deliver("@bob:localhost", bob_other.start(), &mut alice);
+    assert_let!(
+        [OutgoingContent::ToDevice {
+            content: AnyToDeviceEventContent::KeyVerificationCancel(content),
+            ..
+        }] = cancel.as_slice()
+    );
+    assert_eq!(content.code, CancelCode::Accepted);
+    assert_let!(SasState::Ready = alice.state());
+
+    // The verification continues with the accepted device.
+    let start = alice.start();
+    assert_let!(
+        [OutgoingContent::ToDevice { recipient: DeviceIdOrAllDevices::DeviceId(recipient), .. }] =
+            start.as_slice()
+    );
+    assert_eq!(recipient, "BOBDEVICE");
+    assert!(deliver("@alice:localhost", start, &mut bob).is_empty());
+    assert_let!(SasState::Started = bob.state());
+}
+
+#[test]
+fn cancel_requested_devices_when_ready() {
+    let mut config = alice_config();
+    config.their_device_ids = vec![
+        owned_device_id!("BOBDEVICE"),
+        owned_device_id!("BOBOTHERDEVICE"),
+        owned_device_id!("BOBTHIRDDEVICE"),
+    ];
+    let request = to_device_request(&config);
+    let mut alice = SasVerification::new(
+        MockCrypto::new(b"alic"),
+        config,
+        FlowId::ToDevice("txn".into()),
+        ts(0),
+    );
+    let mut bob = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob!"),
+        bob_config(),
+        &request,
+        ts(1),
+    )
+    .unwrap();
+
+    // The other requested devices are cancelled as soon as one device is ready.
+    let cancels = deliver("@bob:localhost", bob.accept_request(), &mut alice);
+    assert_let!(SasState::Ready = alice.state());
+
+    let recipients = cancels
+        .iter()
+        .map(|content| {
+            assert_let!(
+                OutgoingContent::ToDevice {
+                    recipient: DeviceIdOrAllDevices::DeviceId(recipient),
+                    content: AnyToDeviceEventContent::KeyVerificationCancel(content),
+                } = content
+            );
+            assert_eq!(content.code, CancelCode::Accepted);
+            recipient.as_str()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(recipients, ["BOBOTHERDEVICE", "BOBTHIRDDEVICE"]);
+}
+
+#[test]
+fn commitment_uses_received_start_json() {
+    let request = to_device_request(&alice_config());
+    let mut bob = SasVerification::from_to_device_request(
+        MockCrypto::new(b"bob!"),
+        bob_config(),
+        &request,
+        ts(1),
+    )
+    .unwrap();
+    bob.accept_request();
+
+    // The start event contains a field that is unknown to Ruma.
+    let start = json!({
+        "type": "m.key.verification.start",
+        "sender": "@alice:localhost",
+        "content": {
+            "transaction_id": "txn",
+            "from_device": "ALICEDEVICE",
+            "method": "m.sas.v1",
+            "key_agreement_protocols": ["curve25519-hkdf-sha256"],
+            "hashes": ["sha256"],
+            "message_authentication_codes": ["hkdf-hmac-sha256.v2"],
+            "short_authentication_string": ["decimal", "emoji"],
+            "org.example.unknown": { "b": 2, "a": 1 },
+        },
+    });
+    assert!(bob.receive_to_device_event(&from_json_value(start).unwrap(), ts(10)).is_empty());
+
+    let accept = bob.accept();
+    assert_let!(
+        [OutgoingContent::ToDevice {
+            content: AnyToDeviceEventContent::KeyVerificationAccept(content),
+            ..
+        }] = accept.as_slice()
+    );
+    assert_let!(AcceptMethod::SasV1(sas_v1) = &content.method);
+
+    // The commitment is computed with the canonical JSON of the content that was received.
+    let canonical_start = r#"{"from_device":"ALICEDEVICE","hashes":["sha256"],"key_agreement_protocols":["curve25519-hkdf-sha256"],"message_authentication_codes":["hkdf-hmac-sha256.v2"],"method":"m.sas.v1","org.example.unknown":{"a":1,"b":2},"short_authentication_string":["decimal","emoji"],"transaction_id":"txn"}"#;
+    let crypto = MockCrypto::new(b"bob!");
+    let input = format!("{}{canonical_start}", crypto.public_key().encode());
+    assert_eq!(sas_v1.commitment, Base64::new(crypto.sha256(input.as_bytes())));
+}
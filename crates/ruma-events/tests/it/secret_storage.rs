@@ -0,0 +1,145 @@
+#![cfg(feature = "secret-storage-encryption")]
+
+use assert_matches2::assert_matches;
+use js_int::uint;
+use ruma_common::serde::Base64;
+use ruma_events::secret_storage::{
+    key::{PassPhrase, SecretStorageV1AesHmacSha2Properties},
+    secret::SecretEncryptedData,
+    SecretStorageError, SecretStorageKey,
+};
+
+const KEY: [u8; 32] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+];
+const RECOVERY_KEY: &str = "EsSz ykH7 LCZx 7Cae cmKD wcmY JRXi Ybtu 8iQ3 t8Ez nRwK pUY1";
+
+fn encrypted_data(iv: &str, ciphertext: &str, mac: &str) -> SecretEncryptedData {
+    SecretEncryptedData::AesHmacSha2EncryptedData {
+        iv: Base64::parse(iv).unwrap(),
+        ciphertext: Base64::parse(ciphertext).unwrap(),
+        mac: Base64::parse(mac).unwrap(),
+    }
+}
+
+#[test]
+fn key_from_passphrase() {
+    let mut params = PassPhrase::new("saltsaltsalt".to_owned(), uint!(1000));
+    let key = SecretStorageKey::from_passphrase("correct horse battery staple", &params).unwrap();
+    assert_eq!(
+        key.as_bytes(),
+        &[
+            0x0d, 0xd7, 0x8f, 0x53, 0x24, 0x68, 0x72, 0x00, 0x25, 0xd3, 0x75, 0x00, 0x0f, 0x9d,
+            0xa1, 0x5a, 0xbd, 0xd3, 0xe4, 0xf2, 0x40, 0x27, 0xe5, 0xe6, 0x69, 0x63, 0xd6, 0x6b,
+            0x22, 0x9e, 0x41, 0xf6,
+        ]
+    );
+
+    params.bits = uint!(128);
+    assert_matches!(
+        SecretStorageKey::from_passphrase("correct horse battery staple", &params),
+        Err(SecretStorageError::UnsupportedKeyLength)
+    );
+}
+
+#[test]
+fn recovery_key() {
+    let key = SecretStorageKey::new(KEY);
+    assert_eq!(key.to_recovery_key(), RECOVERY_KEY);
+
+    let key = SecretStorageKey::from_recovery_key(RECOVERY_KEY).unwrap();
+    assert_eq!(key.as_bytes(), &KEY);
+
+    // Whitespace is ignored.
+    let key = SecretStorageKey::from_recovery_key(&RECOVERY_KEY.replace(' ', "")).unwrap();
+    assert_eq!(key.as_bytes(), &KEY);
+
+    // Invalid base58.
+    assert_matches!(
+        SecretStorageKey::from_recovery_key("0OIl"),
+        Err(SecretStorageError::InvalidRecoveryKey)
+    );
+
+    // Wrong length.
+    assert_matches!(
+        SecretStorageKey::from_recovery_key(&RECOVERY_KEY[..RECOVERY_KEY.len() - 1]),
+        Err(SecretStorageError::InvalidRecoveryKey)
+    );
+
+    // Wrong parity.
+    let invalid = RECOVERY_KEY.replace("pUY1", "pUY2");
+    assert_matches!(
+        SecretStorageKey::from_recovery_key(&invalid),
+        Err(SecretStorageError::InvalidRecoveryKeyParity)
+    );
+}
+
+#[test]
+fn decrypt_secret() {
+    let key = SecretStorageKey::new(KEY);
+    let data = encrypted_data(
+        "AAECAwQFBgcICQoLDA0ODw",
+        "JamlhFZ/0Ikx",
+        "qhRqMRMSgD2rhTTPQH1v+etfA32hQM2gz6Fwdrewsgo",
+    );
+
+    assert_eq!(key.decrypt(&data, "m.cross_signing.master").unwrap(), "my secret");
+
+    // The secret name is part of the key derivation.
+    assert_matches!(
+        key.decrypt(&data, "m.cross_signing.self_signing"),
+        Err(SecretStorageError::MacMismatch)
+    );
+
+    // Wrong key.
+    let other_key = SecretStorageKey::new([1; 32]);
+    assert_matches!(
+        other_key.decrypt(&data, "m.cross_signing.master"),
+        Err(SecretStorageError::MacMismatch)
+    );
+
+    // Invalid IV.
+    let data = encrypted_data(
+        "AAECAwQFBgcICQoLDA0O",
+        "JamlhFZ/0Ikx",
+        "qhRqMRMSgD2rhTTPQH1v+etfA32hQM2gz6Fwdrewsgo",
+    );
+    assert_matches!(
+        key.decrypt(&data, "m.cross_signing.master"),
+        Err(SecretStorageError::InvalidIvLength)
+    );
+}
+
+#[test]
+fn encrypt_roundtrip() {
+    let key = SecretStorageKey::generate();
+    let data = key.encrypt("my secret", "m.megolm_backup.v1");
+
+    assert_matches!(&data, SecretEncryptedData::AesHmacSha2EncryptedData { iv, .. });
+    assert_eq!(iv.as_bytes().len(), 16);
+    assert_eq!(iv.as_bytes()[8] & 0x80, 0);
+
+    assert_eq!(key.decrypt(&data, "m.megolm_backup.v1").unwrap(), "my secret");
+}
+
+#[test]
+fn check_key() {
+    let key = SecretStorageKey::new(KEY);
+    let properties = SecretStorageV1AesHmacSha2Properties::new(
+        Some(Base64::parse("AAECAwQFBgcICQoLDA0ODw").unwrap()),
+        Some(Base64::parse("ONrOSgDDUXMzIvXsfYBi1m8m075MdjPldfXCxIpU7IY").unwrap()),
+    );
+    assert!(key.check(&properties));
+    assert!(!SecretStorageKey::new([1; 32]).check(&properties));
+    assert!(!key.check(&SecretStorageV1AesHmacSha2Properties::new(None, None)));
+
+    let key = SecretStorageKey::generate();
+    assert!(key.check(&key.properties()));
+}
+
+#[test]
+fn debug_does_not_leak_key() {
+    let key = SecretStorageKey::new(KEY);
+    assert_eq!(format!("{key:?}"), "SecretStorageKey { .. }");
+}
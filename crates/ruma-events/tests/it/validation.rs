@@ -0,0 +1,203 @@
+use assert_matches2::assert_matches;
+use ruma_common::{serde::Raw, OwnedMxcUri};
+use ruma_events::{
+    macros::EventContent,
+    validation::{ContentValidationError, ContentValidationErrorKind},
+    EventContent, GlobalAccountDataEventType, Mentions, OriginalMessageLikeEvent, RawExt,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_value as from_json_value, json};
+
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "com.acme.link", kind = MessageLike)]
+struct LinkEventContent {
+    #[ruma_event(validate(max_length = 10))]
+    title: String,
+
+    #[ruma_event(validate(url_scheme = "https", url_scheme = "mxc"))]
+    url: String,
+
+    #[ruma_event(validate(url_scheme = "mxc"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<OwnedMxcUri>,
+
+    #[ruma_event(validate(required))]
+    #[serde(rename = "m.mentions", skip_serializing_if = "Option::is_none")]
+    mentions: Option<Mentions>,
+}
+
+#[derive(Clone, Debug, Serialize, EventContent)]
+#[ruma_event(type = "com.acme.tag.*", kind = GlobalAccountData)]
+struct TagEventContent {
+    #[ruma_event(type_fragment)]
+    tag: String,
+
+    #[ruma_event(validate(max_length = 2))]
+    rooms: Vec<String>,
+}
+
+fn link_content(json: serde_json::Value) -> serde_json::Result<LinkEventContent> {
+    from_json_value::<Raw<LinkEventContent>>(json).unwrap().deserialize_content()
+}
+
+#[test]
+fn valid_content() {
+    let content = link_content(json!({
+        "title": "Ruma",
+        "url": "https://ruma.dev",
+        "thumbnail": "mxc://notareal.hs/abcdef",
+        "m.mentions": {},
+    }))
+    .unwrap();
+
+    assert_eq!(content.title, "Ruma");
+    assert_eq!(content.url, "https://ruma.dev");
+    content.validate().unwrap();
+}
+
+#[test]
+fn too_long() {
+    let err = link_content(json!({
+        "title": "Ruma, a Matrix library",
+        "url": "https://ruma.dev",
+        "m.mentions": {},
+    }))
+    .unwrap_err();
+    assert!(err.to_string().contains("`title`"));
+
+    let mut content = LinkEventContent {
+        title: "Ruma".to_owned(),
+        url: "mxc://notareal.hs/abcdef".to_owned(),
+        thumbnail: None,
+        mentions: Some(Mentions::new()),
+    };
+    content.validate().unwrap();
+
+    content.title = "Ruma, a Matrix library".to_owned();
+    assert_matches!(
+        content.validate(),
+        Err(ContentValidationError {
+            field: "title",
+            kind: ContentValidationErrorKind::TooLong { max: 10, len: 22 },
+            ..
+        })
+    );
+}
+
+#[test]
+fn invalid_url_scheme() {
+    let err = link_content(json!({
+        "title": "Ruma",
+        "url": "http://ruma.dev",
+        "m.mentions": {},
+    }))
+    .unwrap_err();
+    assert!(err.to_string().contains("`url`"));
+
+    let err = link_content(json!({
+        "title": "Ruma",
+        "url": "https://ruma.dev",
+        "thumbnail": "https://ruma.dev/logo.png",
+        "m.mentions": {},
+    }))
+    .unwrap_err();
+    assert!(err.to_string().contains("`thumbnail`"));
+}
+
+#[test]
+fn missing_required() {
+    let err = link_content(json!({
+        "title": "Ruma",
+        "url": "https://ruma.dev",
+    }))
+    .unwrap_err();
+    assert!(err.to_string().contains("`m.mentions`"));
+
+    // The `Deserialize` implementation doesn't validate the content.
+    let content = from_json_value::<LinkEventContent>(json!({
+        "title": "Ruma",
+        "url": "https://ruma.dev",
+    }))
+    .unwrap();
+    assert_matches!(
+        content.validate(),
+        Err(ContentValidationError {
+            field: "m.mentions",
+            kind: ContentValidationErrorKind::Missing,
+            ..
+        })
+    );
+}
+
+#[test]
+fn renamed_fields() {
+    #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+    #[ruma_event(type = "com.acme.profile", kind = MessageLike)]
+    #[serde(rename_all = "camelCase")]
+    struct ProfileEventContent {
+        #[ruma_event(validate(max_length = 5))]
+        display_name: String,
+
+        #[ruma_event(validate(url_scheme = "mxc"))]
+        #[serde(rename(serialize = "avatar", deserialize = "avatar_url"))]
+        avatar_url: String,
+    }
+
+    let raw = from_json_value::<Raw<ProfileEventContent>>(json!({
+        "displayName": "Ruma, a Matrix library",
+        "avatar_url": "mxc://notareal.hs/abcdef",
+    }))
+    .unwrap();
+    let err = raw.deserialize_content().unwrap_err();
+    assert!(err.to_string().contains("`displayName`"));
+
+    let content = ProfileEventContent {
+        display_name: "Ruma".to_owned(),
+        avatar_url: "https://ruma.dev/logo.png".to_owned(),
+    };
+    assert_matches!(
+        content.validate(),
+        Err(ContentValidationError {
+            field: "avatar_url",
+            kind: ContentValidationErrorKind::InvalidUrlScheme { .. },
+            ..
+        })
+    );
+}
+
+#[test]
+fn invalid_content_in_event() {
+    let json = json!({
+        "content": {
+            "title": "Ruma",
+            "url": "ftp://ruma.dev",
+            "m.mentions": {},
+        },
+        "event_id": "$h29iv0s8:example.com",
+        "origin_server_ts": 1,
+        "room_id": "!roomid:example.com",
+        "sender": "@carl:example.com",
+        "type": "com.acme.link",
+    });
+
+    let err = from_json_value::<OriginalMessageLikeEvent<LinkEventContent>>(json).unwrap_err();
+    assert!(err.to_string().contains("`url`"));
+}
+
+#[test]
+fn type_fragment_content() {
+    let raw =
+        from_json_value::<Raw<TagEventContent>>(json!({ "rooms": ["!a:example.com"] })).unwrap();
+    let content =
+        raw.deserialize_with_type(GlobalAccountDataEventType::from("com.acme.tag.work")).unwrap();
+    assert_eq!(content.tag, "work");
+
+    let raw = from_json_value::<Raw<TagEventContent>>(
+        json!({ "rooms": ["!a:example.com", "!b:example.com", "!c:example.com"] }),
+    )
+    .unwrap();
+    let err = raw
+        .deserialize_with_type(GlobalAccountDataEventType::from("com.acme.tag.work"))
+        .unwrap_err();
+    assert!(err.to_string().contains("`rooms`"));
+}
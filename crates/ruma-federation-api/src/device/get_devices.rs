@@ -85,6 +85,7 @@ pub mod v1 {
 
     /// Information about a user's device.
     #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct UserDevice {
         /// The device ID.
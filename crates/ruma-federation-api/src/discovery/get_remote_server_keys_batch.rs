@@ -72,6 +72,7 @@ pub mod v2 {
 
     /// The query criteria.
     #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct QueryCriteria {
         /// A millisecond POSIX timestamp in milliseconds indicating when the
@@ -52,6 +52,7 @@ pub mod v1 {
 
     /// Arbitrary values that identify this implementation.
     #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Server {
         /// Arbitrary name that identifies this implementation.
@@ -21,6 +21,8 @@ pub mod event;
 pub mod keys;
 pub mod knock;
 pub mod membership;
+#[cfg(all(feature = "client", feature = "json-schema"))]
+pub mod openapi;
 pub mod openid;
 pub mod query;
 pub mod room;
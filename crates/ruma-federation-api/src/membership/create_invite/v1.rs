@@ -67,6 +67,7 @@ pub struct Response {
 
 /// Information included alongside an event that is not signed.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct UnsignedEventContent {
     /// An optional list of simplified events to help the receiver of the invite identify the room.
@@ -77,6 +77,7 @@ impl Response {
 
 /// Full state of the room.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomState {
     #[cfg(not(feature = "unstable-unspecified"))]
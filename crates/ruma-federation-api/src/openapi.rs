@@ -0,0 +1,51 @@
+//! Generation of an [OpenAPI] document for the endpoints of the server-server API.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+
+use ruma_common::api::openapi::OpenApiDocument;
+
+/// Add all the endpoints of the server-server API to the given OpenAPI document.
+///
+/// Endpoints that are behind features that are not enabled are not added.
+#[allow(deprecated)]
+pub fn add_endpoints(document: &mut OpenApiDocument) {
+    document.add_endpoint::<crate::authorization::get_event_authorization::v1::Request>();
+    document.add_endpoint::<crate::backfill::get_backfill::v1::Request>();
+    document.add_endpoint::<crate::device::get_devices::v1::Request>();
+    document.add_endpoint::<crate::directory::get_public_rooms::v1::Request>();
+    document.add_endpoint::<crate::directory::get_public_rooms_filtered::v1::Request>();
+    document.add_endpoint::<crate::discovery::discover_homeserver::Request>();
+    document.add_endpoint::<crate::discovery::get_remote_server_keys::v2::Request>();
+    document.add_endpoint::<crate::discovery::get_remote_server_keys_batch::v2::Request>();
+    document.add_endpoint::<crate::discovery::get_server_keys::v2::Request>();
+    document.add_endpoint::<crate::discovery::get_server_version::v1::Request>();
+    #[cfg(feature = "unstable-msc3723")]
+    document.add_endpoint::<crate::discovery::get_server_versions::msc3723::Request>();
+    document.add_endpoint::<crate::event::get_event::v1::Request>();
+    document.add_endpoint::<crate::event::get_event_by_timestamp::v1::Request>();
+    document.add_endpoint::<crate::event::get_missing_events::v1::Request>();
+    document.add_endpoint::<crate::event::get_room_state::v1::Request>();
+    document.add_endpoint::<crate::event::get_room_state_ids::v1::Request>();
+    document.add_endpoint::<crate::keys::claim_keys::v1::Request>();
+    document.add_endpoint::<crate::keys::get_keys::v1::Request>();
+    document.add_endpoint::<crate::knock::create_knock_event_template::v1::Request>();
+    document.add_endpoint::<crate::knock::send_knock::v1::Request>();
+    document.add_endpoint::<crate::membership::create_invite::v1::Request>();
+    document.add_endpoint::<crate::membership::create_invite::v2::Request>();
+    document.add_endpoint::<crate::membership::create_join_event::v1::Request>();
+    document.add_endpoint::<crate::membership::create_join_event::v2::Request>();
+    document.add_endpoint::<crate::membership::create_leave_event::v1::Request>();
+    document.add_endpoint::<crate::membership::create_leave_event::v2::Request>();
+    document.add_endpoint::<crate::membership::prepare_join_event::v1::Request>();
+    document.add_endpoint::<crate::membership::prepare_leave_event::v1::Request>();
+    document.add_endpoint::<crate::openid::get_openid_userinfo::v1::Request>();
+    document.add_endpoint::<crate::query::get_custom_information::v1::Request>();
+    document.add_endpoint::<crate::query::get_profile_information::v1::Request>();
+    document.add_endpoint::<crate::query::get_room_information::v1::Request>();
+    #[cfg(feature = "unstable-msc3843")]
+    document.add_endpoint::<crate::room::report_content::msc3843::Request>();
+    document.add_endpoint::<crate::space::get_hierarchy::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::bind_callback::v1::Request>();
+    document.add_endpoint::<crate::thirdparty::exchange_invite::v1::Request>();
+    document.add_endpoint::<crate::transactions::send_transaction_message::v1::Request>();
+}
@@ -15,6 +15,7 @@ pub mod get_hierarchy;
 /// To create an instance of this type, first create a `SpaceHierarchyParentSummaryInit` and convert
 /// it via `SpaceHierarchyParentSummary::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SpaceHierarchyParentSummary {
     /// The canonical alias of the room, if any.
@@ -148,6 +149,7 @@ impl From<SpaceHierarchyParentSummaryInit> for SpaceHierarchyParentSummary {
 /// To create an instance of this type, first create a `SpaceHierarchyChildSummaryInit` and convert
 /// it via `SpaceHierarchyChildSummary::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SpaceHierarchyChildSummary {
     /// The canonical alias of the room, if any.
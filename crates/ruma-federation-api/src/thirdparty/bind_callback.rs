@@ -71,6 +71,7 @@ pub mod v1 {
 
     /// A pending invite the third party identifier has received.
     #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ThirdPartyInvite {
         /// The type of third party invite issues.
@@ -16,6 +16,7 @@ use serde_json::{value::RawValue as RawJsonValue, Value as JsonValue};
 
 /// Type for passing ephemeral data to homeservers.
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[serde(tag = "edu_type", content = "content")]
 pub enum Edu {
@@ -81,6 +82,7 @@ impl<'de> Deserialize<'de> for Edu {
 
 /// The content for "m.presence" Edu.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PresenceContent {
     /// A list of presence updates that the receiving server is likely to be interested in.
@@ -96,6 +98,7 @@ impl PresenceContent {
 
 /// An update to the presence of a user.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct PresenceUpdate {
     /// The user ID this presence EDU is for.
@@ -133,6 +136,7 @@ impl PresenceUpdate {
 
 /// The content for "m.receipt" Edu.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ReceiptContent {
     /// Receipts for a particular room.
@@ -149,6 +153,7 @@ impl ReceiptContent {
 
 /// Mapping between user and `ReceiptData`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ReceiptMap {
     /// Read receipts for users in the room.
@@ -165,6 +170,7 @@ impl ReceiptMap {
 
 /// Metadata about the event that was last read and when.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct ReceiptData {
     /// Metadata for the read receipt.
@@ -183,6 +189,7 @@ impl ReceiptData {
 
 /// The content for "m.typing" Edu.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct TypingContent {
     /// The room where the user's typing status has been updated.
@@ -204,6 +211,7 @@ impl TypingContent {
 
 /// The description of the direct-to- device message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct DeviceListUpdateContent {
     /// The user ID who owns the device.
@@ -253,6 +261,7 @@ impl DeviceListUpdateContent {
 
 /// The description of the direct-to- device message.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct DirectDeviceContent {
     /// The user ID of the sender.
@@ -291,6 +300,7 @@ pub type DirectDeviceMessages =
 
 /// The content for an `m.signing_key_update` EDU.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct SigningKeyUpdateContent {
     /// The user ID whose cross-signing keys have changed.
@@ -0,0 +1,49 @@
+#![cfg(all(feature = "client", feature = "json-schema"))]
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// Check that `add_endpoints` lists all the endpoints of the crate, by comparing the paths of the
+/// modules where `METADATA` is declared with the ones of the requests in `src/openapi.rs`.
+#[test]
+fn all_endpoints_are_added() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+    let mut endpoints = BTreeSet::new();
+    collect_endpoints(&src, &src, &mut endpoints);
+
+    let openapi = fs::read_to_string(src.join("openapi.rs")).unwrap();
+    let added = openapi
+        .split("add_endpoint::<crate::")
+        .skip(1)
+        .map(|s| s.split_once("::Request>").unwrap().0.to_owned())
+        .collect::<BTreeSet<_>>();
+
+    assert_eq!(added, endpoints);
+}
+
+/// Collect the paths of the modules declaring a `METADATA` constant in the given directory.
+fn collect_endpoints(src: &Path, dir: &Path, endpoints: &mut BTreeSet<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_endpoints(src, &path, endpoints);
+            continue;
+        }
+
+        let module = path.strip_prefix(src).unwrap().with_extension("");
+        let module = module.iter().map(|s| s.to_str().unwrap()).collect::<Vec<_>>().join("::");
+
+        // The versions of an endpoint are in `pub mod` blocks at the root of the file.
+        let mut version = None;
+        for line in fs::read_to_string(&path).unwrap().lines() {
+            if let Some(name) = line.strip_prefix("pub mod ").and_then(|l| l.strip_suffix(" {")) {
+                version = Some(name);
+            } else if line.trim_start().starts_with("const METADATA: Metadata") {
+                endpoints.insert(match version {
+                    Some(version) => format!("{module}::{version}"),
+                    None => module.clone(),
+                });
+            }
+        }
+    }
+}
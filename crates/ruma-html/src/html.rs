@@ -1,4 +1,4 @@
-use std::{collections::BTreeSet, fmt, io, iter::FusedIterator};
+use std::{collections::BTreeSet, fmt, io, iter::FusedIterator, ops::Range};
 
 use as_variant::as_variant;
 use html5ever::{
@@ -6,10 +6,12 @@ use html5ever::{
     serialize::{serialize, Serialize, SerializeOpts, Serializer, TraversalScope},
     tendril::{StrTendril, TendrilSink},
     tree_builder::{NodeOrText, TreeSink},
-    Attribute, ParseOpts, QualName,
+    Attribute, LocalName, ParseOpts, QualName,
 };
 use tracing::debug;
 
+#[cfg(feature = "matrix")]
+mod convert;
 #[cfg(feature = "matrix")]
 pub mod matrix;
 
@@ -67,7 +69,7 @@ impl Html {
     ///
     /// The node is detached from its previous position.
     pub(crate) fn append_node(&mut self, parent_id: usize, node_id: usize) {
-        self.detach(node_id);
+        self.detach_node(node_id);
 
         self.nodes[node_id].parent = Some(parent_id);
         if let Some(last_child) = self.nodes[parent_id].last_child.take() {
@@ -82,8 +84,8 @@ impl Html {
     /// Insert the given node before the given sibling in this `Html`.
     ///
     /// The node is detached from its previous position.
-    pub(crate) fn insert_before(&mut self, sibling_id: usize, node_id: usize) {
-        self.detach(node_id);
+    pub(crate) fn insert_node_before(&mut self, sibling_id: usize, node_id: usize) {
+        self.detach_node(node_id);
 
         self.nodes[node_id].parent = self.nodes[sibling_id].parent;
         self.nodes[node_id].next_sibling = Some(sibling_id);
@@ -97,7 +99,7 @@ impl Html {
     }
 
     /// Detach the given node from this `Html`.
-    pub(crate) fn detach(&mut self, node_id: usize) {
+    pub(crate) fn detach_node(&mut self, node_id: usize) {
         let (parent, prev_sibling, next_sibling) = {
             let node = &mut self.nodes[node_id];
             (node.parent.take(), node.prev_sibling.take(), node.next_sibling.take())
@@ -151,6 +153,237 @@ impl Html {
     }
 }
 
+/// Methods to edit the HTML tree.
+///
+/// The nodes are identified by their [`NodeId`], that can be obtained with [`NodeRef::id()`] or
+/// when creating a node. Using a `NodeId` from another `Html` is a logic error and might panic or
+/// return unexpected results.
+///
+/// Detached nodes are not part of the tree anymore, so they are not serialized, but they can be
+/// inserted again.
+impl Html {
+    /// Get the node with the given ID.
+    ///
+    /// Returns `None` if there is no node with this ID, or if it is the root node.
+    pub fn node(&self, id: NodeId) -> Option<NodeRef<'_>> {
+        let is_valid = id.0 < self.nodes.len() && id.0 != 0 && id.0 != self.root_id();
+        is_valid.then(|| NodeRef::new(self, id.0))
+    }
+
+    /// Get the mutable data of the element with the given ID.
+    ///
+    /// Returns `None` if the node is not an element.
+    pub fn element_mut(&mut self, id: NodeId) -> Option<&mut ElementData> {
+        self.nodes.get_mut(id.0)?.as_element_mut()
+    }
+
+    /// Get the mutable content of the text node with the given ID.
+    ///
+    /// Returns `None` if the node is not a text node.
+    pub fn text_mut(&mut self, id: NodeId) -> Option<&mut StrTendril> {
+        self.nodes.get_mut(id.0)?.as_text_mut()
+    }
+
+    /// Create a new detached HTML element with the given local name and no attributes.
+    ///
+    /// Returns the ID of the new node.
+    pub fn create_element(&mut self, name: &str) -> NodeId {
+        let name = QualName::new(None, ns!(html), LocalName::from(name));
+        NodeId(self.new_node(NodeData::Element(ElementData { name, attrs: BTreeSet::new() })))
+    }
+
+    /// Create a new detached text node with the given content.
+    ///
+    /// Returns the ID of the new node.
+    pub fn create_text(&mut self, text: &str) -> NodeId {
+        NodeId(self.new_node(NodeData::Text(text.into())))
+    }
+
+    /// Append the given node to the children of the root node of the HTML.
+    ///
+    /// The node is detached from its previous position.
+    pub fn append_to_root(&mut self, node: NodeId) {
+        let root_id = self.root_id();
+        self.append_node(root_id, node.0);
+    }
+
+    /// Append the given node to the children of the given parent.
+    ///
+    /// The node is detached from its previous position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is `parent` or one of its ancestors.
+    pub fn append_child(&mut self, parent: NodeId, node: NodeId) {
+        self.assert_not_ancestor(node.0, parent.0);
+        self.append_node(parent.0, node.0);
+    }
+
+    /// Insert the given node before the given sibling.
+    ///
+    /// The node is detached from its previous position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is `sibling` or one of its ancestors.
+    pub fn insert_before(&mut self, sibling: NodeId, node: NodeId) {
+        self.assert_not_ancestor(node.0, sibling.0);
+        self.insert_node_before(sibling.0, node.0);
+    }
+
+    /// Insert the given node after the given sibling.
+    ///
+    /// The node is detached from its previous position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is `sibling` or one of its ancestors.
+    pub fn insert_after(&mut self, sibling: NodeId, node: NodeId) {
+        self.assert_not_ancestor(node.0, sibling.0);
+
+        match self.nodes[sibling.0].next_sibling {
+            Some(next_sibling) => self.insert_node_before(next_sibling, node.0),
+            None => {
+                if let Some(parent) = self.nodes[sibling.0].parent {
+                    self.append_node(parent, node.0);
+                }
+            }
+        }
+    }
+
+    /// Detach the given node, and its children, from the tree.
+    pub fn detach(&mut self, node: NodeId) {
+        self.detach_node(node.0);
+    }
+
+    /// Replace the given node by another node.
+    ///
+    /// The old node is detached from the tree and the new node is detached from its previous
+    /// position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new` is `old` or one of its ancestors.
+    pub fn replace(&mut self, old: NodeId, new: NodeId) {
+        self.insert_before(old, new);
+        self.detach_node(old.0);
+    }
+
+    /// Replace the given node by its children.
+    pub fn unwrap(&mut self, node: NodeId) {
+        let mut next_child = self.nodes[node.0].first_child;
+        while let Some(child) = next_child {
+            next_child = self.nodes[child].next_sibling;
+            self.insert_node_before(node.0, child);
+        }
+
+        self.detach_node(node.0);
+    }
+
+    /// Split the given text node at the given byte offset.
+    ///
+    /// The text after the offset is moved to a new text node inserted after the given node.
+    ///
+    /// Returns the ID of the new node, or `None` if the node is not a text node or if the offset is
+    /// not on a char boundary of the text.
+    pub fn split_text(&mut self, node: NodeId, offset: usize) -> Option<NodeId> {
+        let text = self.nodes.get_mut(node.0)?.as_text_mut()?;
+        if !text.is_char_boundary(offset) {
+            return None;
+        }
+
+        let end = text.subtendril(offset as u32, (text.len() - offset) as u32);
+        text.pop_back((text.len() - offset) as u32);
+
+        let new_node = NodeId(self.new_node(NodeData::Text(end)));
+        self.insert_after(node, new_node);
+
+        Some(new_node)
+    }
+
+    /// Wrap the given byte range of the given text node with the given element.
+    ///
+    /// The text node is split so that the text in the range is in a separate node, which becomes
+    /// the last child of the wrapper. The wrapper is detached from its previous position and
+    /// inserted where the text was.
+    ///
+    /// Returns the ID of the text node in the wrapper, or `None` if the node is not a text node, if
+    /// the range is empty or if it is not on char boundaries of the text.
+    pub fn wrap_text(
+        &mut self,
+        node: NodeId,
+        range: Range<usize>,
+        wrapper: NodeId,
+    ) -> Option<NodeId> {
+        let text = self.nodes.get(node.0)?.as_text()?;
+        if range.is_empty()
+            || range.end > text.len()
+            || !text.is_char_boundary(range.start)
+            || !text.is_char_boundary(range.end)
+        {
+            return None;
+        }
+
+        let text_len = text.len();
+
+        if range.end < text_len {
+            self.split_text(node, range.end)?;
+        }
+        let wrapped = if range.start > 0 { self.split_text(node, range.start)? } else { node };
+
+        self.replace(wrapped, wrapper);
+        self.append_child(wrapper, wrapped);
+
+        Some(wrapped)
+    }
+
+    /// Apply the given transform to the nodes of the tree.
+    ///
+    /// The nodes are visited depth-first, in the order in which they are serialized.
+    pub fn transform<T: HtmlTransform + ?Sized>(&mut self, transform: &mut T) {
+        let root_id = self.root_id();
+        self.transform_children(root_id, transform);
+    }
+
+    fn transform_children<T: HtmlTransform + ?Sized>(
+        &mut self,
+        parent_id: usize,
+        transform: &mut T,
+    ) {
+        let mut next_child = self.nodes[parent_id].first_child;
+
+        while let Some(child) = next_child {
+            next_child = match transform.transform_node(self, NodeId(child)) {
+                TransformAction::Continue => {
+                    self.transform_children(child, transform);
+                    self.nodes[child].next_sibling
+                }
+                TransformAction::SkipChildren => self.nodes[child].next_sibling,
+                TransformAction::Remove => {
+                    let next_sibling = self.nodes[child].next_sibling;
+                    self.detach_node(child);
+                    next_sibling
+                }
+                TransformAction::Unwrap => {
+                    // Visit the children next, as they take the place of the node.
+                    let next = self.nodes[child].first_child.or(self.nodes[child].next_sibling);
+                    self.unwrap(NodeId(child));
+                    next
+                }
+            };
+        }
+    }
+
+    /// Panics if `node` is `target` or one of its ancestors.
+    fn assert_not_ancestor(&self, node: usize, target: usize) {
+        let mut current = Some(target);
+        while let Some(id) = current {
+            assert_ne!(id, node, "cannot insert a node inside itself");
+            current = self.nodes[id].parent;
+        }
+    }
+}
+
 impl Default for Html {
     fn default() -> Self {
         Self { nodes: vec![Node::new(NodeData::Document)] }
@@ -248,7 +481,7 @@ impl TreeSink for Html {
         new_node: NodeOrText<Self::Handle>,
     ) {
         match new_node {
-            NodeOrText::AppendNode(index) => self.insert_before(*sibling, index),
+            NodeOrText::AppendNode(index) => self.insert_node_before(*sibling, index),
             NodeOrText::AppendText(text) => {
                 // If the previous sibling is also text, add this text to it.
                 if let Some(prev_text) = self.nodes[*sibling]
@@ -258,7 +491,7 @@ impl TreeSink for Html {
                     prev_text.push_tendril(&text);
                 } else {
                     let index = self.new_node(NodeData::Text(text));
-                    self.insert_before(*sibling, index);
+                    self.insert_node_before(*sibling, index);
                 }
             }
         }
@@ -270,7 +503,7 @@ impl TreeSink for Html {
     }
 
     fn remove_from_parent(&mut self, target: &Self::Handle) {
-        self.detach(*target);
+        self.detach_node(*target);
     }
 
     fn reparent_children(&mut self, node: &Self::Handle, new_parent: &Self::Handle) {
@@ -435,6 +668,40 @@ pub struct ElementData {
 }
 
 impl ElementData {
+    /// Get the value of the attribute with the given local name, if any.
+    ///
+    /// Only attributes without a namespace are considered.
+    pub fn attribute(&self, name: &str) -> Option<&StrTendril> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name.ns == ns!() && &attr.name.local == name)
+            .map(|attr| &attr.value)
+    }
+
+    /// Set the value of the attribute with the given local name.
+    ///
+    /// This replaces the previous value of the attribute, if any.
+    pub fn set_attribute(&mut self, name: &str, value: impl Into<StrTendril>) {
+        self.remove_attribute(name);
+        self.attrs.insert(Attribute {
+            name: QualName::new(None, ns!(), LocalName::from(name)),
+            value: value.into(),
+        });
+    }
+
+    /// Remove the attribute with the given local name.
+    ///
+    /// Returns the previous value of the attribute, if any.
+    pub fn remove_attribute(&mut self, name: &str) -> Option<StrTendril> {
+        let attr = self
+            .attrs
+            .iter()
+            .find(|attr| attr.name.ns == ns!() && &attr.name.local == name)
+            .cloned()?;
+        self.attrs.remove(&attr);
+        Some(attr.value)
+    }
+
     /// Convert this element data to typed data as [suggested by the Matrix Specification][spec].
     ///
     /// [spec]: https://spec.matrix.org/latest/client-server-api/#mroommessage-msgtypes
@@ -452,12 +719,14 @@ pub struct NodeRef<'a> {
     pub(crate) html: &'a Html,
     /// The referenced node.
     pub(crate) node: &'a Node,
+    /// The ID of the referenced node.
+    pub(crate) id: usize,
 }
 
 impl<'a> NodeRef<'a> {
     /// Construct a new `NodeRef` for the given HTML and node ID.
     fn new(html: &'a Html, id: usize) -> Self {
-        Self { html, node: &html.nodes[id] }
+        Self { html, node: &html.nodes[id], id }
     }
 
     /// Construct a new `NodeRef` from the same HTML as this node with the given node ID.
@@ -466,6 +735,11 @@ impl<'a> NodeRef<'a> {
         Self::new(html, id)
     }
 
+    /// The ID of the node.
+    pub fn id(&self) -> NodeId {
+        NodeId(self.id)
+    }
+
     /// The data of the node.
     pub fn data(&self) -> &'a NodeData {
         &self.node.data
@@ -534,6 +808,50 @@ impl<'a> NodeRef<'a> {
     }
 }
 
+/// The ID of a node in an [`Html`] tree.
+///
+/// It can be used with the methods of `Html` to edit the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A transform to apply to the nodes of an [`Html`] tree, with [`Html::transform()`].
+///
+/// This is implemented for closures with the same signature as [`HtmlTransform::transform_node()`].
+pub trait HtmlTransform {
+    /// Transform the given node.
+    ///
+    /// The node can be edited in place, and new nodes can be inserted around it. To remove the node
+    /// or replace it with its children, the corresponding [`TransformAction`] should be returned
+    /// instead of modifying the tree directly, so that the traversal can continue.
+    fn transform_node(&mut self, html: &mut Html, node: NodeId) -> TransformAction;
+}
+
+impl<F> HtmlTransform for F
+where
+    F: FnMut(&mut Html, NodeId) -> TransformAction,
+{
+    fn transform_node(&mut self, html: &mut Html, node: NodeId) -> TransformAction {
+        self(html, node)
+    }
+}
+
+/// What to do with a node after it was visited by an [`HtmlTransform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::exhaustive_enums)]
+pub enum TransformAction {
+    /// Keep the node and visit its children.
+    Continue,
+
+    /// Keep the node but don't visit its children.
+    SkipChildren,
+
+    /// Remove the node and its children.
+    Remove,
+
+    /// Replace the node by its children, which are visited next.
+    Unwrap,
+}
+
 /// An iterator through the children of a node.
 ///
 /// Can be constructed with [`Html::children()`] or [`NodeRef::children()`].
@@ -0,0 +1,604 @@
+//! Conversion of HTML to plain text and Markdown.
+
+use super::{
+    matrix::{AnchorUri, MatrixElement, MatrixElementData},
+    Html, NodeData, NodeRef,
+};
+
+impl Html {
+    /// Convert this HTML to plain text.
+    ///
+    /// The text content is kept with a few conventions to preserve the structure of the HTML:
+    ///
+    /// * Blocks are separated by empty lines.
+    /// * List items are prefixed by `-`, or by their number for ordered lists.
+    /// * Blockquotes are prefixed by `> `.
+    /// * The URL of links is added between parentheses after the text, unless it is the same as the
+    ///   text or it is a Matrix URI, which is usually a mention.
+    /// * Spoilers are replaced by `[Spoiler]`, with the reason if there is one, to avoid revealing
+    ///   their content.
+    /// * Table cells are separated by ` | `.
+    ///
+    /// The [rich reply fallback] is removed.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub fn to_plain_text(&self) -> String {
+        let mut writer = Writer::new(Format::PlainText);
+        writer.children(self.children());
+        writer.finish()
+    }
+
+    /// Convert this HTML to [CommonMark] Markdown, with a few extensions.
+    ///
+    /// The following extensions are used:
+    ///
+    /// * Strikethrough and tables from [GitHub Flavored Markdown].
+    /// * Spoilers are wrapped with `||`.
+    ///
+    /// The [rich reply fallback] is removed.
+    ///
+    /// [CommonMark]: https://commonmark.org/
+    /// [GitHub Flavored Markdown]: https://github.github.com/gfm/
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub fn to_markdown(&self) -> String {
+        let mut writer = Writer::new(Format::Markdown);
+        writer.children(self.children());
+        writer.finish()
+    }
+}
+
+/// The format of the output of a [`Writer`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    PlainText,
+    Markdown,
+}
+
+/// A list being written.
+struct List {
+    /// The number of the next item, if this is an ordered list.
+    next_number: Option<i64>,
+}
+
+/// Helper to convert HTML nodes to text.
+struct Writer {
+    /// The output format.
+    format: Format,
+
+    /// The text written so far.
+    out: String,
+
+    /// The prefixes to write at the start of each line.
+    prefixes: Vec<String>,
+
+    /// The marker of a list item that replaces the last prefix on the next line.
+    pending_marker: Option<String>,
+
+    /// The number of line breaks to write before the next content.
+    pending_newlines: usize,
+
+    /// Whether nothing was written on the current line yet.
+    line_start: bool,
+
+    /// Whether whitespace should be written before the next content.
+    pending_space: bool,
+
+    /// The lists that are being written.
+    lists: Vec<List>,
+}
+
+impl Writer {
+    fn new(format: Format) -> Self {
+        Self {
+            format,
+            out: String::new(),
+            prefixes: Vec::new(),
+            pending_marker: None,
+            pending_newlines: 0,
+            line_start: true,
+            pending_space: false,
+            lists: Vec::new(),
+        }
+    }
+
+    fn is_markdown(&self) -> bool {
+        self.format == Format::Markdown
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+
+    /// Write the given string, without escaping.
+    fn write(&mut self, s: &str) {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.pending_newlines += 1;
+                self.line_start = true;
+            }
+
+            if !line.is_empty() {
+                self.start_content();
+                self.out.push_str(line);
+            }
+        }
+    }
+
+    /// Prepare the output to write content.
+    fn start_content(&mut self) {
+        if !self.line_start {
+            return;
+        }
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+
+            let blank_line_prefix = self.prefixes.concat();
+            for _ in 1..self.pending_newlines {
+                self.out.push_str(blank_line_prefix.trim_end());
+                self.out.push('\n');
+            }
+        }
+
+        let marker = self.pending_marker.take();
+        let prefixes_len = self.prefixes.len() - usize::from(marker.is_some());
+        for prefix in &self.prefixes[..prefixes_len] {
+            self.out.push_str(prefix);
+        }
+        if let Some(marker) = marker {
+            self.out.push_str(&marker);
+        }
+
+        self.pending_newlines = 0;
+        self.line_start = false;
+        self.pending_space = false;
+    }
+
+    /// Start a new block separated from the previous content by the given number of line breaks.
+    fn block(&mut self, newlines: usize) {
+        self.pending_space = false;
+
+        // The start of a list item is already separated from the previous content.
+        if self.pending_marker.is_some() || self.out.is_empty() {
+            return;
+        }
+
+        self.pending_newlines = self.pending_newlines.max(newlines);
+        self.line_start = true;
+    }
+
+    /// Write a line break.
+    fn line_break(&mut self) {
+        if self.is_markdown() && !self.line_start {
+            self.out.push('\\');
+        }
+
+        self.pending_space = false;
+        self.pending_newlines += 1;
+        self.line_start = true;
+    }
+
+    /// Write the markup that opens an inline element.
+    fn open_markup(&mut self, markup: &str) {
+        if self.pending_space && !self.line_start {
+            self.write(" ");
+        }
+        self.pending_space = false;
+        self.write(markup);
+    }
+
+    /// Write the markup that closes an inline element.
+    ///
+    /// The markup is written right after the last word, so the closing tag sticks to the content
+    /// of the element. A pending space is not written here but stays pending, to be written before
+    /// the next word or opening markup.
+    fn close_markup(&mut self, markup: &str) {
+        self.write(markup);
+    }
+
+    /// Write the given text, collapsing whitespace.
+    fn text(&mut self, text: &str) {
+        let mut words = text.split(|c: char| c.is_ascii_whitespace());
+
+        if let Some(word) = words.next() {
+            self.word(word);
+        }
+        for word in words {
+            self.pending_space = true;
+            self.word(word);
+        }
+    }
+
+    /// Write the given word.
+    fn word(&mut self, word: &str) {
+        if word.is_empty() {
+            return;
+        }
+
+        if self.pending_space && !self.line_start {
+            self.write(" ");
+        }
+        self.pending_space = false;
+
+        if self.is_markdown() {
+            let escaped = escape_markdown(word, self.line_start);
+            self.write(&escaped);
+        } else {
+            self.write(word);
+        }
+    }
+
+    /// Write the given nodes.
+    fn children<'a>(&mut self, children: impl Iterator<Item = NodeRef<'a>>) {
+        for child in children {
+            self.node(child);
+        }
+    }
+
+    /// Write the given node.
+    fn node(&mut self, node: NodeRef<'_>) {
+        match node.data() {
+            NodeData::Text(text) => self.text(text),
+            NodeData::Element(data) => self.element(node, data.to_matrix()),
+            _ => {}
+        }
+    }
+
+    /// Write the given element.
+    fn element(&mut self, node: NodeRef<'_>, data: MatrixElementData) {
+        match data.element {
+            MatrixElement::P => {
+                self.block(2);
+                self.children(node.children());
+                self.block(2);
+            }
+            MatrixElement::Div | MatrixElement::Details | MatrixElement::Summary => {
+                self.block(1);
+                self.children(node.children());
+                self.block(1);
+            }
+            MatrixElement::H(heading) => {
+                self.block(2);
+                if self.is_markdown() {
+                    let level = usize::from(heading.level.value());
+                    self.write(&format!("{} ", "#".repeat(level)));
+                }
+                self.children(node.children());
+                self.block(2);
+            }
+            MatrixElement::Blockquote => {
+                self.block(2);
+                self.prefixes.push("> ".to_owned());
+                self.children(node.children());
+                self.prefixes.pop();
+                self.block(2);
+            }
+            MatrixElement::Ul => self.list(node, None),
+            MatrixElement::Ol(list) => self.list(node, Some(list.start.unwrap_or(1))),
+            MatrixElement::Li => self.list_item(node),
+            MatrixElement::Pre => self.code_block(node),
+            MatrixElement::Table => self.table(node),
+            MatrixElement::Hr => {
+                self.block(2);
+                self.write("---");
+                self.block(2);
+            }
+            MatrixElement::Br => self.line_break(),
+            MatrixElement::B | MatrixElement::Strong => self.inline(node, "**"),
+            MatrixElement::I | MatrixElement::Em => self.inline(node, "*"),
+            MatrixElement::Del | MatrixElement::S => self.inline(node, "~~"),
+            MatrixElement::Code(_) => self.inline_code(node),
+            MatrixElement::A(anchor) => {
+                let href = node.as_element().and_then(|element| {
+                    element
+                        .attrs
+                        .iter()
+                        .find(|attr| attr.name.local.as_ref() == "href")
+                        .map(|attr| attr.value.to_string())
+                });
+                let is_mention =
+                    matches!(anchor.href, Some(AnchorUri::Matrix(_) | AnchorUri::MatrixTo(_)));
+
+                match href {
+                    Some(href) => self.link(node, &href, is_mention),
+                    None => self.children(node.children()),
+                }
+            }
+            MatrixElement::Span(span) => match span.spoiler {
+                Some(reason) => {
+                    if self.is_markdown() {
+                        self.inline(node, "||");
+                    } else if reason.is_empty() {
+                        self.word("[Spoiler]");
+                    } else {
+                        self.word(&format!("[Spoiler: {reason}]"));
+                    }
+                }
+                None => self.children(node.children()),
+            },
+            MatrixElement::Img(image) => {
+                let alt = image.alt.or(image.title).map(|alt| alt.to_string()).unwrap_or_default();
+
+                match image.src {
+                    Some(src) if self.is_markdown() => {
+                        self.open_markup("![");
+                        self.text(&alt);
+                        self.close_markup(&format!("]({})", escape_markdown_url(src.as_str())));
+                    }
+                    _ => self.text(&alt),
+                }
+            }
+            MatrixElement::MatrixReply => {}
+            _ => self.children(node.children()),
+        }
+    }
+
+    /// Write an inline element with the given Markdown markup around its content.
+    fn inline(&mut self, node: NodeRef<'_>, markup: &str) {
+        if self.is_markdown() {
+            self.open_markup(markup);
+            self.children(node.children());
+            self.close_markup(markup);
+        } else {
+            self.children(node.children());
+        }
+    }
+
+    /// Write a link.
+    fn link(&mut self, node: NodeRef<'_>, href: &str, is_mention: bool) {
+        let text = text_content(node);
+
+        if self.is_markdown() {
+            if text == href {
+                self.open_markup(&format!("<{href}>"));
+            } else {
+                self.open_markup("[");
+                self.children(node.children());
+                self.close_markup(&format!("]({})", escape_markdown_url(href)));
+            }
+        } else {
+            self.children(node.children());
+
+            if text.is_empty() {
+                self.word(href);
+            } else if !is_mention && text != href {
+                self.pending_space = true;
+                self.word(&format!("({href})"));
+            }
+        }
+    }
+
+    /// Write inline code.
+    fn inline_code(&mut self, node: NodeRef<'_>) {
+        let code = text_content(node).replace('\n', " ");
+
+        if self.is_markdown() {
+            let fence = "`".repeat(longest_run(&code, '`') + 1);
+            let padding = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+            self.open_markup(&format!("{fence}{padding}{code}{padding}{fence}"));
+        } else {
+            self.open_markup(&code);
+        }
+    }
+
+    /// Write a code block.
+    fn code_block(&mut self, node: NodeRef<'_>) {
+        let language = node
+            .children()
+            .find_map(|child| {
+                let element = child.as_element()?.to_matrix();
+                match element.element {
+                    MatrixElement::Code(code) => code.language,
+                    _ => None,
+                }
+            })
+            .map(|language| language.to_string());
+        let code = text_content(node);
+        let code = code.strip_suffix('\n').unwrap_or(&code);
+
+        self.block(2);
+
+        if self.is_markdown() {
+            let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+            self.write(&fence);
+            if let Some(language) = language {
+                self.write(&language);
+            }
+            self.write("\n");
+            self.write(code);
+            self.write("\n");
+            self.write(&fence);
+        } else {
+            self.write(code);
+        }
+
+        self.block(2);
+    }
+
+    /// Write a list.
+    fn list(&mut self, node: NodeRef<'_>, start: Option<i64>) {
+        let is_nested = !self.lists.is_empty();
+
+        self.block(if is_nested { 1 } else { 2 });
+        self.lists.push(List { next_number: start });
+        self.children(node.children());
+        self.lists.pop();
+        self.block(if is_nested { 1 } else { 2 });
+    }
+
+    /// Write a list item.
+    fn list_item(&mut self, node: NodeRef<'_>) {
+        let marker = match self.lists.last_mut() {
+            Some(List { next_number: Some(number) }) => {
+                let marker = format!("{number}. ");
+                *number += 1;
+                marker
+            }
+            _ => "- ".to_owned(),
+        };
+
+        self.block(1);
+        self.line_start = true;
+        self.prefixes.push(" ".repeat(marker.len()));
+        self.pending_marker = Some(marker);
+
+        self.children(node.children());
+
+        // The item is empty, write the marker anyway.
+        if self.pending_marker.is_some() {
+            self.start_content();
+            // Remove the trailing whitespace of the marker.
+            self.out.truncate(self.out.trim_end().len());
+        }
+
+        self.prefixes.pop();
+        self.block(1);
+    }
+
+    /// Write a table.
+    fn table(&mut self, node: NodeRef<'_>) {
+        let mut rows = Vec::new();
+        let mut caption = None;
+
+        for child in node.children() {
+            let Some(element) = child.as_element() else {
+                continue;
+            };
+
+            match element.to_matrix().element {
+                MatrixElement::Caption => caption = Some(child),
+                MatrixElement::Tr => rows.push(child),
+                MatrixElement::Thead | MatrixElement::Tbody => {
+                    rows.extend(child.children().filter(|row| {
+                        row.as_element()
+                            .is_some_and(|row| matches!(row.to_matrix().element, MatrixElement::Tr))
+                    }));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(caption) = caption {
+            self.block(2);
+            self.children(caption.children());
+        }
+
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                row.children()
+                    .filter(|cell| {
+                        cell.as_element().is_some_and(|cell| {
+                            matches!(
+                                cell.to_matrix().element,
+                                MatrixElement::Th | MatrixElement::Td
+                            )
+                        })
+                    })
+                    .map(|cell| {
+                        let mut writer = Writer::new(self.format);
+                        writer.children(cell.children());
+                        writer.finish().replace('\n', " ")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let Some(columns) = rows.iter().map(Vec::len).max().filter(|len| *len > 0) else {
+            return;
+        };
+
+        self.block(2);
+
+        for (i, row) in rows.iter().enumerate() {
+            if i > 0 {
+                self.write("\n");
+            }
+
+            if self.is_markdown() {
+                let cells = (0..columns).map(|i| row.get(i).map(String::as_str).unwrap_or(""));
+                self.write(&format!("| {} |", cells.collect::<Vec<_>>().join(" | ")));
+
+                if i == 0 {
+                    self.write("\n");
+                    self.write(&format!("|{}", " --- |".repeat(columns)));
+                }
+            } else {
+                self.write(&row.join(" | "));
+            }
+        }
+
+        self.block(2);
+    }
+}
+
+/// Get the text content of the given node and its descendants.
+pub(super) fn text_content(node: NodeRef<'_>) -> String {
+    let mut text = String::new();
+
+    for child in node.children() {
+        match child.data() {
+            NodeData::Text(t) => text.push_str(t),
+            NodeData::Element(_) => text.push_str(&text_content(child)),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// Get the length of the longest run of the given character in the string.
+fn longest_run(s: &str, c: char) -> usize {
+    s.split(|ch| ch != c).map(str::len).max().unwrap_or_default()
+}
+
+/// Escape the characters of the given word that have a special meaning in Markdown.
+fn escape_markdown(word: &str, line_start: bool) -> String {
+    let mut escaped = String::with_capacity(word.len());
+
+    // Characters that only have a meaning at the start of a line.
+    let mut chars = word.char_indices().peekable();
+    if line_start {
+        if let Some((_, c @ ('#' | '-' | '+' | '='))) = chars.peek() {
+            escaped.push('\\');
+            escaped.push(*c);
+            chars.next();
+        } else {
+            // Ordered list items.
+            let digits = word.bytes().take_while(u8::is_ascii_digit).count();
+            if digits > 0 && matches!(word.as_bytes().get(digits), Some(b'.' | b')')) {
+                escaped.push_str(&word[..digits]);
+                escaped.push('\\');
+                escaped.push(char::from(word.as_bytes()[digits]));
+                chars = word[digits + 1..].char_indices().peekable();
+            }
+        }
+    }
+
+    for (_, c) in chars {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' | '|' | '!') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Escape the characters of the given URL that would end a Markdown link destination.
+fn escape_markdown_url(url: &str) -> String {
+    url.replace('(', "%28").replace(')', "%29").replace(' ', "%20")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape_markdown_url;
+
+    #[test]
+    fn escape_image_src() {
+        assert_eq!(
+            escape_markdown_url("mxc://notareal.hs/a(b) c"),
+            "mxc://notareal.hs/a%28b%29%20c"
+        );
+    }
+}
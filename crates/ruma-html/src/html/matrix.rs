@@ -13,6 +13,10 @@ use crate::sanitizer_config::clean::{
     ALLOWED_SCHEMES_A_HREF_COMPAT, ALLOWED_SCHEMES_A_HREF_STRICT,
 };
 
+mod mentions;
+
+pub use self::mentions::MentionPill;
+
 const CLASS_LANGUAGE_PREFIX: &str = "language-";
 
 /// The data of a Matrix HTML element.
@@ -0,0 +1,139 @@
+//! Extraction and rewriting of mention pills.
+
+use std::ops::Range;
+
+use ruma_common::{matrix_uri::MatrixId, UserId};
+
+use super::{AnchorUri, MatrixElement};
+use crate::{html::convert::text_content, Children, Html, NodeData, NodeId};
+
+/// A mention pill in an HTML message.
+///
+/// A mention pill is a `<a>` element whose `href` is a `matrix:` or `https://matrix.to` URI
+/// referencing a user, a room or a room alias. Permalinks to events are not mention pills.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct MentionPill {
+    /// The ID of the `<a>` node.
+    pub node: NodeId,
+
+    /// The ID of the mentioned user or room.
+    pub id: MatrixId,
+
+    /// The display text of the pill.
+    pub text: String,
+
+    /// The byte range of the display text in the text content of the HTML.
+    ///
+    /// The text content of the HTML is the concatenation of all its text nodes, without the
+    /// [rich reply fallback].
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub position: Range<usize>,
+}
+
+impl MentionPill {
+    /// The ID of the mentioned user, if this pill mentions a user.
+    pub fn user_id(&self) -> Option<&UserId> {
+        match &self.id {
+            MatrixId::User(user_id) => Some(user_id),
+            _ => None,
+        }
+    }
+}
+
+impl Html {
+    /// Get the mention pills in this HTML, in the order in which they appear.
+    ///
+    /// The mention pills in the [rich reply fallback] are ignored.
+    ///
+    /// [rich reply fallback]: https://spec.matrix.org/latest/client-server-api/#fallbacks-for-rich-replies
+    pub fn mention_pills(&self) -> Vec<MentionPill> {
+        let mut pills = Vec::new();
+        let mut offset = 0;
+        collect_mention_pills(self.children(), &mut offset, &mut pills);
+        pills
+    }
+
+    /// Rewrite the display text of the mention pills in this HTML.
+    ///
+    /// The given closure is called for every mention pill, as returned by
+    /// [`Html::mention_pills()`]. If it returns a new text, the content of the `<a>` element is
+    /// replaced by it. The positions of the pills passed to the closure are the ones before any
+    /// pill was rewritten.
+    ///
+    /// Returns the number of pills that were rewritten.
+    pub fn rewrite_mention_pills<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&MentionPill) -> Option<String>,
+    {
+        let mut count = 0;
+
+        for pill in self.mention_pills() {
+            let Some(text) = f(&pill) else {
+                continue;
+            };
+
+            while let Some(child) =
+                self.node(pill.node).and_then(|node| node.first_child()).map(|child| child.id())
+            {
+                self.detach(child);
+            }
+
+            let text_node = self.create_text(&text);
+            self.append_child(pill.node, text_node);
+            count += 1;
+        }
+
+        count
+    }
+}
+
+/// Collect the mention pills in the given nodes and their descendants.
+///
+/// `offset` is the byte offset of the nodes in the text content of the HTML.
+fn collect_mention_pills(children: Children<'_>, offset: &mut usize, pills: &mut Vec<MentionPill>) {
+    for node in children {
+        let data = match node.data() {
+            NodeData::Text(text) => {
+                *offset += text.len();
+                continue;
+            }
+            NodeData::Element(data) => data,
+            _ => continue,
+        };
+
+        match data.to_matrix().element {
+            MatrixElement::MatrixReply => continue,
+            MatrixElement::A(anchor) => {
+                if let Some(id) = anchor.href.as_ref().and_then(mention_id) {
+                    let text = text_content(node);
+                    let start = *offset;
+                    *offset += text.len();
+
+                    pills.push(MentionPill {
+                        node: node.id(),
+                        id: id.clone(),
+                        text,
+                        position: start..*offset,
+                    });
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        collect_mention_pills(node.children(), offset, pills);
+    }
+}
+
+/// The ID mentioned by the given URI, if it is a mention.
+fn mention_id(uri: &AnchorUri) -> Option<&MatrixId> {
+    let id = match uri {
+        AnchorUri::Matrix(uri) => uri.id(),
+        AnchorUri::MatrixTo(uri) => uri.id(),
+        _ => return None,
+    };
+
+    matches!(id, MatrixId::User(_) | MatrixId::Room(_) | MatrixId::RoomAlias(_)).then_some(id)
+}
@@ -29,6 +29,8 @@ pub use self::{helpers::*, html::*, sanitizer_config::*};
 ///
 /// [HTML elements and attributes]: https://spec.matrix.org/latest/client-server-api/#mroommessage-msgtypes
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[allow(clippy::exhaustive_enums)]
 pub enum HtmlSanitizerMode {
     /// Keep only the elements and attributes suggested in the Matrix specification.
@@ -1,69 +1,111 @@
-#![allow(clippy::disallowed_types)]
-
-use std::collections::{HashMap, HashSet};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
 
 pub(crate) mod clean;
+#[cfg(feature = "serde")]
+mod config_serde;
+mod validate;
 
+pub use self::validate::SanitizerConfigError;
 use crate::HtmlSanitizerMode;
 
+/// The name of an element, attribute, URI scheme or class in the config.
+type Name = Cow<'static, str>;
+
 /// Configuration to sanitize HTML elements and attributes.
+///
+/// With the `serde` feature, this can be (de)serialized, for example to load the config from a
+/// file. The fields have the same names as the methods used to construct the config, and the
+/// changes to lists are objects with a `content` and a `behavior`. The config is [validated] when
+/// it is deserialized.
+///
+/// [validated]: Self::validate()
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(remote = "Self", default, deny_unknown_fields)
+)]
 pub struct SanitizerConfig {
     /// The mode of the sanitizer, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     mode: Option<HtmlSanitizerMode>,
 
     /// Change to the list of elements to replace.
     ///
     /// The content is a map of element name to their replacement's element name.
-    replace_elements: Option<List<HashMap<&'static str, &'static str>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    replace_elements: Option<List<BTreeMap<Name, Name>>>,
 
     /// Elements to remove.
-    remove_elements: Option<HashSet<&'static str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    remove_elements: Option<BTreeSet<Name>>,
 
     /// Whether to remove the rich reply fallback.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "std::ops::Not::not"))]
     remove_reply_fallback: bool,
 
     /// Elements to ignore.
-    ignore_elements: Option<HashSet<&'static str>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    ignore_elements: Option<BTreeSet<Name>>,
 
     /// Change to the list of elements to allow.
-    allow_elements: Option<List<HashSet<&'static str>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    allow_elements: Option<List<BTreeSet<Name>>>,
 
     /// Change to the list of attributes to replace per element.
     ///
     /// The content is a map of element name to a map of attribute name to their replacement's
     /// attribute name.
-    replace_attrs: Option<List<HashMap<&'static str, HashMap<&'static str, &'static str>>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "replace_attributes", skip_serializing_if = "Option::is_none")
+    )]
+    replace_attrs: Option<List<BTreeMap<Name, BTreeMap<Name, Name>>>>,
 
     /// Removed attributes per element.
-    remove_attrs: Option<HashMap<&'static str, HashSet<&'static str>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "remove_attributes", skip_serializing_if = "Option::is_none")
+    )]
+    remove_attrs: Option<BTreeMap<Name, BTreeSet<Name>>>,
 
     /// Change to the list of allowed attributes per element.
-    allow_attrs: Option<List<HashMap<&'static str, HashSet<&'static str>>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "allow_attributes", skip_serializing_if = "Option::is_none")
+    )]
+    allow_attrs: Option<List<BTreeMap<Name, BTreeSet<Name>>>>,
 
     /// Denied URI schemes per attribute per element.
     ///
     /// The content is a map of element name to a map of attribute name to a set of schemes.
-    deny_schemes: Option<HashMap<&'static str, HashMap<&'static str, HashSet<&'static str>>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    deny_schemes: Option<BTreeMap<Name, BTreeMap<Name, BTreeSet<Name>>>>,
 
     /// Change to the list of allowed URI schemes per attribute per element.
     ///
     /// The content is a map of element name to a map of attribute name to a set of schemes.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     #[allow(clippy::type_complexity)]
-    allow_schemes:
-        Option<List<HashMap<&'static str, HashMap<&'static str, HashSet<&'static str>>>>>,
+    allow_schemes: Option<List<BTreeMap<Name, BTreeMap<Name, BTreeSet<Name>>>>>,
 
     /// Removed classes per element.
     ///
     /// The content is a map of element name to a set of classes.
-    remove_classes: Option<HashMap<&'static str, HashSet<&'static str>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    remove_classes: Option<BTreeMap<Name, BTreeSet<Name>>>,
 
     /// Change to the list of allowed classes per element.
     ///
     /// The content is a map of element name to a set of classes.
-    allow_classes: Option<List<HashMap<&'static str, HashSet<&'static str>>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    allow_classes: Option<List<BTreeMap<Name, BTreeSet<Name>>>>,
 
     /// Maximum nesting level of the elements.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     max_depth: Option<u32>,
 }
 
@@ -172,7 +214,7 @@ impl SanitizerConfig {
     ///
     /// * `elements`: The list of element names to remove.
     pub fn remove_elements(mut self, elements: impl IntoIterator<Item = &'static str>) -> Self {
-        self.remove_elements = Some(elements.into_iter().collect());
+        self.remove_elements = Some(elements.into_iter().map(Cow::Borrowed).collect());
         self
     }
 
@@ -201,7 +243,7 @@ impl SanitizerConfig {
     ///
     /// * `elements`: The list of element names to ignore.
     pub fn ignore_elements(mut self, elements: impl IntoIterator<Item = &'static str>) -> Self {
-        self.ignore_elements = Some(elements.into_iter().collect());
+        self.ignore_elements = Some(elements.into_iter().map(Cow::Borrowed).collect());
         self
     }
 
@@ -221,7 +263,7 @@ impl SanitizerConfig {
         elements: impl IntoIterator<Item = &'static str>,
         behavior: ListBehavior,
     ) -> Self {
-        let content = elements.into_iter().collect();
+        let content = elements.into_iter().map(Cow::Borrowed).collect();
         self.allow_elements = Some(List { content, behavior });
         self
     }
@@ -398,6 +440,8 @@ impl SanitizerConfig {
 
 /// A list with a behavior.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
 struct List<T> {
     /// The content of this list.
     content: T,
@@ -415,6 +459,8 @@ impl<T> List<T> {
 
 /// The behavior of the setting.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[allow(clippy::exhaustive_enums)]
 pub enum ListBehavior {
     /// The list replaces the default list of the current mode, if one is set.
@@ -439,8 +485,8 @@ pub struct NameReplacement {
 }
 
 impl NameReplacement {
-    fn to_tuple(self) -> (&'static str, &'static str) {
-        (self.old, self.new)
+    fn to_tuple(self) -> (Name, Name) {
+        (Cow::Borrowed(self.old), Cow::Borrowed(self.new))
     }
 }
 
@@ -455,9 +501,9 @@ pub struct PropertiesNames<'a> {
 }
 
 impl<'a> PropertiesNames<'a> {
-    fn to_tuple(self) -> (&'static str, HashSet<&'static str>) {
-        let set = self.properties.iter().copied().collect();
-        (self.parent, set)
+    fn to_tuple(self) -> (Name, BTreeSet<Name>) {
+        let set = self.properties.iter().copied().map(Cow::Borrowed).collect();
+        (Cow::Borrowed(self.parent), set)
     }
 }
 
@@ -472,9 +518,9 @@ pub struct ElementAttributesReplacement<'a> {
 }
 
 impl<'a> ElementAttributesReplacement<'a> {
-    fn to_tuple(self) -> (&'static str, HashMap<&'static str, &'static str>) {
+    fn to_tuple(self) -> (Name, BTreeMap<Name, Name>) {
         let map = self.replacements.iter().map(|r| r.to_tuple()).collect();
-        (self.element, map)
+        (Cow::Borrowed(self.element), map)
     }
 }
 
@@ -491,8 +537,9 @@ pub struct ElementAttributesSchemes<'a> {
 }
 
 impl<'a> ElementAttributesSchemes<'a> {
-    fn to_tuple(self) -> (&'static str, HashMap<&'static str, HashSet<&'static str>>) {
+    #[allow(clippy::type_complexity)]
+    fn to_tuple(self) -> (Name, BTreeMap<Name, BTreeSet<Name>>) {
         let map = self.attr_schemes.iter().map(|s| s.to_tuple()).collect();
-        (self.element, map)
+        (Cow::Borrowed(self.element), map)
     }
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use html5ever::{tendril::StrTendril, Attribute, LocalName};
 use phf::{phf_map, phf_set, Map, Set};
 use wildmatch::WildMatch;
@@ -123,7 +125,7 @@ impl SanitizerConfig {
                 next_child = html.nodes[child].next_sibling;
 
                 if action == NodeAction::Ignore {
-                    html.insert_before(node_id, child);
+                    html.insert_node_before(node_id, child);
                 }
 
                 self.clean_node(html, child, depth + 1);
@@ -131,7 +133,7 @@ impl SanitizerConfig {
         }
 
         if matches!(action, NodeAction::Ignore | NodeAction::Remove) {
-            html.detach(node_id);
+            html.detach_node(node_id);
         } else if let Some(data) = html.nodes[node_id].as_element_mut() {
             self.clean_element_attributes(data);
         }
@@ -159,8 +161,8 @@ impl SanitizerConfig {
 
                         let attr_replacement = list_replacements
                             .and_then(|s| s.get(attr_name))
-                            .or_else(|| mode_replacements.and_then(|s| s.get(attr_name)))
-                            .copied();
+                            .map(|r| r.as_ref())
+                            .or_else(|| mode_replacements.and_then(|s| s.get(attr_name)).copied());
 
                         if let Some(attr_replacement) = attr_replacement {
                             attr.name.local = LocalName::from(attr_replacement);
@@ -176,7 +178,7 @@ impl SanitizerConfig {
                 .replace_elements
                 .as_ref()
                 .and_then(|list| list.content.get(element_name))
-                .copied();
+                .map(|r| r.as_ref());
 
             if element_replacement.is_none() {
                 let list_is_override = self
@@ -301,9 +303,18 @@ impl SanitizerConfig {
                     let mut allowed_schemes = list_attr_schemes
                         .into_iter()
                         .flatten()
-                        .chain(strict_mode_attr_schemes.map(|set| set.iter()).into_iter().flatten())
+                        .map(Cow::as_ref)
+                        .chain(
+                            strict_mode_attr_schemes
+                                .into_iter()
+                                .flat_map(|set| set.iter())
+                                .map(as_str),
+                        )
                         .chain(
-                            compat_mode_attr_schemes.map(|set| set.iter()).into_iter().flatten(),
+                            compat_mode_attr_schemes
+                                .into_iter()
+                                .flat_map(|set| set.iter())
+                                .map(as_str),
                         );
 
                     // Check if the scheme is allowed.
@@ -392,8 +403,12 @@ impl SanitizerConfig {
                                 .map(|set| set.iter())
                                 .into_iter()
                                 .flatten()
+                                .map(Cow::as_ref)
                                 .chain(
-                                    mode_allow_classes.map(|set| set.iter()).into_iter().flatten(),
+                                    mode_allow_classes
+                                        .into_iter()
+                                        .flat_map(|set| set.iter())
+                                        .map(as_str),
                                 );
 
                             for allow_class in allow_classes {
@@ -442,6 +457,13 @@ impl SanitizerConfig {
     }
 }
 
+/// Get a string slice with a shorter lifetime from the given static string slice.
+///
+/// Used to chain iterators over the static lists with iterators over the lists of the config.
+fn as_str<'a>(s: &&'static str) -> &'a str {
+    s
+}
+
 /// The possible actions to apply to an element node.
 #[derive(Debug, PartialEq, Eq)]
 enum NodeAction {
@@ -0,0 +1,27 @@
+//! `Serialize` and `Deserialize` implementations for `SanitizerConfig`.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::SanitizerConfig;
+
+impl Serialize for SanitizerConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Calls the inherent method generated by `#[serde(remote = "Self")]`.
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SanitizerConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Calls the inherent method generated by `#[serde(remote = "Self")]`.
+        let config = Self::deserialize(deserializer)?;
+        config.validate().map_err(de::Error::custom)?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,184 @@
+//! Validation of a `SanitizerConfig`.
+
+use crate::SanitizerConfig;
+
+impl SanitizerConfig {
+    /// Check that the names, URI schemes and classes in this config are valid.
+    ///
+    /// A name or URI scheme that is not valid would never match, since the HTML parser normalizes
+    /// element and attribute names to lowercase and URI schemes are compared case-sensitively, so
+    /// it is most likely a mistake in the config.
+    ///
+    /// This is called automatically when deserializing a `SanitizerConfig`.
+    pub fn validate(&self) -> Result<(), SanitizerConfigError> {
+        let elements = self
+            .replace_elements
+            .iter()
+            .flat_map(|list| list.content.iter().flat_map(|(old, new)| [old, new]))
+            .chain(self.remove_elements.iter().flatten())
+            .chain(self.ignore_elements.iter().flatten())
+            .chain(self.allow_elements.iter().flat_map(|list| &list.content));
+
+        for element in elements {
+            validate_element(element)?;
+        }
+
+        let attributes = self
+            .replace_attrs
+            .iter()
+            .flat_map(|list| &list.content)
+            .flat_map(|(element, attrs)| {
+                attrs.iter().flat_map(move |(old, new)| [(element, old), (element, new)])
+            })
+            .chain(
+                self.remove_attrs
+                    .iter()
+                    .flatten()
+                    .chain(self.allow_attrs.iter().flat_map(|list| &list.content))
+                    .flat_map(|(element, attrs)| attrs.iter().map(move |attr| (element, attr))),
+            );
+
+        for (element, attribute) in attributes {
+            validate_element(element)?;
+            validate_attribute(element, attribute)?;
+        }
+
+        let schemes = self
+            .deny_schemes
+            .iter()
+            .flatten()
+            .chain(self.allow_schemes.iter().flat_map(|list| &list.content))
+            .flat_map(|(element, attrs)| {
+                attrs.iter().flat_map(move |(attribute, schemes)| {
+                    schemes.iter().map(move |scheme| (element, attribute, scheme))
+                })
+            });
+
+        for (element, attribute, scheme) in schemes {
+            validate_element(element)?;
+            validate_attribute(element, attribute)?;
+
+            if !is_valid_scheme(scheme) {
+                return Err(SanitizerConfigError::InvalidScheme {
+                    element: element.to_string(),
+                    attribute: attribute.to_string(),
+                    scheme: scheme.to_string(),
+                });
+            }
+        }
+
+        let classes = self
+            .remove_classes
+            .iter()
+            .flatten()
+            .chain(self.allow_classes.iter().flat_map(|list| &list.content))
+            .flat_map(|(element, classes)| classes.iter().map(move |class| (element, class)));
+
+        for (element, class) in classes {
+            validate_element(element)?;
+
+            if class.is_empty() || class.contains(char::is_whitespace) {
+                return Err(SanitizerConfigError::InvalidClass {
+                    element: element.to_string(),
+                    class: class.to_string(),
+                });
+            }
+        }
+
+        if self.max_depth == Some(0) {
+            return Err(SanitizerConfigError::ZeroMaxDepth);
+        }
+
+        Ok(())
+    }
+}
+
+/// An error encountered when validating a [`SanitizerConfig`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SanitizerConfigError {
+    /// An element name is invalid.
+    ///
+    /// Element names must only contain lowercase ASCII letters, digits and `-`, and start with a
+    /// letter.
+    #[error("invalid element name `{0}`")]
+    InvalidElementName(String),
+
+    /// An attribute name is invalid.
+    ///
+    /// Attribute names must only contain lowercase ASCII letters, digits, `-`, `_`, `.` and `:`,
+    /// and start with a letter.
+    #[error("invalid attribute name `{attribute}` for element `{element}`")]
+    InvalidAttributeName {
+        /// The name of the element.
+        element: String,
+
+        /// The invalid attribute name.
+        attribute: String,
+    },
+
+    /// A URI scheme is invalid.
+    ///
+    /// URI schemes must only contain lowercase ASCII letters, digits, `+`, `-` and `.`, and start
+    /// with a letter.
+    #[error("invalid URI scheme `{scheme}` for attribute `{attribute}` of element `{element}`")]
+    InvalidScheme {
+        /// The name of the element.
+        element: String,
+
+        /// The name of the attribute.
+        attribute: String,
+
+        /// The invalid URI scheme.
+        scheme: String,
+    },
+
+    /// A class is invalid.
+    ///
+    /// Classes must not be empty or contain whitespace.
+    #[error("invalid class `{class}` for element `{element}`")]
+    InvalidClass {
+        /// The name of the element.
+        element: String,
+
+        /// The invalid class.
+        class: String,
+    },
+
+    /// The maximum nesting level is `0`, which would remove all the elements.
+    #[error("maximum depth must be greater than 0")]
+    ZeroMaxDepth,
+}
+
+fn validate_element(element: &str) -> Result<(), SanitizerConfigError> {
+    if is_valid_name(element, |c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        Ok(())
+    } else {
+        Err(SanitizerConfigError::InvalidElementName(element.to_owned()))
+    }
+}
+
+fn validate_attribute(element: &str, attribute: &str) -> Result<(), SanitizerConfigError> {
+    if is_valid_name(attribute, |c| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.' | ':')
+    }) {
+        Ok(())
+    } else {
+        Err(SanitizerConfigError::InvalidAttributeName {
+            element: element.to_owned(),
+            attribute: attribute.to_owned(),
+        })
+    }
+}
+
+fn is_valid_scheme(scheme: &str) -> bool {
+    is_valid_name(scheme, |c| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.')
+    })
+}
+
+/// Whether the given name starts with a lowercase ASCII letter and all its characters match the
+/// given predicate.
+fn is_valid_name(name: &str, is_valid_char: impl Fn(char) -> bool) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase()) && name.chars().all(is_valid_char)
+}
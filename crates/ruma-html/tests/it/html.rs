@@ -1,4 +1,9 @@
 #[cfg(feature = "matrix")]
+mod convert;
+mod edit;
+#[cfg(feature = "matrix")]
 mod matrix;
+#[cfg(feature = "matrix")]
+mod mentions;
 mod navigate;
 mod sanitize;
@@ -0,0 +1,203 @@
+use ruma_html::Html;
+
+#[test]
+fn paragraphs_and_inline_formatting() {
+    let html = Html::parse(
+        "\
+        <h1>Title</h1>\
+        <p>This is a paragraph <span data-mx-color=\"green\">with some color</span></p>\
+        <p>Look at <s>you </s><font color=\"#0000ff\">me!</font><br>\
+        And <strong>this</strong> is <em>important</em> *really*</p>\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "Title\n\
+        \n\
+        This is a paragraph with some color\n\
+        \n\
+        Look at you me!\n\
+        And this is important *really*"
+    );
+    assert_eq!(
+        html.to_markdown(),
+        "# Title\n\
+        \n\
+        This is a paragraph with some color\n\
+        \n\
+        Look at ~~you~~ me\\!\\\n\
+        And **this** is *important* \\*really\\*"
+    );
+}
+
+#[test]
+fn lists() {
+    let html = Html::parse(
+        "\
+        <ul><li>This</li><li>has</li><li>no</li><li>tag</li></ul>\
+        <ol start=\"3\">\
+            <li>Third</li>\
+            <li>Fourth<ol><li>Nested</li></ol></li>\
+            <li></li>\
+        </ol>\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "- This\n\
+        - has\n\
+        - no\n\
+        - tag\n\
+        \n\
+        3. Third\n\
+        4. Fourth\n   \
+           1. Nested\n\
+        5."
+    );
+    assert_eq!(html.to_plain_text(), html.to_markdown());
+}
+
+#[test]
+fn code() {
+    let html = Html::parse(
+        "\
+        <p>Inline <code>let a = `b`;</code></p>\
+        <pre><code class=\"language-html\">&lt;mx-reply&gt;This is a fake reply&lt;/mx-reply&gt;\n\
+        &lt;p&gt;Hello&lt;/p&gt;\n</code></pre>\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "Inline let a = `b`;\n\
+        \n\
+        <mx-reply>This is a fake reply</mx-reply>\n\
+        <p>Hello</p>"
+    );
+    assert_eq!(
+        html.to_markdown(),
+        "Inline ``let a = `b`;``\n\
+        \n\
+        ```html\n\
+        <mx-reply>This is a fake reply</mx-reply>\n\
+        <p>Hello</p>\n\
+        ```"
+    );
+}
+
+#[test]
+fn blockquote_and_reply_fallback() {
+    let html = Html::parse(
+        "\
+        <mx-reply>\
+            <blockquote>\
+                <a href=\"https://matrix.to/#/!n8f893n9:example.com/$1598361704261elfgc:localhost\">In reply to</a> \
+                <a href=\"https://matrix.to/#/@alice:example.com\">@alice:example.com</a>\
+                <br>\
+                Previous message\
+            </blockquote>\
+        </mx-reply>\
+        <blockquote><p>Quote</p><p>On two paragraphs</p></blockquote>\
+        <p>Answer</p>\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "> Quote\n\
+        >\n\
+        > On two paragraphs\n\
+        \n\
+        Answer"
+    );
+    assert_eq!(html.to_plain_text(), html.to_markdown());
+}
+
+#[test]
+fn links_and_mentions() {
+    let html = Html::parse(
+        "\
+        <p>Hello <a href=\"https://matrix.to/#/@alice:example.com\">Alice</a>, \
+        look at <a href=\"https://ruma.dev/\">the website</a> or \
+        <a href=\"https://ruma.dev/\">https://ruma.dev/</a>.</p>\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "Hello Alice, look at the website (https://ruma.dev/) or https://ruma.dev/."
+    );
+    assert_eq!(
+        html.to_markdown(),
+        "Hello [Alice](https://matrix.to/#/@alice:example.com), \
+        look at [the website](https://ruma.dev/) or <https://ruma.dev/>."
+    );
+}
+
+#[test]
+fn spoilers_and_images() {
+    let html = Html::parse(
+        "\
+        <p>The killer is <span data-mx-spoiler=\"\">the butler</span>, \
+        the weapon is <span data-mx-spoiler=\"weapon\">the candlestick</span>.</p>\
+        <img src=\"mxc://notareal.hs/abcdef\" alt=\"A cat\">\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "The killer is [Spoiler], the weapon is [Spoiler: weapon].\n\
+        \n\
+        A cat"
+    );
+    assert_eq!(
+        html.to_markdown(),
+        "The killer is ||the butler||, the weapon is ||the candlestick||.\n\
+        \n\
+        ![A cat](mxc://notareal.hs/abcdef)"
+    );
+}
+
+#[test]
+fn image_src_with_markdown_syntax() {
+    // The source is not a valid MXC URI, so only the alternative text is kept.
+    let html = Html::parse("<img src=\"mxc://notareal.hs/a(b) c\" alt=\"A cat\">");
+
+    assert_eq!(html.to_markdown(), "A cat");
+}
+
+#[test]
+fn tables() {
+    let html = Html::parse(
+        "\
+        <table>\
+            <caption>Scores</caption>\
+            <thead><tr><th>Name</th><th>Score</th></tr></thead>\
+            <tbody>\
+                <tr><td><b>Alice</b></td><td>10</td></tr>\
+                <tr><td>Bob | Carl</td></tr>\
+            </tbody>\
+        </table>\
+        ",
+    );
+
+    assert_eq!(
+        html.to_plain_text(),
+        "Scores\n\
+        \n\
+        Name | Score\n\
+        Alice | 10\n\
+        Bob | Carl"
+    );
+    assert_eq!(
+        html.to_markdown(),
+        "Scores\n\
+        \n\
+        | Name | Score |\n\
+        | --- | --- |\n\
+        | **Alice** | 10 |\n\
+        | Bob \\| Carl |  |"
+    );
+}
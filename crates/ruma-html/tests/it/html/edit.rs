@@ -0,0 +1,145 @@
+use ruma_html::{Html, NodeData, NodeId, TransformAction};
+
+#[test]
+fn insert_detach_replace() {
+    let mut html = Html::parse("<p>First</p><p>Second</p>");
+    let first = html.first_child().unwrap().id();
+    let second = html.last_child().unwrap().id();
+
+    let title = html.create_element("h1");
+    let title_text = html.create_text("Title");
+    html.append_child(title, title_text);
+    html.insert_before(first, title);
+    assert_eq!(html.to_string(), "<h1>Title</h1><p>First</p><p>Second</p>");
+
+    let hr = html.create_element("hr");
+    html.insert_after(first, hr);
+    let end = html.create_text("The end");
+    html.append_to_root(end);
+    assert_eq!(html.to_string(), "<h1>Title</h1><p>First</p><hr><p>Second</p>The end");
+
+    html.detach(hr);
+    let div = html.create_element("div");
+    html.replace(second, div);
+    html.append_child(div, second);
+    assert_eq!(html.to_string(), "<h1>Title</h1><p>First</p><div><p>Second</p></div>The end");
+
+    // Move an existing node.
+    html.insert_after(div, title);
+    html.unwrap(div);
+    assert_eq!(html.to_string(), "<p>First</p><p>Second</p><h1>Title</h1>The end");
+
+    // Detached nodes are not accessible from the tree anymore.
+    assert!(html.node(hr).unwrap().parent().is_none());
+    assert!(html.node(hr).unwrap().next_sibling().is_none());
+}
+
+#[test]
+#[should_panic = "cannot insert a node inside itself"]
+fn insert_node_inside_itself() {
+    let mut html = Html::parse("<div><p>Text</p></div>");
+    let div = html.first_child().unwrap().id();
+    let p = html.first_child().unwrap().first_child().unwrap().id();
+
+    html.append_child(p, div);
+}
+
+#[test]
+fn set_attributes() {
+    let mut html = Html::parse("<a href=\"https://example.org\">Link</a>");
+    let link = html.first_child().unwrap().id();
+
+    let element = html.element_mut(link).unwrap();
+    assert_eq!(element.attribute("href").unwrap().as_ref(), "https://example.org");
+
+    element.set_attribute("href", "https://ruma.dev");
+    element.set_attribute("target", "_blank");
+    assert_eq!(html.to_string(), "<a href=\"https://ruma.dev\" target=\"_blank\">Link</a>");
+
+    let element = html.element_mut(link).unwrap();
+    assert_eq!(element.remove_attribute("target").unwrap().as_ref(), "_blank");
+    assert_eq!(element.remove_attribute("target"), None);
+    assert_eq!(html.to_string(), "<a href=\"https://ruma.dev\">Link</a>");
+
+    let text = html.first_child().unwrap().first_child().unwrap().id();
+    assert!(html.element_mut(text).is_none());
+    html.text_mut(text).unwrap().push_slice(" to Ruma");
+    assert_eq!(html.to_string(), "<a href=\"https://ruma.dev\">Link to Ruma</a>");
+}
+
+#[test]
+fn wrap_text() {
+    let mut html = Html::parse("<p>Hello world, héhé!</p>");
+    let text = html.first_child().unwrap().first_child().unwrap().id();
+
+    let strong = html.create_element("strong");
+    let wrapped = html.wrap_text(text, 6..11, strong).unwrap();
+    assert_eq!(html.to_string(), "<p>Hello <strong>world</strong>, héhé!</p>");
+    assert_eq!(html.node(wrapped).unwrap().as_text().unwrap().as_ref(), "world");
+
+    // Not on a char boundary.
+    let em = html.create_element("em");
+    let end = html.node(strong).unwrap().next_sibling().unwrap().id();
+    assert_eq!(html.wrap_text(end, 2..4, em), None);
+    // Empty range.
+    assert_eq!(html.wrap_text(end, 2..2, em), None);
+    // Out of bounds.
+    assert_eq!(html.wrap_text(end, 2..20, em), None);
+    // Not a text node.
+    assert_eq!(html.wrap_text(strong, 0..1, em), None);
+
+    // The whole text.
+    let wrapped = html.wrap_text(end, 0..9, em).unwrap();
+    assert_eq!(wrapped, end);
+    assert_eq!(html.to_string(), "<p>Hello <strong>world</strong><em>, héhé!</em></p>");
+}
+
+#[test]
+fn transform() {
+    let mut html = Html::parse(
+        "\
+        <p>\
+            Hello <a href=\"https://matrix.to/#/@alice:example.com\">Alice</a>, \
+            look at <a href=\"http://example.org\">this</a>:\
+        </p>\
+        <p><img src=\"mxc://notareal.hs/abcdef\"></p>\
+        <pre><code><a href=\"http://example.org\">not a link</a></code></pre>\
+        ",
+    );
+
+    html.transform(&mut |html: &mut Html, node: NodeId| {
+        let Some(NodeData::Element(element)) = html.node(node).map(|node| node.data()) else {
+            return TransformAction::Continue;
+        };
+
+        match element.name.local.as_ref() {
+            "img" => TransformAction::Remove,
+            "pre" => TransformAction::SkipChildren,
+            "a" => {
+                let href = element.attribute("href").unwrap();
+
+                if href.starts_with("https://matrix.to/") {
+                    // Replace mention pills by their text.
+                    TransformAction::Unwrap
+                } else {
+                    let href = href.replace("http:", "https:");
+                    html.element_mut(node).unwrap().set_attribute("href", href);
+                    TransformAction::Continue
+                }
+            }
+            _ => TransformAction::Continue,
+        }
+    });
+
+    assert_eq!(
+        html.to_string(),
+        "\
+        <p>\
+            Hello Alice, \
+            look at <a href=\"https://example.org\">this</a>:\
+        </p>\
+        <p></p>\
+        <pre><code><a href=\"http://example.org\">not a link</a></code></pre>\
+        "
+    );
+}
@@ -0,0 +1,66 @@
+use assert_matches2::assert_matches;
+use ruma_common::{matrix_uri::MatrixId, room_alias_id, user_id};
+use ruma_html::Html;
+
+#[test]
+fn mention_pills() {
+    let html = Html::parse(
+        "\
+        <mx-reply><blockquote>\
+            <a href=\"https://matrix.to/#/@bob:localhost\">Bob</a> said hello\
+        </blockquote></mx-reply>\
+        <p>Hello <a href=\"https://matrix.to/#/@alice:localhost\">Alice</a>, \
+        <a href=\"https://matrix.to/#/!room:localhost/$event\">look at this</a> in \
+        <a href=\"matrix:r/room:localhost\"><strong>#room</strong></a> \
+        with <a href=\"matrix:u/carl:localhost?action=chat\">Carl</a>!</p>\
+        ",
+    );
+
+    let pills = html.mention_pills();
+    assert_eq!(pills.len(), 3);
+
+    let pill = &pills[0];
+    assert_eq!(pill.user_id(), Some(user_id!("@alice:localhost")));
+    assert_eq!(pill.text, "Alice");
+    assert_eq!(pill.position, 6..11);
+
+    let pill = &pills[1];
+    assert_matches!(&pill.id, MatrixId::RoomAlias(alias));
+    assert_eq!(alias, room_alias_id!("#room:localhost"));
+    assert_eq!(pill.user_id(), None);
+    assert_eq!(pill.text, "#room");
+    assert_eq!(pill.position, 29..34);
+
+    let pill = &pills[2];
+    assert_eq!(pill.user_id(), Some(user_id!("@carl:localhost")));
+    assert_eq!(pill.text, "Carl");
+    assert_eq!(pill.position, 40..44);
+
+    assert_eq!(
+        html.node(pill.node).unwrap().first_child().unwrap().as_text().unwrap().as_ref(),
+        "Carl"
+    );
+}
+
+#[test]
+fn rewrite_mention_pills() {
+    let mut html = Html::parse(
+        "\
+        <p>Hello <a href=\"https://matrix.to/#/@alice:localhost\">Alice</a> and \
+        <a href=\"https://matrix.to/#/@bob:localhost\"><em>Bob</em></a>!</p>\
+        ",
+    );
+
+    let count = html.rewrite_mention_pills(|pill| {
+        (pill.user_id()? == user_id!("@bob:localhost")).then(|| "Robert".to_owned())
+    });
+
+    assert_eq!(count, 1);
+    assert_eq!(
+        html.to_string(),
+        "\
+        <p>Hello <a href=\"https://matrix.to/#/@alice:localhost\">Alice</a> and \
+        <a href=\"https://matrix.to/#/@bob:localhost\">Robert</a>!</p>\
+        "
+    );
+}
@@ -1,6 +1,7 @@
+use assert_matches2::assert_matches;
 use ruma_html::{
     ElementAttributesReplacement, ElementAttributesSchemes, Html, ListBehavior, NameReplacement,
-    PropertiesNames, SanitizerConfig,
+    PropertiesNames, SanitizerConfig, SanitizerConfigError,
 };
 
 #[test]
@@ -847,3 +848,148 @@ fn remove_classes() {
         "
     );
 }
+
+#[test]
+fn validate_config() {
+    let config = SanitizerConfig::strict()
+        .allow_elements(["mx-custom"], ListBehavior::Add)
+        .allow_attributes(
+            [PropertiesNames { parent: "span", properties: &["data-mx-custom"] }],
+            ListBehavior::Add,
+        )
+        .allow_schemes(
+            [ElementAttributesSchemes {
+                element: "a",
+                attr_schemes: &[PropertiesNames { parent: "href", properties: &["web+custom"] }],
+            }],
+            ListBehavior::Add,
+        );
+    config.validate().unwrap();
+
+    let config = SanitizerConfig::new().remove_elements(["SCRIPT"]);
+    assert_matches!(config.validate(), Err(SanitizerConfigError::InvalidElementName(name)));
+    assert_eq!(name, "SCRIPT");
+
+    let config = SanitizerConfig::new()
+        .remove_attributes([PropertiesNames { parent: "span", properties: &["data mx"] }]);
+    assert_matches!(
+        config.validate(),
+        Err(SanitizerConfigError::InvalidAttributeName { element, attribute })
+    );
+    assert_eq!(element, "span");
+    assert_eq!(attribute, "data mx");
+
+    let config = SanitizerConfig::new().deny_schemes([ElementAttributesSchemes {
+        element: "a",
+        attr_schemes: &[PropertiesNames { parent: "href", properties: &["javascript:"] }],
+    }]);
+    assert_matches!(config.validate(), Err(SanitizerConfigError::InvalidScheme { scheme, .. }));
+    assert_eq!(scheme, "javascript:");
+
+    let config = SanitizerConfig::new().max_depth(0);
+    assert_matches!(config.validate(), Err(SanitizerConfigError::ZeroMaxDepth));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn deserialize_config() {
+    use serde_json::json;
+
+    let config: SanitizerConfig = serde_json::from_value(json!({
+        "mode": "compat",
+        "remove_reply_fallback": true,
+        "replace_elements": {
+            "content": { "center": "p" },
+            "behavior": "add",
+        },
+        "remove_elements": ["script"],
+        "remove_attributes": { "span": ["data-mx-bg-color"] },
+        "deny_schemes": { "a": { "href": ["http"] } },
+        "max_depth": 3,
+    }))
+    .unwrap();
+
+    let mut html = Html::parse(
+        "\
+        <mx-reply>This is a reply</mx-reply>\
+        <center>Centered <span data-mx-bg-color=\"red\" data-mx-color=\"green\">text</span></center>\
+        <script>alert(1)</script>\
+        <a href=\"http://localhost\">Insecure</a> <a href=\"matrix:u/alice:localhost\">Alice</a>\
+        <div><div><div><div>Too deep</div></div></div></div>\
+        ",
+    );
+    html.sanitize_with(&config);
+
+    assert_eq!(
+        html.to_string(),
+        "\
+        <p>Centered <span data-mx-color=\"green\">text</span></p>\
+        Insecure <a href=\"matrix:u/alice:localhost\">Alice</a>\
+        <div><div><div></div></div></div>\
+        "
+    );
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn deserialize_invalid_config() {
+    use serde_json::json;
+
+    let err =
+        serde_json::from_value::<SanitizerConfig>(json!({ "allow_element": ["p"] })).unwrap_err();
+    assert!(err.to_string().contains("unknown field `allow_element`"), "{err}");
+
+    let err = serde_json::from_value::<SanitizerConfig>(json!({
+        "allow_elements": { "content": ["p"], "behavior": "append" },
+    }))
+    .unwrap_err();
+    assert!(err.to_string().contains("unknown variant `append`"), "{err}");
+
+    let err = serde_json::from_value::<SanitizerConfig>(json!({
+        "allow_classes": { "content": { "code": ["language rust"] }, "behavior": "add" },
+    }))
+    .unwrap_err();
+    assert_eq!(err.to_string(), "invalid class `language rust` for element `code`");
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serialize_config_roundtrip() {
+    use serde_json::json;
+
+    let config = SanitizerConfig::strict()
+        .remove_reply_fallback()
+        .allow_elements(["mx-custom"], ListBehavior::Add)
+        .replace_attributes(
+            [ElementAttributesReplacement {
+                element: "font",
+                replacements: &[NameReplacement { old: "bgcolor", new: "data-mx-bg-color" }],
+            }],
+            ListBehavior::Override,
+        )
+        .allow_schemes(
+            [ElementAttributesSchemes {
+                element: "a",
+                attr_schemes: &[PropertiesNames { parent: "href", properties: &["matrix", "geo"] }],
+            }],
+            ListBehavior::Add,
+        );
+
+    let json = json!({
+        "mode": "strict",
+        "remove_reply_fallback": true,
+        "allow_elements": { "content": ["mx-custom"], "behavior": "add" },
+        "replace_attributes": {
+            "content": { "font": { "bgcolor": "data-mx-bg-color" } },
+            "behavior": "override",
+        },
+        "allow_schemes": {
+            "content": { "a": { "href": ["geo", "matrix"] } },
+            "behavior": "add",
+        },
+    });
+    assert_eq!(serde_json::to_value(&config).unwrap(), json);
+
+    let config = serde_json::from_value::<SanitizerConfig>(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&config).unwrap(), json);
+}
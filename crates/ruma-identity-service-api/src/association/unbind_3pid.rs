@@ -69,6 +69,7 @@ pub mod v2 {
 
     /// A 3PID to unbind.
     #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ThirdPartyId {
         /// A medium matching the medium of identifier to unbind.
@@ -90,6 +91,7 @@ pub mod v2 {
     /// Must be constructed using the same session ID and client secret generated and passed by the
     /// `requestToken` call for the given 3PID.
     #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct ThreePidOwnershipProof {
         /// The Session ID generated by the `requestToken` call.
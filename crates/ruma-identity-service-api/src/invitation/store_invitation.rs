@@ -171,8 +171,33 @@ pub mod v2 {
         }
     }
 
+    #[cfg(feature = "json-schema")]
+    mod json_schema {
+        use std::borrow::Cow;
+
+        use ruma_common::{
+            exports::serde_json::Value as JsonValue,
+            json_schema::{private::tuple, JsonSchema, SchemaGenerator},
+        };
+
+        use super::{PublicKey, PublicKeys};
+
+        impl JsonSchema for PublicKeys {
+            fn schema_name() -> Cow<'static, str> {
+                Cow::Borrowed("PublicKeys")
+            }
+
+            fn json_schema(generator: &mut SchemaGenerator) -> JsonValue {
+                // The server's long-term public key, followed by the ephemeral public key.
+                let key = generator.subschema_for::<PublicKey>();
+                tuple(vec![key.clone(), key], 2)
+            }
+        }
+    }
+
     /// A server's long-term or ephemeral public key.
     #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[non_exhaustive]
     pub struct PublicKey {
         /// The public key, encoded using [unpadded Base64](https://spec.matrix.org/latest/appendices/#unpadded-base64).
@@ -15,6 +15,8 @@ pub mod discovery;
 pub mod invitation;
 pub mod keys;
 pub mod lookup;
+#[cfg(all(feature = "client", feature = "json-schema"))]
+pub mod openapi;
 pub mod tos;
 
 // Wrapper around `Box<str>` that cannot be used in a meaningful way outside of
@@ -0,0 +1,39 @@
+//! Generation of an [OpenAPI] document for the endpoints of the identity service API.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+
+use ruma_common::api::openapi::OpenApiDocument;
+
+/// Add all the endpoints of the identity service API to the given OpenAPI document.
+///
+/// Endpoints that are behind features that are not enabled are not added.
+#[allow(deprecated)]
+pub fn add_endpoints(document: &mut OpenApiDocument) {
+    document.add_endpoint::<crate::association::bind_3pid::v2::Request>();
+    document.add_endpoint::<crate::association::check_3pid_validity::v2::Request>();
+    document
+        .add_endpoint::<crate::association::email::create_email_validation_session::v2::Request>();
+    document.add_endpoint::<crate::association::email::validate_email::v2::Request>();
+    document.add_endpoint::<crate::association::email::validate_email_by_end_user::v2::Request>();
+    document
+        .add_endpoint::<crate::association::msisdn::create_msisdn_validation_session::v2::Request>(
+        );
+    document.add_endpoint::<crate::association::msisdn::validate_msisdn::v2::Request>();
+    document
+        .add_endpoint::<crate::association::msisdn::validate_msisdn_by_phone_number::v2::Request>();
+    document.add_endpoint::<crate::association::unbind_3pid::v2::Request>();
+    document.add_endpoint::<crate::authentication::get_account_information::v2::Request>();
+    document.add_endpoint::<crate::authentication::logout::v2::Request>();
+    document.add_endpoint::<crate::authentication::register::v2::Request>();
+    document.add_endpoint::<crate::discovery::get_server_status::v2::Request>();
+    document.add_endpoint::<crate::discovery::get_supported_versions::Request>();
+    document.add_endpoint::<crate::invitation::sign_invitation_ed25519::v2::Request>();
+    document.add_endpoint::<crate::invitation::store_invitation::v2::Request>();
+    document.add_endpoint::<crate::keys::check_public_key_validity::v2::Request>();
+    document.add_endpoint::<crate::keys::get_public_key::v2::Request>();
+    document.add_endpoint::<crate::keys::validate_ephemeral_key::v2::Request>();
+    document.add_endpoint::<crate::lookup::get_hash_parameters::v2::Request>();
+    document.add_endpoint::<crate::lookup::lookup_3pid::v2::Request>();
+    document.add_endpoint::<crate::tos::accept_terms_of_service::v2::Request>();
+    document.add_endpoint::<crate::tos::get_terms_of_service::v2::Request>();
+}
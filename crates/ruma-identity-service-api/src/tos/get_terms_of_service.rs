@@ -55,6 +55,7 @@ pub mod v2 {
 
     /// Collection of localized policies.
     #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct Policies {
         /// The version for the policy.
@@ -80,6 +81,7 @@ pub mod v2 {
 
     /// A localized policy offered by a server.
     #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(ruma_common::json_schema::JsonSchema))]
     #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
     pub struct LocalizedPolicy {
         /// The localized name of the policy.
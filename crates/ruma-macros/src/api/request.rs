@@ -10,7 +10,10 @@ use super::{
     attribute::{DeriveRequestMeta, RequestMeta},
     ensure_feature_presence,
 };
-use crate::util::{import_ruma_common, PrivateField};
+use crate::{
+    serde::json_schema::{expand_field_schema, expand_fields_schema},
+    util::{import_ruma_common, PrivateField},
+};
 
 mod incoming;
 mod outgoing;
@@ -77,7 +80,7 @@ pub fn expand_derive_request(input: DeriveInput) -> syn::Result<TokenStream> {
 
     let ruma_common = import_ruma_common();
     let test = request.check(&ruma_common)?;
-    let types_impls = request.expand_all(&ruma_common);
+    let types_impls = request.expand_all(&ruma_common)?;
 
     Ok(quote! {
         #types_impls
@@ -141,7 +144,7 @@ impl Request {
         self.fields.iter().find_map(RequestField::as_query_map_field)
     }
 
-    fn expand_all(&self, ruma_common: &TokenStream) -> TokenStream {
+    fn expand_all(&self, ruma_common: &TokenStream) -> syn::Result<TokenStream> {
         let ruma_macros = quote! { #ruma_common::exports::ruma_macros };
         let serde = quote! { #ruma_common::exports::serde };
 
@@ -186,10 +189,14 @@ impl Request {
 
         let outgoing_request_impl = self.expand_outgoing(ruma_common);
         let incoming_request_impl = self.expand_incoming(ruma_common);
+        let json_schema_impl = cfg!(feature = "json-schema")
+            .then(|| self.expand_json_schema(ruma_common))
+            .transpose()?;
 
-        quote! {
+        Ok(quote! {
             #request_body_struct
             #request_query_struct
+            #json_schema_impl
 
             #[allow(deprecated)]
             mod __request_impls {
@@ -197,7 +204,76 @@ impl Request {
                 #outgoing_request_impl
                 #incoming_request_impl
             }
-        }
+        })
+    }
+
+    /// Generate the `RequestSchema` implementation of the request.
+    fn expand_json_schema(&self, ruma_common: &TokenStream) -> syn::Result<TokenStream> {
+        let json_schema = quote! { #ruma_common::json_schema };
+        let serde_json = quote! { #ruma_common::exports::serde_json };
+
+        let path_parameters = self
+            .path_fields()
+            .map(|field| {
+                let name = field.ident.as_ref().expect("path fields have a name").to_string();
+                let schema = expand_field_schema(field, ruma_common)?;
+                Ok(quote! { (#name, #schema) })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let query_schema = if self.query_map_field().is_some() {
+            quote! {
+                ::std::option::Option::Some(#serde_json::json!({
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                }))
+            }
+        } else if self.has_query_fields() {
+            let schema = expand_fields_schema(
+                self.fields.iter().filter_map(RequestField::as_query_field),
+                false,
+                ruma_common,
+            )?;
+            quote! { ::std::option::Option::Some(#schema) }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+
+        let body_schema = if self.raw_body_field().is_some() {
+            quote! { ::std::option::Option::Some(#json_schema::BodySchema::Binary) }
+        } else if self.has_body_fields() {
+            let schema =
+                expand_fields_schema(self.body_fields(), self.has_newtype_body(), ruma_common)?;
+            quote! { ::std::option::Option::Some(#json_schema::BodySchema::Json(#schema)) }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        Ok(quote! {
+            #[automatically_derived]
+            #[allow(deprecated, unused_variables)]
+            impl #impl_generics #json_schema::RequestSchema for Request #ty_generics #where_clause {
+                fn path_parameters_schemas(
+                    generator: &mut #json_schema::SchemaGenerator,
+                ) -> ::std::vec::Vec<(&'static ::std::primitive::str, #serde_json::Value)> {
+                    ::std::vec![#( #path_parameters ),*]
+                }
+
+                fn query_schema(
+                    generator: &mut #json_schema::SchemaGenerator,
+                ) -> ::std::option::Option<#serde_json::Value> {
+                    #query_schema
+                }
+
+                fn body_schema(
+                    generator: &mut #json_schema::SchemaGenerator,
+                ) -> ::std::option::Option<#json_schema::BodySchema> {
+                    #body_schema
+                }
+            }
+        })
     }
 
     pub(super) fn check(&self, ruma_common: &TokenStream) -> syn::Result<TokenStream> {
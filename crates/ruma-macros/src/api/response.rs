@@ -13,7 +13,10 @@ use super::{
     attribute::{DeriveResponseMeta, ResponseMeta},
     ensure_feature_presence,
 };
-use crate::util::{import_ruma_common, PrivateField};
+use crate::{
+    serde::json_schema::expand_fields_schema,
+    util::{import_ruma_common, PrivateField},
+};
 
 mod incoming;
 mod outgoing;
@@ -95,7 +98,7 @@ pub fn expand_derive_response(input: DeriveInput) -> syn::Result<TokenStream> {
     };
 
     response.check()?;
-    Ok(response.expand_all())
+    response.expand_all()
 }
 
 struct Response {
@@ -130,7 +133,7 @@ impl Response {
         self.fields.iter().any(|f| matches!(&f.kind, &ResponseFieldKind::Header(_)))
     }
 
-    fn expand_all(&self) -> TokenStream {
+    fn expand_all(&self) -> syn::Result<TokenStream> {
         let ruma_common = import_ruma_common();
         let ruma_macros = quote! { #ruma_common::exports::ruma_macros };
         let serde = quote! { #ruma_common::exports::serde };
@@ -160,12 +163,50 @@ impl Response {
         let outgoing_response_impl = self.expand_outgoing(&self.status_ident, &ruma_common);
         let incoming_response_impl = self.expand_incoming(&self.error_ty, &ruma_common);
 
-        quote! {
+        let json_schema_impl = cfg!(feature = "json-schema")
+            .then(|| self.expand_json_schema(&ruma_common))
+            .transpose()?;
+
+        Ok(quote! {
             #response_body_struct
 
             #outgoing_response_impl
             #incoming_response_impl
-        }
+            #json_schema_impl
+        })
+    }
+
+    /// Generate the `ResponseSchema` implementation of the response.
+    fn expand_json_schema(&self, ruma_common: &TokenStream) -> syn::Result<TokenStream> {
+        let json_schema = quote! { #ruma_common::json_schema };
+        let http = quote! { #ruma_common::exports::http };
+        let status_ident = &self.status_ident;
+
+        let body_schema = if self.has_raw_body() {
+            quote! { ::std::option::Option::Some(#json_schema::BodySchema::Binary) }
+        } else {
+            // The body is always JSON, even if it is an empty object.
+            let schema = expand_fields_schema(
+                self.fields.iter().filter_map(ResponseField::as_body_field),
+                self.has_newtype_body(),
+                ruma_common,
+            )?;
+            quote! { ::std::option::Option::Some(#json_schema::BodySchema::Json(#schema)) }
+        };
+
+        Ok(quote! {
+            #[automatically_derived]
+            #[allow(deprecated, unused_variables)]
+            impl #json_schema::ResponseSchema for Response {
+                const STATUS: #http::StatusCode = #http::StatusCode::#status_ident;
+
+                fn body_schema(
+                    generator: &mut #json_schema::SchemaGenerator,
+                ) -> ::std::option::Option<#json_schema::BodySchema> {
+                    #body_schema
+                }
+            }
+        })
     }
 
     pub fn check(&self) -> syn::Result<()> {
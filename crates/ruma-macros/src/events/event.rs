@@ -2,13 +2,13 @@
 
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{parse_quote, Data, DataStruct, DeriveInput, Field, Fields, FieldsNamed};
+use syn::{parse_quote, Data, DataStruct, DeriveInput, Field, Fields, FieldsNamed, Type};
 
 use super::{
     event_parse::{to_kind_variation, EventKind, EventKindVariation},
     util::is_non_stripped_room_event,
 };
-use crate::{import_ruma_events, util::to_camel_case};
+use crate::{import_ruma_events, serde::json_schema::expand_field_schema, util::to_camel_case};
 
 /// Derive `Event` macro code generation.
 pub fn expand_event(input: DeriveInput) -> syn::Result<TokenStream> {
@@ -54,6 +54,13 @@ pub fn expand_event(input: DeriveInput) -> syn::Result<TokenStream> {
         res.extend(expand_eq_ord_event(&input));
     }
 
+    if cfg!(feature = "json-schema") {
+        res.extend(
+            expand_json_schema_event(&input, var, &fields, &ruma_events)
+                .unwrap_or_else(syn::Error::into_compile_error),
+        );
+    }
+
     Ok(res)
 }
 
@@ -319,3 +326,90 @@ fn expand_eq_ord_event(input: &DeriveInput) -> TokenStream {
         }
     }
 }
+
+fn expand_json_schema_event(
+    input: &DeriveInput,
+    var: EventKindVariation,
+    fields: &[Field],
+    ruma_events: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let ruma_common = quote! { #ruma_events::exports::ruma_common };
+    let json_schema = quote! { #ruma_common::json_schema };
+    let serde_json = quote! { #ruma_events::exports::serde_json };
+
+    let ident = &input.ident;
+    let name = ident.to_string();
+    // we know there is a content field already
+    let content_type = &fields
+        .iter()
+        // we also know that the fields are named and have an ident
+        .find(|f| f.ident.as_ref().unwrap() == "content")
+        .unwrap()
+        .ty;
+
+    let properties = fields
+        .iter()
+        .map(|field| {
+            let name = field.ident.as_ref().unwrap();
+            let name_s = name.to_string();
+            let required = !(name == "unsigned" && !var.is_redacted()
+                || name == "state_key" && var == EventKindVariation::Initial);
+            let schema = expand_field_schema(field, &ruma_common)?;
+
+            Ok(quote! {
+                object.property(#name_s, #schema, #required);
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut generics = input.generics.clone();
+    let schema_name = if generics.params.is_empty() {
+        quote! { ::std::borrow::Cow::Borrowed(#name) }
+    } else {
+        let where_clause = generics.make_where_clause();
+        where_clause.predicates.push(parse_quote! {
+            #content_type: #ruma_events::StaticEventContent + #json_schema::JsonSchema
+        });
+        // Other generic fields are associated types of the content, like `C::StateKey`.
+        for field in fields {
+            let ty = &field.ty;
+            if matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.segments.len() > 1 && path.path.segments[0].ident == "C")
+            {
+                where_clause.predicates.push(parse_quote! { #ty: #json_schema::JsonSchema });
+            }
+        }
+
+        let name = format!("{name}_{{}}");
+        quote! {
+            ::std::borrow::Cow::Owned(::std::format!(
+                #name,
+                <#content_type as #json_schema::JsonSchema>::schema_name(),
+            ))
+        }
+    };
+    let (impl_generics, ty_gen, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #json_schema::JsonSchema for #ident #ty_gen #where_clause {
+            fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                #schema_name
+            }
+
+            fn json_schema(
+                generator: &mut #json_schema::SchemaGenerator,
+            ) -> #serde_json::Value {
+                let mut object = #json_schema::private::ObjectSchema::new();
+                object.property(
+                    "type",
+                    #json_schema::private::event_type(
+                        <#content_type as #ruma_events::StaticEventContent>::TYPE,
+                    ),
+                    true,
+                );
+                #( #properties )*
+                object.finish()
+            }
+        }
+    })
+}
@@ -6,14 +6,18 @@ use std::{borrow::Cow, fmt};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream},
     parse_quote,
     punctuated::Punctuated,
-    DeriveInput, Field, Ident, LitStr, Meta, Token, Type,
+    Attribute, DeriveInput, Field, Ident, LitInt, LitStr, Meta, Token, Type,
 };
 
 use super::event_parse::{EventKind, EventKindVariation};
-use crate::util::{m_prefix_name_to_type_name, PrivateField};
+use crate::{
+    serde::{case::RenameRule, json_schema::expand_json_schema},
+    util::{m_prefix_name_to_type_name, skip_meta_value, PrivateField},
+};
 
 mod kw {
     // This `content` field is kept when the event is redacted.
@@ -33,6 +37,11 @@ mod kw {
     syn::custom_keyword!(alias);
     // The content has a form without relation.
     syn::custom_keyword!(without_relation);
+    // The field is validated when the content is deserialized.
+    syn::custom_keyword!(validate);
+    syn::custom_keyword!(max_length);
+    syn::custom_keyword!(required);
+    syn::custom_keyword!(url_scheme);
 }
 
 /// Parses field attributes for `*EventContent` derives.
@@ -46,6 +55,9 @@ enum EventFieldMeta {
     /// The given field holds a part of the event type (replaces the `*` in a `m.foo.*` event
     /// type).
     TypeFragment,
+
+    /// The given field is checked with the given validations when the content is deserialized.
+    Validate(Vec<FieldValidation>),
 }
 
 impl Parse for EventFieldMeta {
@@ -57,6 +69,46 @@ impl Parse for EventFieldMeta {
         } else if lookahead.peek(kw::type_fragment) {
             let _: kw::type_fragment = input.parse()?;
             Ok(EventFieldMeta::TypeFragment)
+        } else if lookahead.peek(kw::validate) {
+            let _: kw::validate = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+            let validations = content.parse_terminated(FieldValidation::parse, Token![,])?;
+            Ok(EventFieldMeta::Validate(validations.into_iter().collect()))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// A validation in a `#[ruma_event(validate(...))]` field attribute.
+enum FieldValidation {
+    /// `max_length = N`: the field must not be longer than `N`.
+    MaxLength(LitInt),
+
+    /// `required`: the `Option` field must be present.
+    Required,
+
+    /// `url_scheme = "scheme"`: the field must be a URL with this scheme.
+    UrlScheme(LitStr),
+}
+
+impl Parse for FieldValidation {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::max_length) {
+            let _: kw::max_length = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            let max: LitInt = input.parse()?;
+            max.base10_parse::<usize>()?;
+            Ok(FieldValidation::MaxLength(max))
+        } else if lookahead.peek(kw::required) {
+            let _: kw::required = input.parse()?;
+            Ok(FieldValidation::Required)
+        } else if lookahead.peek(kw::url_scheme) {
+            let _: kw::url_scheme = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            Ok(FieldValidation::UrlScheme(input.parse()?))
         } else {
             Err(lookahead.error())
         }
@@ -368,6 +420,12 @@ pub fn expand_event_content(
         .unwrap_or_else(syn::Error::into_compile_error)
     });
 
+    let validate_fn = fields
+        .clone()
+        .map(|fields| generate_validate_fn(&input.attrs, fields, ruma_events))
+        .transpose()?
+        .flatten();
+
     let event_content_impl = generate_event_content_impl(
         ident,
         &input.vis,
@@ -378,6 +436,7 @@ pub fn expand_event_content(
         state_key_type.as_ref(),
         unsigned_type,
         &aliases,
+        validate_fn,
         ruma_events,
     )
     .unwrap_or_else(syn::Error::into_compile_error);
@@ -387,6 +446,10 @@ pub fn expand_event_content(
         generate_event_type_aliases(k, ident, &input.vis, &event_type.value(), ruma_events)
             .unwrap_or_else(syn::Error::into_compile_error)
     });
+    let json_schema_impl = cfg!(feature = "json-schema").then(|| {
+        expand_json_schema(input, &quote! { #ruma_events::exports::ruma_common })
+            .unwrap_or_else(syn::Error::into_compile_error)
+    });
 
     Ok(quote! {
         #redacted_event_content
@@ -395,6 +458,7 @@ pub fn expand_event_content(
         #event_content_impl
         #static_event_content_impl
         #type_aliases
+        #json_schema_impl
     })
 }
 
@@ -474,12 +538,17 @@ fn generate_redacted_event_content<'a>(
         state_key_type,
         unsigned_type,
         aliases,
+        None,
         ruma_events,
     )
     .unwrap_or_else(syn::Error::into_compile_error);
 
     let static_event_content_impl =
         generate_static_event_content_impl(&redacted_ident, event_type, ruma_events);
+    let json_schema_impl = cfg!(feature = "json-schema").then(|| {
+        generate_json_schema_impl(&redacted_ident, &kept_redacted_fields, ruma_events)
+            .unwrap_or_else(syn::Error::into_compile_error)
+    });
 
     Ok(quote! {
         // this is the non redacted event content's impl
@@ -506,6 +575,8 @@ fn generate_redacted_event_content<'a>(
         #redacted_event_content
 
         #static_event_content_impl
+
+        #json_schema_impl
     })
 }
 
@@ -629,12 +700,21 @@ fn generate_possibly_redacted_event_content<'a>(
             state_key_type,
             unsigned_type,
             aliases,
+            None,
             ruma_events,
         )
         .unwrap_or_else(syn::Error::into_compile_error);
 
         let static_event_content_impl =
             generate_static_event_content_impl(&possibly_redacted_ident, event_type, ruma_events);
+        let json_schema_impl = cfg!(feature = "json-schema").then(|| {
+            generate_json_schema_impl(
+                &possibly_redacted_ident,
+                &possibly_redacted_fields,
+                ruma_events,
+            )
+            .unwrap_or_else(syn::Error::into_compile_error)
+        });
 
         Ok(quote! {
             #[doc = #doc]
@@ -647,6 +727,8 @@ fn generate_possibly_redacted_event_content<'a>(
             #possibly_redacted_event_content
 
             #static_event_content_impl
+
+            #json_schema_impl
         })
     } else {
         Ok(quote! {
@@ -689,6 +771,7 @@ fn generate_event_content_without_relation<'a>(
         )
     })?;
 
+    let other_fields = other_fields.into_iter().map(without_ruma_event_attrs).collect::<Vec<_>>();
     let without_relation_fields = other_fields.iter().flat_map(|f| &f.ident).collect::<Vec<_>>();
     let without_relation_struct = if other_fields.is_empty() {
         quote! { ; }
@@ -726,6 +809,18 @@ fn generate_event_content_without_relation<'a>(
     })
 }
 
+fn generate_json_schema_impl(
+    ident: &Ident,
+    fields: &[Field],
+    ruma_events: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let input: DeriveInput = parse_quote! {
+        struct #ident { #( #fields, )* }
+    };
+
+    expand_json_schema(&input, &quote! { #ruma_events::exports::ruma_common })
+}
+
 fn generate_event_type_aliases(
     event_kind: EventKind,
     ident: &Ident,
@@ -817,6 +912,7 @@ fn generate_event_content_impl<'a>(
     state_key_type: Option<&TokenStream>,
     unsigned_type: Option<TokenStream>,
     aliases: &[LitStr],
+    validate_fn: Option<TokenStream>,
     ruma_events: &TokenStream,
 ) -> syn::Result<TokenStream> {
     let serde = quote! { #ruma_events::exports::serde };
@@ -960,8 +1056,10 @@ fn generate_event_content_impl<'a>(
                         && matches!(a.parse_args(), Ok(EventFieldMeta::TypeFragment))
                 })
             })
-            .map(PrivateField)
+            .map(without_ruma_event_attrs)
             .collect::<Vec<_>>();
+        let fields_without_type_fragment =
+            fields_without_type_fragment.iter().map(PrivateField).collect::<Vec<_>>();
         let fields_ident_without_type_fragment =
             fields_without_type_fragment.iter().filter_map(|f| f.0.ident.as_ref());
 
@@ -981,13 +1079,17 @@ fn generate_event_content_impl<'a>(
                     {
                         let c: WithoutTypeFragment = #serde_json::from_str(content.get())?;
 
-                        ::std::result::Result::Ok(Self {
+                        let content = Self {
                             #(
                                 #fields_ident_without_type_fragment:
                                     c.#fields_ident_without_type_fragment,
                             )*
                             #type_fragment_field: type_fragment.to_owned(),
-                        })
+                        };
+                        #ruma_events::EventContent::validate(&content)
+                            .map_err(#serde::de::Error::custom)?;
+
+                        ::std::result::Result::Ok(content)
                     } else {
                         ::std::result::Result::Err(#serde::de::Error::custom(
                             ::std::format!(
@@ -1011,6 +1113,8 @@ fn generate_event_content_impl<'a>(
             fn event_type(&self) -> Self::EventType {
                 #event_type_fn_impl
             }
+
+            #validate_fn
         }
 
         #event_content_from_type_impl
@@ -1019,6 +1123,114 @@ fn generate_event_content_impl<'a>(
     })
 }
 
+/// Generate the `EventContent::validate()` method from the `#[ruma_event(validate(...))]`
+/// attributes of the fields.
+///
+/// The errors use the names of the fields in the JSON, according to their `serde` attributes.
+///
+/// Returns `None` if no field needs to be validated.
+fn generate_validate_fn<'a>(
+    container_attrs: &[Attribute],
+    fields: impl Iterator<Item = &'a Field>,
+    ruma_events: &TokenStream,
+) -> syn::Result<Option<TokenStream>> {
+    let validation = quote! { #ruma_events::validation };
+    let rename_all = serde_rename(container_attrs, "rename_all")?
+        .map(|rename_all| {
+            rename_all
+                .value()
+                .parse()
+                .map_err(|_| syn::Error::new_spanned(&rename_all, "invalid value for rename_all"))
+        })
+        .transpose()?
+        .unwrap_or(RenameRule::None);
+
+    let mut checks = Vec::new();
+    for field in fields {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("ruma_event")) {
+            let EventFieldMeta::Validate(validations) = attr.parse_args()? else {
+                continue;
+            };
+
+            let ident = field.ident.as_ref().ok_or_else(|| {
+                syn::Error::new_spanned(attr, "only named fields can be validated")
+            })?;
+            let name = match serde_rename(&field.attrs, "rename")? {
+                Some(rename) => rename.value(),
+                None => rename_all.apply_to_field(&ident.unraw().to_string()),
+            };
+
+            let mut url_schemes = Vec::new();
+            for field_validation in validations {
+                match field_validation {
+                    FieldValidation::MaxLength(max) => checks.push(quote! {
+                        #validation::max_length(#name, &self.#ident, #max)?;
+                    }),
+                    FieldValidation::Required => checks.push(quote! {
+                        #validation::required(#name, &self.#ident)?;
+                    }),
+                    FieldValidation::UrlScheme(scheme) => url_schemes.push(scheme),
+                }
+            }
+
+            if !url_schemes.is_empty() {
+                checks.push(quote! {
+                    #validation::url_scheme(#name, &self.#ident, &[#( #url_schemes ),*])?;
+                });
+            }
+        }
+    }
+
+    if checks.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(quote! {
+        fn validate(
+            &self,
+        ) -> ::std::result::Result<(), #validation::ContentValidationError> {
+            #( #checks )*
+            ::std::result::Result::Ok(())
+        }
+    }))
+}
+
+/// Get the deserialization name set by the given `serde` renaming attribute, like `rename` or
+/// `rename_all`.
+fn serde_rename(attrs: &[Attribute], key: &str) -> syn::Result<Option<LitStr>> {
+    let mut name = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident(key) {
+                return skip_meta_value(&meta);
+            }
+
+            if meta.input.peek(Token![=]) {
+                name = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+
+            meta.parse_nested_meta(|nested| {
+                if nested.path.is_ident("deserialize") {
+                    name = Some(nested.value()?.parse()?);
+                    Ok(())
+                } else {
+                    skip_meta_value(&nested)
+                }
+            })
+        })?;
+    }
+
+    Ok(name)
+}
+
+/// Clone the given field without its `ruma_event` attributes, to re-emit it in another struct.
+fn without_ruma_event_attrs(field: &Field) -> Field {
+    let attrs = field.attrs.iter().filter(|a| !a.path().is_ident("ruma_event")).cloned().collect();
+    Field { attrs, ..field.clone() }
+}
+
 fn generate_static_event_content_impl(
     ident: &Ident,
     event_type: &LitStr,
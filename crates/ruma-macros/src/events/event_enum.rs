@@ -122,6 +122,21 @@ fn expand_event_enum(
     let field_accessor_impl =
         expand_accessor_methods(kind, var, variants, &event_struct, ruma_events)?;
     let from_impl = expand_from_impl(&ident, &event_ty, variants);
+    let json_schema_impl = cfg!(feature = "json-schema").then(|| {
+        let serde_json = quote! { #ruma_events::exports::serde_json };
+        // Events with a type that is not known by Ruma.
+        let custom_schema = quote! {
+            #serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "type": { "type": "string" },
+                    "content": { "type": "object" },
+                },
+                "required": ["type", "content"],
+            })
+        };
+        expand_json_schema_impl(&ident, &event_ty, variants, custom_schema, ruma_events)
+    });
 
     Ok(quote! {
         #( #attrs )*
@@ -145,6 +160,7 @@ fn expand_event_enum(
         #deserialize_impl
         #field_accessor_impl
         #from_impl
+        #json_schema_impl
     })
 }
 
@@ -324,6 +340,12 @@ fn expand_content_enum(
     });
 
     let from_impl = expand_from_impl(&ident, &content, variants);
+    let json_schema_impl = cfg!(feature = "json-schema").then(|| {
+        let serde_json = quote! { #ruma_events::exports::serde_json };
+        // Contents of events with a type that is not known by Ruma.
+        let custom_schema = quote! { #serde_json::json!({ "type": "object" }) };
+        expand_json_schema_impl(&ident, &content, variants, custom_schema, ruma_events)
+    });
 
     let serialize_custom_event_error_path =
         quote! { #ruma_events::serialize_custom_event_error }.to_string();
@@ -364,9 +386,48 @@ fn expand_content_enum(
         }
 
         #from_impl
+        #json_schema_impl
     })
 }
 
+/// Create the `JsonSchema` implementation of an event or event content enum, that matches any of
+/// the given types or the given custom schema.
+fn expand_json_schema_impl(
+    ident: &Ident,
+    types: &[TokenStream],
+    variants: &[EventEnumVariant],
+    custom_schema: TokenStream,
+    ruma_events: &TokenStream,
+) -> TokenStream {
+    let json_schema = quote! { #ruma_events::exports::ruma_common::json_schema };
+    let serde_json = quote! { #ruma_events::exports::serde_json };
+
+    let name = ident.to_string();
+    let variant_attrs = variants.iter().map(|v| &v.attrs);
+
+    quote! {
+        #[automatically_derived]
+        #[allow(unused_qualifications)]
+        impl #json_schema::JsonSchema for #ident {
+            fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                ::std::borrow::Cow::Borrowed(#name)
+            }
+
+            fn json_schema(
+                generator: &mut #json_schema::SchemaGenerator,
+            ) -> #serde_json::Value {
+                let mut schemas = ::std::vec::Vec::new();
+                #(
+                    #( #variant_attrs )*
+                    schemas.push(generator.subschema_for::<#types>());
+                )*
+                schemas.push(#custom_schema);
+                #json_schema::private::any_of(schemas)
+            }
+        }
+    }
+}
+
 /// Create a full content enum from `EventEnumInput`.
 fn expand_full_content_enum(
     kind: EventKind,
@@ -189,6 +189,39 @@ fn generate_enum(
         None
     };
 
+    let json_schema_impl = cfg!(feature = "json-schema").then(|| {
+        let json_schema = quote! { #ruma_common::json_schema };
+        let serde_json = quote! { #ruma_common::exports::serde_json };
+        let name = ident.to_string();
+
+        // Types with a fragment can't be listed, but they are accepted like any other string.
+        let (attrs, event_types): (Vec<_>, Vec<_>) = deduped
+            .iter()
+            .filter(|e| !e.has_type_fragment())
+            .map(|e| (&e.attrs, &e.ev_type))
+            .unzip();
+
+        quote! {
+            #[automatically_derived]
+            impl #json_schema::JsonSchema for #ident {
+                fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                    ::std::borrow::Cow::Borrowed(#name)
+                }
+
+                fn json_schema(
+                    _generator: &mut #json_schema::SchemaGenerator,
+                ) -> #serde_json::Value {
+                    let mut event_types = ::std::vec::Vec::new();
+                    #(
+                        #( #attrs )*
+                        event_types.push(#event_types);
+                    )*
+                    #json_schema::private::string_enum(&event_types)
+                }
+            }
+        }
+    });
+
     Ok(quote! {
         #[doc = #enum_doc]
         ///
@@ -269,5 +302,6 @@ fn generate_enum(
         }
 
         #from_ident_for_timeline
+        #json_schema_impl
     })
 }
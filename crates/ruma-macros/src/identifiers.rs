@@ -8,6 +8,8 @@ use syn::{
     Fields, ImplGenerics, Index, ItemStruct, LitStr, Path, Token,
 };
 
+use crate::util::import_ruma_common;
+
 pub struct IdentifierInput {
     pub dollar_crate: Path,
     pub id: LitStr,
@@ -28,6 +30,7 @@ pub fn expand_id_zst(input: ItemStruct) -> syn::Result<TokenStream> {
     let owned = format_ident!("Owned{id}");
 
     let owned_decl = expand_owned_id(&input);
+    let json_schema_impls = cfg!(feature = "json-schema").then(|| expand_json_schema_impls(&input));
 
     let meta = input.attrs.iter().filter(|attr| attr.path().is_ident("ruma_id")).try_fold(
         IdZstMeta::default(),
@@ -231,9 +234,59 @@ pub fn expand_id_zst(input: ItemStruct) -> syn::Result<TokenStream> {
         #as_str_impls
         #box_partial_eq_string
         #extra_impls
+        #json_schema_impls
     })
 }
 
+fn expand_json_schema_impls(input: &ItemStruct) -> TokenStream {
+    let ruma_common = import_ruma_common();
+    let json_schema = quote! { #ruma_common::json_schema };
+    let serde_json = quote! { #ruma_common::exports::serde_json };
+
+    let id = &input.ident;
+    let owned = format_ident!("Owned{id}");
+    let name = id.to_string();
+
+    let (impl_generics, ty_generics, _where_clause) = input.generics.split_for_impl();
+    let id_ty = quote! { #id #ty_generics };
+    let owned_ty = quote! { #owned #ty_generics };
+
+    // Generic identifiers would all share the same name, so inline them.
+    let is_referenceable = input.generics.params.is_empty();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics #json_schema::JsonSchema for #id_ty {
+            fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                ::std::borrow::Cow::Borrowed(#name)
+            }
+
+            fn is_referenceable() -> bool {
+                #is_referenceable
+            }
+
+            fn json_schema(_generator: &mut #json_schema::SchemaGenerator) -> #serde_json::Value {
+                #serde_json::json!({ "type": "string" })
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #json_schema::JsonSchema for #owned_ty {
+            fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                ::std::borrow::Cow::Borrowed(#name)
+            }
+
+            fn is_referenceable() -> bool {
+                false
+            }
+
+            fn json_schema(generator: &mut #json_schema::SchemaGenerator) -> #serde_json::Value {
+                generator.subschema_for::<#id_ty>()
+            }
+        }
+    }
+}
+
 fn expand_owned_id(input: &ItemStruct) -> TokenStream {
     let id = &input.ident;
     let owned = format_ident!("Owned{id}");
@@ -46,6 +46,7 @@ use self::{
         enum_as_ref_str::expand_enum_as_ref_str,
         enum_from_string::expand_enum_from_string,
         eq_as_ref_str::expand_partial_eq_as_ref_str,
+        json_schema::{expand_json_schema, expand_string_enum_json_schema},
         ord_as_ref_str::{expand_ord_as_ref_str, expand_partial_ord_as_ref_str},
         serialize_as_ref_str::expand_serialize_as_ref_str,
     },
@@ -116,6 +117,9 @@ pub fn event_enum(input: TokenStream) -> TokenStream {
 ///
 /// You can use `cargo doc` to find out more details, its `--document-private-items` flag also lets
 /// you generate documentation for binaries or private parts of a library.
+///
+/// With the `json-schema` feature, this also implements `JsonSchema` for the type and for the
+/// generated redacted types, so the types of all the fields must implement it too.
 #[proc_macro_derive(EventContent, attributes(ruma_event))]
 pub fn derive_event_content(input: TokenStream) -> TokenStream {
     let ruma_events = import_ruma_events();
@@ -341,6 +345,8 @@ pub fn derive_partial_eq_as_ref_str(input: TokenStream) -> TokenStream {
 
 /// Shorthand for the derives `AsRefStr`, `FromString`, `DisplayAsRefStr`, `DebugAsRefStr`,
 /// `SerializeAsRefStr` and `DeserializeFromCowStr`.
+///
+/// With the `json-schema` feature, this also implements `JsonSchema`.
 #[proc_macro_derive(StringEnum, attributes(ruma_enum))]
 pub fn derive_string_enum(input: TokenStream) -> TokenStream {
     fn expand_all(input: ItemEnum) -> syn::Result<proc_macro2::TokenStream> {
@@ -351,6 +357,9 @@ pub fn derive_string_enum(input: TokenStream) -> TokenStream {
         let debug_impl = expand_debug_as_ref_str(&input.ident)?;
         let serialize_impl = expand_serialize_as_ref_str(&input.ident)?;
         let deserialize_impl = expand_deserialize_from_cow_str(&input.ident)?;
+        let json_schema_impl = cfg!(feature = "json-schema")
+            .then(|| expand_string_enum_json_schema(&input, &import_ruma_common()))
+            .transpose()?;
 
         Ok(quote! {
             #as_ref_str_impl
@@ -360,6 +369,7 @@ pub fn derive_string_enum(input: TokenStream) -> TokenStream {
             #debug_impl
             #serialize_impl
             #deserialize_impl
+            #json_schema_impl
         })
     }
 
@@ -367,6 +377,14 @@ pub fn derive_string_enum(input: TokenStream) -> TokenStream {
     expand_all(input).unwrap_or_else(syn::Error::into_compile_error).into()
 }
 
+/// Derive the `JsonSchema` trait according to the `serde` attributes of the type.
+#[proc_macro_derive(JsonSchema, attributes(serde))]
+pub fn derive_json_schema(input: TokenStream) -> TokenStream {
+    let ruma_common = import_ruma_common();
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_json_schema(&input, &ruma_common).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
 /// A derive macro that generates no code, but registers the serde attribute so both `#[serde(...)]`
 /// and `#[cfg_attr(..., serde(...))]` are accepted on the type, its fields and (in case the input
 /// is an enum) variants fields.
@@ -9,6 +9,7 @@ pub mod display_as_ref_str;
 pub mod enum_as_ref_str;
 pub mod enum_from_string;
 pub mod eq_as_ref_str;
+pub mod json_schema;
 pub mod ord_as_ref_str;
 pub mod serialize_as_ref_str;
 mod util;
@@ -0,0 +1,727 @@
+//! Implementation of the `JsonSchema` derive macro.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    meta::ParseNestedMeta, parse_quote, Attribute, Data, DeriveInput, Expr, Field, Fields,
+    GenericArgument, GenericParam, Generics, Ident, ItemEnum, LitStr, PathArguments, Token, Type,
+    Variant,
+};
+
+use super::{
+    case::RenameRule,
+    util::{get_enum_attributes, get_rename_rule},
+};
+use crate::util::skip_meta_value;
+
+/// Generate the `JsonSchema` implementation of a type, according to its `serde` attributes.
+pub fn expand_json_schema(
+    input: &DeriveInput,
+    ruma_common: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let json_schema = quote! { #ruma_common::json_schema };
+    let serde_json = quote! { #ruma_common::exports::serde_json };
+
+    let ident = &input.ident;
+    let name = ident.to_string();
+    let schema = schema_expr(input, &json_schema)?;
+
+    let generics = add_trait_bounds(input.generics.clone(), &json_schema);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Include the type parameters in the name, so the schemas of different instantiations of a
+    // generic type don't collide.
+    let type_params: Vec<_> = input.generics.type_params().map(|param| &param.ident).collect();
+    let schema_name = if type_params.is_empty() {
+        quote! { ::std::borrow::Cow::Borrowed(#name) }
+    } else {
+        quote! {
+            let mut name = ::std::string::String::from(#name);
+            #(
+                name.push('_');
+                name.push_str(&<#type_params as #json_schema::JsonSchema>::schema_name());
+            )*
+            ::std::borrow::Cow::Owned(name)
+        }
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        #[allow(deprecated)]
+        impl #impl_generics #json_schema::JsonSchema for #ident #ty_generics #where_clause {
+            fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                #schema_name
+            }
+
+            fn json_schema(
+                generator: &mut #json_schema::SchemaGenerator,
+            ) -> #serde_json::Value {
+                #schema
+            }
+        }
+    })
+}
+
+/// Generate an expression evaluating to the schema of a struct with the given fields, according
+/// to their `serde` attributes.
+///
+/// The expression expects a `generator` variable containing a `&mut SchemaGenerator`.
+pub fn expand_fields_schema<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+    transparent: bool,
+    ruma_common: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let json_schema = quote! { #ruma_common::json_schema };
+
+    let fields = fields.into_iter();
+    let serde_attr = transparent.then(|| quote! { #[serde(transparent)] });
+    let input: DeriveInput = parse_quote! {
+        #serde_attr
+        struct Fields { #(#fields),* }
+    };
+    let schema = schema_expr(&input, &json_schema)?;
+
+    Ok(schema)
+}
+
+/// Generate an expression evaluating to the schema of the given field, with its documentation as
+/// description.
+///
+/// The expression expects a `generator` variable containing a `&mut SchemaGenerator`.
+pub fn expand_field_schema(field: &Field, ruma_common: &TokenStream) -> syn::Result<TokenStream> {
+    let json_schema = quote! { #ruma_common::json_schema };
+    let schema = field_schema(field, &FieldAttrs::parse(&field.attrs)?, &json_schema);
+    let schema = with_description(schema, &field.attrs, &json_schema);
+
+    Ok(schema)
+}
+
+/// Generate an expression evaluating to the schema of the given type.
+fn schema_expr(input: &DeriveInput, json_schema: &TokenStream) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let container = ContainerAttrs::parse(&input.attrs)?;
+
+    if let Some(into) = &container.into {
+        // The type is serialized as this other type.
+        let ty: Type = into.parse()?;
+        return Ok(with_description(
+            quote! { <#ty as #json_schema::JsonSchema>::json_schema(generator) },
+            &input.attrs,
+            json_schema,
+        ));
+    }
+
+    let schema = match &input.data {
+        Data::Struct(data) => {
+            if container.transparent {
+                let field = data
+                    .fields
+                    .iter()
+                    .find(|f| !FieldAttrs::parse(&f.attrs).is_ok_and(|attrs| attrs.skip))
+                    .ok_or_else(|| {
+                        syn::Error::new_spanned(ident, "transparent struct needs a field")
+                    })?;
+                field_schema(field, &FieldAttrs::parse(&field.attrs)?, json_schema)
+            } else if let Some(tag) = &container.tag {
+                // The tag of a struct is its name.
+                let name =
+                    container.rename.as_ref().map(LitStr::value).unwrap_or_else(|| unraw(ident));
+                fields_schema(&data.fields, &container, Some((&tag.value(), &name)), json_schema)?
+            } else {
+                fields_schema(&data.fields, &container, None, json_schema)?
+            }
+        }
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|v| Ok((v, VariantAttrs::parse(&v.attrs)?)))
+                .filter(|res| !res.as_ref().is_ok_and(|(_, attrs)| attrs.skip))
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            enum_schema(&variants, &container, json_schema)?
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(ident, "unions are not supported"));
+        }
+    };
+
+    Ok(with_description(schema, &input.attrs, json_schema))
+}
+
+/// Generate the `JsonSchema` implementation of an enum deriving `StringEnum`.
+pub fn expand_string_enum_json_schema(
+    input: &ItemEnum,
+    ruma_common: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let json_schema = quote! { #ruma_common::json_schema };
+    let serde_json = quote! { #ruma_common::exports::serde_json };
+
+    let ident = &input.ident;
+    let name = ident.to_string();
+    let rename_rule = get_rename_rule(input)?;
+
+    let values = input
+        .variants
+        .iter()
+        .filter(|v| matches!(v.fields, Fields::Unit))
+        .map(|v| {
+            let attrs = get_enum_attributes(v)?;
+            Ok(attrs
+                .rename
+                .map(|rename| rename.value())
+                .unwrap_or_else(|| rename_rule.apply_to_variant(&v.ident.to_string())))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let schema = with_description(
+        quote! { #json_schema::private::string_enum(&[#( #values ),*]) },
+        &input.attrs,
+        &json_schema,
+    );
+
+    Ok(quote! {
+        #[automatically_derived]
+        #[allow(deprecated)]
+        impl #json_schema::JsonSchema for #ident {
+            fn schema_name() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                ::std::borrow::Cow::Borrowed(#name)
+            }
+
+            fn json_schema(
+                _generator: &mut #json_schema::SchemaGenerator,
+            ) -> #serde_json::Value {
+                #schema
+            }
+        }
+    })
+}
+
+/// Generate the schema of the given fields.
+///
+/// `tag` is the name and value of the tag property to add for internally tagged enum variants.
+fn fields_schema(
+    fields: &Fields,
+    container: &ContainerAttrs,
+    tag: Option<(&str, &str)>,
+    json_schema: &TokenStream,
+) -> syn::Result<TokenStream> {
+    match fields {
+        Fields::Named(fields) => {
+            let properties = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let attrs = FieldAttrs::parse(&field.attrs)?;
+                    if attrs.skip {
+                        return Ok(None);
+                    }
+
+                    if attrs.flatten {
+                        // A flattened `Option` adds the fields of the inner type when it is
+                        // `Some`, so its fields should not be required.
+                        let schema = match option_inner_type(&field.ty) {
+                            Some(ty) if !attrs.with => {
+                                quote! {{
+                                    let schema = generator.subschema_for::<#ty>();
+                                    #json_schema::private::optional_properties(generator, schema)
+                                }}
+                            }
+                            _ => field_schema(field, &attrs, json_schema),
+                        };
+                        let schema = with_description(schema, &field.attrs, json_schema);
+
+                        return Ok(Some(quote! {
+                            object.flatten(#schema);
+                        }));
+                    }
+
+                    let schema = field_schema(field, &attrs, json_schema);
+                    let schema = with_description(schema, &field.attrs, json_schema);
+
+                    let field_ident = field.ident.as_ref().expect("named fields have an ident");
+                    let name = attrs.rename.map(|rename| rename.value()).unwrap_or_else(|| {
+                        container.rename_all.apply_to_field(&unraw(field_ident))
+                    });
+                    let required = !attrs.default
+                        && !attrs.skip_serializing_if
+                        && !container.default
+                        && !is_option(&field.ty);
+
+                    Ok(Some(quote! {
+                        object.property(#name, #schema, #required);
+                    }))
+                })
+                .filter_map(Result::transpose)
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            let tag = tag.map(|(tag, value)| {
+                quote! {
+                    object.property(#tag, #json_schema::private::constant(#value), true);
+                }
+            });
+
+            Ok(quote! {{
+                let mut object = #json_schema::private::ObjectSchema::new();
+                #tag
+                #( #properties )*
+                object.finish()
+            }})
+        }
+        Fields::Unnamed(fields) => {
+            let fields = fields
+                .unnamed
+                .iter()
+                .map(|field| Ok((field, FieldAttrs::parse(&field.attrs)?)))
+                .filter(|res| !res.as_ref().is_ok_and(|(_, attrs)| attrs.skip))
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            if let [(field, attrs)] = fields.as_slice() {
+                // Newtypes are represented by their inner type.
+                let schema = field_schema(field, attrs, json_schema);
+                return Ok(match tag {
+                    Some((tag, value)) => quote! {
+                        #json_schema::private::with_tag(#schema, #tag, #value)
+                    },
+                    None => schema,
+                });
+            }
+
+            let items = fields.iter().map(|(field, attrs)| field_schema(field, attrs, json_schema));
+            let len = fields.len();
+
+            Ok(quote! {
+                #json_schema::private::tuple(
+                    ::std::vec![#( #items ),*],
+                    #len,
+                )
+            })
+        }
+        Fields::Unit => Ok(match tag {
+            Some((tag, value)) => quote! {{
+                let mut object = #json_schema::private::ObjectSchema::new();
+                object.property(#tag, #json_schema::private::constant(#value), true);
+                object.finish()
+            }},
+            None => quote! { #json_schema::private::null() },
+        }),
+    }
+}
+
+/// Generate the schema of an enum.
+fn enum_schema(
+    variants: &[(&Variant, VariantAttrs)],
+    container: &ContainerAttrs,
+    json_schema: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let variant_name = |variant: &Variant, attrs: &VariantAttrs| {
+        attrs
+            .rename
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_else(|| container.rename_all.apply_to_variant(&unraw(&variant.ident)))
+    };
+
+    // The attributes of the enum don't apply to the fields of its variants.
+    let fields_container = ContainerAttrs::new();
+
+    if container.untagged {
+        let schemas = variants
+            .iter()
+            .map(|(variant, attrs)| {
+                let schema = if attrs.untagged_other {
+                    quote! { #json_schema::private::any() }
+                } else {
+                    fields_schema(&variant.fields, &fields_container, None, json_schema)?
+                };
+                Ok(with_description(schema, &variant.attrs, json_schema))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        return Ok(quote! {
+            #json_schema::private::any_of(::std::vec![#( #schemas ),*])
+        });
+    }
+
+    if let Some(tag) = &container.tag {
+        let tag = tag.value();
+        let schemas = variants
+            .iter()
+            .map(|(variant, attrs)| {
+                if attrs.untagged_other {
+                    let schema = untagged_variant_schema(variant, &tag, json_schema)?;
+                    return Ok(with_description(schema, &variant.attrs, json_schema));
+                }
+
+                let name = variant_name(variant, attrs);
+                let schema = match &container.content {
+                    Some(content) => {
+                        let content = content.value();
+                        let inner =
+                            fields_schema(&variant.fields, &fields_container, None, json_schema)?;
+                        let has_content = !matches!(variant.fields, Fields::Unit);
+
+                        quote! {{
+                            let mut object = #json_schema::private::ObjectSchema::new();
+                            object.property(#tag, #json_schema::private::constant(#name), true);
+                            if #has_content {
+                                object.property(#content, #inner, true);
+                            }
+                            object.finish()
+                        }}
+                    }
+                    None => fields_schema(
+                        &variant.fields,
+                        &fields_container,
+                        Some((&tag, &name)),
+                        json_schema,
+                    )?,
+                };
+
+                Ok(with_description(schema, &variant.attrs, json_schema))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        return Ok(tagged_variants_schema(schemas, variants, json_schema));
+    }
+
+    // Externally tagged enum.
+    if variants
+        .iter()
+        .all(|(variant, attrs)| matches!(variant.fields, Fields::Unit) && !attrs.untagged_other)
+    {
+        let names = variants.iter().map(|(variant, attrs)| variant_name(variant, attrs));
+
+        return Ok(quote! {
+            #json_schema::private::enumeration(&[#( #names ),*])
+        });
+    }
+
+    let schemas = variants
+        .iter()
+        .map(|(variant, attrs)| {
+            let name = variant_name(variant, attrs);
+            let schema = match &variant.fields {
+                Fields::Unit if attrs.untagged_other => {
+                    quote! { #json_schema::private::any() }
+                }
+                fields if attrs.untagged_other => {
+                    fields_schema(fields, &fields_container, None, json_schema)?
+                }
+                Fields::Unit => quote! { #json_schema::private::constant(#name) },
+                fields => {
+                    let inner = fields_schema(fields, &fields_container, None, json_schema)?;
+                    quote! {{
+                        let mut object = #json_schema::private::ObjectSchema::new();
+                        object.property(#name, #inner, true);
+                        object.finish()
+                    }}
+                }
+            };
+
+            Ok(with_description(schema, &variant.attrs, json_schema))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(tagged_variants_schema(schemas, variants, json_schema))
+}
+
+/// Generate the schema of a variant of an internally or adjacently tagged enum that is not tagged.
+///
+/// This is a unit variant with `#[serde(other)]`, that matches any value of the tag, or a variant
+/// with `#[serde(untagged)]`.
+fn untagged_variant_schema(
+    variant: &Variant,
+    tag: &str,
+    json_schema: &TokenStream,
+) -> syn::Result<TokenStream> {
+    match &variant.fields {
+        Fields::Unit => Ok(quote! {{
+            let mut object = #json_schema::private::ObjectSchema::new();
+            object.property(
+                #tag,
+                #json_schema::SchemaGenerator::subschema_for::<::std::string::String>(generator),
+                true,
+            );
+            object.finish()
+        }}),
+        fields => fields_schema(fields, &ContainerAttrs::new(), None, json_schema),
+    }
+}
+
+/// Combine the schemas of the variants of a tagged enum.
+///
+/// Variants are exclusive, unless a variant is not tagged, in which case it can also match the
+/// values of other variants.
+fn tagged_variants_schema(
+    schemas: Vec<TokenStream>,
+    variants: &[(&Variant, VariantAttrs)],
+    json_schema: &TokenStream,
+) -> TokenStream {
+    if variants.iter().any(|(_, attrs)| attrs.untagged_other) {
+        quote! { #json_schema::private::any_of(::std::vec![#( #schemas ),*]) }
+    } else {
+        quote! { #json_schema::private::one_of(::std::vec![#( #schemas ),*]) }
+    }
+}
+
+/// Generate the schema of the type of the given field.
+fn field_schema(field: &Field, attrs: &FieldAttrs, json_schema: &TokenStream) -> TokenStream {
+    if attrs.with {
+        // We can't know the representation of the type.
+        return quote! { #json_schema::private::any() };
+    }
+
+    let ty = &field.ty;
+    quote! {
+        generator.subschema_for::<#ty>()
+    }
+}
+
+/// Add the documentation in the given attributes as the description of the schema.
+fn with_description(
+    schema: TokenStream,
+    attrs: &[Attribute],
+    json_schema: &TokenStream,
+) -> TokenStream {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(ToOwned::to_owned).unwrap_or(line))
+        .collect();
+
+    let description = lines.join("\n").trim().to_owned();
+    if description.is_empty() {
+        return schema;
+    }
+
+    quote! {
+        #json_schema::private::with_description(#schema, #description)
+    }
+}
+
+/// Add a `JsonSchema` bound to every type parameter.
+fn add_trait_bounds(mut generics: Generics, json_schema: &TokenStream) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote! { #json_schema::JsonSchema });
+        }
+    }
+    generics
+}
+
+/// Whether the given type is an `Option`.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => {
+            path.qself.is_none()
+                && path.path.segments.last().is_some_and(|segment| segment.ident == "Option")
+        }
+        _ => false,
+    }
+}
+
+/// Get the type inside the given `Option` type.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    if !is_option(ty) {
+        return None;
+    }
+
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// The name of the given identifier, without the `r#` prefix.
+fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    name.strip_prefix("r#").map(ToOwned::to_owned).unwrap_or(name)
+}
+
+/// The `serde` attributes of a container that change its schema.
+struct ContainerAttrs {
+    rename: Option<LitStr>,
+    rename_all: RenameRule,
+    default: bool,
+    transparent: bool,
+    untagged: bool,
+    tag: Option<LitStr>,
+    content: Option<LitStr>,
+    into: Option<LitStr>,
+}
+
+impl ContainerAttrs {
+    fn new() -> Self {
+        Self {
+            rename: None,
+            rename_all: RenameRule::None,
+            default: false,
+            transparent: false,
+            untagged: false,
+            tag: None,
+            content: None,
+            into: None,
+        }
+    }
+
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = Self::new();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    container.rename = parse_serialize_name(&meta)?;
+                } else if meta.path.is_ident("rename_all") {
+                    if let Some(rename_all) = parse_serialize_name(&meta)? {
+                        container.rename_all = rename_all.value().parse().map_err(|_| {
+                            syn::Error::new_spanned(&rename_all, "invalid value for rename_all")
+                        })?;
+                    }
+                } else if meta.path.is_ident("default") {
+                    container.default = true;
+                    skip_meta_value(&meta)?;
+                } else if meta.path.is_ident("transparent") {
+                    container.transparent = true;
+                } else if meta.path.is_ident("untagged") {
+                    container.untagged = true;
+                } else if meta.path.is_ident("tag") {
+                    container.tag = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("content") {
+                    container.content = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("into") {
+                    container.into = Some(meta.value()?.parse()?);
+                } else {
+                    skip_meta_value(&meta)?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(container)
+    }
+}
+
+/// The `serde` attributes of a field that change its schema.
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<LitStr>,
+    skip: bool,
+    flatten: bool,
+    default: bool,
+    skip_serializing_if: bool,
+    with: bool,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(rename) = parse_serialize_name(&meta)? {
+                        field.rename = Some(rename);
+                    }
+                } else if meta.path.is_ident("skip")
+                    || meta.path.is_ident("skip_serializing")
+                    || meta.path.is_ident("skip_deserializing")
+                {
+                    field.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    field.flatten = true;
+                } else if meta.path.is_ident("default") {
+                    field.default = true;
+                    skip_meta_value(&meta)?;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    field.skip_serializing_if = true;
+                    skip_meta_value(&meta)?;
+                } else if meta.path.is_ident("with")
+                    || meta.path.is_ident("serialize_with")
+                    || meta.path.is_ident("deserialize_with")
+                {
+                    field.with = true;
+                    skip_meta_value(&meta)?;
+                } else {
+                    skip_meta_value(&meta)?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(field)
+    }
+}
+
+/// The `serde` attributes of an enum variant that change its schema.
+#[derive(Default)]
+struct VariantAttrs {
+    rename: Option<LitStr>,
+    skip: bool,
+    untagged_other: bool,
+}
+
+impl VariantAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut variant = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(rename) = parse_serialize_name(&meta)? {
+                        variant.rename = Some(rename);
+                    }
+                } else if meta.path.is_ident("skip")
+                    || meta.path.is_ident("skip_serializing")
+                    || meta.path.is_ident("skip_deserializing")
+                {
+                    variant.skip = true;
+                } else if meta.path.is_ident("other") || meta.path.is_ident("untagged") {
+                    variant.untagged_other = true;
+                } else {
+                    skip_meta_value(&meta)?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(variant)
+    }
+}
+
+/// Parse the serialization name of a `rename` or `rename_all` attribute.
+///
+/// Supports both the `rename = "name"` and the `rename(serialize = "name")` forms.
+fn parse_serialize_name(meta: &ParseNestedMeta<'_>) -> syn::Result<Option<LitStr>> {
+    if meta.input.peek(Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+
+    let mut name = None;
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("serialize") {
+            name = Some(nested.value()?.parse()?);
+        } else {
+            skip_meta_value(&nested)?;
+        }
+        Ok(())
+    })?;
+
+    Ok(name)
+}
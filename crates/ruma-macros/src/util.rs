@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::{format_ident, quote, ToTokens};
-use syn::{Field, Ident, LitStr};
+use syn::{meta::ParseNestedMeta, Expr, Field, Ident, LitStr, Token};
 
 pub(crate) fn import_ruma_common() -> TokenStream {
     if let Ok(FoundCrate::Name(name)) = crate_name("ruma-common") {
@@ -91,3 +91,14 @@ impl ToTokens for PrivateField<'_> {
         ty.to_tokens(tokens);
     }
 }
+
+/// Skip the value of a nested attribute that is not used.
+pub(crate) fn skip_meta_value(meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        let _: Expr = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_meta_value(&nested))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+//! Generation of an [OpenAPI] document for the endpoints of the push gateway API.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+
+use ruma_common::api::openapi::OpenApiDocument;
+
+/// Add all the endpoints of the push gateway API to the given OpenAPI document.
+///
+/// Endpoints that are behind features that are not enabled are not added.
+#[allow(deprecated)]
+pub fn add_endpoints(document: &mut OpenApiDocument) {
+    document.add_endpoint::<crate::send_event_notification::v1::Request>();
+}
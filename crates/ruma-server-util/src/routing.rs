@@ -0,0 +1,449 @@
+//! A request router built from the [`Metadata`] of Ruma's endpoint types.
+//!
+//! Every Ruma endpoint knows all of its stable and unstable paths through
+//! [`VersionHistory`][ruma_common::api::VersionHistory]. [`RouterBuilder`] uses this information to
+//! register a handler for all path variants of a request type at once, so that servers don't have
+//! to maintain a separate list of paths.
+//!
+//! [`Router::route`] matches an incoming [`http::Request`] against the registered paths and
+//! methods, extracts and percent-decodes the path parameters and converts the request with
+//! [`IncomingRequest::try_from_http_request`] before calling the handler. Unknown endpoints,
+//! unsupported methods and malformed requests are reported as a [`MatrixError`] that can be sent
+//! back to the client as-is.
+//!
+//! ```
+//! use ruma_client_api::alias::get_alias;
+//! use ruma_server_util::routing::Router;
+//!
+//! let router = Router::builder()
+//!     .route(|request: get_alias::v3::Request| format!("Resolving {}", request.room_alias))
+//!     .build();
+//!
+//! let request = http::Request::get("/_matrix/client/v3/directory/room/%23ruma:matrix.org")
+//!     .body(Vec::<u8>::new())
+//!     .unwrap();
+//! assert_eq!(router.route(request).unwrap(), "Resolving #ruma:matrix.org");
+//! ```
+//!
+//! [`Metadata`]: ruma_common::api::Metadata
+
+use std::{cmp::Ordering, fmt, sync::Arc};
+
+use http::{Method, StatusCode};
+use percent_encoding::percent_decode_str;
+use ruma_common::api::{
+    error::{FromHttpRequestError, MatrixError, MatrixErrorBody},
+    IncomingRequest,
+};
+use serde_json::json;
+use tracing::debug;
+
+type Handler<B, T> =
+    Arc<dyn Fn(http::Request<B>, &[String]) -> Result<T, FromHttpRequestError> + Send + Sync>;
+
+/// A builder for a [`Router`].
+///
+/// Handlers are registered by request type with [`RouterBuilder::route`].
+pub struct RouterBuilder<B, T> {
+    routes: Vec<Route<B, T>>,
+}
+
+impl<B, T> RouterBuilder<B, T>
+where
+    B: AsRef<[u8]>,
+{
+    /// Creates a new empty `RouterBuilder`.
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Registers `handler` for all the paths of the request type `R`.
+    ///
+    /// The method and paths are taken from [`IncomingRequest::METADATA`], including all the
+    /// unstable and stable paths of the endpoint's history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a handler is already registered for the same method and one of the paths of `R`.
+    pub fn route<R, F>(mut self, handler: F) -> Self
+    where
+        R: IncomingRequest + 'static,
+        F: Fn(R) -> T + Send + Sync + 'static,
+    {
+        let handler: Handler<B, T> = Arc::new(move |request, path_args| {
+            R::try_from_http_request(request, path_args).map(&handler)
+        });
+        let method = R::METADATA.method;
+
+        for path in R::METADATA.history.all_paths() {
+            let segments = parse_path(path);
+
+            if self.routes.iter().any(|route| route.method == method && route.segments == segments)
+            {
+                panic!("a handler is already registered for {method} {path}");
+            }
+
+            self.routes.push(Route {
+                method: method.clone(),
+                path,
+                segments,
+                handler: handler.clone(),
+            });
+        }
+
+        self
+    }
+
+    /// Finishes building the `Router`.
+    pub fn build(self) -> Router<B, T> {
+        Router { routes: self.routes }
+    }
+}
+
+impl<B, T> Default for RouterBuilder<B, T>
+where
+    B: AsRef<[u8]>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B, T> fmt::Debug for RouterBuilder<B, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouterBuilder").field("routes", &self.routes).finish()
+    }
+}
+
+/// A router dispatching HTTP requests to handlers registered by request type.
+///
+/// `B` is the type of the HTTP request body and `T` is the type returned by the handlers. Since the
+/// router never looks at the value returned by handlers, `T` can be a response, a future or
+/// anything else that the server needs.
+///
+/// To construct a `Router`, use [`Router::builder()`].
+pub struct Router<B, T> {
+    routes: Vec<Route<B, T>>,
+}
+
+impl<B, T> Router<B, T>
+where
+    B: AsRef<[u8]>,
+{
+    /// Creates a new [`RouterBuilder`].
+    pub fn builder() -> RouterBuilder<B, T> {
+        RouterBuilder::new()
+    }
+
+    /// Returns an iterator over the method and path of all the registered routes.
+    ///
+    /// The paths are in the canon form of [`Metadata`], with `:` prefixing path parameters.
+    ///
+    /// [`Metadata`]: ruma_common::api::Metadata
+    pub fn paths(&self) -> impl Iterator<Item = (&Method, &'static str)> {
+        self.routes.iter().map(|route| (&route.method, route.path))
+    }
+
+    /// Dispatches the given request to the matching handler and returns its result.
+    ///
+    /// If several paths match the request, the one with the most specific path is used, where a
+    /// fixed segment is more specific than a path parameter.
+    ///
+    /// Returns an error with the `M_UNRECOGNIZED` error code and the status code:
+    ///
+    /// * `404 Not Found` if no path matches the request,
+    /// * `405 Method Not Allowed` if a path matches the request but not with its method.
+    ///
+    /// Returns an error with the `M_BAD_JSON` error code and the status code `400 Bad Request` if
+    /// the request could not be converted to the request type of the handler.
+    pub fn route(&self, request: http::Request<B>) -> Result<T, MatrixError> {
+        let mut path_matched = false;
+        let mut best: Option<(&Route<B, T>, Vec<String>)> = None;
+
+        for route in &self.routes {
+            let Some(path_args) = route.match_path(request.uri().path()) else {
+                continue;
+            };
+            path_matched = true;
+
+            if route.method != request.method() {
+                continue;
+            }
+
+            match &best {
+                Some((best_route, _))
+                    if cmp_specificity(&route.segments, &best_route.segments)
+                        != Ordering::Greater => {}
+                _ => best = Some((route, path_args)),
+            }
+        }
+
+        let Some((route, path_args)) = best else {
+            return Err(if path_matched {
+                unrecognized_error(StatusCode::METHOD_NOT_ALLOWED, "Unsupported method")
+            } else {
+                unrecognized_error(StatusCode::NOT_FOUND, "Unrecognized request")
+            });
+        };
+
+        debug!(method = %route.method, path = route.path, "Routing request");
+
+        (route.handler)(request, &path_args).map_err(|error| MatrixError {
+            status_code: StatusCode::BAD_REQUEST,
+            body: MatrixErrorBody::Json(json!({
+                "errcode": "M_BAD_JSON",
+                "error": error.to_string(),
+            })),
+        })
+    }
+}
+
+impl<B, T> fmt::Debug for Router<B, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router").field("routes", &self.routes).finish()
+    }
+}
+
+struct Route<B, T> {
+    method: Method,
+    path: &'static str,
+    segments: Vec<Segment>,
+    handler: Handler<B, T>,
+}
+
+impl<B, T> Route<B, T> {
+    /// Matches the given percent-encoded path against this route.
+    ///
+    /// Returns the percent-decoded path arguments on success. Path parameters never match an empty
+    /// segment.
+    fn match_path(&self, path: &str) -> Option<Vec<String>> {
+        let mut request_segments = path.strip_prefix('/')?.split('/');
+        let mut path_args = Vec::new();
+
+        for segment in &self.segments {
+            let request_segment = request_segments.next()?;
+
+            match segment {
+                Segment::Literal(literal) => {
+                    if *literal != request_segment {
+                        return None;
+                    }
+                }
+                Segment::Parameter => {
+                    if request_segment.is_empty() {
+                        return None;
+                    }
+
+                    let arg = percent_decode_str(request_segment).decode_utf8().ok()?;
+                    path_args.push(arg.into_owned());
+                }
+            }
+        }
+
+        request_segments.next().is_none().then_some(path_args)
+    }
+}
+
+impl<B, T> fmt::Debug for Route<B, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Route").field("method", &self.method).field("path", &self.path).finish()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(&'static str),
+    Parameter,
+}
+
+/// Parses a path in the canon form of `Metadata` into its segments.
+fn parse_path(path: &'static str) -> Vec<Segment> {
+    path.strip_prefix('/')
+        .expect("endpoint paths must start with '/'")
+        .split('/')
+        .map(
+            |segment| {
+                if segment.starts_with(':') {
+                    Segment::Parameter
+                } else {
+                    Segment::Literal(segment)
+                }
+            },
+        )
+        .collect()
+}
+
+/// Compares the specificity of two paths.
+///
+/// Segments are compared from left to right, a literal segment being more specific than a
+/// parameter.
+fn cmp_specificity(a: &[Segment], b: &[Segment]) -> Ordering {
+    let is_literal = |segment: &Segment| matches!(segment, Segment::Literal(_));
+    a.iter().map(is_literal).cmp(b.iter().map(is_literal))
+}
+
+fn unrecognized_error(status_code: StatusCode, message: &str) -> MatrixError {
+    MatrixError {
+        status_code,
+        body: MatrixErrorBody::Json(json!({
+            "errcode": "M_UNRECOGNIZED",
+            "error": message,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use http::{Method, StatusCode};
+    use ruma_client_api::{
+        alias::{delete_alias, get_alias},
+        backup::{delete_backup_keys_for_room, get_backup_keys},
+    };
+    use ruma_common::api::error::{MatrixError, MatrixErrorBody};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{cmp_specificity, parse_path, Router};
+
+    fn router() -> Router<Vec<u8>, String> {
+        Router::builder()
+            .route(|req: get_alias::v3::Request| format!("get {}", req.room_alias))
+            .route(|req: delete_alias::v3::Request| format!("delete {}", req.room_alias))
+            .route(|req: delete_backup_keys_for_room::v3::Request| {
+                format!("delete keys {} {}", req.version, req.room_id)
+            })
+            .route(|req: get_backup_keys::v3::Request| format!("get keys {}", req.version))
+            .build()
+    }
+
+    fn request(method: Method, uri: &str) -> http::Request<Vec<u8>> {
+        http::Request::builder().method(method).uri(uri).body(Vec::new()).unwrap()
+    }
+
+    fn errcode(error: MatrixError) -> JsonValue {
+        match error.body {
+            MatrixErrorBody::Json(json) => json["errcode"].clone(),
+            MatrixErrorBody::NotJson { .. } => panic!("error body should be JSON"),
+        }
+    }
+
+    #[test]
+    fn all_paths_registered() {
+        let router = router();
+        let mut paths =
+            router.paths().map(|(method, path)| format!("{method} {path}")).collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            [
+                "DELETE /_matrix/client/r0/directory/room/:room_alias",
+                "DELETE /_matrix/client/r0/room_keys/keys/:room_id",
+                "DELETE /_matrix/client/unstable/room_keys/keys/:room_id",
+                "DELETE /_matrix/client/v3/directory/room/:room_alias",
+                "DELETE /_matrix/client/v3/room_keys/keys/:room_id",
+                "GET /_matrix/client/r0/directory/room/:room_alias",
+                "GET /_matrix/client/r0/room_keys/keys",
+                "GET /_matrix/client/unstable/room_keys/keys",
+                "GET /_matrix/client/v3/directory/room/:room_alias",
+                "GET /_matrix/client/v3/room_keys/keys",
+            ]
+        );
+    }
+
+    #[test]
+    fn route_with_path_args() {
+        let router = router();
+
+        assert_eq!(
+            router
+                .route(request(Method::GET, "/_matrix/client/r0/directory/room/%23foo:bar.baz"))
+                .unwrap(),
+            "get #foo:bar.baz"
+        );
+        assert_eq!(
+            router
+                .route(request(Method::DELETE, "/_matrix/client/v3/directory/room/%23foo:bar.baz"))
+                .unwrap(),
+            "delete #foo:bar.baz"
+        );
+        assert_eq!(
+            router
+                .route(request(
+                    Method::DELETE,
+                    "/_matrix/client/unstable/room_keys/keys/!room:bar.baz?version=1"
+                ))
+                .unwrap(),
+            "delete keys 1 !room:bar.baz"
+        );
+    }
+
+    #[test]
+    fn unknown_endpoint() {
+        let router = router();
+
+        for uri in [
+            "/_matrix/client/v3/directory/room",
+            "/_matrix/client/v3/directory/room/%23foo:bar.baz/extra",
+            "/_matrix/client/v4/directory/room/%23foo:bar.baz",
+            "/_matrix/client/v3/room_keys/keys/",
+        ] {
+            let error = router.route(request(Method::GET, uri)).unwrap_err();
+            assert_eq!(error.status_code, StatusCode::NOT_FOUND);
+            assert_eq!(errcode(error), json!("M_UNRECOGNIZED"));
+        }
+    }
+
+    #[test]
+    fn unsupported_method() {
+        let error = router()
+            .route(request(Method::PUT, "/_matrix/client/v3/directory/room/%23foo:bar.baz"))
+            .unwrap_err();
+        assert_eq!(error.status_code, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(errcode(error), json!("M_UNRECOGNIZED"));
+    }
+
+    #[test]
+    fn invalid_request() {
+        let router = router();
+
+        let error = router
+            .route(request(Method::GET, "/_matrix/client/v3/directory/room/not_an_alias"))
+            .unwrap_err();
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert_eq!(errcode(error), json!("M_BAD_JSON"));
+
+        // Missing `version` query parameter.
+        let error =
+            router.route(request(Method::GET, "/_matrix/client/v3/room_keys/keys")).unwrap_err();
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert_eq!(errcode(error), json!("M_BAD_JSON"));
+    }
+
+    #[test]
+    fn specificity() {
+        let by_name = parse_path("/_matrix/client/v3/rooms/:room_id/members");
+        let by_param = parse_path("/_matrix/client/v3/rooms/:room_id/:field");
+        let other_params = parse_path("/_matrix/client/v3/rooms/:other_id/:other_field");
+        let early_name = parse_path("/_matrix/client/v3/rooms/joined/:field");
+
+        // A literal segment is more specific than a parameter in the same position.
+        assert_eq!(cmp_specificity(&by_name, &by_param), Ordering::Greater);
+        assert_eq!(cmp_specificity(&by_param, &by_name), Ordering::Less);
+        // The leftmost segments take precedence.
+        assert_eq!(cmp_specificity(&early_name, &by_name), Ordering::Greater);
+        assert_eq!(cmp_specificity(&by_name, &early_name), Ordering::Less);
+        // The names of the parameters don't matter.
+        assert_eq!(cmp_specificity(&by_param, &other_params), Ordering::Equal);
+    }
+
+    #[test]
+    #[should_panic = "a handler is already registered for GET /_matrix/client/r0/directory/room/:room_alias"]
+    fn duplicate_route() {
+        let _router = Router::<Vec<u8>, ()>::builder()
+            .route(|_: get_alias::v3::Request| {})
+            .route(|_: get_alias::v3::Request| {})
+            .build();
+    }
+}
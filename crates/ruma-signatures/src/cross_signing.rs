@@ -0,0 +1,329 @@
+//! Verification of the signatures of cross-signing keys and devices.
+//!
+//! The signatures are checked over the JSON objects as they were received, so the functions of
+//! this module take [`Raw`] keys. Fields that are unknown to Ruma are still covered by the
+//! signatures.
+
+use std::collections::BTreeMap;
+
+use ruma_common::{
+    encryption::{CrossSigningKey, DeviceKeys, KeyUsage},
+    serde::{Base64, Raw},
+    CanonicalJsonObject, CanonicalJsonValue, DeviceKeyAlgorithm, DeviceKeyId, OwnedDeviceId,
+    OwnedDeviceKeyId, OwnedUserId, UserId,
+};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{verify_json, Error};
+
+/// The cross-signing keys of a user.
+#[derive(Clone, Copy, Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct CrossSigningKeys<'a> {
+    /// The master key of the user.
+    pub master_key: &'a Raw<CrossSigningKey>,
+
+    /// The self-signing key of the user, used to sign their devices.
+    pub self_signing_key: Option<&'a Raw<CrossSigningKey>>,
+
+    /// The user-signing key of the user, used to sign the master keys of other users.
+    pub user_signing_key: Option<&'a Raw<CrossSigningKey>>,
+}
+
+impl<'a> CrossSigningKeys<'a> {
+    /// Creates a new `CrossSigningKeys` with the given master key.
+    pub fn new(master_key: &'a Raw<CrossSigningKey>) -> Self {
+        Self { master_key, self_signing_key: None, user_signing_key: None }
+    }
+}
+
+/// The trust state of a device according to cross-signing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::exhaustive_enums)]
+pub enum DeviceTrust {
+    /// The device is signed by the self-signing key of its owner, and the master key of its owner
+    /// is trusted.
+    Verified,
+
+    /// The device is signed by the self-signing key of its owner, but the master key of its owner
+    /// is not trusted.
+    CrossSigned,
+
+    /// The device is not signed by a valid self-signing key of its owner.
+    NotCrossSigned,
+}
+
+/// The result of the verification of the cross-signing keys and devices of a user.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CrossSigningVerification {
+    /// Whether the master key of the user is trusted.
+    ///
+    /// Our own master key is always trusted. The master key of another user is trusted if it is
+    /// signed by our user-signing key, which is itself signed by our master key.
+    pub master_key: Result<(), CrossSigningError>,
+
+    /// Whether the self-signing key of the user is signed by their master key.
+    pub self_signing_key: Result<(), CrossSigningError>,
+
+    /// The trust state of the devices of the user.
+    pub devices: BTreeMap<OwnedDeviceId, DeviceTrust>,
+}
+
+/// Verify the cross-signing chain of the devices of a user.
+///
+/// `own_keys` are the cross-signing keys of the user doing the verification. Their master key is
+/// assumed to be trusted. `user_keys` are the cross-signing keys of the owner of the devices,
+/// which can be the same user.
+///
+/// This checks the master → self-signing → device chain for every device, and the master →
+/// user-signing → other user's master chain if the devices belong to another user. Devices that
+/// don't belong to the owner of `user_keys`, or that can't be deserialized, are ignored.
+///
+/// Returns an error if one of the master keys can't be deserialized.
+pub fn verify_cross_signing<'a>(
+    own_keys: CrossSigningKeys<'_>,
+    user_keys: CrossSigningKeys<'_>,
+    devices: impl IntoIterator<Item = &'a Raw<DeviceKeys>>,
+) -> Result<CrossSigningVerification, CrossSigningError> {
+    let own_master_key = own_keys.master_key.deserialize()?;
+    let user_master_key = SignedObject::new(user_keys.master_key)?;
+    let user_id = &user_master_key.value.user_id;
+
+    let master_key = if *user_id == own_master_key.user_id {
+        if user_master_key.value.keys == own_master_key.keys {
+            Ok(())
+        } else {
+            Err(CrossSigningError::MasterKeyMismatch)
+        }
+    } else {
+        own_keys
+            .user_signing_key
+            .ok_or(CrossSigningError::MissingKey(KeyUsage::UserSigning))
+            .and_then(|user_signing_key| {
+                let user_signing_key = SignedObject::new(user_signing_key)?;
+                check_usage(&user_signing_key.value, KeyUsage::UserSigning)?;
+                check_cross_signing_key(&user_signing_key, &own_master_key)?;
+                check_user_master_key(&user_master_key, &user_signing_key.value)
+            })
+    };
+
+    let self_signing_key = user_keys
+        .self_signing_key
+        .ok_or(CrossSigningError::MissingKey(KeyUsage::SelfSigning))
+        .and_then(|key| {
+            let key = SignedObject::new(key)?;
+            check_usage(&key.value, KeyUsage::SelfSigning)?;
+            check_cross_signing_key(&key, &user_master_key.value).map(|_| key.value)
+        });
+
+    let devices = devices
+        .into_iter()
+        .filter_map(|device| SignedObject::new(device).ok())
+        .filter(|device| device.value.user_id == *user_id)
+        .map(|device| {
+            let cross_signed =
+                self_signing_key.as_ref().is_ok_and(|key| check_device_keys(&device, key).is_ok());
+            let trust = match (cross_signed, master_key.is_ok()) {
+                (true, true) => DeviceTrust::Verified,
+                (true, false) => DeviceTrust::CrossSigned,
+                (false, _) => DeviceTrust::NotCrossSigned,
+            };
+
+            (device.value.device_id, trust)
+        })
+        .collect();
+
+    Ok(CrossSigningVerification {
+        master_key,
+        self_signing_key: self_signing_key.map(|_| ()),
+        devices,
+    })
+}
+
+/// Verify that the given self-signing or user-signing key is signed by the given master key.
+///
+/// Both keys must belong to the same user. The usage of `key` is not checked.
+pub fn verify_cross_signing_key(
+    key: &Raw<CrossSigningKey>,
+    master_key: &Raw<CrossSigningKey>,
+) -> Result<(), CrossSigningError> {
+    check_cross_signing_key(&SignedObject::new(key)?, &master_key.deserialize()?)
+}
+
+/// Verify that the master key of another user is signed by the given user-signing key.
+pub fn verify_user_master_key(
+    master_key: &Raw<CrossSigningKey>,
+    user_signing_key: &Raw<CrossSigningKey>,
+) -> Result<(), CrossSigningError> {
+    check_user_master_key(&SignedObject::new(master_key)?, &user_signing_key.deserialize()?)
+}
+
+/// Verify that the given device is signed by the given self-signing key.
+///
+/// Both must belong to the same user.
+pub fn verify_device_keys(
+    device: &Raw<DeviceKeys>,
+    self_signing_key: &Raw<CrossSigningKey>,
+) -> Result<(), CrossSigningError> {
+    check_device_keys(&SignedObject::new(device)?, &self_signing_key.deserialize()?)
+}
+
+/// A deserialized object along with the JSON object it was received as.
+struct SignedObject<T> {
+    value: T,
+    json: CanonicalJsonObject,
+}
+
+impl<T: DeserializeOwned> SignedObject<T> {
+    fn new(raw: &Raw<T>) -> Result<Self, CrossSigningError> {
+        Ok(Self { value: raw.deserialize()?, json: raw.deserialize_as()? })
+    }
+}
+
+fn check_cross_signing_key(
+    key: &SignedObject<CrossSigningKey>,
+    master_key: &CrossSigningKey,
+) -> Result<(), CrossSigningError> {
+    check_usage(master_key, KeyUsage::Master)?;
+    check_user(&key.value.user_id, &master_key.user_id)?;
+
+    verify_signed_by(&key.json, master_key)
+}
+
+fn check_user_master_key(
+    master_key: &SignedObject<CrossSigningKey>,
+    user_signing_key: &CrossSigningKey,
+) -> Result<(), CrossSigningError> {
+    check_usage(&master_key.value, KeyUsage::Master)?;
+    check_usage(user_signing_key, KeyUsage::UserSigning)?;
+
+    verify_signed_by(&master_key.json, user_signing_key)
+}
+
+fn check_device_keys(
+    device: &SignedObject<DeviceKeys>,
+    self_signing_key: &CrossSigningKey,
+) -> Result<(), CrossSigningError> {
+    check_usage(self_signing_key, KeyUsage::SelfSigning)?;
+    check_user(&device.value.user_id, &self_signing_key.user_id)?;
+
+    verify_signed_by(&device.json, self_signing_key)
+}
+
+fn check_usage(key: &CrossSigningKey, usage: KeyUsage) -> Result<(), CrossSigningError> {
+    if key.usage.contains(&usage) {
+        Ok(())
+    } else {
+        Err(CrossSigningError::MissingUsage(usage))
+    }
+}
+
+fn check_user(user_id: &UserId, expected: &UserId) -> Result<(), CrossSigningError> {
+    if user_id == expected {
+        Ok(())
+    } else {
+        Err(CrossSigningError::UserMismatch {
+            expected: expected.to_owned(),
+            found: user_id.to_owned(),
+        })
+    }
+}
+
+/// Verify that the given object is signed by the Ed25519 key of the given cross-signing key.
+///
+/// Other signatures of the object are ignored.
+fn verify_signed_by(
+    object: &CanonicalJsonObject,
+    signing_key: &CrossSigningKey,
+) -> Result<(), CrossSigningError> {
+    let (key_id, public_key) = signing_key
+        .keys
+        .iter()
+        .find(|(key_id, _)| key_id.algorithm() == DeviceKeyAlgorithm::Ed25519)
+        .ok_or(CrossSigningError::MissingPublicKey)?;
+    let public_key = Base64::parse(public_key)
+        .map_err(|_| CrossSigningError::InvalidPublicKey(key_id.clone()))?;
+
+    let signature = signature(object, &signing_key.user_id, key_id)
+        .ok_or_else(|| CrossSigningError::MissingSignature(key_id.clone()))?;
+    let mut object = object.clone();
+    object.insert(
+        "signatures".to_owned(),
+        CanonicalJsonValue::Object(BTreeMap::from([(
+            signing_key.user_id.to_string(),
+            CanonicalJsonValue::Object(BTreeMap::from([(key_id.to_string(), signature)])),
+        )])),
+    );
+
+    let public_key_map = BTreeMap::from([(
+        signing_key.user_id.to_string(),
+        BTreeMap::from([(key_id.to_string(), public_key)]),
+    )]);
+
+    verify_json(&public_key_map, &object).map_err(CrossSigningError::InvalidSignature)
+}
+
+/// Get the signature of the given object by the given user and key.
+fn signature(
+    object: &CanonicalJsonObject,
+    user_id: &UserId,
+    key_id: &DeviceKeyId,
+) -> Option<CanonicalJsonValue> {
+    let Some(CanonicalJsonValue::Object(signatures)) = object.get("signatures") else {
+        return None;
+    };
+    let Some(CanonicalJsonValue::Object(signatures)) = signatures.get(user_id.as_str()) else {
+        return None;
+    };
+
+    signatures.get(key_id.as_str()).cloned()
+}
+
+/// An error encountered when verifying cross-signing signatures.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CrossSigningError {
+    /// A key belongs to a different user than expected.
+    #[error("key belongs to {found} instead of {expected}")]
+    UserMismatch {
+        /// The expected user.
+        expected: OwnedUserId,
+
+        /// The user that the key belongs to.
+        found: OwnedUserId,
+    },
+
+    /// A key doesn't have the expected usage.
+    #[error("key doesn't have the `{0}` usage")]
+    MissingUsage(KeyUsage),
+
+    /// A cross-signing key that is needed to verify the chain is missing.
+    #[error("the `{0}` cross-signing key is missing")]
+    MissingKey(KeyUsage),
+
+    /// The master key of our own user doesn't match the one we trust.
+    #[error("the master key doesn't match our own master key")]
+    MasterKeyMismatch,
+
+    /// The signing key doesn't contain an Ed25519 public key.
+    #[error("signing key doesn't contain an Ed25519 public key")]
+    MissingPublicKey,
+
+    /// The Ed25519 public key of the signing key is not valid base64.
+    #[error("public key `{0}` is not valid base64")]
+    InvalidPublicKey(OwnedDeviceKeyId),
+
+    /// The object is not signed by the signing key.
+    #[error("missing signature by `{0}`")]
+    MissingSignature(OwnedDeviceKeyId),
+
+    /// The signature by the signing key is invalid.
+    #[error("invalid signature: {0}")]
+    InvalidSignature(Error),
+
+    /// A key or device could not be deserialized.
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
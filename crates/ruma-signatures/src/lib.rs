@@ -41,12 +41,21 @@
 //! To verify a signature on arbitrary JSON, use the `verify_json` function. To verify the
 //! signatures and hashes on an event, use the `verify_event` function. See the documentation for
 //! these respective functions for more details and full examples of use.
+//!
+//! # Cross-signing
+//!
+//! To verify the cross-signing chain of the devices of a user, use the `verify_cross_signing`
+//! function.
 
 #![warn(missing_docs)]
 
 use ruma_common::serde::{AsRefStr, DisplayAsRefStr};
 
 pub use self::{
+    cross_signing::{
+        verify_cross_signing, verify_cross_signing_key, verify_device_keys, verify_user_master_key,
+        CrossSigningError, CrossSigningKeys, CrossSigningVerification, DeviceTrust,
+    },
     error::{Error, JsonError, ParseError, VerificationError},
     functions::{
         canonical_json, content_hash, hash_and_sign_event, reference_hash, sign_json, verify_event,
@@ -57,6 +66,7 @@ pub use self::{
     verification::Verified,
 };
 
+mod cross_signing;
 mod error;
 mod functions;
 mod keys;
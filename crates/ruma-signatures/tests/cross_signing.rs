@@ -0,0 +1,258 @@
+use assert_matches2::assert_matches;
+use ruma_common::{
+    device_id,
+    encryption::{CrossSigningKey, DeviceKeys, KeyUsage},
+    serde::{base64::Standard, Base64, Raw},
+    CanonicalJsonObject,
+};
+use ruma_signatures::{
+    sign_json, verify_cross_signing, verify_device_keys, CrossSigningError, CrossSigningKeys,
+    DeviceTrust, Ed25519KeyPair,
+};
+use serde_json::{
+    from_value as from_json_value, json, value::to_raw_value as to_raw_json_value,
+    Value as JsonValue,
+};
+
+struct Identity {
+    user_id: &'static str,
+    master: Raw<CrossSigningKey>,
+    self_signing: Raw<CrossSigningKey>,
+    user_signing: Raw<CrossSigningKey>,
+    master_pair: Ed25519KeyPair,
+    self_signing_pair: Ed25519KeyPair,
+    user_signing_pair: Ed25519KeyPair,
+}
+
+impl Identity {
+    fn new(user_id: &'static str) -> Self {
+        let master_pair = key_pair("master");
+        let self_signing_pair = key_pair("self_signing");
+        let user_signing_pair = key_pair("user_signing");
+
+        let master = signed(cross_signing_key(user_id, "master", &master_pair), user_id, &[]);
+        let self_signing = signed(
+            cross_signing_key(user_id, "self_signing", &self_signing_pair),
+            user_id,
+            &[&master_pair],
+        );
+        let user_signing = signed(
+            cross_signing_key(user_id, "user_signing", &user_signing_pair),
+            user_id,
+            &[&master_pair],
+        );
+
+        Self {
+            user_id,
+            master,
+            self_signing,
+            user_signing,
+            master_pair,
+            self_signing_pair,
+            user_signing_pair,
+        }
+    }
+
+    fn keys(&self) -> CrossSigningKeys<'_> {
+        CrossSigningKeys {
+            master_key: &self.master,
+            self_signing_key: Some(&self.self_signing),
+            user_signing_key: Some(&self.user_signing),
+        }
+    }
+
+    fn device(&self, device_id: &str, signers: &[&Ed25519KeyPair]) -> Raw<DeviceKeys> {
+        self.device_with_fields(device_id, json!({}), signers)
+    }
+
+    fn device_with_fields(
+        &self,
+        device_id: &str,
+        fields: JsonValue,
+        signers: &[&Ed25519KeyPair],
+    ) -> Raw<DeviceKeys> {
+        let user_id = self.user_id;
+        let device_pair = key_pair(device_id);
+        let mut signers = signers.to_vec();
+        signers.push(&device_pair);
+
+        let mut device = json!({
+            "user_id": user_id,
+            "device_id": device_id,
+            "algorithms": ["m.olm.v1.curve25519-aes-sha2", "m.megolm.v1.aes-sha2"],
+            "keys": {
+                format!("ed25519:{device_id}"): public_key(&device_pair),
+            },
+        });
+        let JsonValue::Object(fields) = fields else { panic!("fields must be an object") };
+        device.as_object_mut().unwrap().extend(fields);
+
+        signed(device, user_id, &signers)
+    }
+}
+
+fn key_pair(version: &str) -> Ed25519KeyPair {
+    Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), version.to_owned()).unwrap()
+}
+
+fn public_key(key_pair: &Ed25519KeyPair) -> String {
+    Base64::<Standard>::new(key_pair.public_key().to_vec()).encode()
+}
+
+fn cross_signing_key(user_id: &str, usage: &str, key_pair: &Ed25519KeyPair) -> JsonValue {
+    json!({
+        "user_id": user_id,
+        "usage": [usage],
+        "keys": {
+            format!("ed25519:{}", key_pair.version()): public_key(key_pair),
+        },
+    })
+}
+
+fn signed<T>(value: JsonValue, signer_id: &str, signers: &[&Ed25519KeyPair]) -> Raw<T> {
+    let mut object: CanonicalJsonObject = from_json_value(value).unwrap();
+    for signer in signers {
+        sign_json(signer_id, *signer, &mut object).unwrap();
+    }
+    object.entry("signatures".to_owned()).or_insert_with(|| json!({}).try_into().unwrap());
+
+    Raw::from_json(to_raw_json_value(&object).unwrap())
+}
+
+#[test]
+fn own_devices() {
+    let alice = Identity::new("@alice:example.org");
+    let devices = [
+        alice.device("SIGNED", &[&alice.self_signing_pair]),
+        alice.device("UNSIGNED", &[]),
+        alice.device("WRONGKEY", &[&alice.user_signing_pair]),
+    ];
+
+    let verification = verify_cross_signing(alice.keys(), alice.keys(), &devices).unwrap();
+    verification.master_key.unwrap();
+    verification.self_signing_key.unwrap();
+    assert_eq!(verification.devices.len(), 3);
+    assert_eq!(verification.devices[device_id!("SIGNED")], DeviceTrust::Verified);
+    assert_eq!(verification.devices[device_id!("UNSIGNED")], DeviceTrust::NotCrossSigned);
+    assert_eq!(verification.devices[device_id!("WRONGKEY")], DeviceTrust::NotCrossSigned);
+
+    assert_matches!(
+        verify_device_keys(&devices[1], &alice.self_signing),
+        Err(CrossSigningError::MissingSignature(_))
+    );
+    assert_matches!(
+        verify_device_keys(&devices[0], &alice.user_signing),
+        Err(CrossSigningError::MissingUsage(KeyUsage::SelfSigning))
+    );
+}
+
+#[test]
+fn other_user_devices() {
+    let alice = Identity::new("@alice:example.org");
+    let mut bob = Identity::new("@bob:example.org");
+    let devices = [bob.device("BOBDEVICE", &[&bob.self_signing_pair])];
+
+    // Bob's master key is not signed by Alice.
+    let verification = verify_cross_signing(alice.keys(), bob.keys(), &devices).unwrap();
+    assert_matches!(verification.master_key, Err(CrossSigningError::MissingSignature(_)));
+    verification.self_signing_key.unwrap();
+    assert_eq!(verification.devices[device_id!("BOBDEVICE")], DeviceTrust::CrossSigned);
+
+    // Alice verified Bob.
+    bob.master = signed(
+        cross_signing_key("@bob:example.org", "master", &bob.master_pair),
+        "@alice:example.org",
+        &[&alice.user_signing_pair],
+    );
+    let verification = verify_cross_signing(alice.keys(), bob.keys(), &devices).unwrap();
+    verification.master_key.unwrap();
+    assert_eq!(verification.devices[device_id!("BOBDEVICE")], DeviceTrust::Verified);
+
+    // Alice doesn't have a user-signing key.
+    let alice_keys = CrossSigningKeys { user_signing_key: None, ..alice.keys() };
+    let verification = verify_cross_signing(alice_keys, bob.keys(), &devices).unwrap();
+    assert_matches!(
+        verification.master_key,
+        Err(CrossSigningError::MissingKey(KeyUsage::UserSigning))
+    );
+    assert_eq!(verification.devices[device_id!("BOBDEVICE")], DeviceTrust::CrossSigned);
+
+    // Devices of other users are ignored.
+    let verification = verify_cross_signing(alice.keys(), alice.keys(), &devices).unwrap();
+    assert!(verification.devices.is_empty());
+}
+
+#[test]
+fn broken_chain() {
+    let alice = Identity::new("@alice:example.org");
+    let mut devices = vec![alice.device("DEVICE", &[&alice.self_signing_pair])];
+
+    // The self-signing key is not signed by the master key.
+    let self_signing = signed(
+        cross_signing_key("@alice:example.org", "self_signing", &alice.self_signing_pair),
+        "@alice:example.org",
+        &[],
+    );
+    let keys = CrossSigningKeys { self_signing_key: Some(&self_signing), ..alice.keys() };
+    let verification = verify_cross_signing(keys, keys, &devices).unwrap();
+    assert_matches!(verification.self_signing_key, Err(CrossSigningError::MissingSignature(_)));
+    assert_eq!(verification.devices[device_id!("DEVICE")], DeviceTrust::NotCrossSigned);
+
+    // The device was modified after it was signed.
+    let mut device: JsonValue = devices[0].deserialize_as().unwrap();
+    device["algorithms"] = json!([]);
+    devices[0] = Raw::from_json(to_raw_json_value(&device).unwrap());
+    let verification = verify_cross_signing(alice.keys(), alice.keys(), &devices).unwrap();
+    assert_eq!(verification.devices[device_id!("DEVICE")], DeviceTrust::NotCrossSigned);
+    assert_matches!(
+        verify_device_keys(&devices[0], &alice.self_signing),
+        Err(CrossSigningError::InvalidSignature(_))
+    );
+
+    // Our own master key was replaced.
+    let other = Identity::new("@alice:example.org");
+    let verification = verify_cross_signing(alice.keys(), other.keys(), &[]).unwrap();
+    assert_matches!(verification.master_key, Err(CrossSigningError::MasterKeyMismatch));
+}
+
+#[test]
+fn unknown_fields() {
+    let alice = Identity::new("@alice:example.org");
+    let device = alice.device_with_fields(
+        "DEHYDRATED",
+        json!({ "dehydrated": true }),
+        &[&alice.self_signing_pair],
+    );
+    verify_device_keys(&device, &alice.self_signing).unwrap();
+
+    // The unknown field is covered by the signature.
+    let mut json: JsonValue = device.deserialize_as().unwrap();
+    json["dehydrated"] = json!(false);
+    let device = Raw::from_json(to_raw_json_value(&json).unwrap());
+    assert_matches!(
+        verify_device_keys(&device, &alice.self_signing),
+        Err(CrossSigningError::InvalidSignature(_))
+    );
+}
+
+#[test]
+fn invalid_json() {
+    let alice = Identity::new("@alice:example.org");
+    let devices = [
+        alice.device("DEVICE", &[&alice.self_signing_pair]),
+        Raw::from_json(to_raw_json_value(&json!({ "device_id": "INVALID" })).unwrap()),
+    ];
+
+    // Devices that can't be deserialized are ignored.
+    let verification = verify_cross_signing(alice.keys(), alice.keys(), &devices).unwrap();
+    assert_eq!(verification.devices.len(), 1);
+    assert_eq!(verification.devices[device_id!("DEVICE")], DeviceTrust::Verified);
+
+    // A master key that can't be deserialized is an error.
+    let master = Raw::from_json(to_raw_json_value(&json!({ "usage": ["master"] })).unwrap());
+    let keys = CrossSigningKeys::new(&master);
+    assert_matches!(
+        verify_cross_signing(alice.keys(), keys, &devices),
+        Err(CrossSigningError::Json(_))
+    );
+}
@@ -43,6 +43,8 @@
 //! * `html` -- Parse HTML to sanitize it or navigate its tree.
 //!   * `html-matrix` -- Enables the `matrix` feature of `ruma-html` to parse HTML elements data to
 //!     typed data as suggested by the Matrix Specification.
+//!   * `html-serde` -- Enables the `serde` feature of `ruma-html` to (de)serialize the sanitizer
+//!     configuration.
 //!
 //! # Unstable features
 //!
@@ -20,6 +20,8 @@ const NIGHTLY: &str = "nightly-2024-05-09";
 mod cargo;
 mod ci;
 mod doc;
+#[cfg(feature = "openapi")]
+mod openapi;
 #[cfg(feature = "default")]
 mod release;
 #[cfg(feature = "default")]
@@ -28,6 +30,8 @@ mod util;
 use cargo::Package;
 use ci::{CiArgs, CiTask};
 use doc::DocTask;
+#[cfg(feature = "openapi")]
+use openapi::OpenApiArgs;
 #[cfg(feature = "default")]
 use release::{ReleaseArgs, ReleaseTask};
 
@@ -45,6 +49,9 @@ enum Command {
     Ci(CiArgs),
     /// Build the docs
     Doc(DocTask),
+    /// Generate the OpenAPI document of an API
+    #[cfg(feature = "openapi")]
+    Openapi(OpenApiArgs),
     /// Publish a new version of a crate on crates.io, `publish` can be used as an alias
     #[cfg(feature = "default")]
     #[clap(alias = "publish")]
@@ -58,6 +65,8 @@ fn main() -> Result<()> {
             ci.run()
         }
         Command::Doc(doc) => doc.run(),
+        #[cfg(feature = "openapi")]
+        Command::Openapi(args) => args.run(),
         #[cfg(feature = "default")]
         Command::Release(args) => {
             let mut task = ReleaseTask::new(args.package, args.version, args.dry_run)?;
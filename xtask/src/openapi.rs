@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use ruma::api::{
+    appservice, client, federation, identity_service, openapi::OpenApiDocument, push_gateway,
+};
+use serde_json::to_string_pretty as to_json_string_pretty;
+
+use crate::{Metadata, Result};
+
+#[derive(Args)]
+pub struct OpenApiArgs {
+    /// The API to generate the OpenAPI document of.
+    #[clap(value_enum)]
+    pub api: Api,
+
+    /// The file to write the document to. It is printed if this is not set.
+    #[clap(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+/// The Matrix APIs implemented by Ruma.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Api {
+    /// The client-server API.
+    Client,
+    /// The server-server API.
+    Federation,
+    /// The application service API.
+    Appservice,
+    /// The identity service API.
+    IdentityService,
+    /// The push gateway API.
+    PushGateway,
+}
+
+impl Api {
+    /// The name of the crate that implements this API.
+    fn crate_name(self) -> &'static str {
+        match self {
+            Api::Client => "ruma-client-api",
+            Api::Federation => "ruma-federation-api",
+            Api::Appservice => "ruma-appservice-api",
+            Api::IdentityService => "ruma-identity-service-api",
+            Api::PushGateway => "ruma-push-gateway-api",
+        }
+    }
+
+    /// The title of the OpenAPI document of this API.
+    fn title(self) -> &'static str {
+        match self {
+            Api::Client => "Matrix Client-Server API",
+            Api::Federation => "Matrix Server-Server API",
+            Api::Appservice => "Matrix Application Service API",
+            Api::IdentityService => "Matrix Identity Service API",
+            Api::PushGateway => "Matrix Push Gateway API",
+        }
+    }
+
+    /// Add the endpoints of this API to the given document.
+    fn add_endpoints(self, document: &mut OpenApiDocument) {
+        match self {
+            Api::Client => client::openapi::add_endpoints(document),
+            Api::Federation => federation::openapi::add_endpoints(document),
+            Api::Appservice => appservice::openapi::add_endpoints(document),
+            Api::IdentityService => identity_service::openapi::add_endpoints(document),
+            Api::PushGateway => push_gateway::openapi::add_endpoints(document),
+        }
+    }
+}
+
+impl OpenApiArgs {
+    pub(crate) fn run(self) -> Result<()> {
+        let metadata = Metadata::load()?;
+        let crate_name = self.api.crate_name();
+        let package = metadata
+            .find_package(crate_name)
+            .ok_or_else(|| format!("package {crate_name} not found"))?;
+
+        let mut document = OpenApiDocument::new(self.api.title(), package.version.to_string());
+        self.api.add_endpoints(&mut document);
+        let json = to_json_string_pretty(&document.into_json())?;
+
+        match self.output {
+            Some(path) => xshell::write_file(path, json + "\n")?,
+            None => println!("{json}"),
+        }
+
+        Ok(())
+    }
+}